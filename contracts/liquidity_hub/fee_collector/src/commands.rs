@@ -102,11 +102,20 @@ fn collect_fees_for_factory(
                 )?);
             }
         }
-        FactoryType::Pool { start_after, limit } => {
+        FactoryType::Pool {
+            start_after,
+            start_after_identifier,
+            limit,
+        } => {
             let response: PairsResponse =
                 deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
                     contract_addr: factory.to_string(),
-                    msg: to_binary(&QueryMsg::Pairs { start_after, limit })?,
+                    msg: to_binary(&QueryMsg::Pairs {
+                        start_after,
+                        start_after_identifier,
+                        limit,
+                        asset_infos: None,
+                    })?,
                 }))?;
 
             for pair in response.pairs {
@@ -209,11 +218,20 @@ pub fn aggregate_fees(
                         store_temporal_asset_info(deps.branch(), vault_info.asset_info.clone())?;
                     }
                 }
-                FactoryType::Pool { start_after, limit } => {
+                FactoryType::Pool {
+                    start_after,
+                    start_after_identifier,
+                    limit,
+                } => {
                     let response: PairsResponse =
                         deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
                             contract_addr: factory.to_string(),
-                            msg: to_binary(&QueryMsg::Pairs { start_after, limit })?,
+                            msg: to_binary(&QueryMsg::Pairs {
+                                start_after,
+                                start_after_identifier,
+                                limit,
+                                asset_infos: None,
+                            })?,
                         }))?;
 
                     for pair in response.pairs {
@@ -286,9 +304,12 @@ pub fn aggregate_fees(
                 Ok(operations) => {
                     let execute_swap_operations_msg =
                         to_binary(&router::ExecuteMsg::ExecuteSwapOperations {
-                            operations,
+                            operations: Some(operations),
+                            ask_asset_info: None,
                             minimum_receive: None,
                             to: None,
+                            deadline: None,
+                            referral: None,
                         })?;
 
                     match offer_asset_info.clone() {
@@ -375,6 +396,7 @@ pub fn forward_fees(
                     factory_addr: config.pool_factory.to_string(),
                     factory_type: FactoryType::Pool {
                         start_after: None,
+                        start_after_identifier: None,
                         limit: Some(30u32),
                     },
                 },
@@ -416,6 +438,7 @@ pub fn forward_fees(
                     factory_addr: config.pool_factory.to_string(),
                     factory_type: FactoryType::Pool {
                         start_after: None,
+                        start_after_identifier: None,
                         limit: Some(30u32),
                     },
                 },
@@ -160,6 +160,9 @@ fn collect_all_factories_cw20_fees_successfully() {
                     },
                     pair_type: PairType::ConstantProduct,
                     token_factory_lp: false,
+                    pair_identifier: None,
+                    initial_liquidity: None,
+                    receiver: None,
                 },
                 &[],
             )
@@ -236,6 +239,7 @@ fn collect_all_factories_cw20_fees_successfully() {
                 ],
                 slippage_tolerance: None,
                 receiver: None,
+                deadline: None,
             },
             &[],
         )
@@ -256,6 +260,7 @@ fn collect_all_factories_cw20_fees_successfully() {
                     belief_price: None,
                     max_spread: None,
                     to: None,
+                    deadline: None,
                 })
                 .unwrap(),
             },
@@ -273,6 +278,7 @@ fn collect_all_factories_cw20_fees_successfully() {
                     belief_price: None,
                     max_spread: None,
                     to: None,
+                    deadline: None,
                 })
                 .unwrap(),
             },
@@ -363,6 +369,7 @@ fn collect_all_factories_cw20_fees_successfully() {
                 factory_addr: pool_factory_address.to_string(),
                 factory_type: FactoryType::Pool {
                     start_after: None,
+                    start_after_identifier: None,
                     limit: Some(u32::try_from(TOKEN_AMOUNT).unwrap()),
                 },
             },
@@ -428,6 +435,9 @@ fn collect_all_factories_cw20_fees_successfully() {
                 ask_asset_info: AssetInfo::Token {
                     contract_addr: cw20_tokens[i as usize].to_string(),
                 },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
             };
             swap_operations.push(swap_operation);
         }
@@ -460,6 +470,7 @@ fn collect_all_factories_cw20_fees_successfully() {
                 factory_addr: pool_factory_address.to_string(),
                 factory_type: FactoryType::Pool {
                     start_after: None,
+                    start_after_identifier: None,
                     limit: Some(u32::try_from(TOKEN_AMOUNT).unwrap()),
                 },
             },
@@ -598,6 +609,9 @@ fn collect_cw20_fees_for_specific_contracts_successfully() {
                     },
                     pair_type: PairType::ConstantProduct,
                     token_factory_lp: false,
+                    pair_identifier: None,
+                    initial_liquidity: None,
+                    receiver: None,
                 },
                 &[],
             )
@@ -672,6 +686,7 @@ fn collect_cw20_fees_for_specific_contracts_successfully() {
                 ],
                 slippage_tolerance: None,
                 receiver: None,
+                deadline: None,
             },
             &[],
         )
@@ -713,6 +728,7 @@ fn collect_cw20_fees_for_specific_contracts_successfully() {
                     belief_price: None,
                     max_spread: None,
                     to: None,
+                    deadline: None,
                 })
                 .unwrap(),
             },
@@ -730,6 +746,7 @@ fn collect_cw20_fees_for_specific_contracts_successfully() {
                     belief_price: None,
                     max_spread: None,
                     to: None,
+                    deadline: None,
                 })
                 .unwrap(),
             },
@@ -1073,6 +1090,9 @@ fn collect_pools_native_fees_successfully() {
                     },
                     pair_type: PairType::ConstantProduct,
                     token_factory_lp: false,
+                    pair_identifier: None,
+                    initial_liquidity: None,
+                    receiver: None,
                 },
                 &[],
             )
@@ -1124,6 +1144,7 @@ fn collect_pools_native_fees_successfully() {
                 ],
                 slippage_tolerance: None,
                 receiver: None,
+                deadline: None,
             },
             &[Coin {
                 denom: "native".to_string(),
@@ -1143,6 +1164,7 @@ fn collect_pools_native_fees_successfully() {
                     factory_addr: pool_factory_address.to_string(),
                     factory_type: FactoryType::Pool {
                         start_after: None,
+                        start_after_identifier: None,
                         limit: None,
                     },
                 },
@@ -1173,6 +1195,7 @@ fn collect_pools_native_fees_successfully() {
                 belief_price: None,
                 max_spread: None,
                 to: None,
+                deadline: None,
             },
             &[Coin {
                 denom: "native".to_string(),
@@ -1192,6 +1215,7 @@ fn collect_pools_native_fees_successfully() {
                     belief_price: None,
                     max_spread: None,
                     to: None,
+                    deadline: None,
                 })
                 .unwrap(),
             },
@@ -1251,6 +1275,7 @@ fn collect_pools_native_fees_successfully() {
                 factory_addr: pool_factory_address.to_string(),
                 factory_type: FactoryType::Pool {
                     start_after: None,
+                    start_after_identifier: None,
                     limit: None,
                 },
             },
@@ -1269,6 +1294,7 @@ fn collect_pools_native_fees_successfully() {
                     factory_addr: pool_factory_address.to_string(),
                     factory_type: FactoryType::Pool {
                         start_after: None,
+                        start_after_identifier: None,
                         limit: None,
                     },
                 },
@@ -1350,6 +1376,7 @@ fn collect_pools_native_fees_successfully() {
                     factory_addr: pool_factory_address.to_string(),
                     factory_type: FactoryType::Pool {
                         start_after: None,
+                        start_after_identifier: None,
                         limit: None,
                     },
                 },
@@ -1377,12 +1404,18 @@ fn collect_pools_native_fees_successfully() {
                 ask_asset_info: AssetInfo::NativeToken {
                     denom: "native".to_string(),
                 },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
             },
             SwapOperation::TerraSwap {
                 offer_asset_info: AssetInfo::NativeToken {
                     denom: "native".to_string(),
                 },
                 ask_asset_info: ask_asset.clone(),
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
             },
         ];
 
@@ -1409,6 +1442,9 @@ fn collect_pools_native_fees_successfully() {
             denom: "native".to_string(),
         },
         ask_asset_info: ask_asset.clone(),
+        belief_price: None,
+        max_spread: None,
+        pair_identifier: None,
     }];
 
     let swap_routes: Vec<SwapRoute> = vec![SwapRoute {
@@ -1437,6 +1473,7 @@ fn collect_pools_native_fees_successfully() {
                 factory_addr: pool_factory_address.to_string(),
                 factory_type: FactoryType::Pool {
                     start_after: None,
+                    start_after_identifier: None,
                     limit: Some(u32::try_from(TOKEN_AMOUNT).unwrap()),
                 },
             },
@@ -1602,6 +1639,9 @@ fn collect_fees_with_pagination_successfully() {
                     },
                     pair_type: PairType::ConstantProduct,
                     token_factory_lp: false,
+                    pair_identifier: None,
+                    initial_liquidity: None,
+                    receiver: None,
                 },
                 &[],
             )
@@ -1652,6 +1692,7 @@ fn collect_fees_with_pagination_successfully() {
                 ],
                 slippage_tolerance: None,
                 receiver: None,
+                deadline: None,
             },
             &[Coin {
                 denom: "native".to_string(),
@@ -1678,6 +1719,7 @@ fn collect_fees_with_pagination_successfully() {
                 belief_price: None,
                 max_spread: None,
                 to: None,
+                deadline: None,
             },
             &[Coin {
                 denom: "native".to_string(),
@@ -1697,6 +1739,7 @@ fn collect_fees_with_pagination_successfully() {
                     belief_price: None,
                     max_spread: None,
                     to: None,
+                    deadline: None,
                 })
                 .unwrap(),
             },
@@ -1749,6 +1792,7 @@ fn collect_fees_with_pagination_successfully() {
 
     // Collect the fees with pagination
     let mut start_after: Option<[AssetInfo; 2]> = None;
+    let mut start_after_identifier: Option<String> = None;
     let mut i = 0;
     // there are 10 pools in this test, and we set the pagination limit to half of that. So we will
     // collect the fees twice using pagination
@@ -1759,7 +1803,9 @@ fn collect_fees_with_pagination_successfully() {
                 &pool_factory_address,
                 &pool_network::factory::QueryMsg::Pairs {
                     start_after: start_after.clone(),
+                    start_after_identifier: start_after_identifier.clone(),
                     limit: Some(u32::try_from(TOKEN_AMOUNT / 2).unwrap()),
+                    asset_infos: None,
                 },
             )
             .unwrap();
@@ -1772,6 +1818,7 @@ fn collect_fees_with_pagination_successfully() {
                     factory_addr: pool_factory_address.to_string(),
                     factory_type: FactoryType::Pool {
                         start_after: start_after.clone(),
+                        start_after_identifier: start_after_identifier.clone(),
                         limit: Some(u32::try_from(TOKEN_AMOUNT / 2).unwrap()),
                     },
                 },
@@ -1780,6 +1827,7 @@ fn collect_fees_with_pagination_successfully() {
         )
         .unwrap();
 
+        start_after_identifier = pairs_response.pair_identifiers.last().cloned();
         start_after = Some(
             pairs_response
                 .clone()
@@ -2564,6 +2612,9 @@ fn aggregate_fees_for_vault() {
                 },
                 pair_type: PairType::ConstantProduct,
                 token_factory_lp: false,
+                pair_identifier: None,
+                initial_liquidity: None,
+                receiver: None,
             },
             &[],
         )
@@ -2602,6 +2653,7 @@ fn aggregate_fees_for_vault() {
             ],
             slippage_tolerance: None,
             receiver: None,
+            deadline: None,
         },
         &[
             Coin {
@@ -2622,6 +2674,9 @@ fn aggregate_fees_for_vault() {
             denom: "ujuno".to_string(),
         },
         ask_asset_info: ask_asset.clone(),
+        belief_price: None,
+        max_spread: None,
+        pair_identifier: None,
     }];
 
     let swap_routes: Vec<SwapRoute> = vec![SwapRoute {
@@ -2961,6 +3016,9 @@ fn collect_and_distribute_fees_successfully() {
                     },
                     pair_type: PairType::ConstantProduct,
                     token_factory_lp: false,
+                    pair_identifier: None,
+                    initial_liquidity: None,
+                    receiver: None,
                 },
                 &[],
             )
@@ -3002,6 +3060,7 @@ fn collect_and_distribute_fees_successfully() {
                 ],
                 slippage_tolerance: None,
                 receiver: None,
+                deadline: None,
             },
             &[
                 Coin {
@@ -3033,6 +3092,7 @@ fn collect_and_distribute_fees_successfully() {
                 belief_price: None,
                 max_spread: None,
                 to: None,
+                deadline: None,
             },
             &[Coin {
                 denom: "uwhale".to_string(),
@@ -3055,6 +3115,7 @@ fn collect_and_distribute_fees_successfully() {
                 belief_price: None,
                 max_spread: None,
                 to: None,
+                deadline: None,
             },
             &[Coin {
                 denom: native_token.clone().to_string(),
@@ -3340,6 +3401,9 @@ fn collect_and_distribute_fees_with_expiring_epoch_successfully() {
                     },
                     pair_type: PairType::ConstantProduct,
                     token_factory_lp: false,
+                    pair_identifier: None,
+                    initial_liquidity: None,
+                    receiver: None,
                 },
                 &[],
             )
@@ -3381,6 +3445,7 @@ fn collect_and_distribute_fees_with_expiring_epoch_successfully() {
                 ],
                 slippage_tolerance: None,
                 receiver: None,
+                deadline: None,
             },
             &[
                 Coin {
@@ -3449,6 +3514,7 @@ fn collect_and_distribute_fees_with_expiring_epoch_successfully() {
             belief_price: None,
             max_spread: None,
             to: None,
+            deadline: None,
         },
         &[Coin {
             denom: "usdc".to_string(),
@@ -3503,6 +3569,7 @@ fn collect_and_distribute_fees_with_expiring_epoch_successfully() {
             belief_price: None,
             max_spread: None,
             to: None,
+            deadline: None,
         },
         &[Coin {
             denom: "usdc".to_string(),
@@ -3877,6 +3944,9 @@ fn create_epoch_unsuccessfully() {
                     },
                     pair_type: PairType::ConstantProduct,
                     token_factory_lp: false,
+                    pair_identifier: None,
+                    initial_liquidity: None,
+                    receiver: None,
                 },
                 &[],
             )
@@ -3918,6 +3988,7 @@ fn create_epoch_unsuccessfully() {
                 ],
                 slippage_tolerance: None,
                 receiver: None,
+                deadline: None,
             },
             &[
                 Coin {
@@ -3949,6 +4020,7 @@ fn create_epoch_unsuccessfully() {
             belief_price: None,
             max_spread: None,
             to: None,
+            deadline: None,
         },
         &[Coin {
             denom: "usdc".to_string(),
@@ -3992,6 +4064,7 @@ fn create_epoch_unsuccessfully() {
             belief_price: None,
             max_spread: None,
             to: None,
+            deadline: None,
         },
         &[Coin {
             denom: "usdc".to_string(),
@@ -4323,6 +4396,9 @@ fn decrease_grace_period_fee_distributor() {
                     },
                     pair_type: PairType::ConstantProduct,
                     token_factory_lp: false,
+                    pair_identifier: None,
+                    initial_liquidity: None,
+                    receiver: None,
                 },
                 &[],
             )
@@ -4364,6 +4440,7 @@ fn decrease_grace_period_fee_distributor() {
                 ],
                 slippage_tolerance: None,
                 receiver: None,
+                deadline: None,
             },
             &[
                 Coin {
@@ -4395,6 +4472,7 @@ fn decrease_grace_period_fee_distributor() {
             belief_price: None,
             max_spread: None,
             to: None,
+            deadline: None,
         },
         &[Coin {
             denom: "usdc".to_string(),
@@ -4434,6 +4512,7 @@ fn decrease_grace_period_fee_distributor() {
             belief_price: None,
             max_spread: None,
             to: None,
+            deadline: None,
         },
         &[Coin {
             denom: "usdc".to_string(),
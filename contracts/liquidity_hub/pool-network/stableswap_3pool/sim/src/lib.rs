@@ -73,7 +73,7 @@ impl Model {
             amp_factor,
             balances,
             n_coins,
-            target_prices: vec![DEFAULT_TARGET_PRICE, DEFAULT_TARGET_PRICE],
+            target_prices: vec![DEFAULT_TARGET_PRICE; n_coins.into()],
             pool_tokens: pool_token_amount,
         }
     }
@@ -135,11 +135,7 @@ impl Model {
     pub fn sim_remove_liquidity_imbalance(&self, amounts: Vec<u128>) -> u128 {
         let gil = Python::acquire_gil();
         return self
-            .call1(
-                gil.python(),
-                "remove_liquidity_imbalance",
-                PyTuple::new(gil.python(), amounts.to_vec()),
-            )
+            .call1(gil.python(), "remove_liquidity_imbalance", (amounts,))
             .unwrap()
             .extract(gil.python())
             .unwrap();
@@ -1,5 +1,5 @@
 use cosmwasm_std::{
-    coins, from_binary, to_binary, Addr, CosmosMsg, Decimal, DepsMut, Env, MessageInfo,
+    coins, from_binary, to_binary, Addr, BankMsg, CosmosMsg, Decimal, DepsMut, Env, MessageInfo,
     OverflowError, Response, StdError, StdResult, Uint128, WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
@@ -36,7 +36,10 @@ pub fn receive_cw20(
             belief_price,
             max_spread,
             to,
+            deadline,
         }) => {
+            helpers::assert_deadline(env.block.time, deadline)?;
+
             // check if the swap feature is enabled
             if !feature_toggle.swaps_enabled {
                 return Err(ContractError::OperationDisabled("swap".to_string()));
@@ -45,7 +48,7 @@ pub fn receive_cw20(
             // only asset contract can execute this message
             let mut authorized: bool = false;
             let config: TrioInfoRaw = TRIO_INFO.load(deps.storage)?;
-            let pools: [Asset; 3] =
+            let pools: Vec<Asset> =
                 config.query_pools(&deps.querier, deps.api, env.contract.address.clone())?;
             for pool in pools.iter() {
                 if let AssetInfo::Token { contract_addr, .. } = &pool.info {
@@ -82,7 +85,13 @@ pub fn receive_cw20(
                 to_addr,
             )
         }
-        Ok(Cw20HookMsg::WithdrawLiquidity {}) => {
+        Ok(Cw20HookMsg::WithdrawLiquidity {
+            target_asset,
+            min_receive,
+            deadline,
+        }) => {
+            helpers::assert_deadline(env.block.time, deadline)?;
+
             // check if the withdrawal feature is enabled
             if !feature_toggle.withdrawals_enabled {
                 return Err(ContractError::OperationDisabled(
@@ -101,7 +110,14 @@ pub fn receive_cw20(
             }
 
             let sender_addr = deps.api.addr_validate(cw20_msg.sender.as_str())?;
-            withdraw_liquidity(deps, env, sender_addr, cw20_msg.amount)
+            withdraw_liquidity(
+                deps,
+                env,
+                sender_addr,
+                cw20_msg.amount,
+                target_asset,
+                min_receive,
+            )
         }
         Err(err) => Err(ContractError::Std(err)),
     }
@@ -112,7 +128,7 @@ pub fn provide_liquidity(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    assets: [Asset; 3],
+    assets: Vec<Asset>,
     slippage_tolerance: Option<Decimal>,
     receiver: Option<String>,
 ) -> Result<Response, ContractError> {
@@ -129,27 +145,25 @@ pub fn provide_liquidity(
     }
 
     let trio_info: TrioInfoRaw = TRIO_INFO.load(deps.storage)?;
-    let mut pools: [Asset; 3] =
+    let mut pools: Vec<Asset> =
         trio_info.query_pools(&deps.querier, deps.api, env.contract.address.clone())?;
-    let deposits: [Uint128; 3] = [
-        assets
-            .iter()
-            .find(|a| a.info.equal(&pools[0].info))
-            .map(|a| a.amount)
-            .expect("Wrong asset info is given"),
-        assets
-            .iter()
-            .find(|a| a.info.equal(&pools[1].info))
-            .map(|a| a.amount)
-            .expect("Wrong asset info is given"),
-        assets
-            .iter()
-            .find(|a| a.info.equal(&pools[2].info))
-            .map(|a| a.amount)
-            .expect("Wrong asset info is given"),
-    ];
 
-    if deposits[0].is_zero() || deposits[1].is_zero() || deposits[2].is_zero() {
+    if assets.len() != pools.len() {
+        return Err(ContractError::AssetMismatch {});
+    }
+
+    let deposits: Vec<Uint128> = pools
+        .iter()
+        .map(|pool| {
+            assets
+                .iter()
+                .find(|a| a.info.equal(&pool.info))
+                .map(|a| a.amount)
+                .ok_or(ContractError::AssetMismatch {})
+        })
+        .collect::<Result<_, _>>()?;
+
+    if deposits.iter().any(|deposit| deposit.is_zero()) {
         return Err(ContractError::InvalidZeroAmount {});
     }
 
@@ -199,15 +213,11 @@ pub fn provide_liquidity(
     let share = if total_share == Uint128::zero() {
         // Make sure at least MINIMUM_LIQUIDITY_AMOUNT is deposited to mitigate the risk of the first
         // depositor preventing small liquidity providers from joining the pool
-        let min_lp_token_amount = MINIMUM_LIQUIDITY_AMOUNT * Uint128::from(3u8);
-        let share = Uint128::try_from(
-            invariant
-                .compute_d(deposits[0], deposits[1], deposits[2])
-                .unwrap(),
-        )
-        .unwrap()
-        .checked_sub(min_lp_token_amount)
-        .map_err(|_| ContractError::InvalidInitialLiquidityAmount(min_lp_token_amount))?;
+        let min_lp_token_amount = MINIMUM_LIQUIDITY_AMOUNT * Uint128::from(pools.len() as u128);
+        let share = Uint128::try_from(invariant.compute_d(&deposits).unwrap())
+            .unwrap()
+            .checked_sub(min_lp_token_amount)
+            .map_err(|_| ContractError::InvalidInitialLiquidityAmount(min_lp_token_amount))?;
 
         messages.append(&mut mint_lp_token_msg(
             liquidity_token.clone(),
@@ -227,12 +237,8 @@ pub fn provide_liquidity(
     } else {
         let amount = invariant
             .compute_mint_amount_for_deposit(
-                deposits[0],
-                deposits[1],
-                deposits[2],
-                pools[0].amount,
-                pools[1].amount,
-                pools[2].amount,
+                &deposits,
+                &pools.iter().map(|pool| pool.amount).collect::<Vec<_>>(),
                 total_share,
             )
             .unwrap();
@@ -262,24 +268,45 @@ pub fn provide_liquidity(
         ("receiver", receiver.as_str()),
         (
             "assets",
-            &format!("{}, {}, {}", assets[0], assets[1], assets[2]),
+            &assets
+                .iter()
+                .map(|asset| asset.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
         ),
         ("share", &share.to_string()),
     ]))
 }
 
 /// Withdraws the liquidity. The user burns the LP tokens in exchange for the tokens provided, including
-/// the swap fees accrued by its share of the pool.
+/// the swap fees accrued by its share of the pool. If a `target_asset` is given, the withdrawal is
+/// paid out in that asset only, computed on the stableswap invariant as Curve's
+/// `remove_liquidity_one_coin` does.
 pub fn withdraw_liquidity(
     deps: DepsMut,
     env: Env,
     sender: Addr,
     amount: Uint128,
+    target_asset: Option<AssetInfo>,
+    min_receive: Option<Uint128>,
 ) -> Result<Response, ContractError> {
     let trio_info: TrioInfoRaw = TRIO_INFO.load(deps.storage)?;
+    let collected_protocol_fees = COLLECTED_PROTOCOL_FEES.load(deps.storage)?;
 
-    let pool_assets: [Asset; 3] =
-        trio_info.query_pools(&deps.querier, deps.api, env.contract.address.clone())?;
+    // subtract the protocol fees from the pool assets
+    let pool_assets: Vec<Asset> = trio_info
+        .query_pools(&deps.querier, deps.api, env.contract.address.clone())?
+        .into_iter()
+        .map(|mut pool_asset| {
+            let protocol_fee = get_protocol_fee_for_asset(
+                collected_protocol_fees.clone(),
+                pool_asset.clone().get_id(),
+            );
+            pool_asset.amount = pool_asset.amount.checked_sub(protocol_fee)?;
+
+            Ok(pool_asset)
+        })
+        .collect::<Result<Vec<_>, OverflowError>>()?;
 
     let liquidity_token = match trio_info.liquidity_token {
         AssetInfoRaw::Token { contract_addr } => {
@@ -290,53 +317,293 @@ pub fn withdraw_liquidity(
 
     let total_share = get_total_share(&deps.as_ref(), liquidity_token.clone())?;
 
-    let collected_protocol_fees = COLLECTED_PROTOCOL_FEES.load(deps.storage)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let refund_assets = if let Some(target_asset) = target_asset {
+        let config = CONFIG.load(deps.storage)?;
+        // check if the swap feature is enabled
+        if !config.feature_toggle.swaps_enabled {
+            return Err(ContractError::OperationDisabled("swap".to_string()));
+        }
 
-    let share_ratio: Decimal = Decimal::from_ratio(amount, total_share);
+        let target_index = pool_assets
+            .iter()
+            .position(|pool_asset| pool_asset.info.equal(&target_asset))
+            .ok_or(ContractError::AssetMismatch {})?;
+        let other_pools: Vec<Uint128> = pool_assets
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != target_index)
+            .map(|(_, pool_asset)| pool_asset.amount)
+            .collect();
 
-    let refund_assets: Result<Vec<Asset>, OverflowError> = pool_assets
-        .iter()
-        .map(|pool_asset| {
-            let protocol_fee = get_protocol_fee_for_asset(
-                collected_protocol_fees.clone(),
-                pool_asset.clone().get_id(),
-            );
+        let invariant = StableSwap::new(
+            config.initial_amp,
+            config.future_amp,
+            env.block.height,
+            config.initial_amp_block,
+            config.future_amp_block,
+        );
+
+        let pool_fees = config.pool_fees;
+        let total_fee = pool_fees
+            .swap_fee
+            .share
+            .checked_add(pool_fees.protocol_fee.share)?
+            .checked_add(pool_fees.burn_fee.share)?;
+
+        let (refund_amount, fee_amount) = invariant
+            .compute_withdraw_one(
+                amount,
+                total_share,
+                pool_assets[target_index].amount,
+                &other_pools,
+                total_fee,
+            )
+            .ok_or(ContractError::LiquidityWithdrawalComputation {})?;
+
+        if let Some(min_receive) = min_receive {
+            if refund_amount < min_receive {
+                return Err(ContractError::MinimumReceiveAssertion {
+                    minimum_receive: min_receive,
+                    amount: refund_amount,
+                });
+            }
+        }
+
+        // the fee is split among the swap, protocol and burn fees. The swap fee stays in the pool
+        if !total_fee.is_zero() {
+            let protocol_fee_amount = fee_amount
+                .multiply_ratio(pool_fees.protocol_fee.share.atomics(), total_fee.atomics());
+            let burn_fee_amount =
+                fee_amount.multiply_ratio(pool_fees.burn_fee.share.atomics(), total_fee.atomics());
+
+            if !burn_fee_amount.is_zero() {
+                let burn_asset = Asset {
+                    info: target_asset.clone(),
+                    amount: burn_fee_amount,
+                };
+
+                store_fee(
+                    deps.storage,
+                    burn_asset.amount,
+                    burn_asset.clone().get_id(),
+                    ALL_TIME_BURNED_FEES,
+                )?;
+
+                messages.push(burn_asset.into_burn_msg()?);
+            }
+
+            let protocol_fee_asset = Asset {
+                info: target_asset.clone(),
+                amount: protocol_fee_amount,
+            };
+            store_fee(
+                deps.storage,
+                protocol_fee_amount,
+                protocol_fee_asset.clone().get_id(),
+                COLLECTED_PROTOCOL_FEES,
+            )?;
+            store_fee(
+                deps.storage,
+                protocol_fee_amount,
+                protocol_fee_asset.get_id(),
+                ALL_TIME_COLLECTED_PROTOCOL_FEES,
+            )?;
+        }
 
-            // subtract the protocol_fee from the amount of the pool_asset
-            let refund_amount = pool_asset.amount.checked_sub(protocol_fee)?;
-            Ok(Asset {
+        vec![Asset {
+            info: target_asset,
+            amount: refund_amount,
+        }]
+    } else {
+        let share_ratio: Decimal = Decimal::from_ratio(amount, total_share);
+
+        pool_assets
+            .iter()
+            .map(|pool_asset| Asset {
                 info: pool_asset.info.clone(),
-                amount: refund_amount * share_ratio,
+                amount: pool_asset.amount * share_ratio,
             })
-        })
-        .collect();
+            .collect()
+    };
 
-    let refund_assets = refund_assets?;
+    for refund_asset in refund_assets.iter() {
+        messages.push(refund_asset.clone().into_msg(sender.clone())?);
+    }
 
-    let burn_lp_token_msg =
-        burn_lp_token_msg(liquidity_token, env.contract.address.to_string(), amount)?;
+    // burn liquidity token
+    messages.push(burn_lp_token_msg(
+        liquidity_token,
+        env.contract.address.to_string(),
+        amount,
+    )?);
 
     // update pool info
-    Ok(Response::new()
-        .add_messages(vec![
-            refund_assets[0].clone().into_msg(sender.clone())?,
-            refund_assets[1].clone().into_msg(sender.clone())?,
-            refund_assets[2].clone().into_msg(sender.clone())?,
-            // burn liquidity token
-            burn_lp_token_msg,
-        ])
-        .add_attributes(vec![
-            ("action", "withdraw_liquidity"),
-            ("sender", sender.as_str()),
-            ("withdrawn_share", &amount.to_string()),
-            (
-                "refund_assets",
-                &format!(
-                    "{}, {}, {}",
-                    refund_assets[0], refund_assets[1], refund_assets[2]
-                ),
-            ),
-        ]))
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "withdraw_liquidity"),
+        ("sender", sender.as_str()),
+        ("withdrawn_share", &amount.to_string()),
+        (
+            "refund_assets",
+            &refund_assets
+                .iter()
+                .map(|asset| asset.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+    ]))
+}
+
+/// Withdraws exactly the given assets from the pool, burning the LP tokens needed to cover them and
+/// the imbalance fee. The cw20 LP is burned from the sender's allowance, while the token factory LP
+/// is sent along with the message.
+pub fn withdraw_imbalance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    assets: Vec<Asset>,
+    max_burn_amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    // an imbalanced withdrawal implies swapping, so both features must be enabled
+    if !config.feature_toggle.withdrawals_enabled {
+        return Err(ContractError::OperationDisabled(
+            "withdraw_liquidity".to_string(),
+        ));
+    }
+    if !config.feature_toggle.swaps_enabled {
+        return Err(ContractError::OperationDisabled("swap".to_string()));
+    }
+
+    let trio_info: TrioInfoRaw = TRIO_INFO.load(deps.storage)?;
+    let collected_protocol_fees = COLLECTED_PROTOCOL_FEES.load(deps.storage)?;
+
+    // subtract the protocol fees from the pool assets
+    let pools: Vec<Asset> = trio_info
+        .query_pools(&deps.querier, deps.api, env.contract.address.clone())?
+        .into_iter()
+        .map(|mut pool| {
+            let protocol_fee =
+                get_protocol_fee_for_asset(collected_protocol_fees.clone(), pool.clone().get_id());
+            pool.amount = pool.amount.checked_sub(protocol_fee)?;
+
+            Ok(pool)
+        })
+        .collect::<Result<Vec<_>, OverflowError>>()?;
+
+    // the LP tokens sent along, if any, cap the amount to burn
+    let (liquidity_token, max_burn_amount) = match trio_info.liquidity_token {
+        AssetInfoRaw::Token { contract_addr } => (
+            deps.api.addr_humanize(&contract_addr)?.to_string(),
+            max_burn_amount,
+        ),
+        AssetInfoRaw::NativeToken { denom } => {
+            if info.funds.len() != 1 || info.funds[0].denom != denom {
+                return Err(ContractError::AssetMismatch {});
+            }
+            let sent_amount = info.funds[0].amount;
+            (denom, max_burn_amount.min(sent_amount))
+        }
+    };
+
+    let total_share = get_total_share(&deps.as_ref(), liquidity_token.clone())?;
+
+    let invariant = StableSwap::new(
+        config.initial_amp,
+        config.future_amp,
+        env.block.height,
+        config.initial_amp_block,
+        config.future_amp_block,
+    );
+    let computation = helpers::compute_withdraw_imbalance(
+        &pools,
+        &assets,
+        total_share,
+        config.pool_fees,
+        invariant,
+    )?;
+
+    if computation.burn_amount > max_burn_amount {
+        return Err(ContractError::MaxBurnAssertion {
+            max_burn_amount,
+            burn_amount: computation.burn_amount,
+        });
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for withdraw_asset in computation.withdraw_assets.iter() {
+        if !withdraw_asset.amount.is_zero() {
+            messages.push(withdraw_asset.clone().into_msg(info.sender.clone())?);
+        }
+    }
+
+    // the swap fees stay in the pool, the protocol fees are collected and the burn fees burned
+    for protocol_fee_asset in computation.protocol_fee_amounts {
+        store_fee(
+            deps.storage,
+            protocol_fee_asset.amount,
+            protocol_fee_asset.clone().get_id(),
+            COLLECTED_PROTOCOL_FEES,
+        )?;
+        store_fee(
+            deps.storage,
+            protocol_fee_asset.amount,
+            protocol_fee_asset.get_id(),
+            ALL_TIME_COLLECTED_PROTOCOL_FEES,
+        )?;
+    }
+    for burn_fee_asset in computation.burn_fee_amounts {
+        if !burn_fee_asset.amount.is_zero() {
+            store_fee(
+                deps.storage,
+                burn_fee_asset.amount,
+                burn_fee_asset.clone().get_id(),
+                ALL_TIME_BURNED_FEES,
+            )?;
+            messages.push(burn_fee_asset.into_burn_msg()?);
+        }
+    }
+
+    // burn the liquidity token, refunding the unused token factory LP
+    if is_factory_token(liquidity_token.as_str()) {
+        messages.push(burn_lp_token_msg(
+            liquidity_token.clone(),
+            env.contract.address.to_string(),
+            computation.burn_amount,
+        )?);
+
+        let refund_amount = info.funds[0].amount.checked_sub(computation.burn_amount)?;
+        if !refund_amount.is_zero() {
+            messages.push(CosmosMsg::Bank(cosmwasm_std::BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: coins(refund_amount.u128(), liquidity_token.as_str()),
+            }));
+        }
+    } else {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: liquidity_token,
+            msg: to_binary(&Cw20ExecuteMsg::BurnFrom {
+                owner: info.sender.to_string(),
+                amount: computation.burn_amount,
+            })?,
+            funds: vec![],
+        }));
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "withdraw_imbalance"),
+        ("sender", info.sender.as_str()),
+        ("withdrawn_share", &computation.burn_amount.to_string()),
+        (
+            "withdrawn_assets",
+            &computation
+                .withdraw_assets
+                .iter()
+                .map(|asset| asset.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+    ]))
 }
 
 /// Swaps tokens. The user must IncreaseAllowance on the token if it is a cw20 token they want to swa
@@ -377,69 +644,18 @@ pub fn swap(
         })
         .collect::<StdResult<Vec<_>>>()?;
 
-    let ask_pool: Asset;
-    let offer_pool: Asset;
-    let unswapped_pool: Asset;
-    let ask_decimal: u8;
-    let offer_decimal: u8;
-
-    if ask_asset.equal(&pools[0].info) {
-        if offer_asset.info.equal(&pools[1].info) {
-            ask_pool = pools[0].clone();
-            offer_pool = pools[1].clone();
-            unswapped_pool = pools[2].clone();
-
-            ask_decimal = trio_info.asset_decimals[0];
-            offer_decimal = trio_info.asset_decimals[1];
-        } else if offer_asset.info.equal(&pools[2].info) {
-            ask_pool = pools[0].clone();
-            offer_pool = pools[2].clone();
-            unswapped_pool = pools[1].clone();
-
-            ask_decimal = trio_info.asset_decimals[0];
-            offer_decimal = trio_info.asset_decimals[2];
-        } else {
-            return Err(ContractError::AssetMismatch {});
-        }
-    } else if ask_asset.equal(&pools[1].info) {
-        if offer_asset.info.equal(&pools[0].info) {
-            ask_pool = pools[1].clone();
-            offer_pool = pools[0].clone();
-            unswapped_pool = pools[2].clone();
-
-            ask_decimal = trio_info.asset_decimals[1];
-            offer_decimal = trio_info.asset_decimals[0];
-        } else if offer_asset.info.equal(&pools[2].info) {
-            ask_pool = pools[1].clone();
-            offer_pool = pools[2].clone();
-            unswapped_pool = pools[0].clone();
-
-            ask_decimal = trio_info.asset_decimals[1];
-            offer_decimal = trio_info.asset_decimals[2];
-        } else {
-            return Err(ContractError::AssetMismatch {});
-        }
-    } else if ask_asset.equal(&pools[2].info) {
-        if offer_asset.info.equal(&pools[0].info) {
-            ask_pool = pools[2].clone();
-            offer_pool = pools[0].clone();
-            unswapped_pool = pools[1].clone();
-
-            ask_decimal = trio_info.asset_decimals[2];
-            offer_decimal = trio_info.asset_decimals[0];
-        } else if offer_asset.info.equal(&pools[1].info) {
-            ask_pool = pools[2].clone();
-            offer_pool = pools[1].clone();
-            unswapped_pool = pools[0].clone();
-
-            ask_decimal = trio_info.asset_decimals[2];
-            offer_decimal = trio_info.asset_decimals[1];
-        } else {
-            return Err(ContractError::AssetMismatch {});
-        }
-    } else {
-        return Err(ContractError::AssetMismatch {});
-    }
+    let helpers::SwapPools {
+        offer_pool,
+        ask_pool,
+        unswapped_pools,
+        offer_decimal,
+        ask_decimal,
+    } = helpers::get_swap_pools(
+        &pools,
+        &trio_info.asset_decimals,
+        &offer_asset.info,
+        &ask_asset,
+    )?;
 
     let offer_amount = offer_asset.amount;
     let config = CONFIG.load(deps.storage)?;
@@ -454,7 +670,7 @@ pub fn swap(
     let swap_computation = helpers::compute_swap(
         offer_pool.amount,
         ask_pool.amount,
-        unswapped_pool.amount,
+        &unswapped_pools,
         offer_amount,
         config.pool_fees,
         invariant,
@@ -541,6 +757,190 @@ pub fn swap(
     ]))
 }
 
+/// Swaps the offer asset for the exact amount of the ask asset. The offer amount is computed with
+/// [helpers::compute_offer_amount]. Native offer assets are taken out of the funds sent, refunding
+/// the surplus, while cw20 offer assets are pulled from the allowance given to the pool.
+pub fn swap_exact_out(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    offer_asset: AssetInfo,
+    ask_asset: Asset,
+    max_offer_amount: Uint128,
+    to: Option<Addr>,
+) -> Result<Response, ContractError> {
+    if ask_asset.amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let trio_info: TrioInfoRaw = TRIO_INFO.load(deps.storage)?;
+    let collected_protocol_fees = COLLECTED_PROTOCOL_FEES.load(deps.storage)?;
+
+    // the native funds sent are already part of the pool balance, and cap the offer amount
+    let offer_amount_sent = match &offer_asset {
+        AssetInfo::NativeToken { denom } => info
+            .funds
+            .iter()
+            .find(|coin| &coin.denom == denom)
+            .map(|coin| coin.amount)
+            .unwrap_or_default(),
+        AssetInfo::Token { .. } => Uint128::zero(),
+    };
+    let max_offer_amount = if offer_asset.is_native_token() {
+        max_offer_amount.min(offer_amount_sent)
+    } else {
+        max_offer_amount
+    };
+
+    // To calculate pool amounts properly we should subtract user deposit and the protocol fees from the pool
+    let pools = trio_info
+        .query_pools(&deps.querier, deps.api, env.contract.address.clone())?
+        .into_iter()
+        .map(|mut pool| {
+            // subtract the protocol fee from the pool
+            let protocol_fee =
+                get_protocol_fee_for_asset(collected_protocol_fees.clone(), pool.clone().get_id());
+            pool.amount = pool.amount.checked_sub(protocol_fee)?;
+
+            if pool.info.equal(&offer_asset) {
+                pool.amount = pool.amount.checked_sub(offer_amount_sent)?
+            }
+
+            Ok(pool)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let helpers::SwapPools {
+        offer_pool,
+        ask_pool,
+        unswapped_pools,
+        ..
+    } = helpers::get_swap_pools(
+        &pools,
+        &trio_info.asset_decimals,
+        &offer_asset,
+        &ask_asset.info,
+    )?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let invariant = StableSwap::new(
+        config.initial_amp,
+        config.future_amp,
+        env.block.height,
+        config.initial_amp_block,
+        config.future_amp_block,
+    );
+
+    let offer_amount_computation = helpers::compute_offer_amount(
+        offer_pool.amount,
+        ask_pool.amount,
+        &unswapped_pools,
+        ask_asset.amount,
+        config.pool_fees,
+        invariant,
+    )?;
+    let offer_amount = offer_amount_computation.offer_amount;
+
+    if offer_amount > max_offer_amount {
+        return Err(ContractError::MaxOfferAssertion {
+            max_offer_amount,
+            offer_amount,
+        });
+    }
+
+    let receiver = to.unwrap_or_else(|| info.sender.clone());
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    match &offer_pool.info {
+        AssetInfo::NativeToken { denom } => {
+            let refund_amount = offer_amount_sent.checked_sub(offer_amount)?;
+            if !refund_amount.is_zero() {
+                messages.push(CosmosMsg::Bank(BankMsg::Send {
+                    to_address: info.sender.to_string(),
+                    amount: coins(refund_amount.u128(), denom),
+                }));
+            }
+        }
+        AssetInfo::Token { contract_addr } => {
+            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: info.sender.to_string(),
+                    recipient: env.contract.address.to_string(),
+                    amount: offer_amount,
+                })?,
+                funds: vec![],
+            }));
+        }
+    }
+
+    messages.push(
+        Asset {
+            info: ask_pool.info.clone(),
+            amount: ask_asset.amount,
+        }
+        .into_msg(receiver.clone())?,
+    );
+
+    // burn ask_asset from the pool
+    if !offer_amount_computation.burn_fee_amount.is_zero() {
+        let burn_asset = Asset {
+            info: ask_pool.info.clone(),
+            amount: offer_amount_computation.burn_fee_amount,
+        };
+
+        store_fee(
+            deps.storage,
+            burn_asset.amount,
+            burn_asset.clone().get_id(),
+            ALL_TIME_BURNED_FEES,
+        )?;
+
+        messages.push(burn_asset.into_burn_msg()?);
+    }
+
+    // Store the protocol fees generated by this swap. The protocol fees are collected on the ask
+    // asset as shown in [compute_offer_amount]
+    store_fee(
+        deps.storage,
+        offer_amount_computation.protocol_fee_amount,
+        ask_pool.clone().get_id(),
+        COLLECTED_PROTOCOL_FEES,
+    )?;
+    store_fee(
+        deps.storage,
+        offer_amount_computation.protocol_fee_amount,
+        ask_pool.clone().get_id(),
+        ALL_TIME_COLLECTED_PROTOCOL_FEES,
+    )?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "swap_exact_out"),
+        ("sender", info.sender.as_str()),
+        ("receiver", receiver.as_str()),
+        ("offer_asset", &offer_pool.info.to_string()),
+        ("ask_asset", &ask_pool.info.to_string()),
+        ("offer_amount", &offer_amount.to_string()),
+        ("return_amount", &ask_asset.amount.to_string()),
+        (
+            "spread_amount",
+            &offer_amount_computation.spread_amount.to_string(),
+        ),
+        (
+            "swap_fee_amount",
+            &offer_amount_computation.swap_fee_amount.to_string(),
+        ),
+        (
+            "protocol_fee_amount",
+            &offer_amount_computation.protocol_fee_amount.to_string(),
+        ),
+        (
+            "burn_fee_amount",
+            &offer_amount_computation.burn_fee_amount.to_string(),
+        ),
+    ]))
+}
+
 /// Updates the [Config] of the contract. Only the owner of the contract can do this.
 #[allow(clippy::too_many_arguments)]
 pub fn update_config(
@@ -644,20 +1044,13 @@ pub fn collect_protocol_fees(deps: DepsMut) -> Result<Response, ContractError> {
     // reset the collected protocol fees
     COLLECTED_PROTOCOL_FEES.save(
         deps.storage,
-        &vec![
-            Asset {
-                info: protocol_fees[0].clone().info,
-                amount: Uint128::zero(),
-            },
-            Asset {
-                info: protocol_fees[1].clone().info,
-                amount: Uint128::zero(),
-            },
-            Asset {
-                info: protocol_fees[2].clone().info,
+        &protocol_fees
+            .iter()
+            .map(|protocol_fee| Asset {
+                info: protocol_fee.info.clone(),
                 amount: Uint128::zero(),
-            },
-        ],
+            })
+            .collect(),
     )?;
 
     let mut messages: Vec<CosmosMsg> = Vec::new();
@@ -54,29 +54,29 @@ pub fn instantiate(
         return Err(ContractError::TokenFactoryAssetBurnDisabled {});
     }
 
+    helpers::validate_asset_infos(&msg.asset_infos, &msg.asset_decimals)?;
+
     let trio_info: &TrioInfoRaw = &TrioInfoRaw {
         contract_addr: deps.api.addr_canonicalize(env.contract.address.as_str())?,
         liquidity_token: AssetInfoRaw::NativeToken {
             denom: "".to_string(),
         },
-        asset_infos: [
-            msg.asset_infos[0].to_raw(deps.api)?,
-            msg.asset_infos[1].to_raw(deps.api)?,
-            msg.asset_infos[2].to_raw(deps.api)?,
-        ],
+        asset_infos: msg
+            .asset_infos
+            .iter()
+            .map(|asset_info| asset_info.to_raw(deps.api))
+            .collect::<StdResult<Vec<AssetInfoRaw>>>()?,
         asset_decimals: msg.asset_decimals,
     };
 
     TRIO_INFO.save(deps.storage, trio_info)?;
 
-    let asset_info_0 = trio_info.asset_infos[0].to_normal(deps.api)?;
-    let asset_info_1 = trio_info.asset_infos[1].to_normal(deps.api)?;
-    let asset_info_2 = trio_info.asset_infos[2].to_normal(deps.api)?;
-
-    let asset0_label = asset_info_0.clone().get_label(&deps.as_ref())?;
-    let asset1_label = asset_info_1.clone().get_label(&deps.as_ref())?;
-    let asset2_label = asset_info_2.clone().get_label(&deps.as_ref())?;
-    let lp_token_name = format!("{asset0_label}-{asset1_label}-{asset2_label}-LP");
+    let asset_labels = msg
+        .asset_infos
+        .iter()
+        .map(|asset_info| asset_info.clone().get_label(&deps.as_ref()))
+        .collect::<StdResult<Vec<String>>>()?;
+    let lp_token_name = format!("{}-LP", asset_labels.join("-"));
 
     // check the fees are valid
     msg.pool_fees.is_valid()?;
@@ -109,27 +109,13 @@ pub fn instantiate(
     CONFIG.save(deps.storage, &config)?;
 
     // Instantiate the collected protocol fees
+    helpers::instantiate_fees(deps.storage, &msg.asset_infos, COLLECTED_PROTOCOL_FEES)?;
     helpers::instantiate_fees(
         deps.storage,
-        asset_info_0.clone(),
-        asset_info_1.clone(),
-        asset_info_2.clone(),
-        COLLECTED_PROTOCOL_FEES,
-    )?;
-    helpers::instantiate_fees(
-        deps.storage,
-        asset_info_0.clone(),
-        asset_info_1.clone(),
-        asset_info_2.clone(),
+        &msg.asset_infos,
         ALL_TIME_COLLECTED_PROTOCOL_FEES,
     )?;
-    helpers::instantiate_fees(
-        deps.storage,
-        asset_info_0,
-        asset_info_1,
-        asset_info_2,
-        ALL_TIME_BURNED_FEES,
-    )?;
+    helpers::instantiate_fees(deps.storage, &msg.asset_infos, ALL_TIME_BURNED_FEES)?;
 
     if msg.token_factory_lp {
         // create native LP token
@@ -188,8 +174,19 @@ pub fn execute(
             assets,
             slippage_tolerance,
             receiver,
-        } => commands::provide_liquidity(deps, env, info, assets, slippage_tolerance, receiver),
-        ExecuteMsg::WithdrawLiquidity {} => {
+            deadline,
+        } => {
+            helpers::assert_deadline(env.block.time, deadline)?;
+
+            commands::provide_liquidity(deps, env, info, assets, slippage_tolerance, receiver)
+        }
+        ExecuteMsg::WithdrawLiquidity {
+            target_asset,
+            min_receive,
+            deadline,
+        } => {
+            helpers::assert_deadline(env.block.time, deadline)?;
+
             // validate that the asset sent is the token factory LP token
             let trio_info = TRIO_INFO.load(deps.storage)?;
             let lp_token_denom = match trio_info.liquidity_token {
@@ -201,7 +198,23 @@ pub fn execute(
                 return Err(ContractError::AssetMismatch {});
             }
 
-            commands::withdraw_liquidity(deps, env, info.sender, info.funds[0].amount)
+            commands::withdraw_liquidity(
+                deps,
+                env,
+                info.sender,
+                info.funds[0].amount,
+                target_asset,
+                min_receive,
+            )
+        }
+        ExecuteMsg::WithdrawImbalance {
+            assets,
+            max_burn_amount,
+            deadline,
+        } => {
+            helpers::assert_deadline(env.block.time, deadline)?;
+
+            commands::withdraw_imbalance(deps, env, info, assets, max_burn_amount)
         }
         ExecuteMsg::Swap {
             offer_asset,
@@ -209,7 +222,10 @@ pub fn execute(
             belief_price,
             max_spread,
             to,
+            deadline,
         } => {
+            helpers::assert_deadline(env.block.time, deadline)?;
+
             // check if the swap feature is enabled
             let feature_toggle: FeatureToggle = CONFIG.load(deps.storage)?.feature_toggle;
             if !feature_toggle.swaps_enabled {
@@ -238,6 +254,37 @@ pub fn execute(
                 to_addr,
             )
         }
+        ExecuteMsg::SwapExactOut {
+            offer_asset,
+            ask_asset,
+            max_offer_amount,
+            to,
+            deadline,
+        } => {
+            helpers::assert_deadline(env.block.time, deadline)?;
+
+            // check if the swap feature is enabled
+            let feature_toggle: FeatureToggle = CONFIG.load(deps.storage)?.feature_toggle;
+            if !feature_toggle.swaps_enabled {
+                return Err(ContractError::OperationDisabled("swap".to_string()));
+            }
+
+            let to_addr = if let Some(to_addr) = to {
+                Some(deps.api.addr_validate(&to_addr)?)
+            } else {
+                None
+            };
+
+            commands::swap_exact_out(
+                deps,
+                env,
+                info,
+                offer_asset,
+                ask_asset,
+                max_offer_amount,
+                to_addr,
+            )
+        }
         ExecuteMsg::UpdateConfig {
             owner,
             fee_collector_addr,
@@ -302,6 +349,22 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
             offer_asset,
             env.block.height,
         )?)?),
+        QueryMsg::SimulateWithdrawImbalance { assets } => Ok(to_binary(
+            &queries::query_withdraw_imbalance_simulation(deps, assets, env.block.height)?,
+        )?),
+        QueryMsg::VirtualPrice {} => Ok(to_binary(&queries::query_virtual_price(
+            deps,
+            env.block.height,
+        )?)?),
+        QueryMsg::SpotPrice {
+            offer_asset_info,
+            ask_asset_info,
+        } => Ok(to_binary(&queries::query_spot_price(
+            deps,
+            offer_asset_info,
+            ask_asset_info,
+            env.block.height,
+        )?)?),
         QueryMsg::Config {} => Ok(to_binary(&queries::query_config(deps)?)?),
         QueryMsg::ProtocolFees { asset_id, all_time } => Ok(to_binary(&queries::query_fees(
             deps,
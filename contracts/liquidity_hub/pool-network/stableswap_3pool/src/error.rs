@@ -1,4 +1,4 @@
-use cosmwasm_std::{OverflowError, StdError, Uint128};
+use cosmwasm_std::{OverflowError, StdError, Timestamp, Uint128};
 use semver::Version;
 use thiserror::Error;
 
@@ -48,6 +48,46 @@ pub enum ContractError {
 
     #[error("Burn fee is not allowed when using factory tokens")]
     TokenFactoryAssetBurnDisabled {},
+
+    #[error("Failed to compute the single asset withdrawal with the given share")]
+    LiquidityWithdrawalComputation {},
+
+    #[error("A pool must hold between {min} and {max} assets, with their decimals")]
+    InvalidNumberOfAssets { min: usize, max: usize },
+
+    #[error("The pool can't hold the same asset more than once")]
+    SameAsset {},
+
+    #[error("Assertion failed; minimum receive amount: {minimum_receive}, amount: {amount}")]
+    MinimumReceiveAssertion {
+        minimum_receive: Uint128,
+        amount: Uint128,
+    },
+
+    #[error(
+        "Assertion failed; maximum burn amount: {max_burn_amount}, burn amount: {burn_amount}"
+    )]
+    MaxBurnAssertion {
+        max_burn_amount: Uint128,
+        burn_amount: Uint128,
+    },
+
+    #[error("Failed to compute the invariant of the pool")]
+    InvariantComputation {},
+
+    #[error(
+        "Assertion failed; maximum offer amount: {max_offer_amount}, offer amount: {offer_amount}"
+    )]
+    MaxOfferAssertion {
+        max_offer_amount: Uint128,
+        offer_amount: Uint128,
+    },
+
+    #[error("Deadline exceeded; deadline: {deadline}, current time: {current_time}")]
+    DeadlineExceeded {
+        deadline: Timestamp,
+        current_time: Timestamp,
+    },
 }
 
 impl From<semver::Error> for ContractError {
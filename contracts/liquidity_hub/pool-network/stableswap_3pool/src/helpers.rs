@@ -1,12 +1,14 @@
 use std::cmp::Ordering;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Decimal, Decimal256, Deps, StdError, StdResult, Storage, Uint128, Uint256};
+use cosmwasm_std::{
+    Decimal, Decimal256, Deps, StdError, StdResult, Storage, Timestamp, Uint128, Uint256,
+};
 use cw_storage_plus::Item;
 
 use white_whale::pool_network::asset::{is_factory_token, Asset, AssetInfo};
 use white_whale::pool_network::querier::query_token_info;
-use white_whale::pool_network::trio::PoolFee;
+use white_whale::pool_network::trio::{PoolFee, MAX_TRIO_ASSETS, MIN_TRIO_ASSETS};
 
 use crate::error::ContractError;
 use crate::stableswap_math::curve::StableSwap;
@@ -14,13 +16,13 @@ use crate::stableswap_math::curve::StableSwap;
 pub fn compute_swap(
     offer_pool: Uint128,
     ask_pool: Uint128,
-    unswapped_pool: Uint128,
+    unswapped_pools: &[Uint128],
     offer_amount: Uint128,
     pool_fees: PoolFee,
     invariant: StableSwap,
 ) -> StdResult<SwapComputation> {
     let result = invariant
-        .swap_to(offer_amount, offer_pool, ask_pool, unswapped_pool)
+        .swap_to(offer_amount, offer_pool, ask_pool, unswapped_pools)
         .unwrap();
 
     let return_amount: Uint256 = result.amount_swapped.into();
@@ -59,25 +61,41 @@ pub struct SwapComputation {
 pub fn compute_offer_amount(
     offer_pool: Uint128,
     ask_pool: Uint128,
-    unswapped_pool: Uint128,
+    unswapped_pools: &[Uint128],
     ask_amount: Uint128,
     pool_fees: PoolFee,
     invariant: StableSwap,
 ) -> StdResult<OfferAmountComputation> {
-    let fees = pool_fees.swap_fee.share + pool_fees.protocol_fee.share + pool_fees.burn_fee.share;
-    let one_minus_commission = Decimal::one() - fees;
-    let inv_one_minus_commission = Decimal::one() / one_minus_commission;
-
-    let before_commission_deduction: Uint128 = ask_amount * inv_one_minus_commission;
+    let fees = pool_fees
+        .swap_fee
+        .share
+        .checked_add(pool_fees.protocol_fee.share)?
+        .checked_add(pool_fees.burn_fee.share)?;
+    let one_minus_commission = Decimal::one().checked_sub(fees)?;
+
+    // the amounts are rounded up in favour of the pool, as the ask amount is paid out in full
+    // ask_amount / (1 - fees)
+    let before_commission_deduction = Uint128::try_from(checked_div_ceil(
+        ask_amount.full_mul(Decimal::one().atomics()),
+        one_minus_commission.atomics().into(),
+    )?)?;
+    // the ask pool can't be drained by the swap
+    if before_commission_deduction >= ask_pool {
+        return Err(StdError::generic_err(
+            "The ask amount can't be taken out of the pool",
+        ));
+    }
 
+    // the invariant is only approximated within a unit, so the offer amount is rounded up too
     let offer_amount = invariant
         .reverse_sim(
             before_commission_deduction,
             offer_pool,
             ask_pool,
-            unswapped_pool,
+            unswapped_pools,
         )
-        .unwrap();
+        .ok_or_else(|| StdError::generic_err("Failed to compute the offer amount"))?
+        .checked_add(Uint128::one())?;
 
     let spread_amount = if before_commission_deduction > offer_amount {
         before_commission_deduction - offer_amount
@@ -104,6 +122,16 @@ pub fn compute_offer_amount(
     })
 }
 
+/// Divides the `numerator` by the `denominator`, rounding up
+fn checked_div_ceil(numerator: Uint256, denominator: Uint256) -> StdResult<Uint256> {
+    let quotient = numerator.checked_div(denominator)?;
+    if quotient.checked_mul(denominator)? == numerator {
+        Ok(quotient)
+    } else {
+        Ok(quotient.checked_add(Uint256::one())?)
+    }
+}
+
 /// Represents the offer amount computation values
 #[cw_serde]
 pub struct OfferAmountComputation {
@@ -114,6 +142,142 @@ pub struct OfferAmountComputation {
     pub burn_fee_amount: Uint128,
 }
 
+/// Represents the pools taking part in a swap
+pub struct SwapPools {
+    pub offer_pool: Asset,
+    pub ask_pool: Asset,
+    /// The amounts of the pools not involved in the swap
+    pub unswapped_pools: Vec<Uint128>,
+    pub offer_decimal: u8,
+    pub ask_decimal: u8,
+}
+
+/// Picks the offer and ask pools out of the given pools, along with the pools not involved in the swap
+pub fn get_swap_pools(
+    pools: &[Asset],
+    asset_decimals: &[u8],
+    offer_asset_info: &AssetInfo,
+    ask_asset_info: &AssetInfo,
+) -> Result<SwapPools, ContractError> {
+    let offer_index = pools
+        .iter()
+        .position(|pool| pool.info.equal(offer_asset_info))
+        .ok_or(ContractError::AssetMismatch {})?;
+    let ask_index = pools
+        .iter()
+        .position(|pool| pool.info.equal(ask_asset_info))
+        .ok_or(ContractError::AssetMismatch {})?;
+
+    if offer_index == ask_index {
+        return Err(ContractError::AssetMismatch {});
+    }
+
+    Ok(SwapPools {
+        offer_pool: pools[offer_index].clone(),
+        ask_pool: pools[ask_index].clone(),
+        unswapped_pools: pools
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != offer_index && *i != ask_index)
+            .map(|(_, pool)| pool.amount)
+            .collect(),
+        offer_decimal: asset_decimals[offer_index],
+        ask_decimal: asset_decimals[ask_index],
+    })
+}
+
+/// Represents the imbalanced withdrawal computation values. The assets are in the order of the pools.
+pub struct WithdrawImbalanceComputation {
+    pub burn_amount: Uint128,
+    pub withdraw_assets: Vec<Asset>,
+    pub swap_fee_amounts: Vec<Asset>,
+    pub protocol_fee_amounts: Vec<Asset>,
+    pub burn_fee_amounts: Vec<Asset>,
+}
+
+/// Computes the LP tokens to burn for withdrawing the given assets from the pools. Assets of the
+/// pool not listed in `assets` are not withdrawn.
+pub fn compute_withdraw_imbalance(
+    pools: &[Asset],
+    assets: &[Asset],
+    total_share: Uint128,
+    pool_fees: PoolFee,
+    invariant: StableSwap,
+) -> Result<WithdrawImbalanceComputation, ContractError> {
+    let mut withdraw_amounts: Vec<Option<Uint128>> = vec![None; pools.len()];
+    for asset in assets {
+        let index = pools
+            .iter()
+            .position(|pool| pool.info.equal(&asset.info))
+            .ok_or(ContractError::AssetMismatch {})?;
+        // each asset can only be listed once
+        if withdraw_amounts[index].replace(asset.amount).is_some() {
+            return Err(ContractError::AssetMismatch {});
+        }
+    }
+    let withdraw_amounts: Vec<Uint128> = withdraw_amounts
+        .into_iter()
+        .map(Option::unwrap_or_default)
+        .collect();
+
+    if withdraw_amounts.iter().all(Uint128::is_zero) {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let total_fee = pool_fees
+        .swap_fee
+        .share
+        .checked_add(pool_fees.protocol_fee.share)?
+        .checked_add(pool_fees.burn_fee.share)?;
+
+    let (burn_amount, fee_amounts) = invariant
+        .compute_withdraw_imbalance(
+            &withdraw_amounts,
+            &pools.iter().map(|pool| pool.amount).collect::<Vec<_>>(),
+            total_share,
+            total_fee,
+        )
+        .ok_or(ContractError::LiquidityWithdrawalComputation {})?;
+
+    // the fee is split among the swap, protocol and burn fees
+    let split_fee = |fee_amount: Uint128, share: Decimal| -> Uint128 {
+        if total_fee.is_zero() {
+            Uint128::zero()
+        } else {
+            fee_amount.multiply_ratio(share.atomics(), total_fee.atomics())
+        }
+    };
+
+    let mut computation = WithdrawImbalanceComputation {
+        burn_amount,
+        withdraw_assets: vec![],
+        swap_fee_amounts: vec![],
+        protocol_fee_amounts: vec![],
+        burn_fee_amounts: vec![],
+    };
+    for ((pool, withdraw_amount), fee_amount) in pools.iter().zip(withdraw_amounts).zip(fee_amounts)
+    {
+        let protocol_fee_amount = split_fee(fee_amount, pool_fees.protocol_fee.share);
+        let burn_fee_amount = split_fee(fee_amount, pool_fees.burn_fee.share);
+        let swap_fee_amount = fee_amount
+            .checked_sub(protocol_fee_amount)?
+            .checked_sub(burn_fee_amount)?;
+
+        let asset = |amount: Uint128| Asset {
+            info: pool.info.clone(),
+            amount,
+        };
+        computation.withdraw_assets.push(asset(withdraw_amount));
+        computation.swap_fee_amounts.push(asset(swap_fee_amount));
+        computation
+            .protocol_fee_amounts
+            .push(asset(protocol_fee_amount));
+        computation.burn_fee_amounts.push(asset(burn_fee_amount));
+    }
+
+    Ok(computation)
+}
+
 /// If `belief_price` and `max_spread` both are given,
 /// we compute new spread else we just use terraswap
 /// spread to check `max_spread`
@@ -190,10 +354,27 @@ pub fn assert_max_spread(
     Ok(())
 }
 
+/// Asserts the block time hasn't gone past the `deadline`, if one is given
+pub fn assert_deadline(
+    block_time: Timestamp,
+    deadline: Option<Timestamp>,
+) -> Result<(), ContractError> {
+    if let Some(deadline) = deadline {
+        if block_time > deadline {
+            return Err(ContractError::DeadlineExceeded {
+                deadline,
+                current_time: block_time,
+            });
+        }
+    }
+
+    Ok(())
+}
+
 pub fn assert_slippage_tolerance(
     slippage_tolerance: &Option<Decimal>,
-    deposits: &[Uint128; 3],
-    pools: &[Asset; 3],
+    deposits: &[Uint128],
+    pools: &[Asset],
     amount: Uint128,
     pool_token_supply: Uint128,
 ) -> Result<(), ContractError> {
@@ -203,17 +384,15 @@ pub fn assert_slippage_tolerance(
             return Err(StdError::generic_err("slippage_tolerance cannot bigger than 1").into());
         }
         let one_minus_slippage_tolerance = Decimal256::one() - slippage_tolerance;
-        let deposits: [Uint256; 3] = [deposits[0].into(), deposits[1].into(), deposits[2].into()];
-        let pools: [Uint256; 3] = [
-            pools[0].amount.into(),
-            pools[1].amount.into(),
-            pools[2].amount.into(),
-        ];
-
-        let pools_total = pools[0].checked_add(pools[1])?.checked_add(pools[2])?;
-        let deposits_total = deposits[0]
-            .checked_add(deposits[1])?
-            .checked_add(deposits[2])?;
+
+        let pools_total = pools.iter().try_fold(Uint256::zero(), |total, pool| {
+            total.checked_add(pool.amount.into())
+        })?;
+        let deposits_total = deposits
+            .iter()
+            .try_fold(Uint256::zero(), |total, deposit| {
+                total.checked_add((*deposit).into())
+            })?;
 
         let pool_ratio = Decimal256::from_ratio(pools_total, pool_token_supply);
         let deposit_ratio = Decimal256::from_ratio(deposits_total, amount);
@@ -247,30 +426,48 @@ pub fn get_protocol_fee_for_asset(
 /// Instantiates fees for a given fee_storage_item
 pub fn instantiate_fees(
     storage: &mut dyn Storage,
-    asset_info_0: AssetInfo,
-    asset_info_1: AssetInfo,
-    asset_info_2: AssetInfo,
+    asset_infos: &[AssetInfo],
     fee_storage_item: Item<Vec<Asset>>,
 ) -> StdResult<()> {
     fee_storage_item.save(
         storage,
-        &vec![
-            Asset {
-                info: asset_info_0,
-                amount: Uint128::zero(),
-            },
-            Asset {
-                info: asset_info_1,
+        &asset_infos
+            .iter()
+            .map(|asset_info| Asset {
+                info: asset_info.clone(),
                 amount: Uint128::zero(),
-            },
-            Asset {
-                info: asset_info_2,
-                amount: Uint128::zero(),
-            },
-        ],
+            })
+            .collect(),
     )
 }
 
+/// Validates the assets of a pool, i.e. that there are between [MIN_TRIO_ASSETS] and
+/// [MAX_TRIO_ASSETS] of them, they are not repeated and there's one decimal for each one.
+pub fn validate_asset_infos(
+    asset_infos: &[AssetInfo],
+    asset_decimals: &[u8],
+) -> Result<(), ContractError> {
+    if asset_infos.len() < MIN_TRIO_ASSETS
+        || asset_infos.len() > MAX_TRIO_ASSETS
+        || asset_infos.len() != asset_decimals.len()
+    {
+        return Err(ContractError::InvalidNumberOfAssets {
+            min: MIN_TRIO_ASSETS,
+            max: MAX_TRIO_ASSETS,
+        });
+    }
+
+    if asset_infos
+        .iter()
+        .enumerate()
+        .any(|(i, asset_info)| asset_infos[i + 1..].contains(asset_info))
+    {
+        return Err(ContractError::SameAsset {});
+    }
+
+    Ok(())
+}
+
 /// Gets the total supply of the given liquidity token
 pub fn get_total_share(deps: &Deps, liquidity_token: String) -> StdResult<Uint128> {
     let total_share = if is_factory_token(liquidity_token.as_str()) {
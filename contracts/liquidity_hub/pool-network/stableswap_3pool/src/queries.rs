@@ -1,10 +1,11 @@
-use cosmwasm_std::{Deps, StdResult};
+use cosmwasm_std::{Decimal256, Deps, StdResult};
 use cw_storage_plus::Item;
 
-use white_whale::pool_network::asset::{Asset, AssetInfoRaw, TrioInfo, TrioInfoRaw};
+use white_whale::pool_network::asset::{Asset, AssetInfo, AssetInfoRaw, TrioInfo, TrioInfoRaw};
 use white_whale::pool_network::trio::{
     ConfigResponse, PoolResponse, ProtocolFeesResponse, ReverseSimulationResponse,
-    SimulationResponse,
+    SimulationResponse, SpotPriceResponse, VirtualPriceResponse,
+    WithdrawImbalanceSimulationResponse,
 };
 
 use crate::error::ContractError;
@@ -86,49 +87,17 @@ pub fn query_simulation(
         })
         .collect::<StdResult<Vec<_>>>()?;
 
-    let ask_pool: Asset;
-    let offer_pool: Asset;
-    let unswapped_pool: Asset;
-
-    if ask_asset.info.equal(&pools[0].info) {
-        if offer_asset.info.equal(&pools[1].info) {
-            ask_pool = pools[0].clone();
-            offer_pool = pools[1].clone();
-            unswapped_pool = pools[2].clone();
-        } else if offer_asset.info.equal(&pools[2].info) {
-            ask_pool = pools[0].clone();
-            offer_pool = pools[2].clone();
-            unswapped_pool = pools[1].clone();
-        } else {
-            return Err(ContractError::AssetMismatch {});
-        }
-    } else if ask_asset.info.equal(&pools[1].info) {
-        if offer_asset.info.equal(&pools[0].info) {
-            ask_pool = pools[1].clone();
-            offer_pool = pools[0].clone();
-            unswapped_pool = pools[2].clone();
-        } else if offer_asset.info.equal(&pools[2].info) {
-            ask_pool = pools[1].clone();
-            offer_pool = pools[2].clone();
-            unswapped_pool = pools[0].clone();
-        } else {
-            return Err(ContractError::AssetMismatch {});
-        }
-    } else if ask_asset.info.equal(&pools[2].info) {
-        if offer_asset.info.equal(&pools[0].info) {
-            ask_pool = pools[2].clone();
-            offer_pool = pools[0].clone();
-            unswapped_pool = pools[1].clone();
-        } else if offer_asset.info.equal(&pools[1].info) {
-            ask_pool = pools[2].clone();
-            offer_pool = pools[1].clone();
-            unswapped_pool = pools[0].clone();
-        } else {
-            return Err(ContractError::AssetMismatch {});
-        }
-    } else {
-        return Err(ContractError::AssetMismatch {});
-    }
+    let helpers::SwapPools {
+        offer_pool,
+        ask_pool,
+        unswapped_pools,
+        ..
+    } = helpers::get_swap_pools(
+        &pools,
+        &trio_info.asset_decimals,
+        &offer_asset.info,
+        &ask_asset.info,
+    )?;
 
     let config = CONFIG.load(deps.storage)?;
     let invariant = StableSwap::new(
@@ -142,7 +111,7 @@ pub fn query_simulation(
     let swap_computation = helpers::compute_swap(
         offer_pool.amount,
         ask_pool.amount,
-        unswapped_pool.amount,
+        &unswapped_pools,
         offer_asset.amount,
         config.pool_fees,
         invariant,
@@ -185,49 +154,17 @@ pub fn query_reverse_simulation(
         })
         .collect::<StdResult<Vec<_>>>()?;
 
-    let ask_pool: Asset;
-    let offer_pool: Asset;
-    let unswapped_pool: Asset;
-
-    if ask_asset.info.equal(&pools[0].info) {
-        if offer_asset.info.equal(&pools[1].info) {
-            ask_pool = pools[0].clone();
-            offer_pool = pools[1].clone();
-            unswapped_pool = pools[2].clone();
-        } else if offer_asset.info.equal(&pools[2].info) {
-            ask_pool = pools[0].clone();
-            offer_pool = pools[2].clone();
-            unswapped_pool = pools[1].clone();
-        } else {
-            return Err(ContractError::AssetMismatch {});
-        }
-    } else if ask_asset.info.equal(&pools[1].info) {
-        if offer_asset.info.equal(&pools[0].info) {
-            ask_pool = pools[1].clone();
-            offer_pool = pools[0].clone();
-            unswapped_pool = pools[2].clone();
-        } else if offer_asset.info.equal(&pools[2].info) {
-            ask_pool = pools[1].clone();
-            offer_pool = pools[2].clone();
-            unswapped_pool = pools[0].clone();
-        } else {
-            return Err(ContractError::AssetMismatch {});
-        }
-    } else if ask_asset.info.equal(&pools[2].info) {
-        if offer_asset.info.equal(&pools[0].info) {
-            ask_pool = pools[2].clone();
-            offer_pool = pools[0].clone();
-            unswapped_pool = pools[1].clone();
-        } else if offer_asset.info.equal(&pools[1].info) {
-            ask_pool = pools[2].clone();
-            offer_pool = pools[1].clone();
-            unswapped_pool = pools[0].clone();
-        } else {
-            return Err(ContractError::AssetMismatch {});
-        }
-    } else {
-        return Err(ContractError::AssetMismatch {});
-    }
+    let helpers::SwapPools {
+        offer_pool,
+        ask_pool,
+        unswapped_pools,
+        ..
+    } = helpers::get_swap_pools(
+        &pools,
+        &trio_info.asset_decimals,
+        &offer_asset.info,
+        &ask_asset.info,
+    )?;
 
     let config = CONFIG.load(deps.storage)?;
     let invariant = StableSwap::new(
@@ -241,7 +178,7 @@ pub fn query_reverse_simulation(
     let offer_amount_computation = helpers::compute_offer_amount(
         offer_pool.amount,
         ask_pool.amount,
-        unswapped_pool.amount,
+        &unswapped_pools,
         ask_asset.amount,
         config.pool_fees,
         invariant,
@@ -256,6 +193,179 @@ pub fn query_reverse_simulation(
     })
 }
 
+/// Queries an imbalanced withdrawal simulation. Used to know how many LP tokens would be burned to
+/// withdraw the given assets
+pub fn query_withdraw_imbalance_simulation(
+    deps: Deps,
+    assets: Vec<Asset>,
+    current_block: u64,
+) -> Result<WithdrawImbalanceSimulationResponse, ContractError> {
+    let trio_info: TrioInfoRaw = TRIO_INFO.load(deps.storage)?;
+
+    let contract_addr = deps.api.addr_humanize(&trio_info.contract_addr)?;
+
+    // To calculate pool amounts properly we should subtract the protocol fees from the pool
+    let collected_protocol_fees = COLLECTED_PROTOCOL_FEES.load(deps.storage)?;
+
+    let pools = trio_info
+        .query_pools(&deps.querier, deps.api, contract_addr)?
+        .into_iter()
+        .map(|mut pool| {
+            // subtract the protocol fee from the pool
+            let protocol_fee =
+                get_protocol_fee_for_asset(collected_protocol_fees.clone(), pool.clone().get_id());
+            pool.amount = pool.amount.checked_sub(protocol_fee)?;
+
+            Ok(pool)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let liquidity_token = match trio_info.liquidity_token {
+        AssetInfoRaw::Token { contract_addr } => {
+            deps.api.addr_humanize(&contract_addr)?.to_string()
+        }
+        AssetInfoRaw::NativeToken { denom } => denom,
+    };
+    let total_share = get_total_share(&deps, liquidity_token)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let invariant = StableSwap::new(
+        config.initial_amp,
+        config.future_amp,
+        current_block,
+        config.initial_amp_block,
+        config.future_amp_block,
+    );
+
+    let computation = helpers::compute_withdraw_imbalance(
+        &pools,
+        &assets,
+        total_share,
+        config.pool_fees,
+        invariant,
+    )?;
+
+    Ok(WithdrawImbalanceSimulationResponse {
+        burn_amount: computation.burn_amount,
+        swap_fee_amounts: computation.swap_fee_amounts,
+        protocol_fee_amounts: computation.protocol_fee_amounts,
+        burn_fee_amounts: computation.burn_fee_amounts,
+    })
+}
+
+/// Queries the virtual price of the LP token, i.e. the invariant `D` of the pool per LP token. It is
+/// zero if there's no liquidity in the pool.
+pub fn query_virtual_price(
+    deps: Deps,
+    current_block: u64,
+) -> Result<VirtualPriceResponse, ContractError> {
+    let trio_info: TrioInfoRaw = TRIO_INFO.load(deps.storage)?;
+
+    let contract_addr = deps.api.addr_humanize(&trio_info.contract_addr)?;
+
+    // To calculate pool amounts properly we should subtract the protocol fees from the pool
+    let collected_protocol_fees = COLLECTED_PROTOCOL_FEES.load(deps.storage)?;
+
+    let pools = trio_info
+        .query_pools(&deps.querier, deps.api, contract_addr)?
+        .into_iter()
+        .map(|pool| {
+            // subtract the protocol fee from the pool
+            let protocol_fee =
+                get_protocol_fee_for_asset(collected_protocol_fees.clone(), pool.clone().get_id());
+
+            Ok(pool.amount.checked_sub(protocol_fee)?)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let liquidity_token = match trio_info.liquidity_token {
+        AssetInfoRaw::Token { contract_addr } => {
+            deps.api.addr_humanize(&contract_addr)?.to_string()
+        }
+        AssetInfoRaw::NativeToken { denom } => denom,
+    };
+    let total_share = get_total_share(&deps, liquidity_token)?;
+
+    if total_share.is_zero() {
+        return Ok(VirtualPriceResponse {
+            virtual_price: Decimal256::zero(),
+        });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let invariant = StableSwap::new(
+        config.initial_amp,
+        config.future_amp,
+        current_block,
+        config.initial_amp_block,
+        config.future_amp_block,
+    );
+
+    let d = invariant
+        .compute_d(&pools)
+        .ok_or(ContractError::InvariantComputation {})?;
+
+    Ok(VirtualPriceResponse {
+        virtual_price: Decimal256::from_ratio(d, total_share),
+    })
+}
+
+/// Queries the marginal price of the offer asset denominated in the ask asset, in the smallest units
+/// of the assets
+pub fn query_spot_price(
+    deps: Deps,
+    offer_asset_info: AssetInfo,
+    ask_asset_info: AssetInfo,
+    current_block: u64,
+) -> Result<SpotPriceResponse, ContractError> {
+    let trio_info: TrioInfoRaw = TRIO_INFO.load(deps.storage)?;
+
+    let contract_addr = deps.api.addr_humanize(&trio_info.contract_addr)?;
+
+    // To calculate pool amounts properly we should subtract the protocol fees from the pool
+    let collected_protocol_fees = COLLECTED_PROTOCOL_FEES.load(deps.storage)?;
+
+    let pools = trio_info
+        .query_pools(&deps.querier, deps.api, contract_addr)?
+        .into_iter()
+        .map(|mut pool| {
+            // subtract the protocol fee from the pool
+            let protocol_fee =
+                get_protocol_fee_for_asset(collected_protocol_fees.clone(), pool.clone().get_id());
+            pool.amount = pool.amount.checked_sub(protocol_fee)?;
+
+            Ok(pool)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let helpers::SwapPools {
+        offer_pool,
+        ask_pool,
+        unswapped_pools,
+        ..
+    } = helpers::get_swap_pools(
+        &pools,
+        &trio_info.asset_decimals,
+        &offer_asset_info,
+        &ask_asset_info,
+    )?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let invariant = StableSwap::new(
+        config.initial_amp,
+        config.future_amp,
+        current_block,
+        config.initial_amp_block,
+        config.future_amp_block,
+    );
+
+    let spot_price = invariant
+        .compute_spot_price(offer_pool.amount, ask_pool.amount, &unswapped_pools)
+        .ok_or(ContractError::InvariantComputation {})?;
+
+    Ok(SpotPriceResponse { spot_price })
+}
+
 /// Queries the [Config], which contains the owner, pool_fees and feature_toggle
 pub fn query_config(deps: Deps) -> Result<ConfigResponse, ContractError> {
     let config = CONFIG.load(deps.storage)?;
@@ -1,11 +1,8 @@
 //! Swap calculations and curve invariant implementation
-use cosmwasm_std::{Uint128, Uint256};
+use cosmwasm_std::{Decimal, Decimal256, Fraction, Uint128, Uint256};
 
 use num_traits::ToPrimitive;
 
-/// Number of coins in a swap.
-pub const N_COINS: u8 = 3;
-
 /// Encodes all results of swapping from a source token to a destination token.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SwapResult {
@@ -68,14 +65,15 @@ impl StableSwap {
         d_init: Uint256,
         d_prod: Uint256,
         sum_x: Uint128,
+        n_coins: u8,
     ) -> Option<Uint256> {
-        let ann = amp_factor.checked_mul(N_COINS.into())?;
+        let ann = amp_factor.checked_mul(n_coins.into())?;
         let leverage = Uint256::from(sum_x).checked_mul(ann.into()).unwrap();
         // d = (ann * sum_x + d_prod * n_coins) * d / ((ann - 1) * d + (n_coins + 1) * d_prod)
         let numerator = d_init
             .checked_mul(
                 d_prod
-                    .checked_mul(N_COINS.into())
+                    .checked_mul(n_coins.into())
                     .unwrap()
                     .checked_add(leverage)
                     .unwrap(),
@@ -86,7 +84,7 @@ impl StableSwap {
             .unwrap()
             .checked_add(
                 d_prod
-                    .checked_mul((N_COINS.checked_add(1)?).into())
+                    .checked_mul((n_coins.checked_add(1)?).into())
                     .unwrap(),
             )
             .unwrap();
@@ -145,50 +143,39 @@ impl StableSwap {
     ///
     /// # Arguments
     ///
-    /// - `amount_a` - The amount of token A owned by the LP pool. (i.e. token A reserves)
-    /// - `amount_b` - The amount of token B owned by the LP pool. (i.e. token B reserves)
-    /// - `amount_c` - The amount of token B owned by the LP pool. (i.e. token C reserves)
+    /// - `amounts` - The amount of each token owned by the LP pool (i.e. the token reserves).
     ///
     #[allow(clippy::unwrap_used)]
-    pub fn compute_d(
-        &self,
-        amount_a: Uint128,
-        amount_b: Uint128,
-        amount_c: Uint128,
-    ) -> Option<Uint256> {
-        let sum_x = amount_a
-            .checked_add(amount_b.checked_add(amount_c).unwrap())
-            .unwrap(); // sum(x_i), a.k.a S
+    pub fn compute_d(&self, amounts: &[Uint128]) -> Option<Uint256> {
+        let n_coins = u8::try_from(amounts.len()).ok()?;
+        let sum_x = amounts
+            .iter()
+            .try_fold(Uint128::zero(), |sum, amount| sum.checked_add(*amount).ok())?; // sum(x_i), a.k.a S
         if sum_x == Uint128::zero() {
             Some(Uint256::zero())
         } else {
             let amp_factor = self.compute_amp_factor()?;
-            let amount_a_times_coins = amount_a.checked_mul(N_COINS.into()).unwrap();
-            let amount_b_times_coins = amount_b.checked_mul(N_COINS.into()).unwrap();
-            let amount_c_times_coins = amount_c.checked_mul(N_COINS.into()).unwrap();
+            let amounts_times_coins = amounts
+                .iter()
+                .map(|amount| amount.checked_mul(n_coins.into()).unwrap())
+                .collect::<Vec<Uint128>>();
 
             // Newton's method to approximate D
             let mut d_prev: Uint256;
             let mut d: Uint256 = sum_x.into();
             for _ in 0..256 {
                 let mut d_prod = d;
-                d_prod = d_prod
-                    .checked_mul(d)
-                    .unwrap()
-                    .checked_div(amount_a_times_coins.into())
-                    .unwrap();
-                d_prod = d_prod
-                    .checked_mul(d)
-                    .unwrap()
-                    .checked_div(amount_b_times_coins.into())
-                    .unwrap();
-                d_prod = d_prod
-                    .checked_mul(d)
-                    .unwrap()
-                    .checked_div(amount_c_times_coins.into())
-                    .unwrap();
+                for amount_times_coins in &amounts_times_coins {
+                    d_prod = d_prod
+                        .checked_mul(d)
+                        .unwrap()
+                        .checked_div((*amount_times_coins).into())
+                        .unwrap();
+                }
                 d_prev = d;
-                d = self.compute_next_d(amp_factor, d, d_prod, sum_x).unwrap();
+                d = self
+                    .compute_next_d(amp_factor, d, d_prod, sum_x, n_coins)
+                    .unwrap();
                 // Equality with the precision of 1
                 if d > d_prev {
                     if d.checked_sub(d_prev).unwrap() <= Uint256::one() {
@@ -204,26 +191,32 @@ impl StableSwap {
     }
 
     /// Computes the amount of pool tokens to mint after a deposit.
-    #[allow(clippy::unwrap_used, clippy::too_many_arguments)]
+    ///
+    /// # Arguments
+    ///
+    /// - `deposit_amounts` - The amount of each token deposited.
+    /// - `swap_amounts` - The reserves of each token, in the same order as `deposit_amounts`.
+    /// - `pool_token_supply` - The total supply of pool tokens.
+    #[allow(clippy::unwrap_used)]
     pub fn compute_mint_amount_for_deposit(
         &self,
-        deposit_amount_a: Uint128,
-        deposit_amount_b: Uint128,
-        deposit_amount_c: Uint128,
-        swap_amount_a: Uint128,
-        swap_amount_b: Uint128,
-        swap_amount_c: Uint128,
+        deposit_amounts: &[Uint128],
+        swap_amounts: &[Uint128],
         pool_token_supply: Uint128,
     ) -> Option<Uint128> {
+        if deposit_amounts.len() != swap_amounts.len() {
+            return None;
+        }
+
         // Initial invariant
-        let d_0 = self.compute_d(swap_amount_a, swap_amount_b, swap_amount_c)?;
-        let new_balances = [
-            swap_amount_a.checked_add(deposit_amount_a).unwrap(),
-            swap_amount_b.checked_add(deposit_amount_b).unwrap(),
-            swap_amount_c.checked_add(deposit_amount_c).unwrap(),
-        ];
+        let d_0 = self.compute_d(swap_amounts)?;
+        let new_balances = swap_amounts
+            .iter()
+            .zip(deposit_amounts)
+            .map(|(swap_amount, deposit_amount)| swap_amount.checked_add(*deposit_amount).unwrap())
+            .collect::<Vec<Uint128>>();
         // Invariant after change
-        let d_1 = self.compute_d(new_balances[0], new_balances[1], new_balances[2])?;
+        let d_1 = self.compute_d(&new_balances)?;
         if d_1 <= d_0 {
             None
         } else {
@@ -236,6 +229,168 @@ impl StableSwap {
         }
     }
 
+    /// Computes the amount of a single token received when burning pool tokens, along with the
+    /// fee charged for the implied exchange of the other tokens. This mirrors Curve's
+    /// `calc_withdraw_one_coin`.
+    ///
+    /// # Arguments
+    ///
+    /// - `pool_token_amount` - The amount of pool tokens to burn.
+    /// - `pool_token_supply` - The total supply of pool tokens.
+    /// - `swap_base_amount` - The reserves of the token to withdraw.
+    /// - `swap_quote_amounts` - The reserves of the other tokens.
+    /// - `fee` - The fee charged on exchanges.
+    #[allow(clippy::unwrap_used)]
+    pub fn compute_withdraw_one(
+        &self,
+        pool_token_amount: Uint128,
+        pool_token_supply: Uint128,
+        swap_base_amount: Uint128,
+        swap_quote_amounts: &[Uint128],
+        fee: Decimal,
+    ) -> Option<(Uint128, Uint128)> {
+        let n_coins = u8::try_from(swap_quote_amounts.len().checked_add(1)?).ok()?;
+        let d_0 = self.compute_d(&[&[swap_base_amount], swap_quote_amounts].concat())?;
+        let d_1 = d_0
+            .checked_sub(
+                Uint256::from(pool_token_amount)
+                    .checked_mul(d_0)
+                    .ok()?
+                    .checked_div(pool_token_supply.into())
+                    .ok()?,
+            )
+            .ok()?;
+        let new_y = self.compute_y(swap_quote_amounts, d_1)?;
+
+        // The fee is charged on the difference between the actual and the balanced withdrawal:
+        // fee * n / (4 * (n - 1))
+        let fee = fee
+            .checked_mul(Decimal::from_ratio(
+                n_coins,
+                n_coins.checked_sub(1)?.checked_mul(4)?,
+            ))
+            .ok()?;
+        let balanced_amount = |amount: Uint128| -> Option<Uint128> {
+            Uint128::try_from(Uint256::from(amount).multiply_ratio(d_1, d_0)).ok()
+        };
+
+        let expected_base_amount = balanced_amount(swap_base_amount)?.checked_sub(new_y).ok()?;
+        let base_reduced = swap_base_amount
+            .checked_sub(expected_base_amount.multiply_ratio(fee.numerator(), fee.denominator()))
+            .ok()?;
+        let quotes_reduced = swap_quote_amounts
+            .iter()
+            .map(|swap_quote_amount| {
+                let expected_quote_amount = swap_quote_amount
+                    .checked_sub(balanced_amount(*swap_quote_amount)?)
+                    .ok()?;
+                swap_quote_amount
+                    .checked_sub(
+                        expected_quote_amount.multiply_ratio(fee.numerator(), fee.denominator()),
+                    )
+                    .ok()
+            })
+            .collect::<Option<Vec<Uint128>>>()?;
+
+        // withdraw less to account for rounding errors
+        let dy = base_reduced
+            .checked_sub(self.compute_y(&quotes_reduced, d_1)?)
+            .ok()?
+            .checked_sub(Uint128::one())
+            .ok()?;
+        let dy_0 = swap_base_amount.checked_sub(new_y).ok()?;
+
+        Some((dy, dy_0.checked_sub(dy).ok()?))
+    }
+
+    /// Computes the amount of pool tokens to burn when withdrawing the given amounts of each token,
+    /// along with the fee charged on each of them for imbalancing the pool. This mirrors Curve's
+    /// `remove_liquidity_imbalance`.
+    ///
+    /// # Arguments
+    ///
+    /// - `withdraw_amounts` - The amount of each token to withdraw.
+    /// - `swap_amounts` - The reserves of each token, in the same order as `withdraw_amounts`.
+    /// - `pool_token_supply` - The total supply of pool tokens.
+    /// - `fee` - The fee charged on exchanges.
+    pub fn compute_withdraw_imbalance(
+        &self,
+        withdraw_amounts: &[Uint128],
+        swap_amounts: &[Uint128],
+        pool_token_supply: Uint128,
+        fee: Decimal,
+    ) -> Option<(Uint128, Vec<Uint128>)> {
+        if withdraw_amounts.len() != swap_amounts.len() {
+            return None;
+        }
+
+        let n_coins = u8::try_from(swap_amounts.len()).ok()?;
+        let new_balances = swap_amounts
+            .iter()
+            .zip(withdraw_amounts)
+            .map(|(swap_amount, withdraw_amount)| swap_amount.checked_sub(*withdraw_amount).ok())
+            .collect::<Option<Vec<Uint128>>>()?;
+        // none of the tokens can be drained from the pool
+        if new_balances.iter().any(|new_balance| new_balance.is_zero()) {
+            return None;
+        }
+
+        // Initial invariant
+        let d_0 = self.compute_d(swap_amounts)?;
+        // Invariant after the withdrawal
+        let d_1 = self.compute_d(&new_balances)?;
+
+        // The fee is charged on the difference between the actual and the balanced withdrawal:
+        // fee * n / (4 * (n - 1))
+        let fee = fee
+            .checked_mul(Decimal::from_ratio(
+                n_coins,
+                n_coins.checked_sub(1)?.checked_mul(4)?,
+            ))
+            .ok()?;
+        let fees = swap_amounts
+            .iter()
+            .zip(&new_balances)
+            .map(|(swap_amount, new_balance)| {
+                let ideal_balance =
+                    Uint128::try_from(Uint256::from(*swap_amount).multiply_ratio(d_1, d_0)).ok()?;
+                let difference = if ideal_balance > *new_balance {
+                    ideal_balance.checked_sub(*new_balance).ok()?
+                } else {
+                    new_balance.checked_sub(ideal_balance).ok()?
+                };
+                Some(difference.multiply_ratio(fee.numerator(), fee.denominator()))
+            })
+            .collect::<Option<Vec<Uint128>>>()?;
+
+        let reduced_balances = new_balances
+            .iter()
+            .zip(&fees)
+            .map(|(new_balance, fee)| new_balance.checked_sub(*fee).ok())
+            .collect::<Option<Vec<Uint128>>>()?;
+        if reduced_balances
+            .iter()
+            .any(|reduced_balance| reduced_balance.is_zero())
+        {
+            return None;
+        }
+        // Invariant after the withdrawal and fees
+        let d_2 = self.compute_d(&reduced_balances)?;
+
+        let burn_amount = Uint256::from(pool_token_supply)
+            .checked_mul(d_0.checked_sub(d_2).ok()?)
+            .ok()?
+            .checked_div(d_0)
+            .ok()?;
+        // burn more to account for rounding errors
+        let burn_amount = Uint128::try_from(burn_amount)
+            .ok()?
+            .checked_add(Uint128::one())
+            .ok()?;
+
+        Some((burn_amount, fees))
+    }
+
     /// Compute the swap amount `y` in proportion to `x`.
     ///
     /// Solve for `y`:
@@ -244,46 +399,36 @@ impl StableSwap {
     /// y**2 + y * (sum' - (A*n**n - 1) * D / (A * n**n)) = D ** (n + 1) / (n ** (2 * n) * prod' * A)
     /// y**2 + b*y = c
     /// ```
+    ///
+    /// where `sum'` and `prod'` are the sum and product of the reserves of all the tokens but the
+    /// one being solved for, i.e. `other_amounts`.
     #[allow(clippy::many_single_char_names, clippy::unwrap_used)]
-    pub fn compute_y_raw(
-        &self,
-        swap_in: Uint128,
-        //swap_out: Uint128,
-        no_swap: Uint128,
-        d: Uint256,
-    ) -> Option<Uint256> {
+    pub fn compute_y_raw(&self, other_amounts: &[Uint128], d: Uint256) -> Option<Uint256> {
+        let n_coins = u8::try_from(other_amounts.len().checked_add(1)?).ok()?;
         let amp_factor = self.compute_amp_factor()?;
-        let ann = amp_factor.checked_mul(N_COINS.into())?; // A * n ** n
+        let ann = amp_factor.checked_mul(n_coins.into())?; // A * n ** n
 
-        // sum' = prod' = x
         // c =  D ** (n + 1) / (n ** (2 * n) * prod' * A)
         let mut c = d;
-
-        c = c
-            .checked_mul(d)
-            .unwrap()
-            .checked_div(swap_in.checked_mul(N_COINS.into()).unwrap().into())
-            .unwrap();
-
-        c = c
-            .checked_mul(d)
-            .unwrap()
-            .checked_div(no_swap.checked_mul(N_COINS.into()).unwrap().into())
-            .unwrap();
+        for amount in other_amounts {
+            c = c
+                .checked_mul(d)
+                .unwrap()
+                .checked_div(amount.checked_mul(n_coins.into()).unwrap().into())
+                .unwrap();
+        }
         c = c
             .checked_mul(d)
             .unwrap()
-            .checked_div(ann.checked_mul(N_COINS.into()).unwrap().into())
+            .checked_div(ann.checked_mul(n_coins.into()).unwrap().into())
             .unwrap();
-        // b = sum(swap_in, no_swap) + D // Ann - D
+        // b = sum' + D // Ann - D
         // not subtracting D here because that could result in a negative.
-        let b = d
-            .checked_div(ann.into())
-            .unwrap()
-            .checked_add(swap_in.into())
-            .unwrap()
-            .checked_add(no_swap.into())
-            .unwrap();
+        let b = other_amounts
+            .iter()
+            .try_fold(d.checked_div(ann.into()).unwrap(), |b, amount| {
+                b.checked_add((*amount).into()).ok()
+            })?;
 
         // Solve for y by approximating: y**2 + b*y = c
         let mut y_prev: Uint256;
@@ -311,27 +456,97 @@ impl StableSwap {
         Some(y)
     }
 
-    /// Computes the swap amount `y` in proportion to `x`.
+    /// Computes the swap amount `y` in proportion to the reserves of the other tokens.
     #[allow(clippy::unwrap_used)]
-    pub fn compute_y(&self, x: Uint128, no_swap: Uint128, d: Uint256) -> Option<Uint128> {
-        let amount = self.compute_y_raw(x, no_swap, d)?;
+    pub fn compute_y(&self, other_amounts: &[Uint128], d: Uint256) -> Option<Uint128> {
+        let amount = self.compute_y_raw(other_amounts, d)?;
         Some(Uint128::try_from(amount).unwrap())
     }
 
+    /// Computes the marginal price of the source token denominated in the destination token, i.e.
+    /// the ratio of the partial derivatives of the invariant:
+    ///
+    /// ```text
+    /// price = (A * n + K / x_source) / (A * n + K / x_destination)
+    /// K = D**(n+1) / (n**n * prod(x_i))
+    /// ```
+    ///
+    /// `unswapped_amounts` are the reserves of the tokens that don't take part in the exchange. The
+    /// price is zero if any of the reserves is empty.
+    pub fn compute_spot_price(
+        &self,
+        swap_source_amount: Uint128,
+        swap_destination_amount: Uint128,
+        unswapped_amounts: &[Uint128],
+    ) -> Option<Decimal256> {
+        let amounts = [
+            &[swap_source_amount, swap_destination_amount],
+            unswapped_amounts,
+        ]
+        .concat();
+        if amounts.iter().any(|amount| amount.is_zero()) {
+            return Some(Decimal256::zero());
+        }
+
+        let n_coins = u8::try_from(amounts.len()).ok()?;
+        let ann = Uint256::from(self.compute_amp_factor()?.checked_mul(n_coins.into())?);
+        let d = self.compute_d(&amounts)?;
+
+        let mut k = d;
+        for amount in &amounts {
+            k = k
+                .checked_mul(d)
+                .ok()?
+                .checked_div(Uint256::from(*amount).checked_mul(n_coins.into()).ok()?)
+                .ok()?;
+        }
+
+        // price = (ann * x_source + K) * x_destination / ((ann * x_destination + K) * x_source)
+        let numerator = ann
+            .checked_mul(swap_source_amount.into())
+            .ok()?
+            .checked_add(k)
+            .ok()?
+            .checked_mul(swap_destination_amount.into())
+            .ok()?;
+        let denominator = ann
+            .checked_mul(swap_destination_amount.into())
+            .ok()?
+            .checked_add(k)
+            .ok()?
+            .checked_mul(swap_source_amount.into())
+            .ok()?;
+
+        Decimal256::checked_from_ratio(numerator, denominator).ok()
+    }
+
     /// Compute SwapResult after an exchange
+    ///
+    /// `unswapped_amounts` are the reserves of the tokens that don't take part in the exchange.
     #[allow(clippy::unwrap_used)]
     pub fn swap_to(
         &self,
         source_amount: Uint128,
         swap_source_amount: Uint128,
         swap_destination_amount: Uint128,
-        unswaped_amount: Uint128,
+        unswapped_amounts: &[Uint128],
     ) -> Option<SwapResult> {
+        let d = self
+            .compute_d(
+                &[
+                    &[swap_source_amount, swap_destination_amount],
+                    unswapped_amounts,
+                ]
+                .concat(),
+            )
+            .unwrap();
         let y = self.compute_y(
-            swap_source_amount.checked_add(source_amount).unwrap(),
-            unswaped_amount,
-            self.compute_d(swap_source_amount, swap_destination_amount, unswaped_amount)
-                .unwrap(),
+            &[
+                &[swap_source_amount.checked_add(source_amount).unwrap()],
+                unswapped_amounts,
+            ]
+            .concat(),
+            d,
         )?;
         // https://github.com/curvefi/curve-contract/blob/b0bbf77f8f93c9c5f4e415bce9cd71f0cdee960e/contracts/pool-templates/base/SwapTemplateBase.vy#L466
         let dy = swap_destination_amount
@@ -352,19 +567,32 @@ impl StableSwap {
     }
 
     /// Compute Computer offer amount for a given amount of ask asset.
+    ///
+    /// `unswapped_amounts` are the reserves of the tokens that don't take part in the exchange.
     #[allow(clippy::unwrap_used)]
     pub fn reverse_sim(
         &self,
         ask_amount: Uint128,
         swap_source_amount: Uint128,
         swap_destination_amount: Uint128,
-        unswaped_amount: Uint128,
+        unswapped_amounts: &[Uint128],
     ) -> Option<Uint128> {
+        let d = self
+            .compute_d(
+                &[
+                    &[swap_source_amount, swap_destination_amount],
+                    unswapped_amounts,
+                ]
+                .concat(),
+            )
+            .unwrap();
         let y = self.compute_y(
-            swap_destination_amount.checked_sub(ask_amount).unwrap(),
-            unswaped_amount,
-            self.compute_d(swap_source_amount, swap_destination_amount, unswaped_amount)
-                .unwrap(),
+            &[
+                &[swap_destination_amount.checked_sub(ask_amount).unwrap()],
+                unswapped_amounts,
+            ]
+            .concat(),
+            d,
         )?;
 
         let offer_needed = y.checked_sub(swap_source_amount).unwrap();
@@ -383,9 +611,12 @@ mod tests {
     use super::*;
     use proptest::prelude::*;
     use rand::Rng;
-    use sim::Model;
+    use sim::{Model, MODEL_FEE_DENOMINATOR, MODEL_FEE_NUMERATOR};
     use std::cmp;
 
+    /// Number of coins in the pools tested against the model, unless stated otherwise.
+    pub const N_COINS: u8 = 3;
+
     /// Timestamp at 0
     pub const ZERO_TS: u64 = 0;
 
@@ -403,9 +634,7 @@ mod tests {
 
     fn check_d(
         model: &Model,
-        amount_a: u128,
-        amount_b: u128,
-        amount_c: u128,
+        amounts: &[u128],
         current_ts: u64,
         start_ramp_ts: u64,
         stop_ramp_ts: u64,
@@ -419,9 +648,10 @@ mod tests {
         };
         let d = swap
             .compute_d(
-                Uint128::new(amount_a),
-                Uint128::new(amount_b),
-                Uint128::new(amount_c),
+                &amounts
+                    .iter()
+                    .map(|amount| Uint128::new(*amount))
+                    .collect::<Vec<_>>(),
             )
             .unwrap();
         assert_eq!(d, Uint256::from(model.sim_d()));
@@ -431,7 +661,7 @@ mod tests {
     fn check_y(
         model: &Model,
         swap_in: u128,
-        no_swap: u128,
+        no_swap: &[u128],
         d: Uint256,
         current_ts: u64,
         start_ramp_ts: u64,
@@ -445,7 +675,18 @@ mod tests {
             stop_ramp_ts,
         };
         let y = swap
-            .compute_y_raw(Uint128::new(swap_in), Uint128::new(no_swap), d)
+            .compute_y_raw(
+                &[
+                    &[Uint128::new(swap_in)],
+                    no_swap
+                        .iter()
+                        .map(|amount| Uint128::new(*amount))
+                        .collect::<Vec<_>>()
+                        .as_slice(),
+                ]
+                .concat(),
+                d,
+            )
             .unwrap();
         assert_eq!(
             Uint128::try_from(y).unwrap().u128(),
@@ -457,24 +698,51 @@ mod tests {
     fn test_curve_math_specific() {
         // Specific cases
         let model_no_balance = Model::new(1, vec![0, 0, 0], N_COINS);
-        check_d(&model_no_balance, 0, 0, 0, 0, 0, 0);
+        check_d(&model_no_balance, &[0, 0, 0], 0, 0, 0);
 
         let amount_a = 1046129065254161082u128;
         let amount_b = 1250710035549196829u128;
         let amount_c = 1111111111111111111u128;
         let model = Model::new(1188, vec![amount_a, amount_b, amount_c], N_COINS);
-        let d = check_d(&model, amount_a, amount_b, amount_c, 0, 0, 0);
+        let d = check_d(&model, &[amount_a, amount_b, amount_c], 0, 0, 0);
         let amount_x = 2045250484898639148u128;
-        check_y(&model, amount_x, amount_c, d, 0, 0, 0);
+        check_y(&model, amount_x, &[amount_c], d, 0, 0, 0);
 
         let amount_a = 862538457714585493u128;
         let amount_b = 492548187909826733u128;
         let amount_c = 777777777777777777u128;
         let model = Model::new(9, vec![amount_a, amount_b, amount_c], N_COINS);
-        let d = check_d(&model, amount_a, amount_b, amount_c, 0, 0, 0);
+        let d = check_d(&model, &[amount_a, amount_b, amount_c], 0, 0, 0);
         let amount_x = 815577754938955939u128;
 
-        check_y(&model, amount_x, amount_c, d, 0, 0, 0);
+        check_y(&model, amount_x, &[amount_c], d, 0, 0, 0);
+    }
+
+    #[test]
+    fn test_curve_math_specific_two_coins() {
+        let amount_a = 1046129065254161082u128;
+        let amount_b = 1250710035549196829u128;
+        let model = Model::new(1188, vec![amount_a, amount_b], 2);
+        let d = check_d(&model, &[amount_a, amount_b], 0, 0, 0);
+        let amount_x = 2045250484898639148u128;
+        check_y(&model, amount_x, &[], d, 0, 0, 0);
+    }
+
+    #[test]
+    fn test_curve_math_specific_four_coins() {
+        let amount_a = 1046129065254161082u128;
+        let amount_b = 1250710035549196829u128;
+        let amount_c = 1111111111111111111u128;
+        let amount_d = 987654321987654321u128;
+        let model = Model::new(1188, vec![amount_a, amount_b, amount_c, amount_d], 4);
+        let d = check_d(&model, &[amount_a, amount_b, amount_c, amount_d], 0, 0, 0);
+        let amount_x = 2045250484898639148u128;
+        check_y(&model, amount_x, &[amount_c, amount_d], d, 0, 0, 0);
+
+        let model = Model::new(9, vec![amount_a, amount_b, amount_c, amount_d], 4);
+        let d = check_d(&model, &[amount_a, amount_b, amount_c, amount_d], 0, 0, 0);
+        let amount_x = 815577754938955939u128;
+        check_y(&model, amount_x, &[amount_c, amount_d], d, 0, 0, 0);
     }
 
     #[test]
@@ -501,12 +769,8 @@ mod tests {
         let pool_token_supply = MAX_TOKENS_IN;
         let actual_mint_amount = invariant
             .compute_mint_amount_for_deposit(
-                deposit_amount_a,
-                deposit_amount_b,
-                deposit_amount_c,
-                swap_amount_a,
-                swap_amount_b,
-                swap_amount_c,
+                &[deposit_amount_a, deposit_amount_b, deposit_amount_c],
+                &[swap_amount_a, swap_amount_b, swap_amount_c],
                 pool_token_supply,
             )
             .unwrap();
@@ -514,6 +778,148 @@ mod tests {
         assert_eq!(actual_mint_amount, expected_mint_amount);
     }
 
+    #[test]
+    fn test_compute_withdraw_one() {
+        let amount_a = 1046129065254161082u128;
+        let amount_b = 1250710035549196829u128;
+        let amount_c = 1111111111111111111u128;
+        let pool_token_supply = 3400000000000000000u128;
+        let model = Model::new_with_pool_tokens(
+            100,
+            vec![amount_a, amount_b, amount_c],
+            N_COINS,
+            pool_token_supply,
+        );
+        let invariant = StableSwap::new(100, 100, ZERO_TS, ZERO_TS, ZERO_TS);
+        let fee = Decimal::from_ratio(MODEL_FEE_NUMERATOR, MODEL_FEE_DENOMINATOR);
+
+        for pool_token_amount in [
+            1_000_000u128,
+            100000000000000000u128,
+            1000000000000000000u128,
+        ] {
+            let (dy, dy_fee) = invariant
+                .compute_withdraw_one(
+                    Uint128::new(pool_token_amount),
+                    Uint128::new(pool_token_supply),
+                    Uint128::new(amount_a),
+                    &[Uint128::new(amount_b), Uint128::new(amount_c)],
+                    fee,
+                )
+                .unwrap();
+            assert_eq!(
+                (dy.u128(), dy_fee.u128()),
+                model.sim_calc_withdraw_one_coin(pool_token_amount, 0)
+            );
+        }
+    }
+
+    #[test]
+    fn test_compute_withdraw_imbalance() {
+        let amount_a = 1046129065254161082u128;
+        let amount_b = 1250710035549196829u128;
+        let amount_c = 1111111111111111111u128;
+        let pool_token_supply = 3400000000000000000u128;
+        let model = Model::new_with_pool_tokens(
+            100,
+            vec![amount_a, amount_b, amount_c],
+            N_COINS,
+            pool_token_supply,
+        );
+        let invariant = StableSwap::new(100, 100, ZERO_TS, ZERO_TS, ZERO_TS);
+        let fee = Decimal::from_ratio(MODEL_FEE_NUMERATOR, MODEL_FEE_DENOMINATOR);
+
+        for withdraw_amounts in [
+            [1_000_000u128, 0, 0],
+            [100000000000000000u128, 0, 200000000000000000u128],
+            [
+                300000000000000000u128,
+                300000000000000000u128,
+                300000000000000000u128,
+            ],
+        ] {
+            let (burn_amount, _) = invariant
+                .compute_withdraw_imbalance(
+                    &withdraw_amounts.map(Uint128::new),
+                    &[
+                        Uint128::new(amount_a),
+                        Uint128::new(amount_b),
+                        Uint128::new(amount_c),
+                    ],
+                    Uint128::new(pool_token_supply),
+                    fee,
+                )
+                .unwrap();
+            // one more pool token is burned to account for rounding errors
+            assert_eq!(
+                burn_amount.u128(),
+                model.sim_remove_liquidity_imbalance(withdraw_amounts.to_vec()) + 1
+            );
+        }
+
+        // the pool can't be drained of any of its tokens
+        assert_eq!(
+            invariant.compute_withdraw_imbalance(
+                &[Uint128::new(amount_a), Uint128::zero(), Uint128::zero()],
+                &[
+                    Uint128::new(amount_a),
+                    Uint128::new(amount_b),
+                    Uint128::new(amount_c),
+                ],
+                Uint128::new(pool_token_supply),
+                fee,
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_compute_spot_price() {
+        let invariant = StableSwap::new(100, 100, ZERO_TS, ZERO_TS, ZERO_TS);
+
+        // a balanced pool is at the peg
+        let balance = Uint128::new(1_000_000_000_000u128);
+        assert_eq!(
+            invariant.compute_spot_price(balance, balance, &[balance]),
+            Some(Decimal256::one())
+        );
+
+        // the price matches the rate of a tiny swap
+        let amount_a = Uint128::new(1046129065254161082u128);
+        let amount_b = Uint128::new(1250710035549196829u128);
+        let amount_c = Uint128::new(1111111111111111111u128);
+        for (source, destination, unswapped) in [
+            (amount_a, amount_b, amount_c),
+            (amount_b, amount_a, amount_c),
+            (amount_c, amount_a, amount_b),
+        ] {
+            let spot_price = invariant
+                .compute_spot_price(source, destination, &[unswapped])
+                .unwrap();
+            let source_amount = Uint128::new(1_000_000_000u128);
+            let swapped = invariant
+                .swap_to(source_amount, source, destination, &[unswapped])
+                .unwrap()
+                .amount_swapped;
+            let rate = Decimal256::from_ratio(swapped, source_amount);
+
+            let difference = if spot_price > rate {
+                spot_price - rate
+            } else {
+                rate - spot_price
+            };
+            assert!(difference < Decimal256::from_ratio(1u8, 1_000_000u128));
+            // the source token is cheaper the more of it there is in the pool
+            assert_eq!(spot_price < Decimal256::one(), source > destination);
+        }
+
+        // an empty pool has no price
+        assert_eq!(
+            invariant.compute_spot_price(balance, Uint128::zero(), &[balance]),
+            Some(Decimal256::zero())
+        );
+    }
+
     #[ignore]
     #[test]
     fn test_curve_math_with_random_inputs() {
@@ -540,9 +946,7 @@ mod tests {
             let model = Model::new(amp_factor, vec![amount_a, amount_b, amount_c], N_COINS);
             let d = check_d(
                 &model,
-                amount_a,
-                amount_b,
-                amount_c,
+                &[amount_a, amount_b, amount_c],
                 current_ts,
                 start_ramp_ts,
                 stop_ramp_ts,
@@ -553,7 +957,7 @@ mod tests {
             check_y(
                 &model,
                 amount_x,
-                amount_c,
+                &[amount_c],
                 d,
                 current_ts,
                 start_ramp_ts,
@@ -598,7 +1002,7 @@ mod tests {
                     source_amount,
                     swap_source_amount,
                     swap_dest_amount,
-                    self.swap_reserve_balance_c,
+                    &[self.swap_reserve_balance_c],
                 )
                 .unwrap();
 
@@ -784,15 +1188,11 @@ mod tests {
             let start_ramp_ts = cmp::max(0, current_ts - MIN_RAMP_DURATION);
             let stop_ramp_ts = cmp::min(u64::MAX, current_ts + MIN_RAMP_DURATION);
             let invariant = StableSwap::new(amp_factor, amp_factor, current_ts, start_ramp_ts, stop_ramp_ts);
-            let d0 = invariant.compute_d(Uint128::new(swap_token_a_amount), Uint128::new(swap_token_b_amount), Uint128::new(swap_token_c_amount)).unwrap();
+            let d0 = invariant.compute_d(&[Uint128::new(swap_token_a_amount), Uint128::new(swap_token_b_amount), Uint128::new(swap_token_c_amount)]).unwrap();
 
             let mint_amount = invariant.compute_mint_amount_for_deposit(
-                    Uint128::new(deposit_amount_a),
-                    Uint128::new(deposit_amount_b),
-                    Uint128::new(deposit_amount_c),
-                    Uint128::new(swap_token_a_amount),
-                    Uint128::new(swap_token_b_amount),
-                    Uint128::new(swap_token_c_amount),
+                    &[Uint128::new(deposit_amount_a), Uint128::new(deposit_amount_b), Uint128::new(deposit_amount_c)],
+                    &[Uint128::new(swap_token_a_amount), Uint128::new(swap_token_b_amount), Uint128::new(swap_token_c_amount)],
                     Uint128::new(pool_token_supply),
                 );
             prop_assume!(mint_amount.is_some());
@@ -801,7 +1201,7 @@ mod tests {
             let new_swap_token_b_amount = swap_token_b_amount + deposit_amount_b;
             let new_swap_token_c_amount = swap_token_c_amount + deposit_amount_c;
             let new_pool_token_supply = pool_token_supply + mint_amount.unwrap().u128();
-            let d1 = invariant.compute_d(Uint128::new(new_swap_token_a_amount), Uint128::new(new_swap_token_b_amount), Uint128::new(new_swap_token_c_amount)).unwrap();
+            let d1 = invariant.compute_d(&[Uint128::new(new_swap_token_a_amount), Uint128::new(new_swap_token_b_amount), Uint128::new(new_swap_token_c_amount)]).unwrap();
 
             assert!(d0 < d1);
             assert!(d0 / Uint256::from( pool_token_supply) <= d1 /  Uint256::from( new_pool_token_supply));
@@ -0,0 +1,174 @@
+use crate::contract::{execute, instantiate};
+use crate::error::ContractError;
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{coin, coins, to_binary, Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
+use white_whale::fee::Fee;
+use white_whale::pool_network::asset::{Asset, AssetInfo};
+use white_whale::pool_network::mock_querier::mock_dependencies;
+use white_whale::pool_network::trio::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolFee};
+
+#[test]
+fn messages_past_deadline_fail() {
+    let mut deps = mock_dependencies(&[
+        coin(1_000_001_000u128, "uusd"),
+        coin(1_000_000_000u128, "uusdc"),
+    ]);
+    deps.querier.with_token_balances(&[
+        (
+            &"asset0000".to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::new(1_000_000_000u128),
+            )],
+        ),
+        (
+            &"liquidity0000".to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::new(1_000_000_000u128),
+            )],
+        ),
+    ]);
+
+    let uusd = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+    let uusdc = AssetInfo::NativeToken {
+        denom: "uusdc".to_string(),
+    };
+    let asset0000 = AssetInfo::Token {
+        contract_addr: "asset0000".to_string(),
+    };
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![uusd.clone(), uusdc.clone(), asset0000.clone()],
+        token_code_id: 10u64,
+        asset_decimals: vec![6u8; 3],
+        pool_fees: PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::zero(),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(3u64),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+        },
+        fee_collector_addr: "collector".to_string(),
+        amp_factor: 100,
+        token_factory_lp: false,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let env = mock_env();
+    let swap_msg = |deadline| ExecuteMsg::Swap {
+        offer_asset: Asset {
+            info: uusd.clone(),
+            amount: Uint128::new(1_000u128),
+        },
+        ask_asset: uusdc.clone(),
+        belief_price: None,
+        max_spread: None,
+        to: None,
+        deadline,
+    };
+
+    // the message can be executed up until the deadline
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &coins(1_000u128, "uusd")),
+        swap_msg(Some(env.block.time)),
+    )
+    .unwrap();
+
+    let deadline = env.block.time.minus_seconds(1);
+    let expected_err = ContractError::DeadlineExceeded {
+        deadline,
+        current_time: env.block.time,
+    };
+
+    for msg in [
+        swap_msg(Some(deadline)),
+        ExecuteMsg::SwapExactOut {
+            offer_asset: uusd.clone(),
+            ask_asset: Asset {
+                info: asset0000.clone(),
+                amount: Uint128::new(900u128),
+            },
+            max_offer_amount: Uint128::new(1_000u128),
+            to: None,
+            deadline: Some(deadline),
+        },
+        ExecuteMsg::ProvideLiquidity {
+            assets: [&uusd, &uusdc, &asset0000]
+                .into_iter()
+                .map(|info| Asset {
+                    info: info.clone(),
+                    amount: Uint128::new(1_000u128),
+                })
+                .collect(),
+            slippage_tolerance: None,
+            receiver: None,
+            deadline: Some(deadline),
+        },
+        ExecuteMsg::WithdrawLiquidity {
+            target_asset: None,
+            min_receive: None,
+            deadline: Some(deadline),
+        },
+        ExecuteMsg::WithdrawImbalance {
+            assets: vec![Asset {
+                info: uusdc.clone(),
+                amount: Uint128::new(1_000u128),
+            }],
+            max_burn_amount: Uint128::new(1_100u128),
+            deadline: Some(deadline),
+        },
+    ] {
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr0000", &coins(1_000u128, "uusd")),
+            msg,
+        )
+        .unwrap_err();
+        assert_eq!(err, expected_err);
+    }
+
+    for (token, hook_msg) in [
+        (
+            "asset0000",
+            Cw20HookMsg::Swap {
+                ask_asset: uusd.clone(),
+                belief_price: None,
+                max_spread: None,
+                to: None,
+                deadline: Some(deadline),
+            },
+        ),
+        (
+            "liquidity0000",
+            Cw20HookMsg::WithdrawLiquidity {
+                target_asset: None,
+                min_receive: None,
+                deadline: Some(deadline),
+            },
+        ),
+    ] {
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(token, &[]),
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "addr0000".to_string(),
+                amount: Uint128::new(1_000u128),
+                msg: to_binary(&hook_msg).unwrap(),
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(err, expected_err);
+    }
+}
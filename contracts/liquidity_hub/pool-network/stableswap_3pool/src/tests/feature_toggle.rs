@@ -28,7 +28,7 @@ fn test_feature_toggle_swap_disabled() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -40,7 +40,7 @@ fn test_feature_toggle_swap_disabled() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::percent(1u64),
@@ -91,6 +91,7 @@ fn test_feature_toggle_swap_disabled() {
         belief_price: None,
         max_spread: None,
         to: None,
+        deadline: None,
     };
     let info = mock_info(
         "addr0000",
@@ -118,6 +119,7 @@ fn test_feature_toggle_swap_disabled() {
             belief_price: None,
             max_spread: None,
             to: None,
+            deadline: None,
         })
         .unwrap(),
     });
@@ -149,7 +151,7 @@ fn test_feature_toggle_withdrawals_disabled() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -161,7 +163,7 @@ fn test_feature_toggle_withdrawals_disabled() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::percent(1u64),
@@ -205,7 +207,12 @@ fn test_feature_toggle_withdrawals_disabled() {
     // withdraw liquidity should fail
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
-        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+            target_asset: None,
+            min_receive: None,
+            deadline: None,
+        })
+        .unwrap(),
         amount: Uint128::from(100u128),
     });
 
@@ -236,7 +243,7 @@ fn test_feature_toggle_deposits_disabled() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -248,7 +255,7 @@ fn test_feature_toggle_deposits_disabled() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::percent(1u64),
@@ -291,7 +298,7 @@ fn test_feature_toggle_deposits_disabled() {
 
     // provide liquidity should fail
     let msg = ExecuteMsg::ProvideLiquidity {
-        assets: [
+        assets: vec![
             Asset {
                 info: AssetInfo::Token {
                     contract_addr: "asset0000".to_string(),
@@ -313,6 +320,7 @@ fn test_feature_toggle_deposits_disabled() {
         ],
         slippage_tolerance: None,
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -0,0 +1,105 @@
+use crate::contract::{instantiate, reply};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{coin, Coin, Decimal, OwnedDeps, Reply, SubMsgResponse, SubMsgResult, Uint128};
+use white_whale::fee::Fee;
+use white_whale::pool_network::asset::AssetInfo;
+use white_whale::pool_network::mock_querier::{mock_dependencies, WasmMockQuerier};
+use white_whale::pool_network::trio::{InstantiateMsg, PoolFee};
+
+pub fn native(denom: &str) -> AssetInfo {
+    AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    }
+}
+
+pub fn token(contract_addr: &str) -> AssetInfo {
+    AssetInfo::Token {
+        contract_addr: contract_addr.to_string(),
+    }
+}
+
+/// Pool fees charging the given protocol and swap fee shares, without burn fee
+pub fn pool_fees(protocol_fee: Decimal, swap_fee: Decimal) -> PoolFee {
+    PoolFee {
+        protocol_fee: Fee {
+            share: protocol_fee,
+        },
+        swap_fee: Fee { share: swap_fee },
+        burn_fee: Fee {
+            share: Decimal::zero(),
+        },
+    }
+}
+
+/// Instantiates a pool of the given 6 decimals assets holding the given pools, with liquidity0000
+/// as its LP token and `total_share` of it issued to addr0000
+pub fn instantiate_pool(
+    asset_infos: Vec<AssetInfo>,
+    pools: &[u128],
+    total_share: u128,
+    pool_fees: PoolFee,
+    amp_factor: u64,
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let mut native_balances: Vec<Coin> = vec![];
+    let mut token_balances: Vec<(String, Vec<(String, Uint128)>)> = vec![(
+        "liquidity0000".to_string(),
+        vec![("addr0000".to_string(), Uint128::new(total_share))],
+    )];
+    for (asset_info, pool) in asset_infos.iter().zip(pools) {
+        match asset_info {
+            AssetInfo::NativeToken { denom } => native_balances.push(coin(*pool, denom)),
+            AssetInfo::Token { contract_addr } => token_balances.push((
+                contract_addr.to_string(),
+                vec![(MOCK_CONTRACT_ADDR.to_string(), Uint128::new(*pool))],
+            )),
+        }
+    }
+
+    let mut deps = mock_dependencies(&native_balances);
+    let token_balances: Vec<(&String, Vec<(&String, &Uint128)>)> = token_balances
+        .iter()
+        .map(|(contract_addr, balances)| {
+            (
+                contract_addr,
+                balances
+                    .iter()
+                    .map(|(address, balance)| (address, balance))
+                    .collect(),
+            )
+        })
+        .collect();
+    deps.querier.with_token_balances(
+        &token_balances
+            .iter()
+            .map(|(contract_addr, balances)| (*contract_addr, balances.as_slice()))
+            .collect::<Vec<_>>(),
+    );
+
+    let msg = InstantiateMsg {
+        asset_decimals: vec![6u8; asset_infos.len()],
+        asset_infos,
+        token_code_id: 10u64,
+        pool_fees,
+        fee_collector_addr: "collector".to_string(),
+        amp_factor,
+        token_factory_lp: false,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    // store liquidity token
+    let reply_msg = Reply {
+        id: 1,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: Some(
+                vec![
+                    10, 13, 108, 105, 113, 117, 105, 100, 105, 116, 121, 48, 48, 48, 48,
+                ]
+                .into(),
+            ),
+        }),
+    };
+    reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    deps
+}
@@ -1,7 +1,11 @@
+mod deadline;
 mod feature_toggle;
+mod helpers;
+mod multi_asset;
 mod protocol_fees;
 mod provide_liquidity;
 mod queries;
 mod swap;
+mod swap_exact_out;
 mod testing;
 mod withdrawals;
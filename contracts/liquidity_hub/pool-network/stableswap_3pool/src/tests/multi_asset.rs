@@ -0,0 +1,248 @@
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use crate::tests::helpers::{instantiate_pool, native, pool_fees};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    coin, from_binary, to_binary, Coin, CosmosMsg, Decimal, Decimal256, OwnedDeps, SubMsg, Uint128,
+    WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use white_whale::pool_network::asset::{Asset, AssetInfo, MINIMUM_LIQUIDITY_AMOUNT};
+use white_whale::pool_network::mock_querier::{mock_dependencies, WasmMockQuerier};
+use white_whale::pool_network::trio::{
+    ExecuteMsg, InstantiateMsg, QueryMsg, SimulationResponse, SpotPriceResponse,
+    VirtualPriceResponse,
+};
+
+const DENOMS: [&str; 4] = ["uusd", "uusdc", "udai", "uusdt"];
+
+fn instantiate_msg(asset_infos: Vec<AssetInfo>, asset_decimals: Vec<u8>) -> InstantiateMsg {
+    InstantiateMsg {
+        asset_infos,
+        token_code_id: 10u64,
+        asset_decimals,
+        pool_fees: pool_fees(Decimal::zero(), Decimal::permille(3u64)),
+        fee_collector_addr: "collector".to_string(),
+        amp_factor: 100,
+        token_factory_lp: false,
+    }
+}
+
+fn instantiate_four_asset_pool(
+    pool_amount: Uint128,
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    instantiate_pool(
+        DENOMS.iter().map(|denom| native(denom)).collect(),
+        &[pool_amount.u128(); 4],
+        0,
+        pool_fees(Decimal::zero(), Decimal::permille(3u64)),
+        100,
+    )
+}
+
+#[test]
+fn provide_liquidity_four_assets() {
+    // the deposits are already part of the pool balances
+    let mut deps = instantiate_four_asset_pool(Uint128::new(1_000_000u128));
+
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: DENOMS
+            .iter()
+            .map(|denom| Asset {
+                info: native(denom),
+                amount: Uint128::new(1_000_000u128),
+            })
+            .collect(),
+        slippage_tolerance: None,
+        receiver: None,
+        deadline: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &DENOMS
+            .iter()
+            .map(|denom| coin(1_000_000u128, denom.to_string()))
+            .collect::<Vec<Coin>>(),
+    );
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // for a balanced pool D is the sum of the deposits, and the minimum liquidity is locked once
+    // per asset
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "liquidity0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Mint {
+                    recipient: MOCK_CONTRACT_ADDR.to_string(),
+                    amount: MINIMUM_LIQUIDITY_AMOUNT * Uint128::from(4u8),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "liquidity0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Mint {
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::new(3_996_000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+
+    // all the assets are needed to provide liquidity
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: DENOMS[..3]
+            .iter()
+            .map(|denom| Asset {
+                info: native(denom),
+                amount: Uint128::new(1_000u128),
+            })
+            .collect(),
+        slippage_tolerance: None,
+        receiver: None,
+        deadline: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &DENOMS[..3]
+            .iter()
+            .map(|denom| coin(1_000u128, denom.to_string()))
+            .collect::<Vec<Coin>>(),
+    );
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(err, ContractError::AssetMismatch {});
+}
+
+#[test]
+fn swap_simulation_four_assets() {
+    let deps = instantiate_four_asset_pool(Uint128::new(1_000_000_000u128));
+
+    // swapping between any two assets of a balanced pool stays close to the peg
+    for (offer, ask) in [(0, 3), (3, 1), (2, 0)] {
+        let simulation: SimulationResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Simulation {
+                    offer_asset: Asset {
+                        info: native(DENOMS[offer]),
+                        amount: Uint128::new(1_000_000u128),
+                    },
+                    ask_asset: Asset {
+                        info: native(DENOMS[ask]),
+                        amount: Uint128::zero(),
+                    },
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(simulation.swap_fee_amount, Uint128::new(2_999u128));
+        assert_eq!(simulation.return_amount, Uint128::new(996_991u128));
+    }
+
+    // an asset can't be swapped for itself
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Simulation {
+            offer_asset: Asset {
+                info: native(DENOMS[1]),
+                amount: Uint128::new(1_000_000u128),
+            },
+            ask_asset: Asset {
+                info: native(DENOMS[1]),
+                amount: Uint128::zero(),
+            },
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AssetMismatch {});
+}
+
+#[test]
+fn instantiate_with_invalid_assets() {
+    let mut deps = mock_dependencies(&[]);
+    let asset_infos =
+        |number_of_assets: usize| (0..number_of_assets).map(|i| native(&format!("denom{i}")));
+
+    for (asset_infos, asset_decimals) in [
+        (asset_infos(1).collect::<Vec<_>>(), vec![6u8]),
+        (asset_infos(9).collect(), vec![6u8; 9]),
+        (asset_infos(4).collect(), vec![6u8; 3]),
+    ] {
+        let err = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            instantiate_msg(asset_infos, asset_decimals),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidNumberOfAssets { min: 2, max: 8 });
+    }
+
+    let err = instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        instantiate_msg(
+            vec![native("uusd"), native("uusdc"), native("uusd")],
+            vec![6u8; 3],
+        ),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::SameAsset {});
+}
+
+#[test]
+fn query_virtual_and_spot_prices_four_assets() {
+    let mut deps = instantiate_four_asset_pool(Uint128::new(1_000_000_000u128));
+
+    // there's no liquidity yet
+    let response: VirtualPriceResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::VirtualPrice {}).unwrap()).unwrap();
+    assert_eq!(response.virtual_price, Decimal256::zero());
+
+    // for a balanced pool D is the sum of the pools
+    deps.querier.with_token_balances(&[(
+        &"liquidity0000".to_string(),
+        &[(&"addr0000".to_string(), &Uint128::new(2_000_000_000u128))],
+    )]);
+    let response: VirtualPriceResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::VirtualPrice {}).unwrap()).unwrap();
+    assert_eq!(response.virtual_price, Decimal256::percent(200));
+
+    let response: SpotPriceResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SpotPrice {
+                offer_asset_info: native(DENOMS[0]),
+                ask_asset_info: native(DENOMS[3]),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(response.spot_price, Decimal256::one());
+
+    for (offer_asset_info, ask_asset_info) in [
+        (native(DENOMS[1]), native(DENOMS[1])),
+        (native(DENOMS[1]), native("uluna")),
+    ] {
+        let err = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SpotPrice {
+                offer_asset_info,
+                ask_asset_info,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AssetMismatch {});
+    }
+}
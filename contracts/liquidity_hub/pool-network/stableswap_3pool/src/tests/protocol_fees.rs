@@ -41,7 +41,7 @@ fn test_protocol_fees() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -53,7 +53,7 @@ fn test_protocol_fees() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::from_ratio(1u128, 1000u128),
@@ -105,6 +105,7 @@ fn test_protocol_fees() {
         belief_price: None,
         max_spread: None,
         to: None,
+        deadline: None,
     };
     let env = mock_env();
     let info = mock_info(
@@ -162,6 +163,7 @@ fn test_protocol_fees() {
         belief_price: None,
         max_spread: None,
         to: None,
+        deadline: None,
     };
     let env = mock_env();
     let info = mock_info(
@@ -236,7 +238,7 @@ fn test_collect_protocol_fees_successful() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -248,7 +250,7 @@ fn test_collect_protocol_fees_successful() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::from_ratio(1u128, 1000u128),
@@ -300,6 +302,7 @@ fn test_collect_protocol_fees_successful() {
         belief_price: None,
         max_spread: None,
         to: None,
+        deadline: None,
     };
     let env = mock_env();
     let info = mock_info(
@@ -326,6 +329,7 @@ fn test_collect_protocol_fees_successful() {
             belief_price: None,
             max_spread: None,
             to: None,
+            deadline: None,
         })
         .unwrap(),
     });
@@ -376,6 +380,7 @@ fn test_collect_protocol_fees_successful() {
             belief_price: None,
             max_spread: None,
             to: None,
+            deadline: None,
         })
         .unwrap(),
     });
@@ -538,7 +543,7 @@ fn test_collect_protocol_fees_successful_1_fee_only() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -550,7 +555,7 @@ fn test_collect_protocol_fees_successful_1_fee_only() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::from_ratio(1u128, 1000u128),
@@ -602,6 +607,7 @@ fn test_collect_protocol_fees_successful_1_fee_only() {
         belief_price: None,
         max_spread: None,
         to: None,
+        deadline: None,
     };
     let env = mock_env();
     let info = mock_info(
@@ -30,7 +30,7 @@ fn provide_liquidity_cw20_lp() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -42,7 +42,7 @@ fn provide_liquidity_cw20_lp() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::percent(1u64),
@@ -82,7 +82,7 @@ fn provide_liquidity_cw20_lp() {
 
     // unsuccessfully providing liquidity since share becomes zero, MINIMUM_LIQUIDITY_AMOUNT provided
     let msg = ExecuteMsg::ProvideLiquidity {
-        assets: [
+        assets: vec![
             Asset {
                 info: AssetInfo::Token {
                     contract_addr: "asset0000".to_string(),
@@ -104,6 +104,7 @@ fn provide_liquidity_cw20_lp() {
         ],
         slippage_tolerance: None,
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -122,7 +123,7 @@ fn provide_liquidity_cw20_lp() {
 
     // successfully provide liquidity for the exist pool
     let msg = ExecuteMsg::ProvideLiquidity {
-        assets: [
+        assets: vec![
             Asset {
                 info: AssetInfo::Token {
                     contract_addr: "asset0000".to_string(),
@@ -144,6 +145,7 @@ fn provide_liquidity_cw20_lp() {
         ],
         slippage_tolerance: None,
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -227,7 +229,7 @@ fn provide_liquidity_cw20_lp() {
     ]);
 
     let msg = ExecuteMsg::ProvideLiquidity {
-        assets: [
+        assets: vec![
             Asset {
                 info: AssetInfo::Token {
                     contract_addr: "asset0000".to_string(),
@@ -249,6 +251,7 @@ fn provide_liquidity_cw20_lp() {
         ],
         slippage_tolerance: None,
         receiver: Some("staking0000".to_string()), // try changing receiver
+        deadline: None,
     };
 
     let env = mock_env();
@@ -294,7 +297,7 @@ fn provide_liquidity_cw20_lp() {
 
     // check wrong argument
     let msg = ExecuteMsg::ProvideLiquidity {
-        assets: [
+        assets: vec![
             Asset {
                 info: AssetInfo::Token {
                     contract_addr: "asset0000".to_string(),
@@ -316,6 +319,7 @@ fn provide_liquidity_cw20_lp() {
         ],
         slippage_tolerance: None,
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -363,7 +367,7 @@ fn provide_liquidity_cw20_lp() {
 
     // failed because the price is under slippage_tolerance
     let msg = ExecuteMsg::ProvideLiquidity {
-        assets: [
+        assets: vec![
             Asset {
                 info: AssetInfo::Token {
                     contract_addr: "asset0000".to_string(),
@@ -385,6 +389,7 @@ fn provide_liquidity_cw20_lp() {
         ],
         slippage_tolerance: Some(Decimal::from_ratio(1u32, 1000u32)),
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -412,7 +417,7 @@ fn provide_liquidity_cw20_lp() {
 
     // failed because the price is under slippage_tolerance
     let msg = ExecuteMsg::ProvideLiquidity {
-        assets: [
+        assets: vec![
             Asset {
                 info: AssetInfo::Token {
                     contract_addr: "asset0000".to_string(),
@@ -434,6 +439,7 @@ fn provide_liquidity_cw20_lp() {
         ],
         slippage_tolerance: Some(Decimal::from_ratio(1u32, 1000u32)),
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -463,7 +469,7 @@ fn provide_liquidity_cw20_lp() {
 
     // successfully provides
     let msg = ExecuteMsg::ProvideLiquidity {
-        assets: [
+        assets: vec![
             Asset {
                 info: AssetInfo::Token {
                     contract_addr: "asset0000".to_string(),
@@ -485,6 +491,7 @@ fn provide_liquidity_cw20_lp() {
         ],
         slippage_tolerance: Some(Decimal::percent(1)),
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -508,7 +515,7 @@ fn provide_liquidity_cw20_lp() {
 
     // successfully provides
     let msg = ExecuteMsg::ProvideLiquidity {
-        assets: [
+        assets: vec![
             Asset {
                 info: AssetInfo::Token {
                     contract_addr: "asset0000".to_string(),
@@ -530,6 +537,7 @@ fn provide_liquidity_cw20_lp() {
         ],
         slippage_tolerance: Some(Decimal::percent(1)),
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -567,7 +575,7 @@ fn provide_liquidity_tokenfactory_lp() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -579,7 +587,7 @@ fn provide_liquidity_tokenfactory_lp() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::percent(1u64),
@@ -603,7 +611,7 @@ fn provide_liquidity_tokenfactory_lp() {
 
     // successfully provide liquidity for the exist pool
     let msg = ExecuteMsg::ProvideLiquidity {
-        assets: [
+        assets: vec![
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: "uusd".to_string(),
@@ -625,6 +633,7 @@ fn provide_liquidity_tokenfactory_lp() {
         ],
         slippage_tolerance: None,
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -698,7 +707,7 @@ fn provide_liquidity_zero_amount() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -710,7 +719,7 @@ fn provide_liquidity_zero_amount() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::percent(1u64),
@@ -750,7 +759,7 @@ fn provide_liquidity_zero_amount() {
 
     // provide invalid (zero) liquidity
     let msg = ExecuteMsg::ProvideLiquidity {
-        assets: [
+        assets: vec![
             Asset {
                 info: AssetInfo::Token {
                     contract_addr: "asset0000".to_string(),
@@ -772,6 +781,7 @@ fn provide_liquidity_zero_amount() {
         ],
         slippage_tolerance: None,
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -808,7 +818,7 @@ fn provide_liquidity_invalid_minimum_lp_amount() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -820,7 +830,7 @@ fn provide_liquidity_invalid_minimum_lp_amount() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::percent(1u64),
@@ -860,7 +870,7 @@ fn provide_liquidity_invalid_minimum_lp_amount() {
 
     // successfully provide liquidity for the exist pool
     let msg = ExecuteMsg::ProvideLiquidity {
-        assets: [
+        assets: vec![
             Asset {
                 info: AssetInfo::Token {
                     contract_addr: "asset0000".to_string(),
@@ -882,6 +892,7 @@ fn provide_liquidity_invalid_minimum_lp_amount() {
         ],
         slippage_tolerance: None,
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
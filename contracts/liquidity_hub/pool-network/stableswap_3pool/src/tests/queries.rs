@@ -28,7 +28,7 @@ fn test_simulations_asset_missmatch() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -40,7 +40,7 @@ fn test_simulations_asset_missmatch() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::from_ratio(1u128, 1000u128),
@@ -135,7 +135,7 @@ fn test_query_pool() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -147,7 +147,7 @@ fn test_query_pool() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::percent(1u64),
@@ -0,0 +1,257 @@
+use crate::contract::{execute, query};
+use crate::error::ContractError;
+use crate::tests::helpers::{instantiate_pool, native, pool_fees, token};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    coins, from_binary, to_binary, BankMsg, CosmosMsg, Decimal, OwnedDeps, SubMsg, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use white_whale::pool_network::asset::{Asset, AssetInfo};
+use white_whale::pool_network::mock_querier::WasmMockQuerier;
+use white_whale::pool_network::trio::{ExecuteMsg, QueryMsg, ReverseSimulationResponse};
+
+fn asset0000() -> AssetInfo {
+    token("asset0000")
+}
+
+/// Instantiates a uusd-uusdc-asset0000 trio holding 1_000_000_000 of each asset, on top of the
+/// uusd sent along with the swap
+fn instantiate_trio(uusd_sent: Uint128) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    instantiate_pool(
+        vec![native("uusd"), native("uusdc"), asset0000()],
+        &[
+            1_000_000_000u128 + uusd_sent.u128(),
+            1_000_000_000u128,
+            1_000_000_000u128,
+        ],
+        0,
+        pool_fees(Decimal::zero(), Decimal::permille(3u64)),
+        100,
+    )
+}
+
+fn reverse_simulation(offer_asset: AssetInfo, ask_asset: Asset) -> Uint128 {
+    let deps = instantiate_trio(Uint128::zero());
+    let simulation: ReverseSimulationResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ReverseSimulation {
+                ask_asset,
+                offer_asset: Asset {
+                    info: offer_asset,
+                    amount: Uint128::zero(),
+                },
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    simulation.offer_amount
+}
+
+#[test]
+fn swap_exact_out_native_offer() {
+    let ask_asset = Asset {
+        info: asset0000(),
+        amount: Uint128::new(1_000_000u128),
+    };
+    let offer_amount = reverse_simulation(native("uusd"), ask_asset.clone());
+
+    let mut deps = instantiate_trio(Uint128::new(1_100_000u128));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1_100_000u128, "uusd")),
+        ExecuteMsg::SwapExactOut {
+            offer_asset: native("uusd"),
+            ask_asset: ask_asset.clone(),
+            max_offer_amount: Uint128::new(1_100_000u128),
+            to: None,
+            deadline: None,
+        },
+    )
+    .unwrap();
+
+    // the surplus of the funds sent is refunded
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: coins(1_100_000u128 - offer_amount.u128(), "uusd"),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::new(1_000_000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+
+    // more than the maximum offer amount would be needed
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1_100_000u128, "uusd")),
+        ExecuteMsg::SwapExactOut {
+            offer_asset: native("uusd"),
+            ask_asset: ask_asset.clone(),
+            max_offer_amount: Uint128::new(1_000_000u128),
+            to: None,
+            deadline: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MaxOfferAssertion {
+            max_offer_amount: Uint128::new(1_000_000u128),
+            offer_amount,
+        }
+    );
+
+    // the funds sent cap the offer amount
+    let mut deps = instantiate_trio(Uint128::new(1_000_000u128));
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1_000_000u128, "uusd")),
+        ExecuteMsg::SwapExactOut {
+            offer_asset: native("uusd"),
+            ask_asset,
+            max_offer_amount: Uint128::new(1_100_000u128),
+            to: None,
+            deadline: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MaxOfferAssertion {
+            max_offer_amount: Uint128::new(1_000_000u128),
+            offer_amount,
+        }
+    );
+}
+
+#[test]
+fn swap_exact_out_token_offer() {
+    let ask_asset = Asset {
+        info: native("uusdc"),
+        amount: Uint128::new(1_000_000u128),
+    };
+    let offer_amount = reverse_simulation(asset0000(), ask_asset.clone());
+
+    // the offer amount is pulled from the allowance given to the trio
+    let mut deps = instantiate_trio(Uint128::zero());
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::SwapExactOut {
+            offer_asset: asset0000(),
+            ask_asset,
+            max_offer_amount: Uint128::new(1_100_000u128),
+            to: Some("addr0001".to_string()),
+            deadline: None,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: "addr0000".to_string(),
+                    recipient: MOCK_CONTRACT_ADDR.to_string(),
+                    amount: offer_amount,
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0001".to_string(),
+                amount: coins(1_000_000u128, "uusdc"),
+            })),
+        ]
+    );
+}
+
+#[test]
+fn swap_exact_out_invalid_assets() {
+    let mut deps = instantiate_trio(Uint128::zero());
+
+    for (offer_asset, ask_asset_info) in [
+        (native("uusd"), native("uusd")),
+        (native("uusd"), native("uluna")),
+        (native("uluna"), asset0000()),
+    ] {
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            ExecuteMsg::SwapExactOut {
+                offer_asset,
+                ask_asset: Asset {
+                    info: ask_asset_info,
+                    amount: Uint128::new(1_000u128),
+                },
+                max_offer_amount: Uint128::new(1_500u128),
+                to: None,
+                deadline: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AssetMismatch {});
+    }
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::SwapExactOut {
+            offer_asset: native("uusd"),
+            ask_asset: Asset {
+                info: asset0000(),
+                amount: Uint128::zero(),
+            },
+            max_offer_amount: Uint128::new(1_500u128),
+            to: None,
+            deadline: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidZeroAmount {});
+}
+
+#[test]
+fn swap_exact_out_beyond_pool_depth() {
+    let mut deps = instantiate_trio(Uint128::new(1_000u128));
+
+    // the ask amount plus the fees can't be taken out of the pool
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1_000u128, "uusd")),
+        ExecuteMsg::SwapExactOut {
+            offer_asset: native("uusd"),
+            ask_asset: Asset {
+                info: asset0000(),
+                amount: Uint128::new(999_000_000u128),
+            },
+            max_offer_amount: Uint128::new(1_000u128),
+            to: None,
+            deadline: None,
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Std(_)));
+}
@@ -35,7 +35,7 @@ fn proper_initialization_cw20_lp() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -47,7 +47,7 @@ fn proper_initialization_cw20_lp() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::percent(1u64),
@@ -146,7 +146,7 @@ fn proper_initialization_tokenfactory_lp() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -158,7 +158,7 @@ fn proper_initialization_tokenfactory_lp() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::percent(1u64),
@@ -213,7 +213,7 @@ fn intialize_with_burnable_token_factory_asset() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "factory/migaloo1436kxs0w2es6xlqpp9rd35e3d0cjnw4sv8j3a7483sgks29jqwgshqdky4/ampWHALE".to_string(),
             },
@@ -225,7 +225,7 @@ fn intialize_with_burnable_token_factory_asset() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::percent(1u64),
@@ -287,7 +287,7 @@ fn intialize_with_burnable_token_factory_asset() {
 
     // now let's try instantiating the contract with burning fees, it should fail
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "factory/migaloo1436kxs0w2es6xlqpp9rd35e3d0cjnw4sv8j3a7483sgks29jqwgshqdky4/ampWHALE".to_string(),
             },
@@ -299,7 +299,7 @@ fn intialize_with_burnable_token_factory_asset() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::percent(1u64),
@@ -334,7 +334,7 @@ fn test_initialization_invalid_fees() {
     )]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -346,7 +346,7 @@ fn test_initialization_invalid_fees() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::from_ratio(Uint128::from(2u8), Uint128::from(1u8)),
@@ -390,7 +390,7 @@ fn test_initialization_invalid_amp() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -402,7 +402,7 @@ fn test_initialization_invalid_amp() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::percent(1u64),
@@ -445,7 +445,7 @@ fn can_migrate_contract() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -457,7 +457,7 @@ fn can_migrate_contract() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::percent(1u64),
@@ -674,7 +674,7 @@ fn test_update_config_unsuccessful() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -686,7 +686,7 @@ fn test_update_config_unsuccessful() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::percent(1u64),
@@ -766,7 +766,7 @@ fn test_update_config_successful() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -778,7 +778,7 @@ fn test_update_config_successful() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::zero(),
@@ -1,17 +1,22 @@
-use crate::contract::{execute, instantiate, reply};
+use crate::contract::{execute, instantiate, query, reply};
 use crate::error::ContractError;
+use crate::stableswap_math::curve::StableSwap;
 use crate::state::{get_fees_for_asset, store_fee, COLLECTED_PROTOCOL_FEES, LP_SYMBOL};
-use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use crate::tests::helpers::{instantiate_pool, native, pool_fees, token};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    attr, coin, to_binary, BankMsg, Coin, CosmosMsg, Decimal, Reply, SubMsg, SubMsgResponse,
-    SubMsgResult, Uint128, WasmMsg,
+    attr, coin, from_binary, to_binary, BankMsg, Coin, CosmosMsg, Decimal, OwnedDeps, Reply,
+    SubMsg, SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use white_whale::fee::Fee;
-use white_whale::pool_network::asset::AssetInfo;
+use white_whale::pool_network::asset::{Asset, AssetInfo};
 use white_whale::pool_network::denom::MsgBurn;
-use white_whale::pool_network::mock_querier::mock_dependencies;
-use white_whale::pool_network::trio::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolFee};
+use white_whale::pool_network::mock_querier::{mock_dependencies, WasmMockQuerier};
+use white_whale::pool_network::trio::{
+    Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolFee, PoolResponse, QueryMsg,
+    WithdrawImbalanceSimulationResponse,
+};
 
 #[test]
 fn withdraw_liquidity_cw20_lp() {
@@ -36,7 +41,7 @@ fn withdraw_liquidity_cw20_lp() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -48,7 +53,7 @@ fn withdraw_liquidity_cw20_lp() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::percent(1u64),
@@ -105,7 +110,12 @@ fn withdraw_liquidity_cw20_lp() {
     // withdraw liquidity
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
-        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+            target_asset: None,
+            min_receive: None,
+            deadline: None,
+        })
+        .unwrap(),
         amount: Uint128::from(100u128),
     });
 
@@ -236,7 +246,7 @@ fn withdraw_liquidity_token_factory_lp() {
     )]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -248,7 +258,7 @@ fn withdraw_liquidity_token_factory_lp() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::percent(1u64),
@@ -271,7 +281,11 @@ fn withdraw_liquidity_token_factory_lp() {
     let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
 
     // withdraw liquidity
-    let msg = ExecuteMsg::WithdrawLiquidity {};
+    let msg = ExecuteMsg::WithdrawLiquidity {
+        target_asset: None,
+        min_receive: None,
+        deadline: None,
+    };
 
     let env = mock_env();
     let info = mock_info(
@@ -359,7 +373,7 @@ fn withdraw_liquidity_token_factory_lp_wrong_asset() {
     )]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -371,7 +385,7 @@ fn withdraw_liquidity_token_factory_lp_wrong_asset() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::percent(1u64),
@@ -394,7 +408,11 @@ fn withdraw_liquidity_token_factory_lp_wrong_asset() {
     let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
 
     // withdraw liquidity
-    let msg = ExecuteMsg::WithdrawLiquidity {};
+    let msg = ExecuteMsg::WithdrawLiquidity {
+        target_asset: None,
+        min_receive: None,
+        deadline: None,
+    };
 
     let env = mock_env();
     let info = mock_info(
@@ -432,7 +450,7 @@ fn test_withdrawal_unauthorized() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -444,7 +462,7 @@ fn test_withdrawal_unauthorized() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::percent(1u64),
@@ -468,7 +486,12 @@ fn test_withdrawal_unauthorized() {
     // withdraw liquidity should fail
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
-        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+            target_asset: None,
+            min_receive: None,
+            deadline: None,
+        })
+        .unwrap(),
         amount: Uint128::from(100u128),
     });
 
@@ -505,7 +528,7 @@ fn test_withdrawal_wrong_message() {
     ]);
 
     let msg = InstantiateMsg {
-        asset_infos: [
+        asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -517,7 +540,7 @@ fn test_withdrawal_wrong_message() {
             },
         ],
         token_code_id: 10u64,
-        asset_decimals: [6u8, 8u8, 10u8],
+        asset_decimals: vec![6u8, 8u8, 10u8],
         pool_fees: PoolFee {
             protocol_fee: Fee {
                 share: Decimal::percent(1u64),
@@ -554,3 +577,451 @@ fn test_withdrawal_wrong_message() {
         _ => panic!("should return ContractError::Std"),
     }
 }
+
+fn instantiate_withdrawal_trio() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    instantiate_pool(
+        vec![native("uusd"), token("asset0000"), token("asset0001")],
+        &[1_000_000, 1_000_000, 1_000_000],
+        3_000_000,
+        pool_fees(Decimal::percent(1u64), Decimal::percent(1u64)),
+        1000,
+    )
+}
+
+#[test]
+fn withdraw_liquidity_to_single_asset() {
+    let mut deps = instantiate_withdrawal_trio();
+
+    let (expected_refund, expected_fee) = StableSwap::new(1000, 1000, 0, 0, 0)
+        .compute_withdraw_one(
+            Uint128::from(300_000u128),
+            Uint128::from(3_000_000u128),
+            Uint128::from(1_000_000u128),
+            &[Uint128::from(1_000_000u128), Uint128::from(1_000_000u128)],
+            Decimal::percent(2u64),
+        )
+        .unwrap();
+
+    // withdrawing a tenth of the balanced pool into a single asset returns a bit less than a
+    // third of the liquidity due to the fees
+    assert!(expected_refund < Uint128::from(300_000u128));
+    assert!(expected_refund > Uint128::from(290_000u128));
+    assert!(!expected_fee.is_zero());
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+            target_asset: Some(AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            }),
+            min_receive: Some(expected_refund),
+            deadline: None,
+        })
+        .unwrap(),
+        amount: Uint128::from(300_000u128),
+    });
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("liquidity0000", &[]),
+        msg,
+    )
+    .unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: vec![coin(expected_refund.u128(), "uusd")],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "liquidity0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Burn {
+                    amount: Uint128::from(300_000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+    assert_eq!(
+        res.attributes.get(3).expect("no log"),
+        &attr("refund_assets", format!("{expected_refund}uusd"))
+    );
+
+    // half of the fee goes to the protocol, the other half stays in the pool
+    let protocol_fee = get_fees_for_asset(
+        deps.as_mut().storage,
+        "uusd".to_string(),
+        COLLECTED_PROTOCOL_FEES,
+    )
+    .unwrap();
+    assert_eq!(
+        protocol_fee.amount,
+        expected_fee.multiply_ratio(1u128, 2u128)
+    );
+}
+
+#[test]
+fn withdraw_liquidity_to_single_asset_minimum_receive() {
+    let mut deps = instantiate_withdrawal_trio();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+            target_asset: Some(AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            }),
+            min_receive: Some(Uint128::from(300_000u128)),
+            deadline: None,
+        })
+        .unwrap(),
+        amount: Uint128::from(300_000u128),
+    });
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("liquidity0000", &[]),
+        msg,
+    )
+    .unwrap_err();
+    match err {
+        ContractError::MinimumReceiveAssertion {
+            minimum_receive, ..
+        } => assert_eq!(minimum_receive, Uint128::from(300_000u128)),
+        _ => panic!("should return ContractError::MinimumReceiveAssertion"),
+    }
+}
+
+#[test]
+fn withdraw_liquidity_to_unknown_asset() {
+    let mut deps = instantiate_withdrawal_trio();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+            target_asset: Some(AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            }),
+            min_receive: None,
+            deadline: None,
+        })
+        .unwrap(),
+        amount: Uint128::from(300_000u128),
+    });
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("liquidity0000", &[]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AssetMismatch {});
+}
+
+#[test]
+fn withdraw_imbalance_cw20_lp() {
+    let mut deps = instantiate_withdrawal_trio();
+
+    let (expected_burn, expected_fees) = StableSwap::new(1000, 1000, 0, 0, 0)
+        .compute_withdraw_imbalance(
+            &[
+                Uint128::from(100_000u128),
+                Uint128::zero(),
+                Uint128::from(50_000u128),
+            ],
+            &[
+                Uint128::from(1_000_000u128),
+                Uint128::from(1_000_000u128),
+                Uint128::from(1_000_000u128),
+            ],
+            Uint128::from(3_000_000u128),
+            Decimal::percent(2u64),
+        )
+        .unwrap();
+
+    // the imbalance costs a bit more LP than a balanced withdrawal of the same value
+    assert!(expected_burn > Uint128::from(150_000u128));
+    assert!(expected_burn < Uint128::from(151_000u128));
+
+    let assets = vec![
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            amount: Uint128::from(50_000u128),
+        },
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(100_000u128),
+        },
+    ];
+
+    let simulation: WithdrawImbalanceSimulationResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SimulateWithdrawImbalance {
+                assets: assets.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(simulation.burn_amount, expected_burn);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::WithdrawImbalance {
+            assets,
+            max_burn_amount: expected_burn,
+            deadline: None,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: vec![coin(100_000u128, "uusd")],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0001".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::from(50_000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "liquidity0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::BurnFrom {
+                    owner: "addr0000".to_string(),
+                    amount: expected_burn,
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+
+    // half of the fees go to the protocol, the other half stays in the pool
+    for (denom, expected_fee) in [("uusd", expected_fees[0]), ("asset0000", expected_fees[1])] {
+        let protocol_fee = get_fees_for_asset(
+            deps.as_mut().storage,
+            denom.to_string(),
+            COLLECTED_PROTOCOL_FEES,
+        )
+        .unwrap();
+        assert_eq!(
+            protocol_fee.amount,
+            expected_fee.multiply_ratio(1u128, 2u128)
+        );
+    }
+    assert_eq!(
+        simulation.protocol_fee_amounts[2].amount,
+        expected_fees[2].multiply_ratio(1u128, 2u128)
+    );
+}
+
+#[test]
+fn withdraw_imbalance_token_factory_lp() {
+    let lp_denom = format!("{}/{MOCK_CONTRACT_ADDR}/{LP_SYMBOL}", "factory");
+
+    let mut deps = mock_dependencies(&[
+        coin(1_000_000u128, "uusd"),
+        coin(1_000_000u128, "uusdc"),
+        coin(1_000_000u128, "udai"),
+    ]);
+    deps.querier.with_balance(&[(
+        &"addr0000".to_string(),
+        vec![coin(3_000_000u128, lp_denom.clone())],
+    )]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uusdc".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "udai".to_string(),
+            },
+        ],
+        token_code_id: 10u64,
+        asset_decimals: vec![6u8, 6u8, 6u8],
+        pool_fees: PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::zero(),
+            },
+            swap_fee: Fee {
+                share: Decimal::percent(1u64),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+        },
+        fee_collector_addr: "collector".to_string(),
+        amp_factor: 1000,
+        token_factory_lp: true,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let total_share =
+        from_binary::<PoolResponse>(&query(deps.as_ref(), mock_env(), QueryMsg::Pool {}).unwrap())
+            .unwrap()
+            .total_share;
+    let (expected_burn, _) = StableSwap::new(1000, 1000, 0, 0, 0)
+        .compute_withdraw_imbalance(
+            &[Uint128::zero(), Uint128::from(10_000u128), Uint128::zero()],
+            &[Uint128::from(1_000_000u128); 3],
+            total_share,
+            Decimal::percent(1u64),
+        )
+        .unwrap();
+
+    let msg = ExecuteMsg::WithdrawImbalance {
+        assets: vec![Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusdc".to_string(),
+            },
+            amount: Uint128::from(10_000u128),
+        }],
+        max_burn_amount: Uint128::from(20_000u128),
+        deadline: None,
+    };
+
+    // the LP tokens must be sent along
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AssetMismatch {});
+
+    // the LP tokens sent cap the amount to burn
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1_000u128, lp_denom.clone())]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MaxBurnAssertion {
+            max_burn_amount: Uint128::from(1_000u128),
+            burn_amount: expected_burn,
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(15_000u128, lp_denom.clone())]),
+        msg,
+    )
+    .unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: vec![coin(10_000u128, "uusdc")],
+            })),
+            SubMsg::new(<MsgBurn as Into<CosmosMsg>>::into(MsgBurn {
+                sender: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Some(white_whale::pool_network::denom::Coin {
+                    denom: lp_denom.clone(),
+                    amount: expected_burn.to_string(),
+                }),
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: vec![coin(15_000u128 - expected_burn.u128(), lp_denom)],
+            })),
+        ]
+    );
+}
+
+#[test]
+fn withdraw_imbalance_failures() {
+    let mut deps = instantiate_withdrawal_trio();
+
+    let uusd = |amount: u128| Asset {
+        info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        amount: Uint128::from(amount),
+    };
+
+    for (assets, error) in [
+        (
+            vec![Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uwhale".to_string(),
+                },
+                amount: Uint128::from(100u128),
+            }],
+            ContractError::AssetMismatch {},
+        ),
+        (vec![uusd(100), uusd(100)], ContractError::AssetMismatch {}),
+        (vec![uusd(0)], ContractError::InvalidZeroAmount {}),
+        // the pool can't be drained
+        (
+            vec![uusd(1_000_000)],
+            ContractError::LiquidityWithdrawalComputation {},
+        ),
+    ] {
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            ExecuteMsg::WithdrawImbalance {
+                assets,
+                max_burn_amount: Uint128::from(3_000_000u128),
+                deadline: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, error);
+    }
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::WithdrawImbalance {
+            assets: vec![uusd(100_000)],
+            max_burn_amount: Uint128::from(100_000u128),
+            deadline: None,
+        },
+    )
+    .unwrap_err();
+    match err {
+        ContractError::MaxBurnAssertion {
+            max_burn_amount,
+            burn_amount,
+        } => {
+            assert_eq!(max_burn_amount, Uint128::from(100_000u128));
+            assert!(burn_amount > max_burn_amount);
+        }
+        _ => panic!("should return ContractError::MaxBurnAssertion"),
+    }
+}
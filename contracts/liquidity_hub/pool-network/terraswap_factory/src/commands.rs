@@ -1,29 +1,35 @@
+use std::mem::discriminant;
+
 use crate::contract::{CREATE_PAIR_RESPONSE, CREATE_TRIO_RESPONSE};
 
 use cosmwasm_std::{
-    to_binary, wasm_execute, CosmosMsg, DepsMut, Env, MessageInfo, ReplyOn, Response, SubMsg,
-    WasmMsg,
+    to_binary, to_vec, wasm_execute, Addr, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Empty, Env,
+    MessageInfo, Order, ReplyOn, Response, StdResult, SubMsg, WasmMsg,
 };
 
+use cw20::Cw20ExecuteMsg;
 use white_whale::pool_network;
-use white_whale::pool_network::asset::{AssetInfo, PairType};
+use white_whale::pool_network::asset::{Asset, AssetInfo, AssetInfoRaw, PairType};
 use white_whale::pool_network::pair::{
     FeatureToggle, InstantiateMsg as PairInstantiateMsg, MigrateMsg as PairMigrateMsg, PoolFee,
 };
-use white_whale::pool_network::querier::query_balance;
 use white_whale::pool_network::trio::{
     FeatureToggle as TrioFeatureToggle, InstantiateMsg as TrioInstantiateMsg,
-    MigrateMsg as TrioMigrateMsg, PoolFee as TrioPoolFee, RampAmp,
+    MigrateMsg as TrioMigrateMsg, PoolFee as TrioPoolFee, RampAmp, MAX_TRIO_ASSETS,
+    MIN_TRIO_ASSETS,
 };
 use white_whale::pool_network::{pair, trio};
 
 use crate::error::ContractError;
 use crate::state::{
-    add_allow_native_token, pair_key, trio_key, Config, TmpPairInfo, TmpTrioInfo, CONFIG, PAIRS,
-    TMP_PAIR_INFO, TMP_TRIO_INFO, TRIOS,
+    add_allow_native_token, default_pair_identifier, may_load_pair, pair_key, read_pairs,
+    read_trios, remove_asset_pool, trio_key, Config, InitialLiquidity, TmpPairInfo, TmpTrioInfo,
+    ALLOWED_CREATORS, CONFIG, DEFAULT_LIMIT, DEFAULT_PAIRS, MAX_LIMIT, PAIRS, TMP_PAIR_INFO,
+    TMP_TRIO_INFO, TRIOS,
 };
 
 /// Updates the contract's [Config]
+#[allow(clippy::too_many_arguments)]
 pub fn update_config(
     deps: DepsMut,
     owner: Option<String>,
@@ -31,6 +37,8 @@ pub fn update_config(
     token_code_id: Option<u64>,
     pair_code_id: Option<u64>,
     trio_code_id: Option<u64>,
+    pool_creation_fee: Option<Asset>,
+    allowlist_enabled: Option<bool>,
 ) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
@@ -57,11 +65,223 @@ pub fn update_config(
         config.fee_collector_addr = deps.api.addr_validate(fee_collector_addr.as_str())?;
     }
 
+    if let Some(pool_creation_fee) = pool_creation_fee {
+        // the fee is taken from the funds sent along with the pool creation
+        if !pool_creation_fee.is_native_token() {
+            return Err(ContractError::InvalidPoolCreationFee {});
+        }
+
+        config.pool_creation_fee = if pool_creation_fee.amount.is_zero() {
+            None
+        } else {
+            Some(pool_creation_fee)
+        };
+    }
+
+    if let Some(allowlist_enabled) = allowlist_enabled {
+        config.allowlist_enabled = allowlist_enabled;
+    }
+
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new().add_attribute("action", "update_config"))
 }
 
+/// Adds and removes the creators allowed to create pools with the given asset
+pub fn update_allowed_creators(
+    deps: DepsMut,
+    asset_info: AssetInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    let asset_info_raw = asset_info.to_raw(deps.api)?;
+
+    for creator in add.iter() {
+        let creator = deps.api.addr_validate(creator)?;
+        ALLOWED_CREATORS.save(
+            deps.storage,
+            (asset_info_raw.as_bytes(), &creator),
+            &Empty {},
+        )?;
+    }
+
+    for creator in remove.iter() {
+        let creator = deps.api.addr_validate(creator)?;
+        ALLOWED_CREATORS.remove(deps.storage, (asset_info_raw.as_bytes(), &creator));
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_allowed_creators"),
+        ("asset_info", &asset_info.to_string()),
+        ("added", &add.join(", ")),
+        ("removed", &remove.join(", ")),
+    ]))
+}
+
+/// Asserts the sender can create a pool with the given assets. When the allowlist is enabled, the
+/// assets with allowed creators are protected, and anyone other than the owner must be an allowed
+/// creator of every protected asset of the pool. Pools without protected assets can only be created
+/// by the owner.
+fn assert_can_create_pool(
+    deps: Deps,
+    config: &Config,
+    sender: &Addr,
+    asset_infos: &[AssetInfoRaw],
+) -> Result<(), ContractError> {
+    if !config.allowlist_enabled || deps.api.addr_canonicalize(sender.as_str())? == config.owner {
+        return Ok(());
+    }
+
+    let protected_assets = asset_infos
+        .iter()
+        .filter(|asset_info| {
+            ALLOWED_CREATORS
+                .prefix(asset_info.as_bytes())
+                .keys_raw(deps.storage, None, None, Order::Ascending)
+                .next()
+                .is_some()
+        })
+        .collect::<Vec<&AssetInfoRaw>>();
+
+    if !protected_assets.is_empty()
+        && protected_assets
+            .iter()
+            .all(|asset_info| ALLOWED_CREATORS.has(deps.storage, (asset_info.as_bytes(), sender)))
+    {
+        return Ok(());
+    }
+
+    Err(ContractError::CreatorNotAllowed {})
+}
+
+/// The maximum length of a pair identifier
+const MAX_PAIR_IDENTIFIER_LENGTH: usize = 64;
+
+/// Validates a pair identifier is made of 1 to [MAX_PAIR_IDENTIFIER_LENGTH] ASCII letters, digits,
+/// `.`, `-` or `_`
+fn validate_pair_identifier(pair_identifier: &str) -> Result<(), ContractError> {
+    if pair_identifier.is_empty()
+        || pair_identifier.len() > MAX_PAIR_IDENTIFIER_LENGTH
+        || !pair_identifier
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+    {
+        return Err(ContractError::InvalidPairIdentifier {});
+    }
+
+    Ok(())
+}
+
+/// Takes the pool creation fee out of the funds sent along with the pool creation. Returns the
+/// message sending the fee to the fee collector, if any, and the funds left to instantiate the
+/// pool with.
+fn collect_pool_creation_fee(
+    config: &Config,
+    mut funds: Vec<Coin>,
+) -> Result<(Option<CosmosMsg>, Vec<Coin>), ContractError> {
+    let Some(pool_creation_fee) = &config.pool_creation_fee else {
+        return Ok((None, funds));
+    };
+
+    let AssetInfo::NativeToken { denom } = &pool_creation_fee.info else {
+        return Err(ContractError::InvalidPoolCreationFee {});
+    };
+
+    let fee_missing = || ContractError::PoolCreationFeeMissing {
+        fee: pool_creation_fee.to_string(),
+    };
+    let coin = funds
+        .iter_mut()
+        .find(|coin| &coin.denom == denom)
+        .ok_or_else(fee_missing)?;
+    coin.amount = coin
+        .amount
+        .checked_sub(pool_creation_fee.amount)
+        .map_err(|_| fee_missing())?;
+    funds.retain(|coin| !coin.amount.is_zero());
+
+    Ok((
+        Some(CosmosMsg::Bank(BankMsg::Send {
+            to_address: config.fee_collector_addr.to_string(),
+            amount: vec![Coin {
+                denom: denom.clone(),
+                amount: pool_creation_fee.amount,
+            }],
+        })),
+        funds,
+    ))
+}
+
+/// Collects the initial liquidity of a pool being created. The native assets are taken out of the
+/// funds sent along with the pool creation, and the cw20 assets are transferred from the sender to
+/// the factory. Returns the messages transferring the cw20 assets and the initial liquidity to
+/// provide once the pool is created.
+fn collect_initial_liquidity(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    asset_infos: &[AssetInfo],
+    initial_liquidity: Option<Vec<Asset>>,
+    receiver: Option<String>,
+    funds: &mut Vec<Coin>,
+) -> Result<(Vec<CosmosMsg>, Option<InitialLiquidity>), ContractError> {
+    let Some(assets) = initial_liquidity else {
+        return Ok((vec![], None));
+    };
+
+    // the pool assets are distinct, so each of them must be in the initial liquidity exactly once
+    if assets.len() != asset_infos.len()
+        || asset_infos.iter().any(|asset_info| {
+            !assets
+                .iter()
+                .any(|asset| &asset.info == asset_info && !asset.amount.is_zero())
+        })
+    {
+        return Err(ContractError::InvalidInitialLiquidity {});
+    }
+
+    let mut messages = vec![];
+    for asset in assets.iter() {
+        match &asset.info {
+            AssetInfo::NativeToken { denom } => {
+                let funds_missing = || ContractError::InitialLiquidityFundsMissing {
+                    asset: asset.to_string(),
+                };
+                let coin = funds
+                    .iter_mut()
+                    .find(|coin| &coin.denom == denom)
+                    .ok_or_else(funds_missing)?;
+                coin.amount = coin
+                    .amount
+                    .checked_sub(asset.amount)
+                    .map_err(|_| funds_missing())?;
+            }
+            AssetInfo::Token { contract_addr } => {
+                messages.push(
+                    wasm_execute(
+                        contract_addr,
+                        &Cw20ExecuteMsg::TransferFrom {
+                            owner: sender.to_string(),
+                            recipient: env.contract.address.to_string(),
+                            amount: asset.amount,
+                        },
+                        vec![],
+                    )?
+                    .into(),
+                );
+            }
+        }
+    }
+    funds.retain(|coin| !coin.amount.is_zero());
+
+    let receiver = match receiver {
+        Some(receiver) => deps.api.addr_validate(&receiver)?,
+        None => sender.clone(),
+    };
+
+    Ok((messages, Some(InitialLiquidity { assets, receiver })))
+}
+
 /// Updates a pair config
 pub fn update_pair_config(
     deps: DepsMut,
@@ -70,6 +290,7 @@ pub fn update_pair_config(
     fee_collector_addr: Option<String>,
     pool_fees: Option<PoolFee>,
     feature_toggle: Option<FeatureToggle>,
+    amp_factor: Option<RampAmp>,
 ) -> Result<Response, ContractError> {
     Ok(Response::new()
         .add_message(wasm_execute(
@@ -79,13 +300,26 @@ pub fn update_pair_config(
                 fee_collector_addr,
                 pool_fees,
                 feature_toggle,
+                amp_factor,
             },
             vec![],
         )?)
         .add_attribute("action", "update_pair_config"))
 }
 
+/// Stops the amplification coefficient ramp of a stableswap pair
+pub fn stop_pair_amp_ramp(deps: DepsMut, pair_addr: String) -> Result<Response, ContractError> {
+    Ok(Response::new()
+        .add_message(wasm_execute(
+            deps.api.addr_validate(pair_addr.as_str())?.to_string(),
+            &pool_network::pair::ExecuteMsg::StopAmpRamp {},
+            vec![],
+        )?)
+        .add_attribute("action", "stop_pair_amp_ramp"))
+}
+
 /// Creates a Pair
+#[allow(clippy::too_many_arguments)]
 pub fn create_pair(
     deps: DepsMut,
     env: Env,
@@ -94,6 +328,9 @@ pub fn create_pair(
     pool_fees: PoolFee,
     pair_type: PairType,
     token_factory_lp: bool,
+    pair_identifier: Option<String>,
+    initial_liquidity: Option<[Asset; 2]>,
+    receiver: Option<String>,
 ) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
 
@@ -101,6 +338,8 @@ pub fn create_pair(
         return Err(ContractError::SameAsset {});
     }
 
+    pair_type.is_valid()?;
+
     let asset_1_decimal =
         match asset_infos[0].query_decimals(env.contract.address.clone(), &deps.querier) {
             Ok(decimal) => decimal,
@@ -125,21 +364,41 @@ pub fn create_pair(
         asset_infos[0].to_raw(deps.api)?,
         asset_infos[1].to_raw(deps.api)?,
     ];
+    assert_can_create_pool(deps.as_ref(), &config, &info.sender, &raw_infos)?;
 
     let asset_decimals = [asset_1_decimal, asset_2_decimal];
 
+    let pair_identifier = match pair_identifier {
+        Some(pair_identifier) => pair_identifier,
+        None => default_pair_identifier(&pair_type, &pool_fees)?,
+    };
+    validate_pair_identifier(&pair_identifier)?;
+
     let pair_key = pair_key(&raw_infos);
-    if let Ok(Some(_)) = PAIRS.may_load(deps.storage, &pair_key) {
+    if PAIRS.has(deps.storage, (&pair_key, &pair_identifier)) {
         return Err(ContractError::ExistingPair {});
     }
 
+    let (fee_msg, mut funds) = collect_pool_creation_fee(&config, info.funds)?;
+    let (transfer_msgs, initial_liquidity) = collect_initial_liquidity(
+        deps.as_ref(),
+        &env,
+        &info.sender,
+        &asset_infos,
+        initial_liquidity.map(Vec::from),
+        receiver,
+        &mut funds,
+    )?;
+
     TMP_PAIR_INFO.save(
         deps.storage,
         &TmpPairInfo {
             pair_key,
+            pair_identifier: pair_identifier.clone(),
             asset_infos: raw_infos,
             asset_decimals,
             pair_type: pair_type.clone(),
+            initial_liquidity,
         },
     )?;
 
@@ -154,13 +413,16 @@ pub fn create_pair(
             ("pair", &format!("{asset0_label}-{asset1_label}")),
             ("pair_label", pair_label.as_str()),
             ("pair_type", pair_type.get_label()),
+            ("pair_identifier", &pair_identifier),
         ])
+        .add_messages(fee_msg)
+        .add_messages(transfer_msgs)
         .add_submessage(SubMsg {
             id: CREATE_PAIR_RESPONSE,
             gas_limit: None,
             msg: CosmosMsg::Wasm(WasmMsg::Instantiate {
                 code_id: config.pair_code_id,
-                funds: info.funds,
+                funds,
                 admin: Some(env.contract.address.to_string()),
                 label: pair_label,
                 msg: to_binary(&PairInstantiateMsg {
@@ -203,96 +465,101 @@ pub fn update_trio_config(
 }
 
 /// Creates a Trio
+#[allow(clippy::too_many_arguments)]
 pub fn create_trio(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    asset_infos: [AssetInfo; 3],
+    asset_infos: Vec<AssetInfo>,
     pool_fees: TrioPoolFee,
     amp_factor: u64,
     token_factory_lp: bool,
+    initial_liquidity: Option<Vec<Asset>>,
+    receiver: Option<String>,
 ) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
 
-    if (asset_infos[0] == asset_infos[1])
-        || (asset_infos[0] == asset_infos[2])
-        || (asset_infos[1] == asset_infos[2])
+    if asset_infos.len() < MIN_TRIO_ASSETS || asset_infos.len() > MAX_TRIO_ASSETS {
+        return Err(ContractError::InvalidNumberOfAssets {
+            min: MIN_TRIO_ASSETS,
+            max: MAX_TRIO_ASSETS,
+        });
+    }
+
+    if asset_infos
+        .iter()
+        .enumerate()
+        .any(|(i, asset_info)| asset_infos[i + 1..].contains(asset_info))
     {
         return Err(ContractError::SameAsset {});
     }
 
-    let asset_1_decimal =
-        match asset_infos[0].query_decimals(env.contract.address.clone(), &deps.querier) {
-            Ok(decimal) => decimal,
-            Err(_) => {
-                return Err(ContractError::InvalidAsset {
-                    asset: asset_infos[0].to_string(),
-                });
-            }
-        };
-
-    let asset_2_decimal =
-        match asset_infos[1].query_decimals(env.contract.address.clone(), &deps.querier) {
-            Ok(decimal) => decimal,
-            Err(_) => {
-                return Err(ContractError::InvalidAsset {
-                    asset: asset_infos[1].to_string(),
-                });
-            }
-        };
-    let asset_3_decimal =
-        match asset_infos[2].query_decimals(env.contract.address.clone(), &deps.querier) {
-            Ok(decimal) => decimal,
-            Err(_) => {
-                return Err(ContractError::InvalidAsset {
-                    asset: asset_infos[2].to_string(),
-                });
-            }
-        };
+    let asset_decimals = asset_infos
+        .iter()
+        .map(|asset_info| {
+            asset_info
+                .query_decimals(env.contract.address.clone(), &deps.querier)
+                .map_err(|_| ContractError::InvalidAsset {
+                    asset: asset_info.to_string(),
+                })
+        })
+        .collect::<Result<Vec<u8>, ContractError>>()?;
 
-    let raw_infos = [
-        asset_infos[0].to_raw(deps.api)?,
-        asset_infos[1].to_raw(deps.api)?,
-        asset_infos[2].to_raw(deps.api)?,
-    ];
+    let raw_infos = asset_infos
+        .iter()
+        .map(|asset_info| asset_info.to_raw(deps.api))
+        .collect::<StdResult<Vec<AssetInfoRaw>>>()?;
 
-    let asset_decimals = [asset_1_decimal, asset_2_decimal, asset_3_decimal];
+    assert_can_create_pool(deps.as_ref(), &config, &info.sender, &raw_infos)?;
 
     let trio_key = trio_key(&raw_infos);
     if let Ok(Some(_)) = TRIOS.may_load(deps.storage, &trio_key) {
         return Err(ContractError::ExistingTrio {});
     }
 
+    let (fee_msg, mut funds) = collect_pool_creation_fee(&config, info.funds)?;
+    let (transfer_msgs, initial_liquidity) = collect_initial_liquidity(
+        deps.as_ref(),
+        &env,
+        &info.sender,
+        &asset_infos,
+        initial_liquidity,
+        receiver,
+        &mut funds,
+    )?;
+
     TMP_TRIO_INFO.save(
         deps.storage,
         &TmpTrioInfo {
             trio_key,
             asset_infos: raw_infos,
-            asset_decimals,
+            asset_decimals: asset_decimals.clone(),
+            initial_liquidity,
         },
     )?;
 
-    // prepare labels for creating the pair token with a meaningful name
-    let asset0_label = asset_infos[0].clone().get_label(&deps.as_ref())?;
-    let asset1_label = asset_infos[1].clone().get_label(&deps.as_ref())?;
-    let asset2_label = asset_infos[2].clone().get_label(&deps.as_ref())?;
-    let trio_label = format!("{asset0_label}-{asset1_label}-{asset2_label} trio");
+    // prepare labels for creating the trio token with a meaningful name
+    let asset_labels = asset_infos
+        .iter()
+        .map(|asset_info| asset_info.clone().get_label(&deps.as_ref()))
+        .collect::<StdResult<Vec<String>>>()?
+        .join("-");
+    let trio_label = format!("{asset_labels} trio");
 
     Ok(Response::new()
         .add_attributes(vec![
             ("action", "create_trio"),
-            (
-                "trio",
-                &format!("{asset0_label}-{asset1_label}-{asset2_label}"),
-            ),
+            ("trio", asset_labels.as_str()),
             ("trio_label", trio_label.as_str()),
         ])
+        .add_messages(fee_msg)
+        .add_messages(transfer_msgs)
         .add_submessage(SubMsg {
             id: CREATE_TRIO_RESPONSE,
             gas_limit: None,
             msg: CosmosMsg::Wasm(WasmMsg::Instantiate {
                 code_id: config.trio_code_id,
-                funds: info.funds,
+                funds,
                 admin: Some(env.contract.address.to_string()),
                 label: trio_label,
                 msg: to_binary(&TrioInstantiateMsg {
@@ -313,6 +580,7 @@ pub fn remove_pair(
     deps: DepsMut,
     _env: Env,
     asset_infos: [AssetInfo; 2],
+    pair_identifier: Option<String>,
 ) -> Result<Response, ContractError> {
     let raw_infos = [
         asset_infos[0].to_raw(deps.api)?,
@@ -320,16 +588,21 @@ pub fn remove_pair(
     ];
 
     let pair_key = pair_key(&raw_infos);
-    let pair = PAIRS.may_load(deps.storage, &pair_key)?;
+    let pair = may_load_pair(deps.storage, &pair_key, pair_identifier)?;
 
-    let Some(pair) = pair else {
+    let Some((pair_identifier, pair)) = pair else {
         return Err(ContractError::UnExistingPair {});
     };
 
-    PAIRS.remove(deps.storage, &pair_key);
+    PAIRS.remove(deps.storage, (&pair_key, &pair_identifier));
+    if DEFAULT_PAIRS.may_load(deps.storage, &pair_key)?.as_ref() == Some(&pair_identifier) {
+        DEFAULT_PAIRS.remove(deps.storage, &pair_key);
+    }
+    remove_asset_pool(deps.storage, &pair.contract_addr)?;
 
     Ok(Response::new().add_attributes(vec![
         ("action", "remove_pair"),
+        ("pair_identifier", &pair_identifier),
         (
             "pair_contract_addr",
             deps.api.addr_humanize(&pair.contract_addr)?.as_ref(),
@@ -340,13 +613,12 @@ pub fn remove_pair(
 pub fn remove_trio(
     deps: DepsMut,
     _env: Env,
-    asset_infos: [AssetInfo; 3],
+    asset_infos: Vec<AssetInfo>,
 ) -> Result<Response, ContractError> {
-    let raw_infos = [
-        asset_infos[0].to_raw(deps.api)?,
-        asset_infos[1].to_raw(deps.api)?,
-        asset_infos[2].to_raw(deps.api)?,
-    ];
+    let raw_infos = asset_infos
+        .iter()
+        .map(|asset_info| asset_info.to_raw(deps.api))
+        .collect::<StdResult<Vec<AssetInfoRaw>>>()?;
 
     let trio_key = trio_key(&raw_infos);
     let trio = TRIOS.may_load(deps.storage, &trio_key)?;
@@ -356,6 +628,7 @@ pub fn remove_trio(
     };
 
     TRIOS.remove(deps.storage, &trio_key);
+    remove_asset_pool(deps.storage, &trio.contract_addr)?;
 
     Ok(Response::new().add_attributes(vec![
         ("action", "remove_trio"),
@@ -366,18 +639,12 @@ pub fn remove_trio(
     ]))
 }
 
-/// Adds native/ibc token with decimals to the factory's whitelist so it can create pairs with that asset
+/// Registers the decimals of a native/ibc token, overriding the ones found in its bank metadata
 pub fn add_native_token_decimals(
     deps: DepsMut,
-    env: Env,
     denom: String,
     decimals: u8,
 ) -> Result<Response, ContractError> {
-    let balance = query_balance(&deps.querier, env.contract.address, denom.to_string())?;
-    if balance.is_zero() {
-        return Err(ContractError::InvalidVerificationBalance {});
-    }
-
     add_allow_native_token(deps.storage, denom.to_string(), decimals)?;
 
     Ok(Response::new().add_attributes(vec![
@@ -413,6 +680,122 @@ pub fn execute_migrate_pair(
     )
 }
 
+/// Migrates a page of the pairs created by the factory, optionally only the ones of the given type.
+/// The `start_after` and `start_after_identifier` of the next page are reported in the attributes
+/// while there are pairs left.
+pub fn execute_migrate_pairs(
+    deps: DepsMut,
+    code_id: Option<u64>,
+    start_after: Option<[AssetInfo; 2]>,
+    start_after_identifier: Option<String>,
+    limit: Option<u32>,
+    pair_type: Option<PairType>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let pair_code_id = code_id.unwrap_or(config.pair_code_id);
+
+    let start_after = start_after
+        .map(|asset_infos| -> StdResult<[AssetInfoRaw; 2]> {
+            Ok([
+                asset_infos[0].to_raw(deps.api)?,
+                asset_infos[1].to_raw(deps.api)?,
+            ])
+        })
+        .transpose()?;
+    let pairs = read_pairs(
+        deps.storage,
+        deps.api,
+        start_after,
+        start_after_identifier,
+        limit,
+    )?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attributes = vec![
+        ("action", "migrate_pairs".to_string()),
+        ("code_id", pair_code_id.to_string()),
+    ];
+    for (_, pair) in pairs.iter() {
+        // pairs are filtered by variant only, regardless of their amp or weights
+        if matches!(&pair_type, Some(pair_type) if discriminant(pair_type) != discriminant(&pair.pair_type))
+        {
+            continue;
+        }
+
+        messages.push(CosmosMsg::Wasm(WasmMsg::Migrate {
+            contract_addr: pair.contract_addr.clone(),
+            new_code_id: pair_code_id,
+            msg: to_binary(&PairMigrateMsg {})?,
+        }));
+        attributes.push(("pair_contract_addr", pair.contract_addr.clone()));
+    }
+    attributes.push(("migrated_pairs", messages.len().to_string()));
+
+    if pairs.len() >= limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize {
+        if let Some((last_pair_identifier, last_pair)) = pairs.last() {
+            attributes.push((
+                "next_start_after",
+                String::from_utf8_lossy(&to_vec(&last_pair.asset_infos)?).to_string(),
+            ));
+            attributes.push(("next_start_after_identifier", last_pair_identifier.clone()));
+        }
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attributes))
+}
+
+/// Migrates a page of the trios created by the factory. The `start_after` of the next page is
+/// reported in the attributes while there are trios left.
+pub fn execute_migrate_trios(
+    deps: DepsMut,
+    code_id: Option<u64>,
+    start_after: Option<Vec<AssetInfo>>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let trio_code_id = code_id.unwrap_or(config.trio_code_id);
+
+    let start_after = start_after
+        .map(|asset_infos| {
+            asset_infos
+                .iter()
+                .map(|asset_info| asset_info.to_raw(deps.api))
+                .collect::<StdResult<Vec<AssetInfoRaw>>>()
+        })
+        .transpose()?;
+    let trios = read_trios(deps.storage, deps.api, start_after, limit)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attributes = vec![
+        ("action", "migrate_trios".to_string()),
+        ("code_id", trio_code_id.to_string()),
+    ];
+    for trio in trios.iter() {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Migrate {
+            contract_addr: trio.contract_addr.clone(),
+            new_code_id: trio_code_id,
+            msg: to_binary(&TrioMigrateMsg {})?,
+        }));
+        attributes.push(("trio_contract_addr", trio.contract_addr.clone()));
+    }
+    attributes.push(("migrated_trios", messages.len().to_string()));
+
+    if trios.len() >= limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize {
+        if let Some(last_trio) = trios.last() {
+            attributes.push((
+                "next_start_after",
+                String::from_utf8_lossy(&to_vec(&last_trio.asset_infos)?).to_string(),
+            ));
+        }
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attributes))
+}
+
 /// Migrates a trio.
 pub fn execute_migrate_trio(
     deps: DepsMut,
@@ -426,7 +809,8 @@ pub fn execute_migrate_trio(
         .querier
         .query_wasm_smart(contract.as_str(), &trio::QueryMsg::Pool {})?;
 
-    if trio_response.assets.len() != 3 {
+    if trio_response.assets.len() < MIN_TRIO_ASSETS || trio_response.assets.len() > MAX_TRIO_ASSETS
+    {
         return Err(ContractError::MigratingWrongPool {});
     }
 
@@ -1,20 +1,27 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult,
+    coin, to_binary, wasm_execute, Addr, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
+    Order, Reply, Response, StdError, StdResult,
 };
 use cw2::{get_contract_version, set_contract_version};
+use cw20::Cw20ExecuteMsg;
 use protobuf::Message;
 
 use semver::Version;
-use white_whale::pool_network::asset::{PairInfoRaw, TrioInfoRaw};
+use white_whale::pool_network::asset::{AssetInfo, PairInfoRaw, TrioInfoRaw};
 use white_whale::pool_network::factory::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use white_whale::pool_network::pair::ExecuteMsg as PairExecuteMsg;
 use white_whale::pool_network::querier::{query_pair_info_from_pair, query_trio_info_from_trio};
+use white_whale::pool_network::trio::ExecuteMsg as TrioExecuteMsg;
 
 use crate::error::ContractError;
 use crate::error::ContractError::MigrateInvalidVersion;
 use crate::response::MsgInstantiateContractResponse;
-use crate::state::{Config, CONFIG, PAIRS, TMP_PAIR_INFO, TMP_TRIO_INFO, TRIOS};
+use crate::state::{
+    add_asset_pool, Config, InitialLiquidity, PoolKey, CONFIG, DEFAULT_PAIRS, PAIRS, TMP_PAIR_INFO,
+    TMP_TRIO_INFO, TRIOS,
+};
 use crate::{commands, queries};
 
 // version info for migration info
@@ -39,6 +46,8 @@ pub fn instantiate(
         pair_code_id: msg.pair_code_id,
         trio_code_id: msg.trio_code_id,
         fee_collector_addr: deps.api.addr_validate(msg.fee_collector_addr.as_str())?,
+        pool_creation_fee: None,
+        allowlist_enabled: false,
     };
 
     CONFIG.save(deps.storage, &config)?;
@@ -53,10 +62,16 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    // Only the owner can execute messages on the factory
-    let config: Config = CONFIG.load(deps.storage)?;
-    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
-        return Err(ContractError::Unauthorized {});
+    // Anyone can create pools, paying the pool creation fee. Every other message can only be
+    // executed by the owner.
+    if !matches!(
+        msg,
+        ExecuteMsg::CreatePair { .. } | ExecuteMsg::CreateTrio { .. }
+    ) {
+        let config: Config = CONFIG.load(deps.storage)?;
+        if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+            return Err(ContractError::Unauthorized {});
+        }
     }
 
     match msg {
@@ -66,6 +81,8 @@ pub fn execute(
             token_code_id,
             pair_code_id,
             trio_code_id,
+            pool_creation_fee,
+            allowlist_enabled,
         } => commands::update_config(
             deps,
             owner,
@@ -73,12 +90,22 @@ pub fn execute(
             token_code_id,
             pair_code_id,
             trio_code_id,
+            pool_creation_fee,
+            allowlist_enabled,
         ),
+        ExecuteMsg::UpdateAllowedCreators {
+            asset_info,
+            add,
+            remove,
+        } => commands::update_allowed_creators(deps, asset_info, add, remove),
         ExecuteMsg::CreatePair {
             asset_infos,
             pool_fees,
             pair_type,
             token_factory_lp,
+            pair_identifier,
+            initial_liquidity,
+            receiver,
         } => commands::create_pair(
             deps,
             env,
@@ -87,12 +114,17 @@ pub fn execute(
             pool_fees,
             pair_type,
             token_factory_lp,
+            pair_identifier,
+            initial_liquidity,
+            receiver,
         ),
         ExecuteMsg::CreateTrio {
             asset_infos,
             pool_fees,
             amp_factor,
             token_factory_lp,
+            initial_liquidity,
+            receiver,
         } => commands::create_trio(
             deps,
             env,
@@ -101,11 +133,16 @@ pub fn execute(
             pool_fees,
             amp_factor,
             token_factory_lp,
+            initial_liquidity,
+            receiver,
         ),
-        ExecuteMsg::RemovePair { asset_infos } => commands::remove_pair(deps, env, asset_infos),
+        ExecuteMsg::RemovePair {
+            asset_infos,
+            pair_identifier,
+        } => commands::remove_pair(deps, env, asset_infos, pair_identifier),
         ExecuteMsg::RemoveTrio { asset_infos } => commands::remove_trio(deps, env, asset_infos),
         ExecuteMsg::AddNativeTokenDecimals { denom, decimals } => {
-            commands::add_native_token_decimals(deps, env, denom, decimals)
+            commands::add_native_token_decimals(deps, denom, decimals)
         }
         ExecuteMsg::MigratePair { contract, code_id } => {
             commands::execute_migrate_pair(deps, contract, code_id)
@@ -113,12 +150,32 @@ pub fn execute(
         ExecuteMsg::MigrateTrio { contract, code_id } => {
             commands::execute_migrate_trio(deps, contract, code_id)
         }
+        ExecuteMsg::MigratePairs {
+            code_id,
+            start_after,
+            start_after_identifier,
+            limit,
+            pair_type,
+        } => commands::execute_migrate_pairs(
+            deps,
+            code_id,
+            start_after,
+            start_after_identifier,
+            limit,
+            pair_type,
+        ),
+        ExecuteMsg::MigrateTrios {
+            code_id,
+            start_after,
+            limit,
+        } => commands::execute_migrate_trios(deps, code_id, start_after, limit),
         ExecuteMsg::UpdatePairConfig {
             pair_addr,
             owner,
             fee_collector_addr,
             pool_fees,
             feature_toggle,
+            amp_factor,
         } => commands::update_pair_config(
             deps,
             pair_addr,
@@ -126,7 +183,9 @@ pub fn execute(
             fee_collector_addr,
             pool_fees,
             feature_toggle,
+            amp_factor,
         ),
+        ExecuteMsg::StopPairAmpRamp { pair_addr } => commands::stop_pair_amp_ramp(deps, pair_addr),
         ExecuteMsg::UpdateTrioConfig {
             trio_addr,
             owner,
@@ -169,19 +228,65 @@ fn create_pair_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractErro
     let pair_contract = deps.api.addr_validate(&res.address)?;
     let pair_info = query_pair_info_from_pair(&deps.querier, pair_contract.clone())?;
 
+    let pair_contract_raw = deps.api.addr_canonicalize(pair_contract.as_str())?;
     PAIRS.save(
         deps.storage,
-        &tmp_pair_info.pair_key,
+        (&tmp_pair_info.pair_key, &tmp_pair_info.pair_identifier),
         &PairInfoRaw {
             liquidity_token: pair_info.liquidity_token.to_raw(deps.api)?,
-            contract_addr: deps.api.addr_canonicalize(pair_contract.as_str())?,
-            asset_infos: tmp_pair_info.asset_infos,
+            contract_addr: pair_contract_raw.clone(),
+            asset_infos: tmp_pair_info.asset_infos.clone(),
             asset_decimals: tmp_pair_info.asset_decimals,
             pair_type: tmp_pair_info.pair_type,
         },
     )?;
+    // the first pair of the assets is their default one
+    if !DEFAULT_PAIRS.has(deps.storage, &tmp_pair_info.pair_key)
+        && PAIRS
+            .prefix(&tmp_pair_info.pair_key)
+            .keys_raw(deps.storage, None, None, Order::Ascending)
+            .nth(1)
+            .is_none()
+    {
+        DEFAULT_PAIRS.save(
+            deps.storage,
+            &tmp_pair_info.pair_key,
+            &tmp_pair_info.pair_identifier,
+        )?;
+    }
+    add_asset_pool(
+        deps.storage,
+        &tmp_pair_info.asset_infos,
+        &pair_contract_raw,
+        PoolKey::Pair {
+            pair_key: tmp_pair_info.pair_key,
+            pair_identifier: tmp_pair_info.pair_identifier,
+        },
+    )?;
+
+    let mut messages = vec![];
+    if let Some(initial_liquidity) = tmp_pair_info.initial_liquidity {
+        let (allowance_msgs, funds) =
+            prepare_initial_liquidity(&pair_contract, &initial_liquidity)?;
+        messages.extend(allowance_msgs);
+        messages.push(
+            wasm_execute(
+                pair_contract.to_string(),
+                &PairExecuteMsg::ProvideLiquidity {
+                    assets: initial_liquidity.assets.try_into().map_err(|_| {
+                        StdError::generic_err("The initial liquidity of a pair holds two assets")
+                    })?,
+                    slippage_tolerance: None,
+                    receiver: Some(initial_liquidity.receiver.to_string()),
+                    deadline: None,
+                },
+                funds,
+            )?
+            .into(),
+        );
+    }
 
-    Ok(Response::new().add_attributes(vec![
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
         ("pair_contract_addr", pair_contract.as_str()),
         (
             "liquidity_token_addr",
@@ -201,18 +306,47 @@ fn create_trio_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractErro
     let trio_contract = deps.api.addr_validate(&res.address)?;
     let trio_info = query_trio_info_from_trio(&deps.querier, trio_contract.clone())?;
 
+    let trio_contract_raw = deps.api.addr_canonicalize(trio_contract.as_str())?;
     TRIOS.save(
         deps.storage,
         &tmp_trio_info.trio_key,
         &TrioInfoRaw {
             liquidity_token: trio_info.liquidity_token.to_raw(deps.api)?,
-            contract_addr: deps.api.addr_canonicalize(trio_contract.as_str())?,
-            asset_infos: tmp_trio_info.asset_infos,
+            contract_addr: trio_contract_raw.clone(),
+            asset_infos: tmp_trio_info.asset_infos.clone(),
             asset_decimals: tmp_trio_info.asset_decimals,
         },
     )?;
+    add_asset_pool(
+        deps.storage,
+        &tmp_trio_info.asset_infos,
+        &trio_contract_raw,
+        PoolKey::Trio {
+            trio_key: tmp_trio_info.trio_key,
+        },
+    )?;
 
-    Ok(Response::new().add_attributes(vec![
+    let mut messages = vec![];
+    if let Some(initial_liquidity) = tmp_trio_info.initial_liquidity {
+        let (allowance_msgs, funds) =
+            prepare_initial_liquidity(&trio_contract, &initial_liquidity)?;
+        messages.extend(allowance_msgs);
+        messages.push(
+            wasm_execute(
+                trio_contract.to_string(),
+                &TrioExecuteMsg::ProvideLiquidity {
+                    assets: initial_liquidity.assets,
+                    slippage_tolerance: None,
+                    receiver: Some(initial_liquidity.receiver.to_string()),
+                    deadline: None,
+                },
+                funds,
+            )?
+            .into(),
+        );
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
         ("trio_contract_addr", trio_contract.as_str()),
         (
             "liquidity_token_addr",
@@ -221,14 +355,58 @@ fn create_trio_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractErro
     ]))
 }
 
+/// Prepares the provision of the initial liquidity held by the factory to the given pool. Returns
+/// the messages allowing the pool to spend the cw20 assets and the native assets to send along with
+/// the provision.
+fn prepare_initial_liquidity(
+    pool: &Addr,
+    initial_liquidity: &InitialLiquidity,
+) -> StdResult<(Vec<CosmosMsg>, Vec<Coin>)> {
+    let mut allowance_msgs = vec![];
+    let mut funds = vec![];
+    for asset in initial_liquidity.assets.iter() {
+        match &asset.info {
+            AssetInfo::NativeToken { denom } => funds.push(coin(asset.amount.u128(), denom)),
+            AssetInfo::Token { contract_addr } => allowance_msgs.push(
+                wasm_execute(
+                    contract_addr,
+                    &Cw20ExecuteMsg::IncreaseAllowance {
+                        spender: pool.to_string(),
+                        amount: asset.amount,
+                        expires: None,
+                    },
+                    vec![],
+                )?
+                .into(),
+            ),
+        }
+    }
+    // the funds sent along with a message must be sorted by denom
+    funds.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+    Ok((allowance_msgs, funds))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&queries::query_config(deps)?),
-        QueryMsg::Pair { asset_infos } => to_binary(&queries::query_pair(deps, asset_infos)?),
-        QueryMsg::Pairs { start_after, limit } => {
-            to_binary(&queries::query_pairs(deps, start_after, limit)?)
-        }
+        QueryMsg::Pair {
+            asset_infos,
+            pair_identifier,
+        } => to_binary(&queries::query_pair(deps, asset_infos, pair_identifier)?),
+        QueryMsg::Pairs {
+            start_after,
+            start_after_identifier,
+            limit,
+            asset_infos,
+        } => to_binary(&queries::query_pairs(
+            deps,
+            start_after,
+            start_after_identifier,
+            limit,
+            asset_infos,
+        )?),
         QueryMsg::Trio { asset_infos } => to_binary(&queries::query_trio(deps, asset_infos)?),
         QueryMsg::Trios { start_after, limit } => {
             to_binary(&queries::query_trios(deps, start_after, limit)?)
@@ -236,6 +414,29 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::NativeTokenDecimals { denom } => {
             to_binary(&queries::query_native_token_decimal(deps, denom)?)
         }
+        QueryMsg::AllNativeTokenDecimals { start_after, limit } => to_binary(
+            &queries::query_all_native_token_decimals(deps, start_after, limit)?,
+        ),
+        QueryMsg::AllowedCreators {
+            asset_info,
+            start_after,
+            limit,
+        } => to_binary(&queries::query_allowed_creators(
+            deps,
+            asset_info,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::PoolsByAsset {
+            asset_info,
+            start_after,
+            limit,
+        } => to_binary(&queries::query_pools_by_asset(
+            deps,
+            asset_info,
+            start_after,
+            limit,
+        )?),
     }
 }
 
@@ -257,9 +458,21 @@ pub fn migrate(mut deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Respons
     if storage_version <= Version::parse("1.0.8")? {
         migrations::migrate_to_v110(deps.branch())?;
     }
-    if storage_version <= Version::parse("1.2.0")? {
+    if storage_version < Version::parse("1.2.0")? {
         migrations::migrate_to_v120(deps.branch())?;
     }
+    if storage_version < Version::parse("1.3.0")? {
+        migrations::migrate_to_v130(deps.branch())?;
+    }
+    if storage_version < Version::parse("1.4.0")? {
+        migrations::migrate_to_v140(deps.branch())?;
+    }
+    if storage_version < Version::parse("1.5.0")? {
+        migrations::migrate_to_v150(deps.branch())?;
+    }
+    if storage_version < Version::parse("1.6.0")? {
+        migrations::migrate_to_v160(deps.branch())?;
+    }
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     Ok(Response::default())
@@ -16,15 +16,21 @@ pub enum ContractError {
         current_version: Version,
     },
 
-    #[error("The provided assets are both the same")]
+    #[error("The provided assets are the same")]
     SameAsset {},
 
+    #[error("A trio must hold between {min} and {max} assets")]
+    InvalidNumberOfAssets { min: usize, max: usize },
+
     #[error("{asset} is invalid")]
     InvalidAsset { asset: String },
 
     #[error("Pair already exist")]
     ExistingPair {},
 
+    #[error("The pair identifier must be 1 to 64 ASCII letters, digits, '.', '-' or '_'")]
+    InvalidPairIdentifier {},
+
     #[error("Pair doesn't exist")]
     UnExistingPair {},
 
@@ -34,12 +40,24 @@ pub enum ContractError {
     #[error("Trio doesn't exist")]
     NonExistantTrio {},
 
-    #[error("A balance greater than zero is required by the factory to verify the asset")]
-    InvalidVerificationBalance {},
-
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("The sender is not allowed to create pools with the given assets")]
+    CreatorNotAllowed {},
+
+    #[error("The initial liquidity must hold a non-zero amount of each of the assets of the pool")]
+    InvalidInitialLiquidity {},
+
+    #[error("The initial liquidity of {asset} must be sent along with the pool creation")]
+    InitialLiquidityFundsMissing { asset: String },
+
+    #[error("Invalid pool creation fee; it must be a native token")]
+    InvalidPoolCreationFee {},
+
+    #[error("The pool creation fee of {fee} must be sent along with the pool creation")]
+    PoolCreationFeeMissing { fee: String },
+
     #[error("Attempt to migrate a pool to a different type. Make sure you are using the write Migrate message, e.g. MigratePair or MigrateTrio for pairs and trios respectively.")]
     MigratingWrongPool {},
 }
@@ -1,10 +1,14 @@
-use crate::state::Config;
+use crate::state::{
+    add_asset_pool, default_pair_identifier, trio_key, Config, PoolKey, CONFIG, DEFAULT_PAIRS,
+    PAIRS, TMP_PAIR_INFO, TRIOS,
+};
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, CanonicalAddr, DepsMut, Order, StdError, Uint128};
 use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use white_whale::pool_network::asset::{AssetInfo, AssetInfoRaw, PairType};
+use white_whale::pool_network::asset::{AssetInfo, AssetInfoRaw, PairInfoRaw, PairType};
+use white_whale::pool_network::pair;
 
 /// Migrate state of the factory from PascalCase to snake_case for the following items:
 /// [`PairInfoRaw`], [`PairInfo`], [`AssetInfoRaw`], [`AssetRaw`], [`TmpPairInfo`]
@@ -131,14 +135,23 @@ pub fn migrate_to_v120(deps: DepsMut) -> Result<(), StdError> {
         pub token_code_id: u64,
     }
 
+    #[cw_serde]
+    pub struct NewConfig {
+        pub owner: CanonicalAddr,
+        pub fee_collector_addr: Addr,
+        pub pair_code_id: u64,
+        pub trio_code_id: u64,
+        pub token_code_id: u64,
+    }
+
     const OLD_CONFIG: Item<OldConfig> = Item::new("config");
-    const NEW_CONFIG: Item<Config> = Item::new("config");
+    const NEW_CONFIG: Item<NewConfig> = Item::new("config");
 
     let old_config = OLD_CONFIG.may_load(deps.storage)?;
     if let Some(old_config) = old_config {
         NEW_CONFIG.save(
             deps.storage,
-            &Config {
+            &NewConfig {
                 owner: old_config.owner,
                 fee_collector_addr: old_config.fee_collector_addr,
                 pair_code_id: old_config.pair_code_id,
@@ -195,3 +208,129 @@ pub fn migrate_to_v120(deps: DepsMut) -> Result<(), StdError> {
 
     Ok(())
 }
+
+/// Migrate the keys of the trios to the length-prefixed format, so trios can hold a variable number
+/// of assets. The stored [`TrioInfoRaw`] doesn't change, as arrays and vectors serialize the same way.
+pub fn migrate_to_v130(deps: DepsMut) -> Result<(), StdError> {
+    // all the old keys are removed before saving the new ones, so a new key can't be overwritten
+    // by the removal of an old one
+    let all_trios = TRIOS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    all_trios.iter().for_each(|(key, _)| {
+        TRIOS.remove(deps.storage, key);
+    });
+
+    all_trios
+        .into_iter()
+        .try_for_each(|(_, trio)| -> Result<(), StdError> {
+            TRIOS.save(deps.storage, &trio_key(&trio.asset_infos), &trio)?;
+
+            Ok(())
+        })?;
+
+    Ok(())
+}
+
+/// Migrate the config of the factory to add the pool creation fee and the allowlist, which are
+/// disabled by default so pools can still be created as before.
+pub fn migrate_to_v140(deps: DepsMut) -> Result<(), StdError> {
+    #[cw_serde]
+    pub struct OldConfig {
+        pub owner: CanonicalAddr,
+        pub fee_collector_addr: Addr,
+        pub pair_code_id: u64,
+        pub trio_code_id: u64,
+        pub token_code_id: u64,
+    }
+
+    const OLD_CONFIG: Item<OldConfig> = Item::new("config");
+
+    let old_config = OLD_CONFIG.load(deps.storage)?;
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: old_config.owner,
+            fee_collector_addr: old_config.fee_collector_addr,
+            pair_code_id: old_config.pair_code_id,
+            trio_code_id: old_config.trio_code_id,
+            token_code_id: old_config.token_code_id,
+            pool_creation_fee: None,
+            allowlist_enabled: false,
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Migrate the pairs to be keyed by their assets and pair identifier, so there can be several pairs
+/// of the same assets. The identifier of the existing pairs is the default one, built out of their
+/// type and the fees they are configured with, and they are the default pairs of their assets.
+pub fn migrate_to_v150(deps: DepsMut) -> Result<(), StdError> {
+    const OLD_PAIRS: Map<&[u8], PairInfoRaw> = Map::new("pair_info");
+
+    let all_pairs = OLD_PAIRS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    all_pairs
+        .into_iter()
+        .try_for_each(|(key, pair)| -> Result<(), StdError> {
+            let pair_config: pair::ConfigResponse = deps.querier.query_wasm_smart(
+                deps.api.addr_humanize(&pair.contract_addr)?,
+                &pair::QueryMsg::Config {},
+            )?;
+            let pair_identifier = default_pair_identifier(&pair.pair_type, &pair_config.pool_fees)?;
+
+            OLD_PAIRS.remove(deps.storage, &key);
+            PAIRS.save(deps.storage, (&key, &pair_identifier), &pair)?;
+            DEFAULT_PAIRS.save(deps.storage, &key, &pair_identifier)?;
+
+            Ok(())
+        })?;
+
+    // the pending pair info is only used between the creation of a pair and its reply, so it can
+    // be dropped instead of adding the pair identifier to it
+    TMP_PAIR_INFO.remove(deps.storage);
+
+    Ok(())
+}
+
+/// Index the existing pairs and trios by each of their assets, so they can be looked up by asset
+pub fn migrate_to_v160(deps: DepsMut) -> Result<(), StdError> {
+    let all_pairs = PAIRS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    all_pairs.into_iter().try_for_each(
+        |((pair_key, pair_identifier), pair)| -> Result<(), StdError> {
+            add_asset_pool(
+                deps.storage,
+                &pair.asset_infos,
+                &pair.contract_addr,
+                PoolKey::Pair {
+                    pair_key,
+                    pair_identifier,
+                },
+            )
+        },
+    )?;
+
+    let all_trios = TRIOS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    all_trios
+        .into_iter()
+        .try_for_each(|(trio_key, trio)| -> Result<(), StdError> {
+            add_asset_pool(
+                deps.storage,
+                &trio.asset_infos,
+                &trio.contract_addr,
+                PoolKey::Trio { trio_key },
+            )
+        })?;
+
+    Ok(())
+}
@@ -1,11 +1,15 @@
 use crate::state::{
-    pair_key, read_pairs, read_trios, trio_key, Config, ALLOW_NATIVE_TOKENS, CONFIG, PAIRS, TRIOS,
+    may_load_pair, pair_key, read_allowed_creators, read_native_token_decimals, read_pairs,
+    read_pairs_by_assets, read_pools_by_asset, read_trios, trio_key, Config, ALLOW_NATIVE_TOKENS,
+    CONFIG, TRIOS,
 };
-use cosmwasm_std::{Deps, StdResult};
-use white_whale::pool_network::asset::{AssetInfo, PairInfo, PairInfoRaw, TrioInfo, TrioInfoRaw};
+use cosmwasm_std::{Deps, StdError, StdResult};
+use white_whale::pool_network::asset::{AssetInfo, AssetInfoRaw, PairInfo, TrioInfo, TrioInfoRaw};
 use white_whale::pool_network::factory::{
-    ConfigResponse, NativeTokenDecimalsResponse, PairsResponse, TriosResponse,
+    AllNativeTokenDecimalsResponse, AllowedCreatorsResponse, ConfigResponse,
+    NativeTokenDecimalsResponse, PairsResponse, PoolsByAssetResponse, TriosResponse,
 };
+use white_whale::pool_network::querier::query_denom_metadata_decimals;
 
 /// Queries [Config]
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
@@ -16,49 +20,81 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         pair_code_id: config.pair_code_id,
         trio_code_id: config.trio_code_id,
         fee_collector_addr: config.fee_collector_addr.to_string(),
+        pool_creation_fee: config.pool_creation_fee,
+        allowlist_enabled: config.allowlist_enabled,
     };
 
     Ok(resp)
 }
 
 /// Queries info about a given Pair
-pub fn query_pair(deps: Deps, asset_infos: [AssetInfo; 2]) -> StdResult<PairInfo> {
+pub fn query_pair(
+    deps: Deps,
+    asset_infos: [AssetInfo; 2],
+    pair_identifier: Option<String>,
+) -> StdResult<PairInfo> {
     let pair_key = pair_key(&[
         asset_infos[0].to_raw(deps.api)?,
         asset_infos[1].to_raw(deps.api)?,
     ]);
-    let pair_info: PairInfoRaw = PAIRS.load(deps.storage, &pair_key)?;
+    let (_, pair_info) = may_load_pair(deps.storage, &pair_key, pair_identifier)?
+        .ok_or_else(|| StdError::not_found("PairInfoRaw"))?;
     pair_info.to_normal(deps.api)
 }
 
-/// Queries all the pairs created by the factory
+/// Queries all the pairs created by the factory, or all the pairs of the given assets
 pub fn query_pairs(
     deps: Deps,
     start_after: Option<[AssetInfo; 2]>,
+    start_after_identifier: Option<String>,
     limit: Option<u32>,
+    asset_infos: Option<[AssetInfo; 2]>,
 ) -> StdResult<PairsResponse> {
-    let start_after = if let Some(start_after) = start_after {
-        Some([
-            start_after[0].to_raw(deps.api)?,
-            start_after[1].to_raw(deps.api)?,
-        ])
+    let pairs = if let Some(asset_infos) = asset_infos {
+        read_pairs_by_assets(
+            deps.storage,
+            deps.api,
+            [
+                asset_infos[0].to_raw(deps.api)?,
+                asset_infos[1].to_raw(deps.api)?,
+            ],
+        )?
     } else {
-        None
+        let start_after = if let Some(start_after) = start_after {
+            Some([
+                start_after[0].to_raw(deps.api)?,
+                start_after[1].to_raw(deps.api)?,
+            ])
+        } else {
+            None
+        };
+
+        read_pairs(
+            deps.storage,
+            deps.api,
+            start_after,
+            start_after_identifier,
+            limit,
+        )?
     };
 
-    let pairs: Vec<PairInfo> = read_pairs(deps.storage, deps.api, start_after, limit)?;
-    let resp = PairsResponse { pairs };
+    let (pair_identifiers, pairs) = pairs.into_iter().unzip();
+    let resp = PairsResponse {
+        pairs,
+        pair_identifiers,
+    };
 
     Ok(resp)
 }
 
 /// Queries info about a given Trio
-pub fn query_trio(deps: Deps, asset_infos: [AssetInfo; 3]) -> StdResult<TrioInfo> {
-    let trio_key = trio_key(&[
-        asset_infos[0].to_raw(deps.api)?,
-        asset_infos[1].to_raw(deps.api)?,
-        asset_infos[2].to_raw(deps.api)?,
-    ]);
+pub fn query_trio(deps: Deps, asset_infos: Vec<AssetInfo>) -> StdResult<TrioInfo> {
+    let trio_key = trio_key(
+        &asset_infos
+            .iter()
+            .map(|asset_info| asset_info.to_raw(deps.api))
+            .collect::<StdResult<Vec<AssetInfoRaw>>>()?,
+    );
     let trio_info: TrioInfoRaw = TRIOS.load(deps.storage, &trio_key)?;
     trio_info.to_normal(deps.api)
 }
@@ -66,15 +102,16 @@ pub fn query_trio(deps: Deps, asset_infos: [AssetInfo; 3]) -> StdResult<TrioInfo
 /// Queries all the trios created by the factory
 pub fn query_trios(
     deps: Deps,
-    start_after: Option<[AssetInfo; 3]>,
+    start_after: Option<Vec<AssetInfo>>,
     limit: Option<u32>,
 ) -> StdResult<TriosResponse> {
     let start_after = if let Some(start_after) = start_after {
-        Some([
-            start_after[0].to_raw(deps.api)?,
-            start_after[1].to_raw(deps.api)?,
-            start_after[2].to_raw(deps.api)?,
-        ])
+        Some(
+            start_after
+                .iter()
+                .map(|asset_info| asset_info.to_raw(deps.api))
+                .collect::<StdResult<Vec<AssetInfoRaw>>>()?,
+        )
     } else {
         None
     };
@@ -85,12 +122,72 @@ pub fn query_trios(
     Ok(resp)
 }
 
-/// Query the native token decimals
+/// Query the native token decimals, falling back to the bank metadata of the denom if none were
+/// registered
 pub fn query_native_token_decimal(
     deps: Deps,
     denom: String,
 ) -> StdResult<NativeTokenDecimalsResponse> {
-    let decimals = ALLOW_NATIVE_TOKENS.load(deps.storage, denom.as_bytes())?;
+    let decimals = match ALLOW_NATIVE_TOKENS.may_load(deps.storage, denom.as_bytes())? {
+        Some(decimals) => decimals,
+        None => query_denom_metadata_decimals(&deps.querier, denom)?,
+    };
 
     Ok(NativeTokenDecimalsResponse { decimals })
 }
+
+/// Queries the decimals registered for native tokens
+pub fn query_all_native_token_decimals(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllNativeTokenDecimalsResponse> {
+    let native_token_decimals = read_native_token_decimals(deps.storage, start_after, limit)?;
+
+    Ok(AllNativeTokenDecimalsResponse {
+        native_token_decimals,
+    })
+}
+
+/// Queries the creators allowed to create pools with the given asset
+pub fn query_allowed_creators(
+    deps: Deps,
+    asset_info: AssetInfo,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllowedCreatorsResponse> {
+    let start_after = start_after
+        .map(|start_after| deps.api.addr_validate(&start_after))
+        .transpose()?;
+
+    let creators = read_allowed_creators(
+        deps.storage,
+        &asset_info.to_raw(deps.api)?,
+        start_after,
+        limit,
+    )?;
+
+    Ok(AllowedCreatorsResponse { creators })
+}
+
+/// Queries the pairs and trios holding the given asset
+pub fn query_pools_by_asset(
+    deps: Deps,
+    asset_info: AssetInfo,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<PoolsByAssetResponse> {
+    let start_after = start_after
+        .map(|start_after| deps.api.addr_canonicalize(&start_after))
+        .transpose()?;
+
+    let pools = read_pools_by_asset(
+        deps.storage,
+        deps.api,
+        &asset_info.to_raw(deps.api)?,
+        start_after,
+        limit,
+    )?;
+
+    Ok(PoolsByAssetResponse { pools })
+}
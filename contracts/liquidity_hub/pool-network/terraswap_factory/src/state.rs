@@ -1,9 +1,11 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Api, CanonicalAddr, Order, StdResult, Storage};
-use cw_storage_plus::{Bound, Item, Map};
+use cosmwasm_std::{Addr, Api, CanonicalAddr, Empty, Order, StdResult, Storage};
+use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, Item, Map, MultiIndex, PrefixBound};
 use white_whale::pool_network::asset::{
-    AssetInfoRaw, PairInfo, PairInfoRaw, PairType, TrioInfo, TrioInfoRaw,
+    Asset, AssetInfoRaw, PairInfo, PairInfoRaw, PairType, TrioInfo, TrioInfoRaw,
 };
+use white_whale::pool_network::factory::{NativeTokenDecimalsInfo, PoolInfo};
+use white_whale::pool_network::pair::PoolFee;
 
 #[cw_serde]
 pub struct Config {
@@ -12,6 +14,10 @@ pub struct Config {
     pub pair_code_id: u64,
     pub trio_code_id: u64,
     pub token_code_id: u64,
+    /// Native fee charged for creating a pool, sent to the fee collector
+    pub pool_creation_fee: Option<Asset>,
+    /// If true, only the owner and the [ALLOWED_CREATORS] of an asset can create pools with it
+    pub allowlist_enabled: bool,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
@@ -19,13 +25,29 @@ pub const CONFIG: Item<Config> = Item::new("config");
 #[cw_serde]
 pub struct TmpPairInfo {
     pub pair_key: Vec<u8>,
+    pub pair_identifier: String,
     pub asset_infos: [AssetInfoRaw; 2],
     pub asset_decimals: [u8; 2],
     pub pair_type: PairType,
+    pub initial_liquidity: Option<InitialLiquidity>,
+}
+
+/// Liquidity held by the factory to be provided to a pool once it's created
+#[cw_serde]
+pub struct InitialLiquidity {
+    pub assets: Vec<Asset>,
+    /// Receiver of the LP tokens
+    pub receiver: Addr,
 }
 
 pub const TMP_PAIR_INFO: Item<TmpPairInfo> = Item::new("tmp_pair_info");
-pub const PAIRS: Map<&[u8], PairInfoRaw> = Map::new("pair_info");
+/// The pairs created by the factory, keyed by their assets and their pair identifier so there can
+/// be several pairs of the same assets, e.g. with different types or fee tiers
+pub const PAIRS: Map<(&[u8], &str), PairInfoRaw> = Map::new("pairs");
+/// The identifier of the default pair of some assets, keyed by their pair key. It's the first pair
+/// created for the assets, or the legacy pair migrated to [PAIRS], so creating more pairs of the
+/// same assets doesn't change which pair is used when no identifier is given.
+pub const DEFAULT_PAIRS: Map<&[u8], String> = Map::new("default_pairs");
 
 pub fn pair_key(asset_infos: &[AssetInfoRaw; 2]) -> Vec<u8> {
     let mut asset_infos = asset_infos.to_vec();
@@ -34,73 +56,133 @@ pub fn pair_key(asset_infos: &[AssetInfoRaw; 2]) -> Vec<u8> {
     [asset_infos[0].as_bytes(), asset_infos[1].as_bytes()].concat()
 }
 
+/// Builds the default identifier of a pair out of its type and its total fee, e.g.
+/// `ConstantProduct-0.003`
+pub fn default_pair_identifier(pair_type: &PairType, pool_fees: &PoolFee) -> StdResult<String> {
+    let total_fee = pool_fees
+        .protocol_fee
+        .share
+        .checked_add(pool_fees.swap_fee.share)?
+        .checked_add(pool_fees.burn_fee.share)?;
+
+    Ok(format!("{}-{total_fee}", pair_type.get_label()))
+}
+
+/// Loads the pair of the given assets with the given identifier, along with its identifier. If no
+/// identifier is given, the default pair of the assets in [DEFAULT_PAIRS] is loaded, if any.
+pub fn may_load_pair(
+    storage: &dyn Storage,
+    pair_key: &[u8],
+    pair_identifier: Option<String>,
+) -> StdResult<Option<(String, PairInfoRaw)>> {
+    let pair_identifier = match pair_identifier {
+        Some(pair_identifier) => pair_identifier,
+        None => match DEFAULT_PAIRS.may_load(storage, pair_key)? {
+            Some(pair_identifier) => pair_identifier,
+            None => return Ok(None),
+        },
+    };
+
+    Ok(PAIRS
+        .may_load(storage, (pair_key, &pair_identifier))?
+        .map(|pair| (pair_identifier, pair)))
+}
+
 #[cw_serde]
 pub struct TmpTrioInfo {
     pub trio_key: Vec<u8>,
-    pub asset_infos: [AssetInfoRaw; 3],
-    pub asset_decimals: [u8; 3],
+    pub asset_infos: Vec<AssetInfoRaw>,
+    pub asset_decimals: Vec<u8>,
+    pub initial_liquidity: Option<InitialLiquidity>,
 }
 
 pub const TMP_TRIO_INFO: Item<TmpTrioInfo> = Item::new("tmp_trio_info");
 pub const TRIOS: Map<&[u8], TrioInfoRaw> = Map::new("trio_info");
 
-pub fn trio_key(asset_infos: &[AssetInfoRaw; 3]) -> Vec<u8> {
+/// Builds the key of a trio out of its assets. The assets are sorted, and each of them is prefixed
+/// with its length so the keys of trios holding a different number of assets can't collide.
+pub fn trio_key(asset_infos: &[AssetInfoRaw]) -> Vec<u8> {
     let mut asset_infos = asset_infos.to_vec();
     asset_infos.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
 
-    [
-        asset_infos[0].as_bytes(),
-        asset_infos[1].as_bytes(),
-        asset_infos[2].as_bytes(),
-    ]
-    .concat()
+    asset_infos
+        .iter()
+        .flat_map(|asset_info| {
+            let bytes = asset_info.as_bytes();
+            [&(bytes.len() as u16).to_be_bytes(), bytes].concat()
+        })
+        .collect()
 }
 
 // settings for pagination
-const MAX_LIMIT: u32 = 30;
-const DEFAULT_LIMIT: u32 = 10;
+pub(crate) const MAX_LIMIT: u32 = 30;
+pub(crate) const DEFAULT_LIMIT: u32 = 10;
+/// Reads the pairs created by the factory along with their identifiers, starting after the pair of
+/// the `start_after` assets with the `start_after_identifier`, or after all the pairs of the
+/// `start_after` assets if no identifier is given.
 pub fn read_pairs(
     storage: &dyn Storage,
     api: &dyn Api,
     start_after: Option<[AssetInfoRaw; 2]>,
+    start_after_identifier: Option<String>,
     limit: Option<u32>,
-) -> StdResult<Vec<PairInfo>> {
+) -> StdResult<Vec<(String, PairInfo)>> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let start = calc_range_start(start_after).map(Bound::ExclusiveRaw);
+    let start_key = start_after.map(|asset_infos| pair_key(&asset_infos));
 
-    PAIRS
-        .range(storage, start, None, Order::Ascending)
+    let pairs = match (&start_key, &start_after_identifier) {
+        (Some(start_key), Some(start_after_identifier)) => PAIRS.range(
+            storage,
+            Some(Bound::exclusive((
+                start_key.as_slice(),
+                start_after_identifier.as_str(),
+            ))),
+            None,
+            Order::Ascending,
+        ),
+        _ => PAIRS.prefix_range(
+            storage,
+            start_key.as_deref().map(PrefixBound::exclusive),
+            None,
+            Order::Ascending,
+        ),
+    };
+
+    pairs
         .take(limit)
         .map(|item| {
-            let (_, v) = item?;
-            v.to_normal(api)
+            let ((_, pair_identifier), pair) = item?;
+            Ok((pair_identifier, pair.to_normal(api)?))
         })
-        .collect::<StdResult<Vec<PairInfo>>>()
+        .collect()
 }
 
-// this will set the first key after the provided key, by appending a 1 byte
-fn calc_range_start(start_after: Option<[AssetInfoRaw; 2]>) -> Option<Vec<u8>> {
-    start_after.map(|asset_infos| {
-        let mut asset_infos = asset_infos.to_vec();
-        asset_infos.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
-
-        let mut v = [asset_infos[0].as_bytes(), asset_infos[1].as_bytes()]
-            .concat()
-            .as_slice()
-            .to_vec();
-        v.push(1);
-        v
-    })
+/// Reads all the pairs of the given assets along with their identifiers
+pub fn read_pairs_by_assets(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    asset_infos: [AssetInfoRaw; 2],
+) -> StdResult<Vec<(String, PairInfo)>> {
+    PAIRS
+        .prefix(&pair_key(&asset_infos))
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (pair_identifier, v) = item?;
+            Ok((pair_identifier, v.to_normal(api)?))
+        })
+        .collect::<StdResult<Vec<(String, PairInfo)>>>()
 }
 
 pub fn read_trios(
     storage: &dyn Storage,
     api: &dyn Api,
-    start_after: Option<[AssetInfoRaw; 3]>,
+    start_after: Option<Vec<AssetInfoRaw>>,
     limit: Option<u32>,
 ) -> StdResult<Vec<TrioInfo>> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let start = trio_calc_range_start(start_after).map(Bound::ExclusiveRaw);
+    let start = start_after
+        .map(|asset_infos| trio_key(&asset_infos))
+        .map(Bound::ExclusiveRaw);
 
     TRIOS
         .range(storage, start, None, Order::Ascending)
@@ -112,25 +194,146 @@ pub fn read_trios(
         .collect::<StdResult<Vec<TrioInfo>>>()
 }
 
-// this will set the first key after the provided key, by appending a 1 byte
-fn trio_calc_range_start(start_after: Option<[AssetInfoRaw; 3]>) -> Option<Vec<u8>> {
-    start_after.map(|asset_infos| {
-        let mut asset_infos = asset_infos.to_vec();
-        asset_infos.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
-
-        let mut v = [
-            asset_infos[0].as_bytes(),
-            asset_infos[1].as_bytes(),
-            asset_infos[2].as_bytes(),
-        ]
-        .concat()
-        .as_slice()
-        .to_vec();
-        v.push(1);
-        v
+/// The key of a pool created by the factory in either [PAIRS] or [TRIOS]
+#[cw_serde]
+pub enum PoolKey {
+    Pair {
+        pair_key: Vec<u8>,
+        pair_identifier: String,
+    },
+    Trio {
+        trio_key: Vec<u8>,
+    },
+}
+
+/// A pool holding a given asset
+#[cw_serde]
+pub struct AssetPool {
+    pub contract_addr: CanonicalAddr,
+    pub pool_key: PoolKey,
+}
+
+pub struct AssetPoolIndexes<'a> {
+    pub contract_addr: MultiIndex<'a, Vec<u8>, AssetPool, (Vec<u8>, Vec<u8>)>,
+}
+
+impl<'a> IndexList<AssetPool> for AssetPoolIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<AssetPool>> + '_> {
+        let v: Vec<&dyn Index<AssetPool>> = vec![&self.contract_addr];
+        Box::new(v.into_iter())
+    }
+}
+
+/// The pools created by the factory keyed by each of their assets and their contract address, so
+/// the pools holding an asset can be looked up. The pools are indexed by contract address, so all
+/// the entries of a pool can be found when it's removed.
+pub fn asset_pools<'a>() -> IndexedMap<'a, (&'a [u8], &'a [u8]), AssetPool, AssetPoolIndexes<'a>> {
+    let indexes = AssetPoolIndexes {
+        contract_addr: MultiIndex::new(
+            |_, asset_pool| asset_pool.contract_addr.to_vec(),
+            "asset_pools",
+            "asset_pools__contract_addr",
+        ),
+    };
+
+    IndexedMap::new("asset_pools", indexes)
+}
+
+/// Adds the pool at `contract_addr` to the pools of each of its assets
+pub fn add_asset_pool(
+    storage: &mut dyn Storage,
+    asset_infos: &[AssetInfoRaw],
+    contract_addr: &CanonicalAddr,
+    pool_key: PoolKey,
+) -> StdResult<()> {
+    let asset_pool = AssetPool {
+        contract_addr: contract_addr.clone(),
+        pool_key,
+    };
+
+    asset_infos.iter().try_for_each(|asset_info| {
+        asset_pools().save(
+            storage,
+            (asset_info.as_bytes(), contract_addr.as_slice()),
+            &asset_pool,
+        )
+    })
+}
+
+/// Removes the pool at `contract_addr` from the pools of all of its assets
+pub fn remove_asset_pool(
+    storage: &mut dyn Storage,
+    contract_addr: &CanonicalAddr,
+) -> StdResult<()> {
+    let keys = asset_pools()
+        .idx
+        .contract_addr
+        .prefix(contract_addr.to_vec())
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(Vec<u8>, Vec<u8>)>>>()?;
+
+    keys.iter().try_for_each(|(asset_info, contract_addr)| {
+        asset_pools().remove(storage, (asset_info, contract_addr))
     })
 }
 
+/// Reads the pairs and trios holding the given asset, ordered by contract address
+pub fn read_pools_by_asset(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    asset_info: &AssetInfoRaw,
+    start_after: Option<CanonicalAddr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PoolInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .as_ref()
+        .map(|contract_addr| Bound::exclusive(contract_addr.as_slice()));
+
+    asset_pools()
+        .prefix(asset_info.as_bytes())
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, asset_pool) = item?;
+            match asset_pool.pool_key {
+                PoolKey::Pair {
+                    pair_key,
+                    pair_identifier,
+                } => Ok(PoolInfo::Pair {
+                    pair_info: PAIRS
+                        .load(storage, (&pair_key, &pair_identifier))?
+                        .to_normal(api)?,
+                    pair_identifier,
+                }),
+                PoolKey::Trio { trio_key } => Ok(PoolInfo::Trio {
+                    trio_info: TRIOS.load(storage, &trio_key)?.to_normal(api)?,
+                }),
+            }
+        })
+        .collect()
+}
+
+// key : (asset info, creator)
+pub const ALLOWED_CREATORS: Map<(&[u8], &Addr), Empty> = Map::new("allowed_creators");
+
+pub fn read_allowed_creators(
+    storage: &dyn Storage,
+    asset_info: &AssetInfoRaw,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<String>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    ALLOWED_CREATORS
+        .prefix(asset_info.as_bytes())
+        .keys(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| Ok(item?.to_string()))
+        .collect()
+}
+
 // key : asset info / value: decimals
 pub const ALLOW_NATIVE_TOKENS: Map<&[u8], u8> = Map::new("allow_native_token");
 pub fn add_allow_native_token(
@@ -141,6 +344,29 @@ pub fn add_allow_native_token(
     ALLOW_NATIVE_TOKENS.save(storage, denom.as_bytes(), &decimals)
 }
 
+pub fn read_native_token_decimals(
+    storage: &dyn Storage,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<NativeTokenDecimalsInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .as_ref()
+        .map(|denom| Bound::exclusive(denom.as_bytes()));
+
+    ALLOW_NATIVE_TOKENS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (denom, decimals) = item?;
+            Ok(NativeTokenDecimalsInfo {
+                denom: String::from_utf8(denom)?,
+                decimals,
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod allow_native_token {
 
@@ -2,15 +2,23 @@ use cosmwasm_std::testing::{
     mock_dependencies_with_balance, mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR,
 };
 use cosmwasm_std::{
-    attr, coin, from_binary, to_binary, Api, CanonicalAddr, Coin, CosmosMsg, Decimal, OwnedDeps,
-    Reply, ReplyOn, Response, SubMsg, SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
+    attr, coin, from_binary, from_slice, to_binary, to_vec, Api, BankMsg, CanonicalAddr, Coin,
+    CosmosMsg, Decimal, OwnedDeps, Reply, ReplyOn, Response, StdError, SubMsg, SubMsgResponse,
+    SubMsgResult, Uint128, WasmMsg,
 };
+use cw20::Cw20ExecuteMsg;
+use cw_storage_plus::Map;
 
 use white_whale::fee::Fee;
 use white_whale::pool_network;
-use white_whale::pool_network::asset::{AssetInfo, AssetInfoRaw, PairInfo, PairInfoRaw, PairType};
+use white_whale::pool_network::asset::{
+    Asset, AssetInfo, AssetInfoRaw, PairInfo, PairInfoRaw, PairType, TrioInfo, TrioInfoRaw,
+};
+use white_whale::pool_network::denom::{DenomUnit, Metadata};
 use white_whale::pool_network::factory::{
-    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, NativeTokenDecimalsResponse, QueryMsg,
+    AllNativeTokenDecimalsResponse, AllowedCreatorsResponse, ConfigResponse, ExecuteMsg,
+    InstantiateMsg, MigrateMsg, NativeTokenDecimalsInfo, NativeTokenDecimalsResponse,
+    PairsResponse, PoolInfo, PoolsByAssetResponse, QueryMsg,
 };
 use white_whale::pool_network::mock_querier::{
     mock_dependencies, mock_dependencies_trio, WasmMockQuerier, WasmMockTrioQuerier,
@@ -20,12 +28,15 @@ use white_whale::pool_network::pair::{
 };
 use white_whale::pool_network::trio::{
     InstantiateMsg as TrioInstantiateMsg, MigrateMsg as TrioMigrateMsg, PoolFee as TrioPoolFee,
+    RampAmp,
 };
 
 use crate::contract::{execute, instantiate, migrate, query, reply};
 use crate::error::ContractError;
+use crate::migrations::{migrate_to_v130, migrate_to_v150};
 use crate::state::{
-    pair_key, trio_key, TmpPairInfo, TmpTrioInfo, PAIRS, TMP_PAIR_INFO, TMP_TRIO_INFO,
+    pair_key, trio_key, TmpPairInfo, TmpTrioInfo, DEFAULT_PAIRS, PAIRS, TMP_PAIR_INFO,
+    TMP_TRIO_INFO, TRIOS,
 };
 
 #[test]
@@ -99,6 +110,8 @@ fn update_config() {
         trio_code_id: None,
         token_code_id: None,
         fee_collector_addr: None,
+        pool_creation_fee: None,
+        allowlist_enabled: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -121,6 +134,8 @@ fn update_config() {
         trio_code_id: Some(300u64),
         token_code_id: Some(200u64),
         fee_collector_addr: Some("new_collector".to_string()),
+        pool_creation_fee: None,
+        allowlist_enabled: None,
     };
 
     let res = execute(deps.as_mut(), env, info, msg).unwrap();
@@ -144,6 +159,8 @@ fn update_config() {
         pair_code_id: None,
         trio_code_id: None,
         token_code_id: None,
+        pool_creation_fee: None,
+        allowlist_enabled: None,
     };
 
     let res = execute(deps.as_mut(), env, info, msg);
@@ -229,6 +246,9 @@ fn create_pair() {
         },
         pair_type: PairType::ConstantProduct,
         token_factory_lp: false,
+        pair_identifier: None,
+        initial_liquidity: None,
+        receiver: None,
     };
 
     let env = mock_env();
@@ -247,6 +267,7 @@ fn create_pair() {
             attr("pair", "uusd-mAAPL"),
             attr("pair_label", "uusd-mAAPL pair"),
             attr("pair_type", "ConstantProduct"),
+            attr("pair_identifier", "ConstantProduct-0.02"),
         ]
     );
     assert_eq!(
@@ -301,6 +322,8 @@ fn create_pair() {
             pair_key: pair_key(&raw_infos),
             asset_decimals: [6u8, 8u8],
             pair_type: PairType::ConstantProduct,
+            pair_identifier: "ConstantProduct-0.02".to_string(),
+            initial_liquidity: None,
         }
     );
 }
@@ -335,6 +358,9 @@ fn create_stableswap_pair() {
         },
         pair_type: PairType::StableSwap { amp: 100 },
         token_factory_lp: false,
+        pair_identifier: None,
+        initial_liquidity: None,
+        receiver: None,
     };
 
     let env = mock_env();
@@ -347,6 +373,7 @@ fn create_stableswap_pair() {
             attr("pair", "uusd-mAAPL"),
             attr("pair_label", "uusd-mAAPL pair"),
             attr("pair_type", "StableSwap"),
+            attr("pair_identifier", "StableSwap-0.02"),
         ]
     );
     assert_eq!(
@@ -397,7 +424,101 @@ fn create_stableswap_pair() {
             pair_key: pair_key(&raw_infos),
             asset_decimals: [6u8, 8u8],
             pair_type: PairType::StableSwap { amp: 100 },
+            pair_identifier: "StableSwap-0.02".to_string(),
+            initial_liquidity: None,
+        }
+    );
+}
+
+#[test]
+fn create_weighted_pair() {
+    let mut deps = mock_dependencies(&[coin(10u128, "uusd".to_string())]);
+    deps = init(deps);
+    deps.querier
+        .with_pool_factory(&[], &[("uusd".to_string(), 6u8)]);
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+    ];
+    let pool_fees = PoolFee {
+        protocol_fee: Fee {
+            share: Decimal::percent(1u64),
+        },
+        swap_fee: Fee {
+            share: Decimal::percent(1u64),
+        },
+        burn_fee: Fee {
+            share: Decimal::zero(),
+        },
+    };
+
+    // the weights must be at least 1% of the total each
+    let msg = ExecuteMsg::CreatePair {
+        asset_infos: asset_infos.clone(),
+        pool_fees: pool_fees.clone(),
+        pair_type: PairType::Weighted { weights: [1, 200] },
+        token_factory_lp: false,
+        pair_identifier: None,
+        initial_liquidity: None,
+        receiver: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg);
+    match res {
+        Ok(_) => panic!("Should return ContractError::Std"),
+        Err(ContractError::Std(StdError::GenericErr { msg, .. })) => {
+            assert_eq!(msg, "Invalid pair weights")
         }
+        _ => panic!("Should return ContractError::Std"),
+    }
+
+    let msg = ExecuteMsg::CreatePair {
+        asset_infos: asset_infos.clone(),
+        pool_fees: pool_fees.clone(),
+        pair_type: PairType::Weighted { weights: [80, 20] },
+        token_factory_lp: false,
+        pair_identifier: None,
+        initial_liquidity: None,
+        receiver: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "create_pair"),
+            attr("pair", "uusd-mAAPL"),
+            attr("pair_label", "uusd-mAAPL pair"),
+            attr("pair_type", "Weighted"),
+            attr("pair_identifier", "Weighted-0.02"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![SubMsg {
+            id: 1,
+            gas_limit: None,
+            reply_on: ReplyOn::Success,
+            msg: WasmMsg::Instantiate {
+                msg: to_binary(&PairInstantiateMsg {
+                    asset_infos: asset_infos.clone(),
+                    token_code_id: 123u64,
+                    asset_decimals: [6u8, 8u8],
+                    pool_fees,
+                    fee_collector_addr: "collector".to_string(),
+                    pair_type: PairType::Weighted { weights: [80, 20] },
+                    token_factory_lp: false,
+                })
+                .unwrap(),
+                code_id: 321u64,
+                funds: vec![],
+                label: "uusd-mAAPL pair".to_string(),
+                admin: Some(MOCK_CONTRACT_ADDR.to_string()),
+            }
+            .into(),
+        },]
     );
 }
 
@@ -447,6 +568,9 @@ fn create_pair_native_token_and_ibc_token() {
         },
         pair_type: PairType::ConstantProduct,
         token_factory_lp: false,
+        pair_identifier: None,
+        initial_liquidity: None,
+        receiver: None,
     };
 
     let env = mock_env();
@@ -459,6 +583,7 @@ fn create_pair_native_token_and_ibc_token() {
             attr("pair", "uusd-ibc/2739...5EB2"),
             attr("pair_label", "uusd-ibc/2739...5EB2 pair"),
             attr("pair_type", "ConstantProduct"),
+            attr("pair_identifier", "ConstantProduct-0.02"),
         ]
     );
     assert_eq!(
@@ -509,6 +634,8 @@ fn create_pair_native_token_and_ibc_token() {
             pair_key: pair_key(&raw_infos),
             asset_decimals: [6u8, 6u8],
             pair_type: PairType::ConstantProduct,
+            pair_identifier: "ConstantProduct-0.02".to_string(),
+            initial_liquidity: None,
         }
     );
 }
@@ -566,6 +693,9 @@ fn create_ibc_tokens_pair() {
         },
         pair_type: PairType::ConstantProduct,
         token_factory_lp: false,
+        pair_identifier: None,
+        initial_liquidity: None,
+        receiver: None,
     };
 
     let env = mock_env();
@@ -578,6 +708,7 @@ fn create_ibc_tokens_pair() {
             attr("pair", "ibc/4CD5...3D04-ibc/2739...5EB2"),
             attr("pair_label", "ibc/4CD5...3D04-ibc/2739...5EB2 pair"),
             attr("pair_type", "ConstantProduct"),
+            attr("pair_identifier", "ConstantProduct-0.02"),
         ]
     );
     assert_eq!(
@@ -628,6 +759,8 @@ fn create_ibc_tokens_pair() {
             pair_key: pair_key(&raw_infos),
             asset_decimals: [6u8, 6u8],
             pair_type: PairType::ConstantProduct,
+            pair_identifier: "ConstantProduct-0.02".to_string(),
+            initial_liquidity: None,
         }
     );
 }
@@ -697,6 +830,7 @@ fn create_pair_ethereum_asset_and_ibc_token() {
             attr("pair", "peggy0x87a...1B5-ibc/2739...5EB2"),
             attr("pair_label", "peggy0x87a...1B5-ibc/2739...5EB2 pair"),
             attr("pair_type", "ConstantProduct"),
+            attr("pair_identifier", "ConstantProduct-0.02"),
         ]
     );
     assert_eq!(
@@ -780,6 +914,9 @@ fn fail_to_create_same_pair() {
         },
         pair_type: PairType::ConstantProduct,
         token_factory_lp: false,
+        pair_identifier: None,
+        initial_liquidity: None,
+        receiver: None,
     };
 
     let env = mock_env();
@@ -823,6 +960,9 @@ fn fail_to_create_existing_pair() {
         },
         pair_type: PairType::ConstantProduct,
         token_factory_lp: false,
+        pair_identifier: None,
+        initial_liquidity: None,
+        receiver: None,
     };
 
     let env = mock_env();
@@ -838,7 +978,7 @@ fn fail_to_create_existing_pair() {
     PAIRS
         .save(
             &mut deps.storage,
-            &pair_key,
+            (&pair_key, "ConstantProduct-0.02"),
             &PairInfoRaw {
                 liquidity_token: AssetInfoRaw::Token {
                     contract_addr: CanonicalAddr::from(vec![]),
@@ -860,6 +1000,354 @@ fn fail_to_create_existing_pair() {
     }
 }
 
+#[test]
+fn create_pair_with_denom_metadata_decimals() {
+    let mut deps = mock_dependencies(&[coin(10u128, "uusd".to_string())]);
+    deps = init(deps);
+    deps.querier
+        .with_pool_factory(&[], &[("uusd".to_string(), 6u8)]);
+    deps.querier.with_denom_metadata(&[(
+        &"factory/creator/uwhale".to_string(),
+        &Metadata {
+            denom_units: vec![
+                DenomUnit {
+                    denom: "factory/creator/uwhale".to_string(),
+                    exponent: 0,
+                    aliases: vec![],
+                },
+                DenomUnit {
+                    denom: "whale".to_string(),
+                    exponent: 8,
+                    aliases: vec![],
+                },
+            ],
+            base: "factory/creator/uwhale".to_string(),
+            display: "whale".to_string(),
+            ..Metadata::default()
+        },
+    )]);
+
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: "factory/creator/uwhale".to_string(),
+        },
+    ];
+
+    let msg = ExecuteMsg::CreatePair {
+        asset_infos: asset_infos.clone(),
+        pool_fees: PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::percent(1u64),
+            },
+            swap_fee: Fee {
+                share: Decimal::percent(1u64),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+        },
+        pair_type: PairType::ConstantProduct,
+        token_factory_lp: false,
+        pair_identifier: None,
+        initial_liquidity: None,
+        receiver: None,
+    };
+
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    // the decimals of the denom without registered decimals come from its metadata
+    assert_eq!(
+        TMP_PAIR_INFO.load(&deps.storage).unwrap().asset_decimals,
+        [6u8, 8u8]
+    );
+}
+
+#[test]
+fn create_pair_with_initial_liquidity() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+    ];
+    let initial_liquidity = [
+        Asset {
+            info: asset_infos[1].clone(),
+            amount: Uint128::new(2_000_000u128),
+        },
+        Asset {
+            info: asset_infos[0].clone(),
+            amount: Uint128::new(1_000_000u128),
+        },
+    ];
+    let pair_info = PairInfo {
+        asset_infos: asset_infos.clone(),
+        contract_addr: "pair0000".to_string(),
+        liquidity_token: AssetInfo::Token {
+            contract_addr: "liquidity0000".to_string(),
+        },
+        asset_decimals: [6u8, 8u8],
+        pair_type: PairType::ConstantProduct,
+    };
+    deps.querier.with_pool_factory(
+        &[(&"pair0000".to_string(), &pair_info)],
+        &[("uusd".to_string(), 6u8)],
+    );
+
+    let create_pair = |initial_liquidity: Option<[Asset; 2]>| ExecuteMsg::CreatePair {
+        asset_infos: asset_infos.clone(),
+        pool_fees: PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::percent(1u64),
+            },
+            swap_fee: Fee {
+                share: Decimal::percent(1u64),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+        },
+        pair_type: PairType::ConstantProduct,
+        token_factory_lp: false,
+        pair_identifier: None,
+        initial_liquidity,
+        receiver: Some("receiver".to_string()),
+    };
+
+    // the native assets of the initial liquidity must be sent along
+    match execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator", &[coin(999_999u128, "uusd")]),
+        create_pair(Some(initial_liquidity.clone())),
+    ) {
+        Err(ContractError::InitialLiquidityFundsMissing { .. }) => (),
+        _ => panic!("should return ContractError::InitialLiquidityFundsMissing"),
+    }
+
+    // the initial liquidity must hold the assets of the pair
+    let mut other_liquidity = initial_liquidity.clone();
+    other_liquidity[1].info = AssetInfo::NativeToken {
+        denom: "uluna".to_string(),
+    };
+    match execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator", &[coin(1_000_000u128, "uusd")]),
+        create_pair(Some(other_liquidity)),
+    ) {
+        Err(ContractError::InvalidInitialLiquidity {}) => (),
+        _ => panic!("should return ContractError::InvalidInitialLiquidity"),
+    }
+
+    // the cw20 assets are transferred to the factory, and the native ones are held by it
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator", &[coin(1_000_000u128, "uusd")]),
+        create_pair(Some(initial_liquidity.clone())),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(
+        res.messages[0],
+        SubMsg::new(WasmMsg::Execute {
+            contract_addr: "asset0001".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: "creator".to_string(),
+                recipient: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Uint128::new(2_000_000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+    match &res.messages[1].msg {
+        CosmosMsg::Wasm(WasmMsg::Instantiate { funds, .. }) => assert!(funds.is_empty()),
+        _ => panic!("should instantiate the pair"),
+    }
+
+    // once the pair is created, the initial liquidity is provided to it
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: 1,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(vec![10, 8, 112, 97, 105, 114, 48, 48, 48, 48].into()),
+            }),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "asset0001".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: "pair0000".to_string(),
+                    amount: Uint128::new(2_000_000u128),
+                    expires: None,
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "pair0000".to_string(),
+                msg: to_binary(&pool_network::pair::ExecuteMsg::ProvideLiquidity {
+                    assets: initial_liquidity,
+                    slippage_tolerance: None,
+                    receiver: Some("receiver".to_string()),
+                    deadline: None,
+                })
+                .unwrap(),
+                funds: vec![coin(1_000_000u128, "uusd")],
+            }),
+        ]
+    );
+}
+
+#[test]
+fn create_trio_with_initial_liquidity() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+    deps.querier.with_pool_factory(
+        &[],
+        &[
+            ("uusd".to_string(), 6u8),
+            ("uwhale".to_string(), 6u8),
+            ("uluna".to_string(), 6u8),
+        ],
+    );
+
+    let asset_infos = ["uwhale", "uusd", "uluna"]
+        .iter()
+        .map(|denom| AssetInfo::NativeToken {
+            denom: denom.to_string(),
+        })
+        .collect::<Vec<AssetInfo>>();
+    let initial_liquidity = asset_infos
+        .iter()
+        .map(|asset_info| Asset {
+            info: asset_info.clone(),
+            amount: Uint128::new(1_000_000u128),
+        })
+        .collect::<Vec<Asset>>();
+    deps.querier.with_trios(&[(
+        &"trio0000".to_string(),
+        &TrioInfo {
+            asset_infos: asset_infos.clone(),
+            contract_addr: "trio0000".to_string(),
+            liquidity_token: AssetInfo::Token {
+                contract_addr: "liquidity0000".to_string(),
+            },
+            asset_decimals: vec![6u8, 6u8, 6u8],
+        },
+    )]);
+
+    // the pool creation fee is taken on top of the initial liquidity, and the funds left are sent
+    // along with the instantiation as usual
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateConfig {
+            owner: None,
+            fee_collector_addr: None,
+            token_code_id: None,
+            pair_code_id: None,
+            trio_code_id: None,
+            pool_creation_fee: Some(Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uwhale".to_string(),
+                },
+                amount: Uint128::new(100u128),
+            }),
+            allowlist_enabled: None,
+        },
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(
+            "creator",
+            &[
+                coin(1_000_000u128, "uluna"),
+                coin(1_000_001u128, "uusd"),
+                coin(1_000_100u128, "uwhale"),
+            ],
+        ),
+        ExecuteMsg::CreateTrio {
+            asset_infos,
+            pool_fees: TrioPoolFee {
+                protocol_fee: Fee {
+                    share: Decimal::percent(1u64),
+                },
+                swap_fee: Fee {
+                    share: Decimal::percent(1u64),
+                },
+                burn_fee: Fee {
+                    share: Decimal::zero(),
+                },
+            },
+            amp_factor: 100,
+            token_factory_lp: false,
+            initial_liquidity: Some(initial_liquidity.clone()),
+            receiver: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 2);
+    match &res.messages[1].msg {
+        CosmosMsg::Wasm(WasmMsg::Instantiate { funds, .. }) => {
+            assert_eq!(funds, &vec![coin(1u128, "uusd")])
+        }
+        _ => panic!("should instantiate the trio"),
+    }
+
+    // the LP tokens of the initial liquidity go to the creator by default
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: 2,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(vec![10, 8, 116, 114, 105, 111, 48, 48, 48, 48].into()),
+            }),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "trio0000".to_string(),
+            msg: to_binary(&pool_network::trio::ExecuteMsg::ProvideLiquidity {
+                assets: initial_liquidity,
+                slippage_tolerance: None,
+                receiver: Some("creator".to_string()),
+                deadline: None,
+            })
+            .unwrap(),
+            funds: vec![
+                coin(1_000_000u128, "uluna"),
+                coin(1_000_000u128, "uusd"),
+                coin(1_000_000u128, "uwhale"),
+            ],
+        })]
+    );
+}
+
 #[test]
 fn fail_to_create_pair_with_inactive_denoms() {
     let mut deps = mock_dependencies(&[coin(10u128, "uusd".to_string())]);
@@ -889,6 +1377,9 @@ fn fail_to_create_pair_with_inactive_denoms() {
         },
         pair_type: PairType::ConstantProduct,
         token_factory_lp: false,
+        pair_identifier: None,
+        initial_liquidity: None,
+        receiver: None,
     };
 
     let env = mock_env();
@@ -933,6 +1424,9 @@ fn fail_to_create_pair_with_invalid_denom() {
         },
         pair_type: PairType::ConstantProduct,
         token_factory_lp: false,
+        pair_identifier: None,
+        initial_liquidity: None,
+        receiver: None,
     };
 
     let env = mock_env();
@@ -969,6 +1463,9 @@ fn fail_to_create_pair_with_invalid_denom() {
         },
         pair_type: PairType::ConstantProduct,
         token_factory_lp: false,
+        pair_identifier: None,
+        initial_liquidity: None,
+        receiver: None,
     };
 
     let env = mock_env();
@@ -1023,6 +1520,9 @@ fn fail_to_create_pair_with_unknown_token() {
         },
         pair_type: PairType::ConstantProduct,
         token_factory_lp: false,
+        pair_identifier: None,
+        initial_liquidity: None,
+        receiver: None,
     };
 
     let env = mock_env();
@@ -1077,6 +1577,9 @@ fn fail_to_create_pair_with_unknown_ibc_token() {
         },
         pair_type: PairType::ConstantProduct,
         token_factory_lp: false,
+        pair_identifier: None,
+        initial_liquidity: None,
+        receiver: None,
     };
 
     let env = mock_env();
@@ -1125,6 +1628,8 @@ fn reply_test() {
                 pair_key,
                 asset_decimals: [8u8, 8u8],
                 pair_type: PairType::ConstantProduct,
+                pair_identifier: "ConstantProduct-0.02".to_string(),
+                initial_liquidity: None,
             },
         )
         .unwrap();
@@ -1168,6 +1673,7 @@ fn reply_test() {
         mock_env(),
         QueryMsg::Pair {
             asset_infos: asset_infos.clone(),
+            pair_identifier: None,
         },
     )
     .unwrap();
@@ -1241,39 +1747,18 @@ fn failed_add_allow_native_token_with_non_admin() {
 }
 
 #[test]
-fn failed_add_allow_native_token_with_zero_factory_balance() {
-    let mut deps = mock_dependencies(&[coin(0u128, "uluna".to_string())]);
-    deps = init(deps);
-
-    let msg = ExecuteMsg::AddNativeTokenDecimals {
-        denom: "uluna".to_string(),
-        decimals: 6u8,
-    };
-
-    let info = mock_info("addr0000", &[]);
-
-    let res = execute(deps.as_mut(), mock_env(), info, msg);
-    match res {
-        Ok(_) => panic!("should return ContractError::InvalidVerificationBalance"),
-        Err(ContractError::InvalidVerificationBalance {}) => (),
-        _ => panic!("should return ContractError::InvalidVerificationBalance"),
-    }
-}
-
-#[test]
-fn append_add_allow_native_token_with_already_exist_token() {
-    let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
+fn add_allow_native_token_without_factory_balance() {
+    let mut deps = mock_dependencies(&[]);
     deps = init(deps);
 
     let msg = ExecuteMsg::AddNativeTokenDecimals {
         denom: "uluna".to_string(),
-
         decimals: 6u8,
     };
 
     let info = mock_info("addr0000", &[]);
 
-    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let res = query(
         deps.as_ref(),
@@ -1284,16 +1769,159 @@ fn append_add_allow_native_token_with_already_exist_token() {
     )
     .unwrap();
     let res: NativeTokenDecimalsResponse = from_binary(&res).unwrap();
-    assert_eq!(6u8, res.decimals);
-
-    let msg = ExecuteMsg::AddNativeTokenDecimals {
-        denom: "uluna".to_string(),
-        decimals: 7u8,
-    };
+    assert_eq!(6u8, res.decimals)
+}
 
-    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+#[test]
+fn query_native_token_decimals_from_denom_metadata() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
 
-    let res = query(
+    let denom = "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2".to_string();
+    deps.querier.with_denom_metadata(&[(
+        &denom,
+        &Metadata {
+            denom_units: vec![
+                DenomUnit {
+                    denom: denom.clone(),
+                    exponent: 0,
+                    aliases: vec!["uatom".to_string()],
+                },
+                DenomUnit {
+                    denom: "atom".to_string(),
+                    exponent: 6,
+                    aliases: vec![],
+                },
+            ],
+            base: denom.clone(),
+            display: "atom".to_string(),
+            ..Metadata::default()
+        },
+    )]);
+
+    let query_decimals = |deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, denom: &str| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NativeTokenDecimals {
+                denom: denom.to_string(),
+            },
+        )
+        .map(|res| {
+            from_binary::<NativeTokenDecimalsResponse>(&res)
+                .unwrap()
+                .decimals
+        })
+    };
+
+    // the decimals are derived from the display unit
+    assert_eq!(query_decimals(&deps, &denom).unwrap(), 6u8);
+    // denoms without registered decimals nor metadata can't be resolved
+    query_decimals(&deps, "uxxx").unwrap_err();
+
+    // registered decimals override the metadata
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::AddNativeTokenDecimals {
+            denom: denom.clone(),
+            decimals: 8u8,
+        },
+    )
+    .unwrap();
+    assert_eq!(query_decimals(&deps, &denom).unwrap(), 8u8);
+}
+
+#[test]
+fn query_all_native_token_decimals() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+
+    for (denom, decimals) in [("uwhale", 6u8), ("aevmos", 18u8), ("uluna", 6u8)] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            ExecuteMsg::AddNativeTokenDecimals {
+                denom: denom.to_string(),
+                decimals,
+            },
+        )
+        .unwrap();
+    }
+
+    let query_all = |deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+                     start_after: Option<String>,
+                     limit: Option<u32>| {
+        let res: AllNativeTokenDecimalsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::AllNativeTokenDecimals { start_after, limit },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res.native_token_decimals
+    };
+
+    assert_eq!(
+        query_all(&deps, None, Some(2)),
+        vec![
+            NativeTokenDecimalsInfo {
+                denom: "aevmos".to_string(),
+                decimals: 18u8,
+            },
+            NativeTokenDecimalsInfo {
+                denom: "uluna".to_string(),
+                decimals: 6u8,
+            },
+        ]
+    );
+    assert_eq!(
+        query_all(&deps, Some("uluna".to_string()), None),
+        vec![NativeTokenDecimalsInfo {
+            denom: "uwhale".to_string(),
+            decimals: 6u8,
+        }]
+    );
+}
+
+#[test]
+fn append_add_allow_native_token_with_already_exist_token() {
+    let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
+    deps = init(deps);
+
+    let msg = ExecuteMsg::AddNativeTokenDecimals {
+        denom: "uluna".to_string(),
+
+        decimals: 6u8,
+    };
+
+    let info = mock_info("addr0000", &[]);
+
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::NativeTokenDecimals {
+            denom: "uluna".to_string(),
+        },
+    )
+    .unwrap();
+    let res: NativeTokenDecimalsResponse = from_binary(&res).unwrap();
+    assert_eq!(6u8, res.decimals);
+
+    let msg = ExecuteMsg::AddNativeTokenDecimals {
+        denom: "uluna".to_string(),
+        decimals: 7u8,
+    };
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(
         deps.as_ref(),
         mock_env(),
         QueryMsg::NativeTokenDecimals {
@@ -1323,22 +1951,11 @@ fn execute_transactions_unauthorized() {
     // unauthorized user
     let info = mock_info("unauthorized", &[]);
 
-    // Try executing ExecuteMsg::CreatePair
-    let msg = ExecuteMsg::CreatePair {
-        asset_infos,
-        pool_fees: PoolFee {
-            protocol_fee: Fee {
-                share: Decimal::percent(1u64),
-            },
-            swap_fee: Fee {
-                share: Decimal::percent(1u64),
-            },
-            burn_fee: Fee {
-                share: Decimal::zero(),
-            },
-        },
-        pair_type: PairType::ConstantProduct,
-        token_factory_lp: false,
+    // Try executing ExecuteMsg::UpdateAllowedCreators
+    let msg = ExecuteMsg::UpdateAllowedCreators {
+        asset_info: asset_infos[0].clone(),
+        add: vec!["unauthorized".to_string()],
+        remove: vec![],
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
 
@@ -1369,6 +1986,8 @@ fn execute_transactions_unauthorized() {
         token_code_id: None,
         pair_code_id: None,
         trio_code_id: None,
+        pool_creation_fee: None,
+        allowlist_enabled: None,
     };
 
     let res = execute(deps.as_mut(), env, info, msg);
@@ -1508,6 +2127,285 @@ fn failed_migrate_trio_with_no_admin() {
     }
 }
 
+#[test]
+fn migrate_pairs_in_pages() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+
+    let native = |denom: &str| AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    };
+    let pairs = [
+        (
+            "pair0000",
+            [native("uusd"), native("uluna")],
+            PairType::ConstantProduct,
+        ),
+        (
+            "pair0001",
+            [native("uusd"), native("uluna")],
+            PairType::StableSwap { amp: 100 },
+        ),
+        (
+            "pair0002",
+            [native("uwhale"), native("uluna")],
+            PairType::ConstantProduct,
+        ),
+        (
+            "pair0003",
+            [native("uwhale"), native("uluna")],
+            PairType::StableSwap { amp: 500 },
+        ),
+    ];
+    for (contract_addr, asset_infos, pair_type) in pairs.iter() {
+        let raw_infos = [
+            asset_infos[0].to_raw(&deps.api).unwrap(),
+            asset_infos[1].to_raw(&deps.api).unwrap(),
+        ];
+        PAIRS
+            .save(
+                &mut deps.storage,
+                (&pair_key(&raw_infos), &format!("{contract_addr}-id")),
+                &PairInfoRaw {
+                    liquidity_token: AssetInfoRaw::Token {
+                        contract_addr: deps
+                            .api
+                            .addr_canonicalize(&format!("liquidity{contract_addr}"))
+                            .unwrap(),
+                    },
+                    contract_addr: deps.api.addr_canonicalize(contract_addr).unwrap(),
+                    asset_infos: raw_infos,
+                    asset_decimals: [6u8, 6u8],
+                    pair_type: pair_type.clone(),
+                },
+            )
+            .unwrap();
+    }
+
+    let migrate_msg = |contract_addr: &str| -> CosmosMsg {
+        WasmMsg::Migrate {
+            contract_addr: contract_addr.to_string(),
+            new_code_id: 654u64,
+            msg: to_binary(&PairMigrateMsg {}).unwrap(),
+        }
+        .into()
+    };
+    let migrate_pairs = |start_after: Option<([AssetInfo; 2], &str)>,
+                         limit: Option<u32>,
+                         pair_type: Option<PairType>| {
+        ExecuteMsg::MigratePairs {
+            code_id: Some(654u64),
+            start_after_identifier: start_after
+                .as_ref()
+                .map(|(_, identifier)| identifier.to_string()),
+            start_after: start_after.map(|(asset_infos, _)| asset_infos),
+            limit,
+            pair_type,
+        }
+    };
+
+    // only the owner can migrate the pairs
+    match execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("noadmin", &[]),
+        migrate_pairs(None, None, None),
+    ) {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("should return ContractError::Unauthorized"),
+    }
+
+    // a page holds `limit` pairs, even if there are more pairs of its last assets
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        migrate_pairs(None, Some(1), None),
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(migrate_msg("pair0000"))]);
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "migrate_pairs"),
+            attr("code_id", "654"),
+            attr("pair_contract_addr", "pair0000"),
+            attr("migrated_pairs", "1"),
+            attr(
+                "next_start_after",
+                String::from_utf8(to_vec(&pairs[0].1).unwrap()).unwrap()
+            ),
+            attr("next_start_after_identifier", "pair0000-id"),
+        ]
+    );
+
+    // the next page starts right after the last pair, and the last page doesn't report a next page
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        migrate_pairs(Some((pairs[0].1.clone(), "pair0000-id")), None, None),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(migrate_msg("pair0001")),
+            SubMsg::new(migrate_msg("pair0002")),
+            SubMsg::new(migrate_msg("pair0003"))
+        ]
+    );
+    assert_eq!(res.attributes.last(), Some(&attr("migrated_pairs", "3")));
+
+    // without an identifier, the page starts after all the pairs of the given assets
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::MigratePairs {
+            code_id: Some(654u64),
+            start_after: Some(pairs[0].1.clone()),
+            start_after_identifier: None,
+            limit: None,
+            pair_type: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(migrate_msg("pair0002")),
+            SubMsg::new(migrate_msg("pair0003"))
+        ]
+    );
+
+    // only the pairs of the given type are migrated
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        migrate_pairs(None, None, Some(PairType::ConstantProduct)),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(migrate_msg("pair0000")),
+            SubMsg::new(migrate_msg("pair0002"))
+        ]
+    );
+
+    // the pairs are filtered by type regardless of their parameters, e.g. their amp
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        migrate_pairs(None, None, Some(PairType::StableSwap { amp: 1 })),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(migrate_msg("pair0001")),
+            SubMsg::new(migrate_msg("pair0003"))
+        ]
+    );
+}
+
+#[test]
+fn migrate_trios_in_pages() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+
+    let native = |denom: &str| AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    };
+    let trios = [
+        (
+            "trio0000",
+            vec![native("uusd"), native("uluna"), native("uwhale")],
+        ),
+        (
+            "trio0001",
+            vec![native("uusd"), native("uluna"), native("uatom")],
+        ),
+    ];
+    for (contract_addr, asset_infos) in trios.iter() {
+        let raw_infos = asset_infos
+            .iter()
+            .map(|asset_info| asset_info.to_raw(&deps.api).unwrap())
+            .collect::<Vec<AssetInfoRaw>>();
+        TRIOS
+            .save(
+                &mut deps.storage,
+                &trio_key(&raw_infos),
+                &TrioInfoRaw {
+                    liquidity_token: AssetInfoRaw::Token {
+                        contract_addr: deps
+                            .api
+                            .addr_canonicalize(&format!("liquidity{contract_addr}"))
+                            .unwrap(),
+                    },
+                    contract_addr: deps.api.addr_canonicalize(contract_addr).unwrap(),
+                    asset_infos: raw_infos,
+                    asset_decimals: vec![6u8, 6u8, 6u8],
+                },
+            )
+            .unwrap();
+    }
+
+    let first_page = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::MigrateTrios {
+            code_id: None,
+            start_after: None,
+            limit: Some(1),
+        },
+    )
+    .unwrap();
+    assert_eq!(first_page.messages.len(), 1);
+    let next_start_after = first_page
+        .attributes
+        .iter()
+        .find(|attribute| attribute.key == "next_start_after")
+        .unwrap();
+
+    let second_page = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::MigrateTrios {
+            code_id: None,
+            start_after: Some(from_slice(next_start_after.value.as_bytes()).unwrap()),
+            limit: Some(1),
+        },
+    )
+    .unwrap();
+    assert_eq!(second_page.messages.len(), 1);
+
+    // every trio is migrated to the trio code id in the config once
+    let mut contract_addrs = [first_page.messages, second_page.messages]
+        .concat()
+        .into_iter()
+        .map(|sub_msg| match sub_msg.msg {
+            CosmosMsg::Wasm(WasmMsg::Migrate {
+                contract_addr,
+                new_code_id,
+                msg,
+            }) => {
+                assert_eq!(new_code_id, 456u64);
+                assert_eq!(msg, to_binary(&TrioMigrateMsg {}).unwrap());
+                contract_addr
+            }
+            _ => panic!("should be a migrate message"),
+        })
+        .collect::<Vec<String>>();
+    contract_addrs.sort();
+    assert_eq!(contract_addrs, vec!["trio0000", "trio0001"]);
+}
+
 #[test]
 fn delete_pair() {
     let mut deps = mock_dependencies(&[coin(10u128, "uusd".to_string())]);
@@ -1533,7 +2431,7 @@ fn delete_pair() {
     PAIRS
         .save(
             &mut deps.storage,
-            &pair_key_vec,
+            (&pair_key_vec, "ConstantProduct-0.02"),
             &PairInfoRaw {
                 liquidity_token: AssetInfoRaw::Token {
                     contract_addr: CanonicalAddr(cosmwasm_std::Binary(vec![])),
@@ -1545,12 +2443,22 @@ fn delete_pair() {
             },
         )
         .unwrap();
+    DEFAULT_PAIRS
+        .save(
+            &mut deps.storage,
+            &pair_key_vec,
+            &"ConstantProduct-0.02".to_string(),
+        )
+        .unwrap();
 
-    let pair = PAIRS.load(&deps.storage, &pair_key_vec);
+    let pair = PAIRS.load(&deps.storage, (&pair_key_vec, "ConstantProduct-0.02"));
 
     assert!(pair.is_ok(), "pair key should exist");
 
-    let msg = ExecuteMsg::RemovePair { asset_infos };
+    let msg = ExecuteMsg::RemovePair {
+        asset_infos,
+        pair_identifier: None,
+    };
     let env = mock_env();
     let info = mock_info("addr0000", &[]);
     let res = execute(deps.as_mut(), env, info, msg).unwrap();
@@ -1559,13 +2467,15 @@ fn delete_pair() {
         res.attributes,
         vec![
             attr("action", "remove_pair"),
+            attr("pair_identifier", "ConstantProduct-0.02"),
             attr("pair_contract_addr", "pair0000"),
         ]
     );
 
-    let pair = PAIRS.load(&deps.storage, &pair_key_vec);
+    let pair = PAIRS.load(&deps.storage, (&pair_key_vec, "ConstantProduct-0.02"));
 
     assert!(pair.is_err(), "pair key should not exist");
+    assert!(!DEFAULT_PAIRS.has(&deps.storage, &pair_key_vec));
 }
 
 #[test]
@@ -1583,7 +2493,10 @@ fn delete_pair_failed_if_not_found() {
         },
     ];
 
-    let msg = ExecuteMsg::RemovePair { asset_infos };
+    let msg = ExecuteMsg::RemovePair {
+        asset_infos,
+        pair_identifier: None,
+    };
     let env = mock_env();
     let info = mock_info("addr0000", &[]);
     let res = execute(deps.as_mut(), env, info, msg);
@@ -1616,6 +2529,10 @@ fn update_pair_config() {
             },
         }),
         feature_toggle: None,
+        amp_factor: Some(RampAmp {
+            future_a: 200,
+            future_block: 20_000,
+        }),
     };
 
     let env = mock_env();
@@ -1644,12 +2561,49 @@ fn update_pair_config() {
                         },
                     }),
                     feature_toggle: None,
+                    amp_factor: Some(RampAmp {
+                        future_a: 200,
+                        future_block: 20_000,
+                    }),
                 })
                 .unwrap(),
             })
     );
 }
 
+#[test]
+fn stop_pair_amp_ramp() {
+    let mut deps = mock_dependencies(&[coin(10u128, "uusd".to_string())]);
+    deps = init(deps);
+
+    let msg = ExecuteMsg::StopPairAmpRamp {
+        pair_addr: "pair_addr".to_string(),
+    };
+
+    // only the owner can stop the ramp
+    let info = mock_info("not_owner", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    match res {
+        Ok(_) => panic!("should return ContractError::Unauthorized"),
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("should return ContractError::Unauthorized"),
+    }
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attributes(vec![attr("action", "stop_pair_amp_ramp")])
+            .add_message(WasmMsg::Execute {
+                contract_addr: "pair_addr".to_string(),
+                funds: vec![],
+                msg: to_binary(&pool_network::pair::ExecuteMsg::StopAmpRamp {}).unwrap(),
+            })
+    );
+}
+
 #[test]
 fn create_trio_cw20_lp() {
     let mut deps = mock_dependencies(&[
@@ -1666,7 +2620,7 @@ fn create_trio_cw20_lp() {
             ("udai".to_string(), 10u8),
         ],
     );
-    let asset_infos = [
+    let asset_infos = vec![
         AssetInfo::NativeToken {
             denom: "uusd".to_string(),
         },
@@ -1693,6 +2647,8 @@ fn create_trio_cw20_lp() {
         },
         amp_factor: 1000,
         token_factory_lp: false,
+        initial_liquidity: None,
+        receiver: None,
     };
 
     let env = mock_env();
@@ -1716,7 +2672,7 @@ fn create_trio_cw20_lp() {
                 msg: to_binary(&TrioInstantiateMsg {
                     asset_infos: asset_infos.clone(),
                     token_code_id: 123u64,
-                    asset_decimals: [6u8, 8u8, 10u8],
+                    asset_decimals: vec![6u8, 8u8, 10u8],
                     pool_fees: TrioPoolFee {
                         protocol_fee: Fee {
                             share: Decimal::percent(1u64),
@@ -1742,7 +2698,7 @@ fn create_trio_cw20_lp() {
         },]
     );
 
-    let raw_infos = [
+    let raw_infos = vec![
         asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
         asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
         asset_infos[2].to_raw(deps.as_ref().api).unwrap(),
@@ -1753,7 +2709,8 @@ fn create_trio_cw20_lp() {
         TmpTrioInfo {
             asset_infos: raw_infos.clone(),
             trio_key: trio_key(&raw_infos),
-            asset_decimals: [6u8, 8u8, 10u8],
+            asset_decimals: vec![6u8, 8u8, 10u8],
+            initial_liquidity: None,
         }
     );
 }
@@ -1789,7 +2746,7 @@ fn create_trio_with_native_tokens_token_factory_lp() {
         ],
     );
 
-    let asset_infos = [
+    let asset_infos = vec![
         AssetInfo::NativeToken {
             denom: "uusd".to_string(),
         },
@@ -1819,6 +2776,8 @@ fn create_trio_with_native_tokens_token_factory_lp() {
         },
         amp_factor: 1000,
         token_factory_lp: true,
+        initial_liquidity: None,
+        receiver: None,
     };
 
     let env = mock_env();
@@ -1845,7 +2804,7 @@ fn create_trio_with_native_tokens_token_factory_lp() {
                 msg: to_binary(&TrioInstantiateMsg {
                     asset_infos: asset_infos.clone(),
                     token_code_id: 123u64,
-                    asset_decimals: [6u8, 6u8, 6u8],
+                    asset_decimals: vec![6u8, 6u8, 6u8],
                     pool_fees: TrioPoolFee {
                         protocol_fee: Fee {
                             share: Decimal::percent(1u64),
@@ -1871,7 +2830,7 @@ fn create_trio_with_native_tokens_token_factory_lp() {
         },]
     );
 
-    let raw_infos = [
+    let raw_infos = vec![
         asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
         asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
         asset_infos[2].to_raw(deps.as_ref().api).unwrap(),
@@ -1882,7 +2841,1120 @@ fn create_trio_with_native_tokens_token_factory_lp() {
         TmpTrioInfo {
             asset_infos: raw_infos.clone(),
             trio_key: trio_key(&raw_infos),
-            asset_decimals: [6u8, 6u8, 6u8],
+            asset_decimals: vec![6u8, 6u8, 6u8],
+            initial_liquidity: None,
         }
     );
 }
+
+#[test]
+fn create_trio_with_four_assets() {
+    let mut deps = mock_dependencies(&[
+        coin(10u128, "uusd".to_string()),
+        coin(10u128, "uusdc".to_string()),
+        coin(10u128, "udai".to_string()),
+        coin(10u128, "uusdt".to_string()),
+    ]);
+    deps = init(deps);
+    deps.querier.with_pool_factory(
+        &[],
+        &[
+            ("uusd".to_string(), 6u8),
+            ("uusdc".to_string(), 8u8),
+            ("udai".to_string(), 10u8),
+            ("uusdt".to_string(), 6u8),
+        ],
+    );
+    let asset_infos = vec![
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: "uusdc".to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: "udai".to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: "uusdt".to_string(),
+        },
+    ];
+
+    let msg = ExecuteMsg::CreateTrio {
+        asset_infos: asset_infos.clone(),
+        pool_fees: TrioPoolFee {
+            protocol_fee: Fee {
+                share: Decimal::percent(1u64),
+            },
+            swap_fee: Fee {
+                share: Decimal::percent(1u64),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+        },
+        amp_factor: 1000,
+        token_factory_lp: false,
+        initial_liquidity: None,
+        receiver: None,
+    };
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "create_trio"),
+            attr("trio", "uusd-uusdc-udai-uusdt"),
+            attr("trio_label", "uusd-uusdc-udai-uusdt trio"),
+        ]
+    );
+
+    let raw_infos = asset_infos
+        .iter()
+        .map(|asset_info| asset_info.to_raw(deps.as_ref().api).unwrap())
+        .collect::<Vec<AssetInfoRaw>>();
+
+    assert_eq!(
+        TMP_TRIO_INFO.load(&deps.storage).unwrap(),
+        TmpTrioInfo {
+            asset_infos: raw_infos.clone(),
+            trio_key: trio_key(&raw_infos),
+            asset_decimals: vec![6u8, 8u8, 10u8, 6u8],
+            initial_liquidity: None,
+        }
+    );
+}
+
+#[test]
+fn create_trio_with_invalid_number_of_assets() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+
+    let asset_info = |i: usize| AssetInfo::NativeToken {
+        denom: format!("denom{i}"),
+    };
+
+    for number_of_assets in [0usize, 1, 9] {
+        let msg = ExecuteMsg::CreateTrio {
+            asset_infos: (0..number_of_assets).map(asset_info).collect(),
+            pool_fees: TrioPoolFee {
+                protocol_fee: Fee {
+                    share: Decimal::percent(1u64),
+                },
+                swap_fee: Fee {
+                    share: Decimal::percent(1u64),
+                },
+                burn_fee: Fee {
+                    share: Decimal::zero(),
+                },
+            },
+            amp_factor: 1000,
+            token_factory_lp: false,
+            initial_liquidity: None,
+            receiver: None,
+        };
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg);
+        match res {
+            Ok(_) => panic!("Should return ContractError::InvalidNumberOfAssets"),
+            Err(ContractError::InvalidNumberOfAssets { min, max }) => {
+                assert_eq!((min, max), (2, 8))
+            }
+            _ => panic!("Should return ContractError::InvalidNumberOfAssets"),
+        }
+    }
+
+    // the same asset can't be used more than once, even when not adjacent
+    let msg = ExecuteMsg::CreateTrio {
+        asset_infos: vec![asset_info(0), asset_info(1), asset_info(2), asset_info(0)],
+        pool_fees: TrioPoolFee {
+            protocol_fee: Fee {
+                share: Decimal::percent(1u64),
+            },
+            swap_fee: Fee {
+                share: Decimal::percent(1u64),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+        },
+        amp_factor: 1000,
+        token_factory_lp: false,
+        initial_liquidity: None,
+        receiver: None,
+    };
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg);
+    match res {
+        Ok(_) => panic!("Should return ContractError::SameAsset"),
+        Err(ContractError::SameAsset { .. }) => (),
+        _ => panic!("Should return ContractError::SameAsset"),
+    }
+}
+
+#[test]
+fn trio_keys_are_unique_across_number_of_assets() {
+    let asset_info = |denom: &str| AssetInfoRaw::NativeToken {
+        denom: denom.to_string(),
+    };
+
+    // without length prefixes both keys would be "abcd"
+    assert_ne!(
+        trio_key(&[asset_info("ab"), asset_info("cd")]),
+        trio_key(&[asset_info("a"), asset_info("b"), asset_info("cd")])
+    );
+    // the key doesn't depend on the order of the assets
+    assert_eq!(
+        trio_key(&[asset_info("a"), asset_info("b"), asset_info("c")]),
+        trio_key(&[asset_info("c"), asset_info("a"), asset_info("b")])
+    );
+}
+
+#[test]
+fn migrate_trio_keys() {
+    let mut deps = mock_dependencies(&[]);
+
+    let asset_infos = vec![
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: "uusdc".to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: "udai".to_string(),
+        },
+    ];
+    let raw_infos = asset_infos
+        .iter()
+        .map(|asset_info| asset_info.to_raw(deps.as_ref().api).unwrap())
+        .collect::<Vec<AssetInfoRaw>>();
+
+    // trios used to be keyed by the plain concatenation of their sorted assets
+    let mut sorted_infos = raw_infos.clone();
+    sorted_infos.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+    let old_key = sorted_infos
+        .iter()
+        .flat_map(|asset_info| asset_info.as_bytes().to_vec())
+        .collect::<Vec<u8>>();
+
+    let trio_info = TrioInfoRaw {
+        asset_infos: raw_infos.clone(),
+        contract_addr: deps.api.addr_canonicalize("trio0000").unwrap(),
+        liquidity_token: AssetInfoRaw::Token {
+            contract_addr: deps.api.addr_canonicalize("liquidity0000").unwrap(),
+        },
+        asset_decimals: vec![6u8, 6u8, 6u8],
+    };
+    TRIOS
+        .save(deps.as_mut().storage, &old_key, &trio_info)
+        .unwrap();
+
+    migrate_to_v130(deps.as_mut()).unwrap();
+
+    assert!(!TRIOS.has(&deps.storage, &old_key));
+    let res: TrioInfo =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Trio { asset_infos }).unwrap())
+            .unwrap();
+    assert_eq!(res, trio_info.to_normal(deps.as_ref().api).unwrap());
+}
+
+#[test]
+fn create_pair_with_pool_creation_fee() {
+    let mut deps = mock_dependencies(&[coin(10u128, "uusd".to_string())]);
+    deps = init(deps);
+    deps.querier
+        .with_pool_factory(&[], &[("uusd".to_string(), 6u8)]);
+
+    // the pool creation fee must be a native token
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        fee_collector_addr: None,
+        token_code_id: None,
+        pair_code_id: None,
+        trio_code_id: None,
+        pool_creation_fee: Some(Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            amount: Uint128::new(100u128),
+        }),
+        allowlist_enabled: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg);
+    match res {
+        Err(ContractError::InvalidPoolCreationFee {}) => (),
+        _ => panic!("Must return ContractError::InvalidPoolCreationFee"),
+    }
+
+    let pool_creation_fee = Asset {
+        info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        amount: Uint128::new(100u128),
+    };
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        fee_collector_addr: None,
+        token_code_id: None,
+        pair_code_id: None,
+        trio_code_id: None,
+        pool_creation_fee: Some(pool_creation_fee.clone()),
+        allowlist_enabled: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    assert_eq!(config_res.pool_creation_fee, Some(pool_creation_fee));
+
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+    ];
+    let pool_fees = PoolFee {
+        protocol_fee: Fee {
+            share: Decimal::percent(1u64),
+        },
+        swap_fee: Fee {
+            share: Decimal::percent(1u64),
+        },
+        burn_fee: Fee {
+            share: Decimal::zero(),
+        },
+    };
+    let msg = ExecuteMsg::CreatePair {
+        asset_infos: asset_infos.clone(),
+        pool_fees: pool_fees.clone(),
+        pair_type: PairType::ConstantProduct,
+        token_factory_lp: false,
+        pair_identifier: None,
+        initial_liquidity: None,
+        receiver: None,
+    };
+
+    // anyone can create a pair, but the fee must be paid
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator", &[coin(99u128, "uusd")]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::PoolCreationFeeMissing { .. }) => (),
+        _ => panic!("Must return ContractError::PoolCreationFeeMissing"),
+    }
+
+    // the fee goes to the fee collector, and the rest of the funds to the pair
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator", &[coin(101u128, "uusd")]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(BankMsg::Send {
+                to_address: "collector".to_string(),
+                amount: vec![coin(100u128, "uusd")],
+            }),
+            SubMsg::reply_on_success(
+                WasmMsg::Instantiate {
+                    msg: to_binary(&PairInstantiateMsg {
+                        asset_infos,
+                        token_code_id: 123u64,
+                        asset_decimals: [6u8, 8u8],
+                        pool_fees,
+                        fee_collector_addr: "collector".to_string(),
+                        pair_type: PairType::ConstantProduct,
+                        token_factory_lp: false,
+                    })
+                    .unwrap(),
+                    code_id: 321u64,
+                    funds: vec![coin(1u128, "uusd")],
+                    label: "uusd-mAAPL pair".to_string(),
+                    admin: Some(MOCK_CONTRACT_ADDR.to_string()),
+                },
+                1,
+            ),
+        ]
+    );
+
+    // a zero fee removes it
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        fee_collector_addr: None,
+        token_code_id: None,
+        pair_code_id: None,
+        trio_code_id: None,
+        pool_creation_fee: Some(Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::zero(),
+        }),
+        allowlist_enabled: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    assert_eq!(config_res.pool_creation_fee, None);
+}
+
+#[test]
+fn create_pair_with_allowlist() {
+    let mut deps = mock_dependencies(&[coin(10u128, "uusd".to_string())]);
+    deps = init(deps);
+    deps.querier
+        .with_pool_factory(&[], &[("uusd".to_string(), 6u8)]);
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        fee_collector_addr: None,
+        token_code_id: None,
+        pair_code_id: None,
+        trio_code_id: None,
+        pool_creation_fee: None,
+        allowlist_enabled: Some(true),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let token = AssetInfo::Token {
+        contract_addr: "asset0001".to_string(),
+    };
+    let msg = ExecuteMsg::CreatePair {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            token.clone(),
+        ],
+        pool_fees: PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::percent(1u64),
+            },
+            swap_fee: Fee {
+                share: Decimal::percent(1u64),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+        },
+        pair_type: PairType::ConstantProduct,
+        token_factory_lp: false,
+        pair_identifier: None,
+        initial_liquidity: None,
+        receiver: None,
+    };
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::CreatorNotAllowed {}) => (),
+        _ => panic!("Must return ContractError::CreatorNotAllowed"),
+    }
+
+    // the owner can always create pools
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+
+    // allowing the creator for the only protected asset of the pool is enough
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateAllowedCreators {
+            asset_info: token.clone(),
+            add: vec!["creator".to_string(), "creator2".to_string()],
+            remove: vec![],
+        },
+    )
+    .unwrap();
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AllowedCreators {
+            asset_info: token.clone(),
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let allowed_creators: AllowedCreatorsResponse = from_binary(&query_res).unwrap();
+    assert_eq!(
+        allowed_creators.creators,
+        vec!["creator".to_string(), "creator2".to_string()]
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateAllowedCreators {
+            asset_info: token.clone(),
+            add: vec![],
+            remove: vec!["creator".to_string()],
+        },
+    )
+    .unwrap();
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AllowedCreators {
+            asset_info: token,
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let allowed_creators: AllowedCreatorsResponse = from_binary(&query_res).unwrap();
+    assert_eq!(allowed_creators.creators, vec!["creator2".to_string()]);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::CreatorNotAllowed {}) => (),
+        _ => panic!("Must return ContractError::CreatorNotAllowed"),
+    }
+
+    // a creator allowed for one asset can't create pools with another protected asset
+    let uusd = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateAllowedCreators {
+            asset_info: uusd.clone(),
+            add: vec!["creator".to_string()],
+            remove: vec![],
+        },
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::CreatorNotAllowed {}) => (),
+        _ => panic!("Must return ContractError::CreatorNotAllowed"),
+    }
+
+    // the creator must be allowed for every protected asset of the pool
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateAllowedCreators {
+            asset_info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            add: vec!["creator".to_string()],
+            remove: vec![],
+        },
+    )
+    .unwrap();
+    execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+}
+
+#[test]
+fn create_pairs_with_same_assets() {
+    let mut deps = mock_dependencies(&[coin(10u128, "uusd".to_string())]);
+    deps = init(deps);
+    deps.querier
+        .with_pool_factory(&[], &[("uusd".to_string(), 6u8)]);
+
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+    ];
+    let raw_infos = [
+        asset_infos[0].to_raw(&deps.api).unwrap(),
+        asset_infos[1].to_raw(&deps.api).unwrap(),
+    ];
+    let pool_fees = |swap_fee: u64| PoolFee {
+        protocol_fee: Fee {
+            share: Decimal::zero(),
+        },
+        swap_fee: Fee {
+            share: Decimal::permille(swap_fee),
+        },
+        burn_fee: Fee {
+            share: Decimal::zero(),
+        },
+    };
+    let create_pair = |pair_type: PairType, swap_fee: u64, pair_identifier: Option<&str>| {
+        ExecuteMsg::CreatePair {
+            asset_infos: asset_infos.clone(),
+            pool_fees: pool_fees(swap_fee),
+            pair_type,
+            token_factory_lp: false,
+            pair_identifier: pair_identifier.map(|pair_identifier| pair_identifier.to_string()),
+            initial_liquidity: None,
+            receiver: None,
+        }
+    };
+    // replies to the creation of the pair, which registers it
+    let register_pair = |deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+                         contract_addr: &str| {
+        reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        [
+                            vec![10, contract_addr.len() as u8],
+                            contract_addr.as_bytes().to_vec(),
+                        ]
+                        .concat()
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+    };
+
+    let info = mock_info("addr0000", &[]);
+
+    // pairs of the same assets with a different type or fee tier can coexist
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        create_pair(PairType::ConstantProduct, 3, None),
+    )
+    .unwrap();
+    register_pair(&mut deps, "pair0000");
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        create_pair(PairType::StableSwap { amp: 100 }, 3, None),
+    )
+    .unwrap();
+    register_pair(&mut deps, "pair0001");
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        create_pair(PairType::ConstantProduct, 1, None),
+    )
+    .unwrap();
+    register_pair(&mut deps, "pair0002");
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        create_pair(PairType::ConstantProduct, 3, None),
+    );
+    match res {
+        Err(ContractError::ExistingPair {}) => (),
+        _ => panic!("Must return ContractError::ExistingPair"),
+    }
+
+    // a custom identifier allows another pair with the same type and fee tier
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        create_pair(PairType::ConstantProduct, 3, Some("custom")),
+    )
+    .unwrap();
+    register_pair(&mut deps, "pair0003");
+
+    // identifiers are made of up to 64 ASCII letters, digits, '.', '-' or '_'
+    let too_long_identifier = "a".repeat(65);
+    for pair_identifier in ["", "with space", "with/slash", too_long_identifier.as_str()] {
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            create_pair(PairType::ConstantProduct, 3, Some(pair_identifier)),
+        );
+        match res {
+            Err(ContractError::InvalidPairIdentifier {}) => (),
+            _ => panic!("Must return ContractError::InvalidPairIdentifier"),
+        }
+    }
+
+    let query_pair = |deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+                      pair_identifier: Option<&str>| {
+        let res: PairInfo = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Pair {
+                    asset_infos: asset_infos.clone(),
+                    pair_identifier: pair_identifier
+                        .map(|pair_identifier| pair_identifier.to_string()),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res.contract_addr
+    };
+
+    assert_eq!(query_pair(&deps, Some("ConstantProduct-0.003")), "pair0000");
+    assert_eq!(query_pair(&deps, Some("StableSwap-0.003")), "pair0001");
+    assert_eq!(query_pair(&deps, Some("ConstantProduct-0.001")), "pair0002");
+    assert_eq!(query_pair(&deps, Some("custom")), "pair0003");
+    // the first pair created is the default one, even though the identifiers of pairs created
+    // later sort before its own
+    assert_eq!(query_pair(&deps, None), "pair0000");
+
+    // all the pairs of the assets are returned when filtering by them
+    let res: PairsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Pairs {
+                start_after: None,
+                start_after_identifier: None,
+                limit: None,
+                asset_infos: Some(asset_infos.clone()),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.pairs
+            .iter()
+            .map(|pair| pair.contract_addr.as_str())
+            .collect::<Vec<&str>>(),
+        vec!["pair0002", "pair0000", "pair0001", "pair0003"]
+    );
+    assert_eq!(
+        res.pair_identifiers,
+        vec![
+            "ConstantProduct-0.001",
+            "ConstantProduct-0.003",
+            "StableSwap-0.003",
+            "custom"
+        ]
+    );
+
+    // pages hold `limit` pairs, and the next page starts after the assets and the identifier of
+    // the last pair
+    let query_pairs = |deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+                       start_after: Option<&str>| {
+        let res: PairsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Pairs {
+                    start_after: start_after.map(|_| asset_infos.clone()),
+                    start_after_identifier: start_after.map(|identifier| identifier.to_string()),
+                    limit: Some(3),
+                    asset_infos: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res.pair_identifiers
+    };
+    assert_eq!(
+        query_pairs(&deps, None),
+        vec![
+            "ConstantProduct-0.001",
+            "ConstantProduct-0.003",
+            "StableSwap-0.003"
+        ]
+    );
+    assert_eq!(query_pairs(&deps, Some("StableSwap-0.003")), vec!["custom"]);
+    assert!(query_pairs(&deps, Some("custom")).is_empty());
+
+    // without an identifier, the page starts after all the pairs of the given assets
+    let res: PairsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Pairs {
+                start_after: Some(asset_infos.clone()),
+                start_after_identifier: None,
+                limit: Some(1),
+                asset_infos: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(res.pairs.is_empty());
+
+    // a specific pair can be removed
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::RemovePair {
+            asset_infos: asset_infos.clone(),
+            pair_identifier: Some("StableSwap-0.003".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "remove_pair"),
+            attr("pair_identifier", "StableSwap-0.003"),
+            attr("pair_contract_addr", "pair0001"),
+        ]
+    );
+    assert!(!PAIRS.has(&deps.storage, (&pair_key(&raw_infos), "StableSwap-0.003")));
+    assert!(PAIRS.has(
+        &deps.storage,
+        (&pair_key(&raw_infos), "ConstantProduct-0.003")
+    ));
+    assert_eq!(query_pair(&deps, None), "pair0000");
+
+    // the default pair is removed if no identifier is given
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::RemovePair {
+            asset_infos: asset_infos.clone(),
+            pair_identifier: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "remove_pair"),
+            attr("pair_identifier", "ConstantProduct-0.003"),
+            attr("pair_contract_addr", "pair0000"),
+        ]
+    );
+
+    // once the default pair is gone, no other pair of the assets takes its place, not even one
+    // created afterwards
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        create_pair(PairType::ConstantProduct, 3, Some("0")),
+    )
+    .unwrap();
+    register_pair(&mut deps, "pair0004");
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Pair {
+            asset_infos: asset_infos.clone(),
+            pair_identifier: None,
+        },
+    );
+    match res {
+        Err(StdError::NotFound { .. }) => (),
+        _ => panic!("Must return StdError::NotFound"),
+    }
+    assert_eq!(query_pair(&deps, Some("0")), "pair0004");
+    assert_eq!(query_pair(&deps, Some("ConstantProduct-0.001")), "pair0002");
+}
+
+#[test]
+fn migrate_pairs_to_pair_identifiers() {
+    let mut deps = mock_dependencies(&[]);
+
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+    ];
+    let raw_infos = [
+        asset_infos[0].to_raw(&deps.api).unwrap(),
+        asset_infos[1].to_raw(&deps.api).unwrap(),
+    ];
+    let pair_info = PairInfoRaw {
+        liquidity_token: AssetInfoRaw::Token {
+            contract_addr: deps.api.addr_canonicalize("liquidity0000").unwrap(),
+        },
+        contract_addr: deps.api.addr_canonicalize("pair0000").unwrap(),
+        asset_infos: raw_infos.clone(),
+        asset_decimals: [6u8, 8u8],
+        pair_type: PairType::ConstantProduct,
+    };
+
+    // the pairs used to be keyed by their assets only
+    const OLD_PAIRS: Map<&[u8], PairInfoRaw> = Map::new("pair_info");
+    let old_key = pair_key(&raw_infos);
+    OLD_PAIRS
+        .save(deps.as_mut().storage, &old_key, &pair_info)
+        .unwrap();
+
+    migrate_to_v150(deps.as_mut()).unwrap();
+
+    assert!(!OLD_PAIRS.has(&deps.storage, &old_key));
+    // the identifier is built out of the fees the pair is configured with
+    let res: PairInfo = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Pair {
+                asset_infos: asset_infos.clone(),
+                pair_identifier: Some("ConstantProduct-0.003".to_string()),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, pair_info.to_normal(deps.as_ref().api).unwrap());
+
+    // and the migrated pair is the default one of its assets
+    let res: PairInfo = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Pair {
+                asset_infos,
+                pair_identifier: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, pair_info.to_normal(deps.as_ref().api).unwrap());
+}
+
+#[test]
+fn pools_by_asset() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+
+    let uusd = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+    let uluna = AssetInfo::NativeToken {
+        denom: "uluna".to_string(),
+    };
+    let token = AssetInfo::Token {
+        contract_addr: "asset0001".to_string(),
+    };
+    let pair_info = |contract_addr: &str, asset_infos: [AssetInfo; 2]| PairInfo {
+        asset_infos,
+        contract_addr: contract_addr.to_string(),
+        liquidity_token: AssetInfo::Token {
+            contract_addr: format!("liquidity{contract_addr}"),
+        },
+        asset_decimals: [6u8, 8u8],
+        pair_type: PairType::ConstantProduct,
+    };
+    let pairs = [
+        pair_info("pair0000", [uusd.clone(), token.clone()]),
+        pair_info("pair0001", [token.clone(), uluna.clone()]),
+    ];
+    let trio = TrioInfo {
+        asset_infos: vec![uusd.clone(), token.clone(), uluna.clone()],
+        contract_addr: "trio0000".to_string(),
+        liquidity_token: AssetInfo::Token {
+            contract_addr: "liquiditytrio0000".to_string(),
+        },
+        asset_decimals: vec![6u8, 8u8, 6u8],
+    };
+    deps.querier.with_pool_factory(
+        &[
+            (&"pair0000".to_string(), &pairs[0]),
+            (&"pair0001".to_string(), &pairs[1]),
+        ],
+        &[],
+    );
+    deps.querier.with_trios(&[(&"trio0000".to_string(), &trio)]);
+
+    // the instantiate reply of a pool, with the pool address as the first field of the response
+    let instantiate_reply = |id: u64, contract_addr: &str| Reply {
+        id,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: Some(
+                [
+                    vec![10, contract_addr.len() as u8],
+                    contract_addr.as_bytes().to_vec(),
+                ]
+                .concat()
+                .into(),
+            ),
+        }),
+    };
+
+    // create the pools through their replies, which index them by asset
+    for pair in pairs.iter() {
+        let raw_infos = [
+            pair.asset_infos[0].to_raw(&deps.api).unwrap(),
+            pair.asset_infos[1].to_raw(&deps.api).unwrap(),
+        ];
+        TMP_PAIR_INFO
+            .save(
+                &mut deps.storage,
+                &TmpPairInfo {
+                    pair_key: pair_key(&raw_infos),
+                    pair_identifier: "ConstantProduct-0.02".to_string(),
+                    asset_infos: raw_infos,
+                    asset_decimals: [6u8, 8u8],
+                    pair_type: PairType::ConstantProduct,
+                    initial_liquidity: None,
+                },
+            )
+            .unwrap();
+        reply(
+            deps.as_mut(),
+            mock_env(),
+            instantiate_reply(1, &pair.contract_addr),
+        )
+        .unwrap();
+    }
+
+    let raw_infos = trio
+        .asset_infos
+        .iter()
+        .map(|asset_info| asset_info.to_raw(&deps.api).unwrap())
+        .collect::<Vec<AssetInfoRaw>>();
+    TMP_TRIO_INFO
+        .save(
+            &mut deps.storage,
+            &TmpTrioInfo {
+                trio_key: trio_key(&raw_infos),
+                asset_infos: raw_infos,
+                asset_decimals: vec![6u8, 8u8, 6u8],
+                initial_liquidity: None,
+            },
+        )
+        .unwrap();
+    reply(deps.as_mut(), mock_env(), instantiate_reply(2, "trio0000")).unwrap();
+
+    let pools_by_asset = |deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+                          asset_info: &AssetInfo,
+                          start_after: Option<String>,
+                          limit: Option<u32>| {
+        let res: PoolsByAssetResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::PoolsByAsset {
+                    asset_info: asset_info.clone(),
+                    start_after,
+                    limit,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res.pools
+    };
+    let contract_addrs = |pools: &[PoolInfo]| {
+        let mut contract_addrs = pools
+            .iter()
+            .map(|pool| match pool {
+                PoolInfo::Pair { pair_info, .. } => pair_info.contract_addr.clone(),
+                PoolInfo::Trio { trio_info } => trio_info.contract_addr.clone(),
+            })
+            .collect::<Vec<String>>();
+        contract_addrs.sort();
+        contract_addrs
+    };
+
+    let pools = pools_by_asset(&deps, &uusd, None, None);
+    assert_eq!(contract_addrs(&pools), vec!["pair0000", "trio0000"]);
+    assert!(pools.contains(&PoolInfo::Trio {
+        trio_info: trio.clone()
+    }));
+
+    // paginate through the pools of the token
+    let first_page = pools_by_asset(&deps, &token, None, Some(2));
+    assert_eq!(first_page.len(), 2);
+    let last_contract_addr = match first_page.last().unwrap() {
+        PoolInfo::Pair { pair_info, .. } => pair_info.contract_addr.clone(),
+        PoolInfo::Trio { trio_info } => trio_info.contract_addr.clone(),
+    };
+    let second_page = pools_by_asset(&deps, &token, Some(last_contract_addr), Some(2));
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(
+        contract_addrs(&[first_page, second_page].concat()),
+        vec!["pair0000", "pair0001", "trio0000"]
+    );
+
+    // removing the pools removes them from the pools of each of their assets
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::RemovePair {
+            asset_infos: [uusd.clone(), token.clone()],
+            pair_identifier: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        contract_addrs(&pools_by_asset(&deps, &uusd, None, None)),
+        vec!["trio0000"]
+    );
+    assert_eq!(
+        contract_addrs(&pools_by_asset(&deps, &token, None, None)),
+        vec!["pair0001", "trio0000"]
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::RemoveTrio {
+            asset_infos: trio.asset_infos.clone(),
+        },
+    )
+    .unwrap();
+    assert!(pools_by_asset(&deps, &uusd, None, None).is_empty());
+    assert_eq!(
+        contract_addrs(&pools_by_asset(&deps, &uluna, None, None)),
+        vec!["pair0001"]
+    );
+}
@@ -1,22 +1,24 @@
 use cosmwasm_std::{
-    coins, from_binary, to_binary, Addr, CosmosMsg, Decimal, DepsMut, Env, MessageInfo,
+    coins, from_binary, to_binary, Addr, BankMsg, CosmosMsg, Decimal, DepsMut, Env, MessageInfo,
     OverflowError, Response, StdError, StdResult, Uint128, WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 
 use white_whale::pool_network::asset::{
-    is_factory_token, Asset, AssetInfo, AssetInfoRaw, PairInfoRaw, MINIMUM_LIQUIDITY_AMOUNT,
+    is_factory_token, Asset, AssetInfo, AssetInfoRaw, PairInfoRaw, PairType,
+    MINIMUM_LIQUIDITY_AMOUNT,
 };
 use white_whale::pool_network::denom::{Coin, MsgBurn, MsgMint};
 use white_whale::pool_network::pair::{Config, Cw20HookMsg, FeatureToggle, PoolFee};
-use white_whale::pool_network::U256;
+use white_whale::pool_network::trio::RampAmp;
 
+use crate::contract::{MAX_AMP, MAX_AMP_CHANGE, MIN_AMP, MIN_RAMP_BLOCKS};
 use crate::error::ContractError;
 use crate::helpers;
 use crate::helpers::{get_protocol_fee_for_asset, get_total_share, has_factory_token};
 use crate::state::{
-    store_fee, ALL_TIME_BURNED_FEES, ALL_TIME_COLLECTED_PROTOCOL_FEES, COLLECTED_PROTOCOL_FEES,
-    CONFIG, PAIR_INFO,
+    load_pair_info, store_fee, AmpRamp, ALL_TIME_BURNED_FEES, ALL_TIME_COLLECTED_PROTOCOL_FEES,
+    AMP_RAMP, COLLECTED_PROTOCOL_FEES, CONFIG, PAIR_INFO,
 };
 
 /// Receives cw20 tokens. Used to swap and withdraw from the pool.
@@ -36,7 +38,10 @@ pub fn receive_cw20(
             belief_price,
             max_spread,
             to,
+            deadline,
         }) => {
+            helpers::assert_deadline(env.block.time, deadline)?;
+
             // check if the swap feature is enabled
             if !feature_toggle.swaps_enabled {
                 return Err(ContractError::OperationDisabled("swap".to_string()));
@@ -81,7 +86,13 @@ pub fn receive_cw20(
                 to_addr,
             )
         }
-        Ok(Cw20HookMsg::WithdrawLiquidity {}) => {
+        Ok(Cw20HookMsg::WithdrawLiquidity {
+            target_asset,
+            min_receive,
+            deadline,
+        }) => {
+            helpers::assert_deadline(env.block.time, deadline)?;
+
             // check if the withdrawal feature is enabled
             if !feature_toggle.withdrawals_enabled {
                 return Err(ContractError::OperationDisabled(
@@ -100,13 +111,22 @@ pub fn receive_cw20(
             }
 
             let sender_addr = deps.api.addr_validate(cw20_msg.sender.as_str())?;
-            withdraw_liquidity(deps, env, sender_addr, cw20_msg.amount)
+            withdraw_liquidity(
+                deps,
+                env,
+                sender_addr,
+                cw20_msg.amount,
+                target_asset,
+                min_receive,
+            )
         }
         Err(err) => Err(ContractError::Std(err)),
     }
 }
 
-/// Provides liquidity. The user must IncreaseAllowance on the token when providing cw20 tokens
+/// Provides liquidity. The user must IncreaseAllowance on the token when providing cw20 tokens.
+/// If only one of the assets is provided, part of it is swapped for the other asset before
+/// providing liquidity.
 pub fn provide_liquidity(
     deps: DepsMut,
     env: Env,
@@ -116,7 +136,8 @@ pub fn provide_liquidity(
     receiver: Option<String>,
 ) -> Result<Response, ContractError> {
     // check if the deposit feature is enabled
-    let feature_toggle: FeatureToggle = CONFIG.load(deps.storage)?.feature_toggle;
+    let config = CONFIG.load(deps.storage)?;
+    let feature_toggle: FeatureToggle = config.feature_toggle;
     if !feature_toggle.deposits_enabled {
         return Err(ContractError::OperationDisabled(
             "provide_liquidity".to_string(),
@@ -127,7 +148,7 @@ pub fn provide_liquidity(
         asset.assert_sent_native_token_balance(&info)?;
     }
 
-    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+    let pair_info: PairInfoRaw = load_pair_info(deps.storage, env.block.height)?;
     let mut pools: [Asset; 2] =
         pair_info.query_pools(&deps.querier, deps.api, env.contract.address.clone())?;
     let deposits: [Uint128; 2] = [
@@ -143,7 +164,7 @@ pub fn provide_liquidity(
             .expect("Wrong asset info is given"),
     ];
 
-    if deposits[0].is_zero() || deposits[1].is_zero() {
+    if deposits[0].is_zero() && deposits[1].is_zero() {
         return Err(ContractError::InvalidZeroAmount {});
     }
 
@@ -151,15 +172,17 @@ pub fn provide_liquidity(
     for (i, pool) in pools.iter_mut().enumerate() {
         // If the pool is token contract, then we need to execute TransferFrom msg to receive funds
         if let AssetInfo::Token { contract_addr, .. } = &pool.info {
-            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: contract_addr.to_string(),
-                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
-                    owner: info.sender.to_string(),
-                    recipient: env.contract.address.to_string(),
-                    amount: deposits[i],
-                })?,
-                funds: vec![],
-            }));
+            if !deposits[i].is_zero() {
+                messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: contract_addr.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                        owner: info.sender.to_string(),
+                        recipient: env.contract.address.to_string(),
+                        amount: deposits[i],
+                    })?,
+                    funds: vec![],
+                }));
+            }
         } else {
             // If the asset is native token, balance is already increased
             // To calculate it properly we should subtract user deposit from the pool
@@ -175,65 +198,87 @@ pub fn provide_liquidity(
         pool.amount = pool.amount.checked_sub(protocol_fee)?;
     }
 
-    let liquidity_token = match pair_info.liquidity_token {
-        AssetInfoRaw::Token { contract_addr } => {
-            deps.api.addr_humanize(&contract_addr)?.to_string()
-        }
-        AssetInfoRaw::NativeToken { denom } => denom,
+    helpers::update_price_accumulators(deps.storage, env.block.time.seconds(), &pools, &pair_info)?;
+
+    let liquidity_token = match &pair_info.liquidity_token {
+        AssetInfoRaw::Token { contract_addr } => deps.api.addr_humanize(contract_addr)?.to_string(),
+        AssetInfoRaw::NativeToken { denom } => denom.to_string(),
     };
 
     let total_share = get_total_share(&deps.as_ref(), liquidity_token.clone())?;
-    let share = if total_share == Uint128::zero() {
-        // Make sure at least MINIMUM_LIQUIDITY_AMOUNT is deposited to mitigate the risk of the first
-        // depositor preventing small liquidity providers from joining the pool
-        let share = Uint128::new(
-            (U256::from(deposits[0].u128())
-                .checked_mul(U256::from(deposits[1].u128()))
-                .ok_or::<ContractError>(ContractError::LiquidityShareComputation {}))?
-            .integer_sqrt()
-            .as_u128(),
-        )
-        .checked_sub(MINIMUM_LIQUIDITY_AMOUNT)
-        .map_err(|_| ContractError::InvalidInitialLiquidityAmount(MINIMUM_LIQUIDITY_AMOUNT))?;
+    let provision = helpers::compute_liquidity_provision(
+        deposits,
+        &pools,
+        total_share,
+        &pair_info,
+        config.pool_fees,
+        slippage_tolerance,
+    )?;
 
+    if total_share.is_zero() {
         messages.append(&mut mint_lp_token_msg(
             liquidity_token.clone(),
             env.contract.address.to_string(),
             env.contract.address.to_string(),
             MINIMUM_LIQUIDITY_AMOUNT,
         )?);
+    }
 
-        // share should be above zero after subtracting the MINIMUM_LIQUIDITY_AMOUNT
-        if share.is_zero() {
-            return Err(ContractError::InvalidInitialLiquidityAmount(
-                MINIMUM_LIQUIDITY_AMOUNT,
-            ));
+    let mut swap_attributes = vec![];
+    if let Some(single_sided_swap) = &provision.swap {
+        // check if the swap feature is enabled
+        if !feature_toggle.swaps_enabled {
+            return Err(ContractError::OperationDisabled("swap".to_string()));
         }
 
-        share
-    } else {
-        // min(1, 2)
-        // 1. sqrt(deposit_0 * exchange_rate_0_to_1 * deposit_0) * (total_share / sqrt(pool_0 * pool_1))
-        // == deposit_0 * total_share / pool_0
-        // 2. sqrt(deposit_1 * exchange_rate_1_to_0 * deposit_1) * (total_share / sqrt(pool_1 * pool_1))
-        // == deposit_1 * total_share / pool_1
-        let amount = std::cmp::min(
-            deposits[0].multiply_ratio(total_share, pools[0].amount),
-            deposits[1].multiply_ratio(total_share, pools[1].amount),
-        );
-
-        // assert slippage tolerance
-        helpers::assert_slippage_tolerance(
-            &slippage_tolerance,
-            &deposits,
-            &pools,
-            pair_info.pair_type,
-            amount,
-            total_share,
+        let ask_pool = &pools[1 - single_sided_swap.offer_index];
+        let computation = &single_sided_swap.computation;
+
+        // burn ask_asset from the pool
+        if !computation.burn_fee_amount.is_zero() {
+            let burn_asset = Asset {
+                info: ask_pool.info.clone(),
+                amount: computation.burn_fee_amount,
+            };
+
+            store_fee(
+                deps.storage,
+                burn_asset.amount,
+                burn_asset.clone().get_id(),
+                ALL_TIME_BURNED_FEES,
+            )?;
+
+            messages.push(burn_asset.into_burn_msg()?);
+        }
+
+        // Store the protocol fees generated by the swap
+        store_fee(
+            deps.storage,
+            computation.protocol_fee_amount,
+            ask_pool.clone().get_id(),
+            COLLECTED_PROTOCOL_FEES,
+        )?;
+        store_fee(
+            deps.storage,
+            computation.protocol_fee_amount,
+            ask_pool.clone().get_id(),
+            ALL_TIME_COLLECTED_PROTOCOL_FEES,
         )?;
 
-        amount
-    };
+        swap_attributes = vec![
+            (
+                "swap_offer_amount",
+                single_sided_swap.offer_amount.to_string(),
+            ),
+            ("swap_return_amount", computation.return_amount.to_string()),
+            ("swap_fee_amount", computation.swap_fee_amount.to_string()),
+            (
+                "protocol_fee_amount",
+                computation.protocol_fee_amount.to_string(),
+            ),
+            ("burn_fee_amount", computation.burn_fee_amount.to_string()),
+        ];
+    }
 
     // mint LP token to sender
     let receiver = receiver.unwrap_or_else(|| info.sender.to_string());
@@ -241,82 +286,186 @@ pub fn provide_liquidity(
         liquidity_token,
         receiver.clone(),
         env.contract.address.to_string(),
-        share,
+        provision.share,
     )?);
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        ("action", "provide_liquidity"),
-        ("sender", info.sender.as_str()),
-        ("receiver", receiver.as_str()),
-        ("assets", &format!("{}, {}", assets[0], assets[1])),
-        ("share", &share.to_string()),
-    ]))
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            ("action", "provide_liquidity"),
+            ("sender", info.sender.as_str()),
+            ("receiver", receiver.as_str()),
+            ("assets", &format!("{}, {}", assets[0], assets[1])),
+            ("share", &provision.share.to_string()),
+        ])
+        .add_attributes(swap_attributes))
 }
 
 /// Withdraws the liquidity. The user burns the LP tokens in exchange for the tokens provided, including
-/// the swap fees accrued by its share of the pool.
+/// the swap fees accrued by its share of the pool. If a `target_asset` is given, the share of the
+/// other asset is swapped for it, so the withdrawal is paid out in a single asset.
 pub fn withdraw_liquidity(
     deps: DepsMut,
     env: Env,
     sender: Addr,
     amount: Uint128,
+    target_asset: Option<AssetInfo>,
+    min_receive: Option<Uint128>,
 ) -> Result<Response, ContractError> {
-    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
-    let pool_assets: [Asset; 2] =
-        pair_info.query_pools(&deps.querier, deps.api, env.contract.address.clone())?;
+    let pair_info: PairInfoRaw = load_pair_info(deps.storage, env.block.height)?;
+    let collected_protocol_fees = COLLECTED_PROTOCOL_FEES.load(deps.storage)?;
 
-    let liquidity_token = match pair_info.liquidity_token {
-        AssetInfoRaw::Token { contract_addr } => {
-            deps.api.addr_humanize(&contract_addr)?.to_string()
-        }
-        AssetInfoRaw::NativeToken { denom } => denom,
+    // subtract the protocol fees from the pool assets
+    let pool_assets: Vec<Asset> = pair_info
+        .query_pools(&deps.querier, deps.api, env.contract.address.clone())?
+        .into_iter()
+        .map(|mut pool_asset| {
+            let protocol_fee = get_protocol_fee_for_asset(
+                collected_protocol_fees.clone(),
+                pool_asset.clone().get_id(),
+            );
+            pool_asset.amount = pool_asset.amount.checked_sub(protocol_fee)?;
+
+            Ok(pool_asset)
+        })
+        .collect::<Result<Vec<_>, OverflowError>>()?;
+
+    helpers::update_price_accumulators(
+        deps.storage,
+        env.block.time.seconds(),
+        &pool_assets,
+        &pair_info,
+    )?;
+
+    let liquidity_token = match &pair_info.liquidity_token {
+        AssetInfoRaw::Token { contract_addr } => deps.api.addr_humanize(contract_addr)?.to_string(),
+        AssetInfoRaw::NativeToken { denom } => denom.to_string(),
     };
 
     let total_share = get_total_share(&deps.as_ref(), liquidity_token.clone())?;
 
-    let collected_protocol_fees = COLLECTED_PROTOCOL_FEES.load(deps.storage)?;
-
     let share_ratio: Decimal = Decimal::from_ratio(amount, total_share);
 
-    let refund_assets: Result<Vec<Asset>, OverflowError> = pool_assets
+    let mut refund_assets: Vec<Asset> = pool_assets
         .iter()
-        .map(|pool_asset| {
-            let protocol_fee = get_protocol_fee_for_asset(
-                collected_protocol_fees.clone(),
-                pool_asset.clone().get_id(),
-            );
-
-            // subtract the protocol_fee from the amount of the pool_asset
-            let refund_amount = pool_asset.amount.checked_sub(protocol_fee)?;
-            Ok(Asset {
-                info: pool_asset.info.clone(),
-                amount: refund_amount * share_ratio,
-            })
+        .map(|pool_asset| Asset {
+            info: pool_asset.info.clone(),
+            amount: pool_asset.amount * share_ratio,
         })
         .collect();
 
-    let refund_assets = refund_assets?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if let Some(target_asset) = target_asset {
+        let config = CONFIG.load(deps.storage)?;
+        // check if the swap feature is enabled
+        if !config.feature_toggle.swaps_enabled {
+            return Err(ContractError::OperationDisabled("swap".to_string()));
+        }
+
+        let (target_index, offer_index) = if target_asset.equal(&pool_assets[0].info) {
+            (0, 1)
+        } else if target_asset.equal(&pool_assets[1].info) {
+            (1, 0)
+        } else {
+            return Err(ContractError::AssetMismatch {});
+        };
+
+        let mut target_refund = refund_assets[target_index].clone();
+        let offer_refund = refund_assets[offer_index].clone();
+
+        if !offer_refund.amount.is_zero() {
+            // swap the share of the other asset against what's left in the pool after the withdrawal
+            let swap_computation = helpers::compute_swap(
+                pool_assets[offer_index]
+                    .amount
+                    .checked_sub(offer_refund.amount)?,
+                pool_assets[target_index]
+                    .amount
+                    .checked_sub(target_refund.amount)?,
+                offer_refund.amount,
+                config.pool_fees,
+                &if offer_index == 0 {
+                    pair_info.pair_type.clone()
+                } else {
+                    pair_info.pair_type.reversed()
+                },
+                pair_info.asset_decimals[offer_index],
+                pair_info.asset_decimals[target_index],
+            )?;
+
+            target_refund.amount = target_refund
+                .amount
+                .checked_add(swap_computation.return_amount)?;
+
+            // burn ask_asset from the pool
+            if !swap_computation.burn_fee_amount.is_zero() {
+                let burn_asset = Asset {
+                    info: target_refund.info.clone(),
+                    amount: swap_computation.burn_fee_amount,
+                };
+
+                store_fee(
+                    deps.storage,
+                    burn_asset.amount,
+                    burn_asset.clone().get_id(),
+                    ALL_TIME_BURNED_FEES,
+                )?;
+
+                messages.push(burn_asset.into_burn_msg()?);
+            }
+
+            // Store the protocol fees generated by the swap
+            store_fee(
+                deps.storage,
+                swap_computation.protocol_fee_amount,
+                target_refund.clone().get_id(),
+                COLLECTED_PROTOCOL_FEES,
+            )?;
+            store_fee(
+                deps.storage,
+                swap_computation.protocol_fee_amount,
+                target_refund.clone().get_id(),
+                ALL_TIME_COLLECTED_PROTOCOL_FEES,
+            )?;
+        }
+
+        if let Some(min_receive) = min_receive {
+            if target_refund.amount < min_receive {
+                return Err(ContractError::MinimumReceiveAssertion {
+                    minimum_receive: min_receive,
+                    amount: target_refund.amount,
+                });
+            }
+        }
 
-    let burn_lp_token_msg =
-        burn_lp_token_msg(liquidity_token, env.contract.address.to_string(), amount)?;
+        refund_assets = vec![target_refund];
+    }
+
+    for refund_asset in refund_assets.iter() {
+        messages.push(refund_asset.clone().into_msg(sender.clone())?);
+    }
+
+    // burn liquidity token
+    messages.push(burn_lp_token_msg(
+        liquidity_token,
+        env.contract.address.to_string(),
+        amount,
+    )?);
 
     // update pool info
-    Ok(Response::new()
-        .add_messages(vec![
-            refund_assets[0].clone().into_msg(sender.clone())?,
-            refund_assets[1].clone().into_msg(sender.clone())?,
-            // burn liquidity token
-            burn_lp_token_msg,
-        ])
-        .add_attributes(vec![
-            ("action", "withdraw_liquidity"),
-            ("sender", sender.as_str()),
-            ("withdrawn_share", &amount.to_string()),
-            (
-                "refund_assets",
-                &format!("{}, {}", refund_assets[0], refund_assets[1]),
-            ),
-        ]))
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "withdraw_liquidity"),
+        ("sender", sender.as_str()),
+        ("withdrawn_share", &amount.to_string()),
+        (
+            "refund_assets",
+            &refund_assets
+                .iter()
+                .map(|asset| asset.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+    ]))
 }
 
 /// Swaps tokens from the pool. The user provides an offer asset and receives the ask asset in return.
@@ -333,7 +482,7 @@ pub fn swap(
 ) -> Result<Response, ContractError> {
     offer_asset.assert_sent_native_token_balance(&info)?;
 
-    let pair_info = PAIR_INFO.load(deps.storage)?;
+    let pair_info = load_pair_info(deps.storage, env.block.height)?;
 
     // determine what's the offer and ask pool based on the offer_asset
     let offer_pool: Asset;
@@ -342,6 +491,7 @@ pub fn swap(
 
     let offer_decimal: u8;
     let ask_decimal: u8;
+    let swap_type: PairType;
 
     // To calculate pool amounts properly we should subtract user deposit and the protocol fees from the pool
     let pools = pair_info
@@ -361,18 +511,22 @@ pub fn swap(
         })
         .collect::<StdResult<Vec<_>>>()?;
 
+    helpers::update_price_accumulators(deps.storage, env.block.time.seconds(), &pools, &pair_info)?;
+
     if offer_asset.info.equal(&pools[0].info) {
         offer_pool = pools[0].clone();
         ask_pool = pools[1].clone();
 
         offer_decimal = pair_info.asset_decimals[0];
         ask_decimal = pair_info.asset_decimals[1];
+        swap_type = pair_info.pair_type.clone();
     } else if offer_asset.info.equal(&pools[1].info) {
         offer_pool = pools[1].clone();
         ask_pool = pools[0].clone();
 
         offer_decimal = pair_info.asset_decimals[1];
         ask_decimal = pair_info.asset_decimals[0];
+        swap_type = pair_info.pair_type.reversed();
     } else {
         return Err(ContractError::AssetMismatch {});
     }
@@ -385,7 +539,7 @@ pub fn swap(
         ask_pool.amount,
         offer_amount,
         pool_fees,
-        &pair_info.pair_type,
+        &swap_type,
         offer_decimal,
         ask_decimal,
     )?;
@@ -472,14 +626,212 @@ pub fn swap(
     ]))
 }
 
+/// Swaps the offer asset for the exact amount of the ask asset. The offer amount is computed with
+/// [helpers::compute_offer_amount]. Native offer assets are taken out of the funds sent, refunding
+/// the surplus, while cw20 offer assets are pulled from the allowance given to the pool.
+pub fn swap_exact_out(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ask_asset: Asset,
+    max_offer_amount: Uint128,
+    to: Option<Addr>,
+) -> Result<Response, ContractError> {
+    if ask_asset.amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let pair_info = load_pair_info(deps.storage, env.block.height)?;
+    let collected_protocol_fees = COLLECTED_PROTOCOL_FEES.load(deps.storage)?;
+
+    let pools = pair_info
+        .query_pools(&deps.querier, deps.api, env.contract.address.clone())?
+        .into_iter()
+        .map(|mut pool| {
+            // subtract the protocol fee from the pool
+            let protocol_fee =
+                get_protocol_fee_for_asset(collected_protocol_fees.clone(), pool.clone().get_id());
+            pool.amount = pool.amount.checked_sub(protocol_fee)?;
+
+            Ok(pool)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let (mut offer_pool, ask_pool, offer_decimal, ask_decimal, swap_type) =
+        if ask_asset.info.equal(&pools[1].info) {
+            (
+                pools[0].clone(),
+                pools[1].clone(),
+                pair_info.asset_decimals[0],
+                pair_info.asset_decimals[1],
+                pair_info.pair_type.clone(),
+            )
+        } else if ask_asset.info.equal(&pools[0].info) {
+            (
+                pools[1].clone(),
+                pools[0].clone(),
+                pair_info.asset_decimals[1],
+                pair_info.asset_decimals[0],
+                pair_info.pair_type.reversed(),
+            )
+        } else {
+            return Err(ContractError::AssetMismatch {});
+        };
+
+    // the native funds sent are already part of the pool balance, and cap the offer amount
+    let mut max_offer_amount = max_offer_amount;
+    let offer_amount_sent = match &offer_pool.info {
+        AssetInfo::NativeToken { denom } => {
+            let sent = info
+                .funds
+                .iter()
+                .find(|coin| &coin.denom == denom)
+                .map(|coin| coin.amount)
+                .unwrap_or_default();
+            offer_pool.amount = offer_pool.amount.checked_sub(sent)?;
+            max_offer_amount = max_offer_amount.min(sent);
+
+            sent
+        }
+        AssetInfo::Token { .. } => Uint128::zero(),
+    };
+
+    let current_pools = if offer_pool.info.equal(&pools[0].info) {
+        vec![offer_pool.clone(), ask_pool.clone()]
+    } else {
+        vec![ask_pool.clone(), offer_pool.clone()]
+    };
+    helpers::update_price_accumulators(
+        deps.storage,
+        env.block.time.seconds(),
+        &current_pools,
+        &pair_info,
+    )?;
+
+    let pool_fees = CONFIG.load(deps.storage)?.pool_fees;
+    let offer_amount_computation = helpers::compute_offer_amount(
+        offer_pool.amount,
+        ask_pool.amount,
+        ask_asset.amount,
+        pool_fees,
+        &swap_type,
+        offer_decimal,
+        ask_decimal,
+    )?;
+    let offer_amount = offer_amount_computation.offer_amount;
+
+    if offer_amount > max_offer_amount {
+        return Err(ContractError::MaxOfferAssertion {
+            max_offer_amount,
+            offer_amount,
+        });
+    }
+
+    let receiver = to.unwrap_or_else(|| info.sender.clone());
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    match &offer_pool.info {
+        AssetInfo::NativeToken { denom } => {
+            let refund_amount = offer_amount_sent.checked_sub(offer_amount)?;
+            if !refund_amount.is_zero() {
+                messages.push(CosmosMsg::Bank(BankMsg::Send {
+                    to_address: info.sender.to_string(),
+                    amount: coins(refund_amount.u128(), denom),
+                }));
+            }
+        }
+        AssetInfo::Token { contract_addr } => {
+            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: info.sender.to_string(),
+                    recipient: env.contract.address.to_string(),
+                    amount: offer_amount,
+                })?,
+                funds: vec![],
+            }));
+        }
+    }
+
+    messages.push(
+        Asset {
+            info: ask_pool.info.clone(),
+            amount: ask_asset.amount,
+        }
+        .into_msg(receiver.clone())?,
+    );
+
+    // burn ask_asset from the pool
+    if !offer_amount_computation.burn_fee_amount.is_zero() {
+        let burn_asset = Asset {
+            info: ask_pool.info.clone(),
+            amount: offer_amount_computation.burn_fee_amount,
+        };
+
+        store_fee(
+            deps.storage,
+            burn_asset.amount,
+            burn_asset.clone().get_id(),
+            ALL_TIME_BURNED_FEES,
+        )?;
+
+        messages.push(burn_asset.into_burn_msg()?);
+    }
+
+    // Store the protocol fees generated by this swap. The protocol fees are collected on the ask
+    // asset as shown in [compute_offer_amount]
+    store_fee(
+        deps.storage,
+        offer_amount_computation.protocol_fee_amount,
+        ask_pool.clone().get_id(),
+        COLLECTED_PROTOCOL_FEES,
+    )?;
+    store_fee(
+        deps.storage,
+        offer_amount_computation.protocol_fee_amount,
+        ask_pool.clone().get_id(),
+        ALL_TIME_COLLECTED_PROTOCOL_FEES,
+    )?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "swap_exact_out"),
+        ("sender", info.sender.as_str()),
+        ("receiver", receiver.as_str()),
+        ("offer_asset", &offer_pool.info.to_string()),
+        ("ask_asset", &ask_pool.info.to_string()),
+        ("offer_amount", &offer_amount.to_string()),
+        ("return_amount", &ask_asset.amount.to_string()),
+        (
+            "spread_amount",
+            &offer_amount_computation.spread_amount.to_string(),
+        ),
+        (
+            "swap_fee_amount",
+            &offer_amount_computation.swap_fee_amount.to_string(),
+        ),
+        (
+            "protocol_fee_amount",
+            &offer_amount_computation.protocol_fee_amount.to_string(),
+        ),
+        (
+            "burn_fee_amount",
+            &offer_amount_computation.burn_fee_amount.to_string(),
+        ),
+        ("swap_type", pair_info.pair_type.get_label()),
+    ]))
+}
+
 /// Updates the [Config] of the contract. Only the owner of the contract can do this.
+#[allow(clippy::too_many_arguments)]
 pub fn update_config(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     owner: Option<String>,
     fee_collector_addr: Option<String>,
     pool_fees: Option<PoolFee>,
     feature_toggle: Option<FeatureToggle>,
+    amp_factor: Option<RampAmp>,
 ) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
     if deps.api.addr_validate(info.sender.as_str())? != config.owner {
@@ -519,11 +871,87 @@ pub fn update_config(
         config.fee_collector_addr = deps.api.addr_validate(fee_collector_addr.as_str())?;
     }
 
+    if let Some(ramp) = amp_factor {
+        let current_amp = match load_pair_info(deps.storage, env.block.height)?.pair_type {
+            PairType::StableSwap { amp } => amp,
+            _ => {
+                return Err(ContractError::Std(StdError::generic_err(
+                    "Amp can only be ramped on stableswap pairs",
+                )))
+            }
+        };
+
+        //check new amp value and ramp time are valid
+        if ramp.future_a < MIN_AMP || ramp.future_a > MAX_AMP {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "Future amp must be between {MIN_AMP} and {MAX_AMP}"
+            ))));
+        }
+        if (ramp.future_a > current_amp) && (ramp.future_a > current_amp * MAX_AMP_CHANGE)
+            || (ramp.future_a < current_amp) && (ramp.future_a * MAX_AMP_CHANGE < current_amp)
+        {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Amp change over max",
+            )));
+        }
+        if ramp.future_block < env.block.height + MIN_RAMP_BLOCKS {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Amp change ramp time under minimum",
+            )));
+        }
+
+        AMP_RAMP.save(
+            deps.storage,
+            &AmpRamp {
+                initial_amp: current_amp,
+                future_amp: ramp.future_a,
+                initial_amp_block: env.block.height,
+                future_amp_block: ramp.future_block,
+            },
+        )?;
+    }
+
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new().add_attribute("action", "update_config"))
 }
 
+/// Stops an ongoing amplification coefficient ramp, freezing the amp at its current value
+pub fn stop_amp_ramp(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if deps.api.addr_validate(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Std(StdError::generic_err("unauthorized")));
+    }
+
+    let current_amp = match load_pair_info(deps.storage, env.block.height)?.pair_type {
+        PairType::StableSwap { amp } => amp,
+        _ => {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Amp can only be ramped on stableswap pairs",
+            )))
+        }
+    };
+
+    AMP_RAMP.save(
+        deps.storage,
+        &AmpRamp {
+            initial_amp: current_amp,
+            future_amp: current_amp,
+            initial_amp_block: env.block.height,
+            future_amp_block: env.block.height,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "stop_amp_ramp"),
+        ("amp", &current_amp.to_string()),
+    ]))
+}
+
 /// Collects all protocol fees accrued by the pool
 pub fn collect_protocol_fees(deps: DepsMut) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
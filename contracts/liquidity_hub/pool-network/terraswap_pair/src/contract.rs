@@ -2,7 +2,7 @@
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
     to_binary, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Reply, ReplyOn,
-    Response, StdError, StdResult, SubMsg, WasmMsg,
+    Response, StdError, StdResult, SubMsg, Uint256, WasmMsg,
 };
 use cw2::{get_contract_version, set_contract_version};
 use cw20::MinterResponse;
@@ -20,8 +20,8 @@ use crate::error::ContractError;
 use crate::helpers::has_factory_token;
 use crate::response::MsgInstantiateContractResponse;
 use crate::state::{
-    ALL_TIME_BURNED_FEES, ALL_TIME_COLLECTED_PROTOCOL_FEES, COLLECTED_PROTOCOL_FEES, CONFIG,
-    LP_SYMBOL, PAIR_INFO,
+    Observation, ALL_TIME_BURNED_FEES, ALL_TIME_COLLECTED_PROTOCOL_FEES, COLLECTED_PROTOCOL_FEES,
+    CONFIG, LP_SYMBOL, OBSERVATIONS, PAIR_INFO,
 };
 use crate::{commands, helpers, queries};
 
@@ -31,6 +31,15 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 const INSTANTIATE_REPLY_ID: u64 = 1;
 
+/// Minimum amplification coefficient.
+pub const MIN_AMP: u64 = 1;
+/// Maximum amplification coefficient.
+pub const MAX_AMP: u64 = 1_000_000;
+/// Minimum number of blocks an amplification coefficient change must take place over.
+pub const MIN_RAMP_BLOCKS: u64 = 10000;
+/// Maximum factor the amplification coefficient can be changed by in a single command.
+pub const MAX_AMP_CHANGE: u64 = 10;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -44,6 +53,8 @@ pub fn instantiate(
         return Err(ContractError::TokenFactoryAssetBurnDisabled {});
     }
 
+    msg.pair_type.is_valid()?;
+
     let pair_info: &PairInfoRaw = &PairInfoRaw {
         contract_addr: deps.api.addr_canonicalize(env.contract.address.as_str())?,
         liquidity_token: AssetInfoRaw::NativeToken {
@@ -102,6 +113,17 @@ pub fn instantiate(
         ALL_TIME_BURNED_FEES,
     )?;
 
+    // start tracking the cumulative prices of the pair
+    OBSERVATIONS.save(
+        deps.storage,
+        env.block.time.seconds(),
+        &Observation {
+            timestamp: env.block.time.seconds(),
+            price0_cumulative: Uint256::zero(),
+            price1_cumulative: Uint256::zero(),
+        },
+    )?;
+
     if msg.token_factory_lp {
         // create native LP token
         PAIR_INFO.update(deps.storage, |mut pair_info| -> StdResult<_> {
@@ -159,8 +181,19 @@ pub fn execute(
             assets,
             slippage_tolerance,
             receiver,
-        } => commands::provide_liquidity(deps, env, info, assets, slippage_tolerance, receiver),
-        ExecuteMsg::WithdrawLiquidity {} => {
+            deadline,
+        } => {
+            helpers::assert_deadline(env.block.time, deadline)?;
+
+            commands::provide_liquidity(deps, env, info, assets, slippage_tolerance, receiver)
+        }
+        ExecuteMsg::WithdrawLiquidity {
+            target_asset,
+            min_receive,
+            deadline,
+        } => {
+            helpers::assert_deadline(env.block.time, deadline)?;
+
             // validate that the asset sent is the token factory LP token
             let pair_info = PAIR_INFO.load(deps.storage)?;
             let lp_token_denom = match pair_info.liquidity_token {
@@ -172,14 +205,24 @@ pub fn execute(
                 return Err(ContractError::AssetMismatch {});
             }
 
-            commands::withdraw_liquidity(deps, env, info.sender, info.funds[0].amount)
+            commands::withdraw_liquidity(
+                deps,
+                env,
+                info.sender,
+                info.funds[0].amount,
+                target_asset,
+                min_receive,
+            )
         }
         ExecuteMsg::Swap {
             offer_asset,
             belief_price,
             max_spread,
             to,
+            deadline,
         } => {
+            helpers::assert_deadline(env.block.time, deadline)?;
+
             // check if the swap feature is enabled
             let feature_toggle: FeatureToggle = CONFIG.load(deps.storage)?.feature_toggle;
             if !feature_toggle.swaps_enabled {
@@ -207,19 +250,45 @@ pub fn execute(
                 to_addr,
             )
         }
+        ExecuteMsg::SwapExactOut {
+            ask_asset,
+            max_offer_amount,
+            to,
+            deadline,
+        } => {
+            helpers::assert_deadline(env.block.time, deadline)?;
+
+            // check if the swap feature is enabled
+            let feature_toggle: FeatureToggle = CONFIG.load(deps.storage)?.feature_toggle;
+            if !feature_toggle.swaps_enabled {
+                return Err(ContractError::OperationDisabled("swap".to_string()));
+            }
+
+            let to_addr = if let Some(to_addr) = to {
+                Some(deps.api.addr_validate(&to_addr)?)
+            } else {
+                None
+            };
+
+            commands::swap_exact_out(deps, env, info, ask_asset, max_offer_amount, to_addr)
+        }
         ExecuteMsg::UpdateConfig {
             owner,
             fee_collector_addr,
             pool_fees,
             feature_toggle,
+            amp_factor,
         } => commands::update_config(
             deps,
+            env,
             info,
             owner,
             fee_collector_addr,
             pool_fees,
             feature_toggle,
+            amp_factor,
         ),
+        ExecuteMsg::StopAmpRamp {} => commands::stop_amp_ramp(deps, env, info),
         ExecuteMsg::CollectProtocolFees {} => commands::collect_protocol_fees(deps),
     }
 }
@@ -246,15 +315,17 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
-        QueryMsg::Pair {} => Ok(to_binary(&queries::query_pair_info(deps)?)?),
+        QueryMsg::Pair {} => Ok(to_binary(&queries::query_pair_info(deps, env)?)?),
         QueryMsg::Pool {} => Ok(to_binary(&queries::query_pool(deps)?)?),
-        QueryMsg::Simulation { offer_asset } => {
-            Ok(to_binary(&queries::query_simulation(deps, offer_asset)?)?)
-        }
+        QueryMsg::Simulation { offer_asset } => Ok(to_binary(&queries::query_simulation(
+            deps,
+            env,
+            offer_asset,
+        )?)?),
         QueryMsg::ReverseSimulation { ask_asset } => Ok(to_binary(
-            &queries::query_reverse_simulation(deps, ask_asset)?,
+            &queries::query_reverse_simulation(deps, env, ask_asset)?,
         )?),
         QueryMsg::Config {} => Ok(to_binary(&queries::query_config(deps)?)?),
         QueryMsg::ProtocolFees { asset_id, all_time } => Ok(to_binary(&queries::query_fees(
@@ -271,12 +342,29 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
             ALL_TIME_BURNED_FEES,
             None,
         )?)?),
+        QueryMsg::ProvideLiquiditySimulation { assets } => Ok(to_binary(
+            &queries::query_provide_liquidity_simulation(deps, env, assets)?,
+        )?),
+        QueryMsg::Twap { start_age, end_age } => Ok(to_binary(&queries::query_twap(
+            deps, env, start_age, end_age,
+        )?)?),
+        QueryMsg::Amp {} => Ok(to_binary(&queries::query_amp(deps, env)?)?),
+        QueryMsg::VirtualPrice {} => Ok(to_binary(&queries::query_virtual_price(deps, env)?)?),
+        QueryMsg::SpotPrice {
+            offer_asset_info,
+            ask_asset_info,
+        } => Ok(to_binary(&queries::query_spot_price(
+            deps,
+            env,
+            offer_asset_info,
+            ask_asset_info,
+        )?)?),
     }
 }
 
 #[cfg(not(tarpaulin_include))]
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(mut deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(mut deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
     use crate::migrations;
 
     let version: Version = CONTRACT_VERSION.parse()?;
@@ -297,6 +385,9 @@ pub fn migrate(mut deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Respons
     if storage_version == Version::parse("1.2.0")? {
         migrations::migrate_to_v130(deps.branch())?;
     }
+    if storage_version < Version::parse("1.4.0")? {
+        migrations::migrate_to_v140(deps.branch(), &env)?;
+    }
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     Ok(Response::default())
@@ -1,6 +1,6 @@
 use cosmwasm_std::{
     CheckedFromRatioError, CheckedMultiplyRatioError, ConversionOverflowError, DivideByZeroError,
-    OverflowError, StdError, Uint128,
+    OverflowError, StdError, Timestamp, Uint128,
 };
 use semver::Version;
 use thiserror::Error;
@@ -72,6 +72,32 @@ pub enum ContractError {
 
     #[error("Burn fee is not allowed when using factory tokens")]
     TokenFactoryAssetBurnDisabled {},
+
+    #[error("Assertion failed; minimum receive amount: {minimum_receive}, amount: {amount}")]
+    MinimumReceiveAssertion {
+        minimum_receive: Uint128,
+        amount: Uint128,
+    },
+
+    #[error("Invalid TWAP window, start_age must be greater than end_age and at most {0} seconds")]
+    InvalidTwapWindow(u64),
+
+    #[error("There isn't enough price history to compute the TWAP")]
+    TwapHistoryUnavailable {},
+
+    #[error(
+        "Assertion failed; maximum offer amount: {max_offer_amount}, offer amount: {offer_amount}"
+    )]
+    MaxOfferAssertion {
+        max_offer_amount: Uint128,
+        offer_amount: Uint128,
+    },
+
+    #[error("Deadline exceeded; deadline: {deadline}, current time: {current_time}")]
+    DeadlineExceeded {
+        deadline: Timestamp,
+        current_time: Timestamp,
+    },
 }
 
 impl From<semver::Error> for ContractError {
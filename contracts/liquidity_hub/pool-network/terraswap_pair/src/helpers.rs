@@ -2,15 +2,22 @@ use std::cmp::Ordering;
 use std::ops::Mul;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Decimal, Decimal256, Deps, StdError, StdResult, Storage, Uint128, Uint256};
-use cw_storage_plus::Item;
+use cosmwasm_std::{
+    Addr, Decimal, Decimal256, Deps, Fraction, Order, StdError, StdResult, Storage, Timestamp,
+    Uint128, Uint256,
+};
+use cw_storage_plus::{Bound, Item};
 
-use white_whale::pool_network::asset::{is_factory_token, Asset, AssetInfo, PairType};
+use white_whale::pool_network::asset::{
+    is_factory_token, Asset, AssetInfo, PairInfoRaw, PairType, MINIMUM_LIQUIDITY_AMOUNT,
+};
 use white_whale::pool_network::pair::PoolFee;
 use white_whale::pool_network::querier::query_token_info;
+use white_whale::pool_network::U256;
 
 use crate::error::ContractError;
 use crate::math::Decimal256Helper;
+use crate::state::{Observation, COLLECTED_PROTOCOL_FEES, MAX_TWAP_AGE, OBSERVATIONS};
 
 /// The amount of iterations to perform when calculating the Newton-Raphson approximation.
 const NEWTON_ITERATIONS: u64 = 32;
@@ -140,6 +147,8 @@ pub fn calculate_stableswap_y(
     Err(ContractError::ConvergeError {})
 }
 
+/// Computes the result of swapping the `offer_amount` on the given pools. For weighted pairs, the
+/// weights of the `swap_type` are expected in the [offer, ask] order, see [PairType::reversed].
 pub fn compute_swap(
     offer_pool: Uint128,
     ask_pool: Uint128,
@@ -189,6 +198,50 @@ pub fn compute_swap(
                     .map_err(|_| ContractError::SwapOverflowError {})?,
             })
         }
+        PairType::Weighted { weights } => {
+            // offer => ask
+            // ask_amount = ask_pool * (1 - (offer_pool / (offer_pool + offer_amount)) ^ (offer_weight / ask_weight)) - swap_fee - protocol_fee - burn_fee
+            let pool_ratio =
+                Decimal256::from_ratio(offer_pool, offer_pool.checked_add(offer_amount)?)
+                    .checked_pow_decimal(Decimal256::from_ratio(weights[0], weights[1]))?;
+            let return_amount: Uint256 = ask_pool * Decimal256::one().checked_sub(pool_ratio)?;
+
+            // calculate spread, swap and protocol fees. The exchange rate is the spot price, which
+            // accounts for the weights of the pools
+            let exchange_rate = Decimal256::from_ratio(
+                ask_pool.checked_mul(Uint256::from(weights[0]))?,
+                offer_pool.checked_mul(Uint256::from(weights[1]))?,
+            );
+            let spread_amount: Uint256 =
+                (offer_amount * exchange_rate).saturating_sub(return_amount);
+            let swap_fee_amount: Uint256 = pool_fees.swap_fee.compute(return_amount);
+            let protocol_fee_amount: Uint256 = pool_fees.protocol_fee.compute(return_amount);
+            let burn_fee_amount: Uint256 = pool_fees.burn_fee.compute(return_amount);
+
+            // swap and protocol fee will be absorbed by the pool. Burn fee amount will be burned on a subsequent msg.
+            let return_amount: Uint256 = return_amount
+                .checked_sub(swap_fee_amount)?
+                .checked_sub(protocol_fee_amount)?
+                .checked_sub(burn_fee_amount)?;
+
+            Ok(SwapComputation {
+                return_amount: return_amount
+                    .try_into()
+                    .map_err(|_| ContractError::SwapOverflowError {})?,
+                spread_amount: spread_amount
+                    .try_into()
+                    .map_err(|_| ContractError::SwapOverflowError {})?,
+                swap_fee_amount: swap_fee_amount
+                    .try_into()
+                    .map_err(|_| ContractError::SwapOverflowError {})?,
+                protocol_fee_amount: protocol_fee_amount
+                    .try_into()
+                    .map_err(|_| ContractError::SwapOverflowError {})?,
+                burn_fee_amount: burn_fee_amount
+                    .try_into()
+                    .map_err(|_| ContractError::SwapOverflowError {})?,
+            })
+        }
         PairType::StableSwap { amp } => {
             let offer_pool = Decimal256::decimal_with_precision(offer_pool, offer_precision)?;
             let ask_pool = Decimal256::decimal_with_precision(ask_pool, ask_precision)?;
@@ -254,32 +307,131 @@ pub struct SwapComputation {
     pub burn_fee_amount: Uint128,
 }
 
+/// Computes the amount to offer to get the `ask_amount` out of the given pools. For weighted pairs,
+/// the weights of the `pair_type` are expected in the [offer, ask] order, see [PairType::reversed].
 pub fn compute_offer_amount(
     offer_pool: Uint128,
     ask_pool: Uint128,
     ask_amount: Uint128,
     pool_fees: PoolFee,
-) -> StdResult<OfferAmountComputation> {
+    pair_type: &PairType,
+    offer_precision: u8,
+    ask_precision: u8,
+) -> Result<OfferAmountComputation, ContractError> {
+    if let PairType::StableSwap { amp } = pair_type {
+        let offer_pool = Decimal256::decimal_with_precision(offer_pool, offer_precision)?;
+        let ask_pool = Decimal256::decimal_with_precision(ask_pool, ask_precision)?;
+
+        let before_fees = (Decimal256::one()
+            .checked_sub(pool_fees.protocol_fee.to_decimal_256())?
+            .checked_sub(pool_fees.swap_fee.to_decimal_256())?
+            .checked_sub(pool_fees.burn_fee.to_decimal_256())?)
+        .inv()
+        .unwrap_or_else(Decimal256::one)
+        .checked_mul(Decimal256::decimal_with_precision(
+            ask_amount,
+            ask_precision,
+        )?)?;
+
+        let before_fees_offer = before_fees.to_uint256_with_precision(offer_precision.into())?;
+        let before_fees_ask = before_fees.to_uint256_with_precision(ask_precision.into())?;
+
+        let max_precision = offer_precision.max(ask_precision);
+
+        let new_offer_pool_amount = calculate_stableswap_y(
+            offer_pool,
+            ask_pool,
+            before_fees,
+            amp,
+            max_precision,
+            StableSwapDirection::ReverseSimulate,
+        )?;
+
+        // the new offer pool amount is only approximated within a unit, so the offer amount is
+        // rounded up in favour of the pool
+        let offer_amount = new_offer_pool_amount
+            .checked_sub(Uint128::try_from(
+                offer_pool.to_uint256_with_precision(u32::from(max_precision))?,
+            )?)?
+            .checked_add(Uint128::one())?;
+
+        // convert into the original offer precision
+        let offer_amount = match max_precision.cmp(&offer_precision) {
+            Ordering::Equal => offer_amount,
+            // note that Less should never happen (as max_precision = max(offer_precision, ask_precision))
+            Ordering::Less => offer_amount.checked_mul(Uint128::new(
+                10u128.pow((offer_precision - max_precision).into()),
+            ))?,
+            Ordering::Greater => Uint128::try_from(checked_div_ceil(
+                offer_amount.into(),
+                Uint256::from(10u128.pow((max_precision - offer_precision).into())),
+            )?)?,
+        };
+
+        let spread_amount = offer_amount.saturating_sub(Uint128::try_from(before_fees_offer)?);
+        let swap_fee_amount = pool_fees.swap_fee.compute(before_fees_ask);
+        let protocol_fee_amount = pool_fees.protocol_fee.compute(before_fees_ask);
+        let burn_fee_amount = pool_fees.burn_fee.compute(before_fees_ask);
+
+        return Ok(OfferAmountComputation {
+            offer_amount,
+            spread_amount,
+            swap_fee_amount: swap_fee_amount.try_into()?,
+            protocol_fee_amount: protocol_fee_amount.try_into()?,
+            burn_fee_amount: burn_fee_amount.try_into()?,
+        });
+    }
+
     let offer_pool: Uint256 = offer_pool.into();
     let ask_pool: Uint256 = ask_pool.into();
     let ask_amount: Uint256 = ask_amount.into();
 
-    // ask => offer
-    // offer_amount = cp / (ask_pool - ask_amount / (1 - fees)) - offer_pool
-    let fees = pool_fees.swap_fee.to_decimal_256()
-        + pool_fees.protocol_fee.to_decimal_256()
-        + pool_fees.burn_fee.to_decimal_256();
-    let one_minus_commission = Decimal256::one() - fees;
-    let inv_one_minus_commission = Decimal256::one() / one_minus_commission;
+    let fees = pool_fees
+        .swap_fee
+        .to_decimal_256()
+        .checked_add(pool_fees.protocol_fee.to_decimal_256())?
+        .checked_add(pool_fees.burn_fee.to_decimal_256())?;
+    let one_minus_commission = Decimal256::one().checked_sub(fees)?;
+
+    // the amounts are rounded up in favour of the pool, as the ask amount is paid out in full
+    // ask_amount / (1 - fees)
+    let before_commission_deduction = checked_div_ceil(
+        ask_amount.checked_mul(Decimal256::one().atomics())?,
+        one_minus_commission.atomics(),
+    )?;
+    // fails if the ask amount can't be taken out of the pool
+    let ask_pool_after_swap = ask_pool.checked_sub(before_commission_deduction)?;
 
-    let cp: Uint256 = offer_pool * ask_pool;
-    let offer_amount: Uint256 = Uint256::one()
-        .multiply_ratio(cp, ask_pool - ask_amount * inv_one_minus_commission)
-        - offer_pool;
+    let (offer_amount, exchange_rate): (Uint256, Decimal256) = match pair_type {
+        PairType::Weighted { weights } => {
+            // ask => offer
+            // offer_amount = offer_pool * ((ask_pool / (ask_pool - ask_amount / (1 - fees))) ^ (ask_weight / offer_weight) - 1)
+            let pool_ratio = Decimal256::checked_from_ratio(ask_pool, ask_pool_after_swap)?
+                .checked_pow_decimal(Decimal256::from_ratio(weights[1], weights[0]))?;
 
-    let before_commission_deduction: Uint256 = ask_amount * inv_one_minus_commission;
-    let before_spread_deduction: Uint256 =
-        offer_amount * Decimal256::from_ratio(ask_pool, offer_pool);
+            (
+                checked_div_ceil(
+                    offer_pool.checked_mul(pool_ratio.checked_sub(Decimal256::one())?.atomics())?,
+                    Decimal256::one().atomics(),
+                )?,
+                Decimal256::from_ratio(
+                    ask_pool.checked_mul(Uint256::from(weights[0]))?,
+                    offer_pool.checked_mul(Uint256::from(weights[1]))?,
+                ),
+            )
+        }
+        _ => {
+            // ask => offer
+            // offer_amount = cp / (ask_pool - ask_amount / (1 - fees)) - offer_pool
+            let cp: Uint256 = offer_pool.checked_mul(ask_pool)?;
+            (
+                checked_div_ceil(cp, ask_pool_after_swap)?.checked_sub(offer_pool)?,
+                Decimal256::checked_from_ratio(ask_pool, offer_pool)?,
+            )
+        }
+    };
+
+    let before_spread_deduction: Uint256 = offer_amount * exchange_rate;
 
     let spread_amount = if before_spread_deduction > before_commission_deduction {
         before_spread_deduction - before_commission_deduction
@@ -300,6 +452,16 @@ pub fn compute_offer_amount(
     })
 }
 
+/// Divides the `numerator` by the `denominator`, rounding up
+fn checked_div_ceil(numerator: Uint256, denominator: Uint256) -> Result<Uint256, ContractError> {
+    let quotient = numerator.checked_div(denominator)?;
+    if quotient.checked_mul(denominator)? == numerator {
+        Ok(quotient)
+    } else {
+        Ok(quotient.checked_add(Uint256::one())?)
+    }
+}
+
 /// Represents the offer amount computation values
 #[cw_serde]
 pub struct OfferAmountComputation {
@@ -379,6 +541,23 @@ pub fn assert_max_spread(
     Ok(())
 }
 
+/// Asserts the block time hasn't gone past the `deadline`, if one is given
+pub fn assert_deadline(
+    block_time: Timestamp,
+    deadline: Option<Timestamp>,
+) -> Result<(), ContractError> {
+    if let Some(deadline) = deadline {
+        if block_time > deadline {
+            return Err(ContractError::DeadlineExceeded {
+                deadline,
+                current_time: block_time,
+            });
+        }
+    }
+
+    Ok(())
+}
+
 pub fn assert_slippage_tolerance(
     slippage_tolerance: &Option<Decimal>,
     deposits: &[Uint128; 2],
@@ -413,7 +592,7 @@ pub fn assert_slippage_tolerance(
                     return Err(ContractError::MaxSlippageAssertion {});
                 }
             }
-            PairType::ConstantProduct => {
+            PairType::ConstantProduct | PairType::Weighted { .. } => {
                 if Decimal256::from_ratio(deposits[0], deposits[1]) * one_minus_slippage_tolerance
                     > Decimal256::from_ratio(pools[0], pools[1])
                     || Decimal256::from_ratio(deposits[1], deposits[0])
@@ -429,6 +608,258 @@ pub fn assert_slippage_tolerance(
     Ok(())
 }
 
+/// The amount of iterations to perform when searching the amount to swap on a single-sided
+/// liquidity provision to a stableswap pool.
+const SINGLE_SIDED_SWAP_ITERATIONS: u64 = 64;
+
+/// Represents the swap performed when providing liquidity with a single asset
+#[cw_serde]
+pub struct SingleSidedSwap {
+    /// Index of the pool the offer asset belongs to
+    pub offer_index: usize,
+    pub offer_amount: Uint128,
+    pub computation: SwapComputation,
+}
+
+/// Represents the liquidity provision computation values
+#[cw_serde]
+pub struct LiquidityProvision {
+    /// The LP share to be minted to the provider
+    pub share: Uint128,
+    /// The amounts deposited into the pool, after the single-sided swap if any
+    pub deposits: [Uint128; 2],
+    /// The swap performed to balance a single-sided provision
+    pub swap: Option<SingleSidedSwap>,
+}
+
+/// Computes the LP share minted for the given deposits. If only one of the deposits is non-zero,
+/// the amount of that asset that balances the deposit is swapped for the other asset first, paying
+/// the usual fees. In that case the `slippage_tolerance` is enforced as the max spread of the swap.
+pub fn compute_liquidity_provision(
+    deposits: [Uint128; 2],
+    pools: &[Asset; 2],
+    total_share: Uint128,
+    pair_info: &PairInfoRaw,
+    pool_fees: PoolFee,
+    slippage_tolerance: Option<Decimal>,
+) -> Result<LiquidityProvision, ContractError> {
+    let mut deposits = deposits;
+    let mut pool_amounts = [pools[0].amount, pools[1].amount];
+    let mut swap = None;
+
+    if deposits[0].is_zero() || deposits[1].is_zero() {
+        // a single-sided provision needs a price to swap at, i.e. existing liquidity
+        if total_share.is_zero() || (deposits[0].is_zero() && deposits[1].is_zero()) {
+            return Err(ContractError::InvalidZeroAmount {});
+        }
+
+        let (offer_index, ask_index) = if deposits[0].is_zero() {
+            (1, 0)
+        } else {
+            (0, 1)
+        };
+        let offer_decimal = pair_info.asset_decimals[offer_index];
+        let ask_decimal = pair_info.asset_decimals[ask_index];
+        let swap_type = if offer_index == 0 {
+            pair_info.pair_type.clone()
+        } else {
+            pair_info.pair_type.reversed()
+        };
+
+        let offer_amount = compute_single_sided_offer_amount(
+            deposits[offer_index],
+            pool_amounts[offer_index],
+            pool_amounts[ask_index],
+            pool_fees.clone(),
+            &swap_type,
+            offer_decimal,
+            ask_decimal,
+        )?;
+
+        let computation = compute_swap(
+            pool_amounts[offer_index],
+            pool_amounts[ask_index],
+            offer_amount,
+            pool_fees,
+            &swap_type,
+            offer_decimal,
+            ask_decimal,
+        )?;
+
+        if computation.return_amount.is_zero() {
+            return Err(ContractError::InvalidZeroAmount {});
+        }
+
+        assert_max_spread(
+            None,
+            slippage_tolerance,
+            Asset {
+                info: pools[offer_index].info.clone(),
+                amount: offer_amount,
+            },
+            Asset {
+                info: pools[ask_index].info.clone(),
+                amount: computation.return_amount,
+            },
+            computation.spread_amount,
+            offer_decimal,
+            ask_decimal,
+        )?;
+
+        // the swap fee stays in the pool, while the protocol fee is set aside and the burn fee burned
+        pool_amounts[offer_index] = pool_amounts[offer_index].checked_add(offer_amount)?;
+        pool_amounts[ask_index] = pool_amounts[ask_index]
+            .checked_sub(computation.return_amount)?
+            .checked_sub(computation.protocol_fee_amount)?
+            .checked_sub(computation.burn_fee_amount)?;
+
+        deposits[offer_index] = deposits[offer_index].checked_sub(offer_amount)?;
+        deposits[ask_index] = computation.return_amount;
+
+        swap = Some(SingleSidedSwap {
+            offer_index,
+            offer_amount,
+            computation,
+        });
+    }
+
+    let share = if total_share.is_zero() {
+        // Make sure at least MINIMUM_LIQUIDITY_AMOUNT is deposited to mitigate the risk of the first
+        // depositor preventing small liquidity providers from joining the pool
+        let share = Uint128::new(
+            (U256::from(deposits[0].u128())
+                .checked_mul(U256::from(deposits[1].u128()))
+                .ok_or::<ContractError>(ContractError::LiquidityShareComputation {}))?
+            .integer_sqrt()
+            .as_u128(),
+        )
+        .checked_sub(MINIMUM_LIQUIDITY_AMOUNT)
+        .map_err(|_| ContractError::InvalidInitialLiquidityAmount(MINIMUM_LIQUIDITY_AMOUNT))?;
+
+        // share should be above zero after subtracting the MINIMUM_LIQUIDITY_AMOUNT
+        if share.is_zero() {
+            return Err(ContractError::InvalidInitialLiquidityAmount(
+                MINIMUM_LIQUIDITY_AMOUNT,
+            ));
+        }
+
+        share
+    } else {
+        // min(1, 2)
+        // 1. sqrt(deposit_0 * exchange_rate_0_to_1 * deposit_0) * (total_share / sqrt(pool_0 * pool_1))
+        // == deposit_0 * total_share / pool_0
+        // 2. sqrt(deposit_1 * exchange_rate_1_to_0 * deposit_1) * (total_share / sqrt(pool_1 * pool_1))
+        // == deposit_1 * total_share / pool_1
+        let amount = std::cmp::min(
+            deposits[0].multiply_ratio(total_share, pool_amounts[0]),
+            deposits[1].multiply_ratio(total_share, pool_amounts[1]),
+        );
+
+        // the slippage of single-sided provisions was already asserted on the swap
+        if swap.is_none() {
+            assert_slippage_tolerance(
+                &slippage_tolerance,
+                &deposits,
+                pools,
+                pair_info.pair_type.clone(),
+                amount,
+                total_share,
+            )?;
+        }
+
+        amount
+    };
+
+    Ok(LiquidityProvision {
+        share,
+        deposits,
+        swap,
+    })
+}
+
+/// Computes the amount of the deposit to swap so the remaining deposit and the swap return are in
+/// the same ratio as the pools after the swap. Only the constant product curve has a closed form
+/// for it, so the amount is searched for on the other curves.
+fn compute_single_sided_offer_amount(
+    deposit: Uint128,
+    offer_pool: Uint128,
+    ask_pool: Uint128,
+    pool_fees: PoolFee,
+    pair_type: &PairType,
+    offer_precision: u8,
+    ask_precision: u8,
+) -> Result<Uint128, ContractError> {
+    match pair_type {
+        PairType::ConstantProduct => {
+            // offer_amount = offer_pool * (sqrt((2 - fee)^2 + 4 * (1 - fee) * deposit / offer_pool) - (2 - fee)) / (2 * (1 - fee))
+            let fee = pool_fees
+                .swap_fee
+                .to_decimal_256()
+                .checked_add(pool_fees.protocol_fee.to_decimal_256())?
+                .checked_add(pool_fees.burn_fee.to_decimal_256())?;
+            let two = Decimal256::from_ratio(2u8, 1u8);
+            let one_minus_fee = Decimal256::one().checked_sub(fee)?;
+            let two_minus_fee = two.checked_sub(fee)?;
+
+            let root = two_minus_fee
+                .checked_mul(two_minus_fee)?
+                .checked_add(
+                    Decimal256::from_ratio(4u8, 1u8)
+                        .checked_mul(one_minus_fee)?
+                        .checked_mul(Decimal256::checked_from_ratio(deposit, offer_pool)?)?,
+                )?
+                .sqrt();
+            let ratio = root
+                .checked_sub(two_minus_fee)?
+                .checked_div(two.checked_mul(one_minus_fee)?)?;
+
+            let offer_amount: Uint128 = (Uint256::from_uint128(offer_pool) * ratio)
+                .try_into()
+                .map_err(|_| ContractError::SwapOverflowError {})?;
+
+            Ok(offer_amount.min(deposit))
+        }
+        PairType::StableSwap { .. } | PairType::Weighted { .. } => {
+            // search for the amount at which
+            // (deposit - offer_amount) / new_offer_pool == return_amount / new_ask_pool
+            let mut low = Uint128::zero();
+            let mut high = deposit;
+            for _ in 0..SINGLE_SIDED_SWAP_ITERATIONS {
+                if high.checked_sub(low)? <= Uint128::one() {
+                    break;
+                }
+
+                let offer_amount = low + (high - low) / Uint128::new(2u128);
+                let computation = compute_swap(
+                    offer_pool,
+                    ask_pool,
+                    offer_amount,
+                    pool_fees.clone(),
+                    pair_type,
+                    offer_precision,
+                    ask_precision,
+                )?;
+
+                let new_offer_pool = offer_pool.checked_add(offer_amount)?;
+                let new_ask_pool = ask_pool
+                    .checked_sub(computation.return_amount)?
+                    .checked_sub(computation.protocol_fee_amount)?
+                    .checked_sub(computation.burn_fee_amount)?;
+
+                if deposit.checked_sub(offer_amount)?.full_mul(new_ask_pool)
+                    > computation.return_amount.full_mul(new_offer_pool)
+                {
+                    low = offer_amount;
+                } else {
+                    high = offer_amount;
+                }
+            }
+
+            Ok(low)
+        }
+    }
+}
+
 /// Gets the protocol fee amount for the given asset_id
 pub fn get_protocol_fee_for_asset(
     collected_protocol_fees: Vec<Asset>,
@@ -447,6 +878,27 @@ pub fn get_protocol_fee_for_asset(
     }
 }
 
+/// Queries the pools of the pair, net of the protocol fees collected in them
+pub fn get_pools_net_of_protocol_fees(
+    deps: &Deps,
+    pair_info: &PairInfoRaw,
+    contract_addr: Addr,
+) -> StdResult<Vec<Asset>> {
+    let collected_protocol_fees = COLLECTED_PROTOCOL_FEES.load(deps.storage)?;
+
+    pair_info
+        .query_pools(&deps.querier, deps.api, contract_addr)?
+        .into_iter()
+        .map(|mut pool| {
+            let protocol_fee =
+                get_protocol_fee_for_asset(collected_protocol_fees.clone(), pool.clone().get_id());
+            pool.amount = pool.amount.checked_sub(protocol_fee)?;
+
+            Ok(pool)
+        })
+        .collect()
+}
+
 /// Instantiates fees for a given fee_storage_item
 pub fn instantiate_fees(
     storage: &mut dyn Storage,
@@ -492,3 +944,183 @@ pub fn has_factory_token(assets: &[AssetInfo]) -> bool {
         AssetInfo::NativeToken { denom } => is_factory_token(denom),
     })
 }
+
+/// Computes the spot prices of the pair, i.e. the marginal price of each asset denominated in the
+/// other one, expressed in the smallest units of the assets. Both prices are zero if any of the
+/// pools is empty.
+pub fn compute_spot_prices(
+    pools: &[Asset],
+    pair_type: &PairType,
+    asset_decimals: [u8; 2],
+) -> Result<[Decimal256; 2], ContractError> {
+    if pools[0].amount.is_zero() || pools[1].amount.is_zero() {
+        return Ok([Decimal256::zero(), Decimal256::zero()]);
+    }
+
+    match pair_type {
+        PairType::ConstantProduct => Ok([
+            Decimal256::from_ratio(pools[1].amount, pools[0].amount),
+            Decimal256::from_ratio(pools[0].amount, pools[1].amount),
+        ]),
+        PairType::Weighted { weights } => {
+            // the price of each asset is the ratio of the weighted pools
+            let weighted_pools = [
+                Uint256::from(pools[0].amount).checked_mul(Uint256::from(weights[1]))?,
+                Uint256::from(pools[1].amount).checked_mul(Uint256::from(weights[0]))?,
+            ];
+
+            Ok([
+                Decimal256::from_ratio(weighted_pools[1], weighted_pools[0]),
+                Decimal256::from_ratio(weighted_pools[0], weighted_pools[1]),
+            ])
+        }
+        PairType::StableSwap { amp } => {
+            let pool_0 = Decimal256::decimal_with_precision(pools[0].amount, asset_decimals[0])?;
+            let pool_1 = Decimal256::decimal_with_precision(pools[1].amount, asset_decimals[1])?;
+            let d = calculate_stableswap_d(
+                pool_0,
+                pool_1,
+                amp,
+                asset_decimals[0].max(asset_decimals[1]),
+            )?;
+
+            // the marginal price is the ratio of the partial derivatives of the invariant
+            // ann * (x + y) + d = ann * d + d^3 / (4xy), that is
+            // price_0 = (ann + d^3 / (4x^2y)) / (ann + d^3 / (4xy^2))
+            let n_coins = Decimal256::from_ratio(N_COINS, Uint256::from_u128(1));
+            let ann = Decimal256::from_ratio(
+                Uint256::from_u128((*amp).into()).checked_mul(N_COINS)?,
+                1u8,
+            );
+            let d_product = d
+                .checked_div(pool_0.checked_mul(n_coins)?)?
+                .checked_mul(d.checked_div(pool_1.checked_mul(n_coins)?)?)?;
+            let derivative_0 = ann.checked_add(d_product.checked_mul(d.checked_div(pool_0)?)?)?;
+            let derivative_1 = ann.checked_add(d_product.checked_mul(d.checked_div(pool_1)?)?)?;
+
+            // convert the prices back into the smallest units of the assets
+            let precision_ratio = Decimal256::from_ratio(
+                10u128.pow(asset_decimals[1].into()),
+                10u128.pow(asset_decimals[0].into()),
+            );
+
+            Ok([
+                derivative_0
+                    .checked_div(derivative_1)?
+                    .checked_mul(precision_ratio)?,
+                derivative_1
+                    .checked_div(derivative_0)?
+                    .checked_div(precision_ratio)?,
+            ])
+        }
+    }
+}
+
+/// Computes the invariant of the pair, i.e. the value of the pools in units of a balanced pool. For
+/// constant product pairs that's `2 * sqrt(x * y)`, for weighted pairs `x^w0 * y^w1 / (w0^w0 * w1^w1)`
+/// with normalized weights, and for stableswap pairs `D`, expressed in the smallest units of the
+/// asset with the most decimals. The invariant is zero if any of the pools is empty.
+pub fn compute_invariant(
+    pools: &[Asset],
+    pair_type: &PairType,
+    asset_decimals: [u8; 2],
+) -> Result<Decimal256, ContractError> {
+    if pools[0].amount.is_zero() || pools[1].amount.is_zero() {
+        return Ok(Decimal256::zero());
+    }
+
+    let pool_0 = Decimal256::from_ratio(pools[0].amount, 1u8);
+    let pool_1 = Decimal256::from_ratio(pools[1].amount, 1u8);
+
+    match pair_type {
+        PairType::ConstantProduct => Ok(pool_0
+            .checked_mul(pool_1)?
+            .sqrt()
+            .checked_mul(Decimal256::from_ratio(N_COINS, 1u8))?),
+        PairType::Weighted { weights } => {
+            let total_weight = u128::from(weights[0]) + u128::from(weights[1]);
+            let weight_0 = Decimal256::from_ratio(weights[0], total_weight);
+            let weight_1 = Decimal256::from_ratio(weights[1], total_weight);
+
+            Ok(pool_0
+                .checked_pow_decimal(weight_0)?
+                .checked_mul(pool_1.checked_pow_decimal(weight_1)?)?
+                .checked_div(
+                    weight_0
+                        .checked_pow_decimal(weight_0)?
+                        .checked_mul(weight_1.checked_pow_decimal(weight_1)?)?,
+                )?)
+        }
+        PairType::StableSwap { amp } => {
+            let precision = asset_decimals[0].max(asset_decimals[1]);
+            let d = calculate_stableswap_d(
+                Decimal256::decimal_with_precision(pools[0].amount, asset_decimals[0])?,
+                Decimal256::decimal_with_precision(pools[1].amount, asset_decimals[1])?,
+                amp,
+                precision,
+            )?;
+
+            Ok(d.checked_mul(Decimal256::from_ratio(10u128.pow(precision.into()), 1u8))?)
+        }
+    }
+}
+
+/// Updates the cumulative prices of the pair with the price that has been in effect since the last
+/// observation. It must be called with the pools before they are modified by the current operation,
+/// net of protocol fees.
+pub fn update_price_accumulators(
+    storage: &mut dyn Storage,
+    block_time: u64,
+    pools: &[Asset],
+    pair_info: &PairInfoRaw,
+) -> Result<(), ContractError> {
+    let last_observation = OBSERVATIONS
+        .range(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?;
+
+    let observation = match last_observation {
+        // the price in effect was already accounted for in this block
+        Some((timestamp, _)) if timestamp >= block_time => return Ok(()),
+        Some((timestamp, last_observation)) => {
+            let elapsed = Uint256::from(block_time - timestamp);
+            let prices =
+                compute_spot_prices(pools, &pair_info.pair_type, pair_info.asset_decimals)?;
+
+            Observation {
+                timestamp: block_time,
+                price0_cumulative: last_observation
+                    .price0_cumulative
+                    .wrapping_add(prices[0].atomics().wrapping_mul(elapsed)),
+                price1_cumulative: last_observation
+                    .price1_cumulative
+                    .wrapping_add(prices[1].atomics().wrapping_mul(elapsed)),
+            }
+        }
+        None => Observation {
+            timestamp: block_time,
+            price0_cumulative: Uint256::zero(),
+            price1_cumulative: Uint256::zero(),
+        },
+    };
+
+    OBSERVATIONS.save(storage, block_time, &observation)?;
+
+    // prune the observations that fell out of the TWAP window, keeping the most recent of them so
+    // the start of the window can still be interpolated
+    let stale_observations = OBSERVATIONS
+        .keys(
+            storage,
+            None,
+            Some(Bound::inclusive(block_time.saturating_sub(MAX_TWAP_AGE))),
+            Order::Descending,
+        )
+        .skip(1)
+        .collect::<StdResult<Vec<u64>>>()?;
+
+    for timestamp in stale_observations {
+        OBSERVATIONS.remove(storage, timestamp);
+    }
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::{Decimal256, Uint256};
+use cosmwasm_std::{Decimal256, Uint128, Uint256};
 
 use crate::error::ContractError;
 
@@ -15,8 +15,14 @@ pub trait Decimal256Helper {
     ) -> Result<Decimal256, ContractError>;
 
     fn to_uint256_with_precision(&self, precision: u32) -> Result<Uint256, ContractError>;
+
+    fn checked_pow_decimal(&self, exponent: Decimal256) -> Result<Decimal256, ContractError>;
 }
 
+/// The amount of binary digits of the fractional part of the exponent taken into account when
+/// raising a decimal to a decimal power, enough to cover the 18 decimal places of a [Decimal256].
+const FRACTIONAL_EXPONENT_BITS: u32 = 60;
+
 impl Decimal256Helper for Decimal256 {
     fn decimal_with_precision(
         value: impl Into<Uint256>,
@@ -42,4 +48,37 @@ impl Decimal256Helper for Decimal256 {
 
         Ok(value.checked_div(10u128.pow(self.decimal_places() - precision).into())?)
     }
+
+    /// Raises the decimal to a decimal power. The integer part of the exponent is applied with
+    /// [Decimal256::checked_pow], while the fractional part is applied bit by bit as the product of
+    /// the successive square roots of the base, i.e. x^0.5, x^0.25, x^0.125 and so on.
+    fn checked_pow_decimal(&self, exponent: Decimal256) -> Result<Decimal256, ContractError> {
+        let integer_part = exponent.floor();
+        let integer_exponent = Uint128::try_from(
+            integer_part
+                .atomics()
+                .checked_div(Decimal256::one().atomics())?,
+        )?;
+        let mut result = self.checked_pow(
+            u32::try_from(integer_exponent.u128())
+                .map_err(|_| ContractError::DecimalOverflow {})?,
+        )?;
+
+        let mut fraction = exponent.checked_sub(integer_part)?;
+        let mut root = *self;
+        for _ in 0..FRACTIONAL_EXPONENT_BITS {
+            if fraction.is_zero() {
+                break;
+            }
+
+            root = root.sqrt();
+            fraction = fraction.checked_mul(Decimal256::from_ratio(2u8, 1u8))?;
+            if fraction >= Decimal256::one() {
+                result = result.checked_mul(root)?;
+                fraction = fraction.checked_sub(Decimal256::one())?;
+            }
+        }
+
+        Ok(result)
+    }
 }
@@ -1,6 +1,6 @@
 #![cfg(not(tarpaulin_include))]
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, CanonicalAddr, Decimal, DepsMut, StdError, Uint128};
+use cosmwasm_std::{Addr, CanonicalAddr, Decimal, DepsMut, Env, StdError, Uint128, Uint256};
 use cw_storage_plus::Item;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -11,7 +11,9 @@ use white_whale::pool_network::asset::{AssetInfo, AssetInfoRaw, PairType};
 use white_whale::pool_network::pair::{Config, FeatureToggle};
 
 use crate::helpers::instantiate_fees;
-use crate::state::{ALL_TIME_BURNED_FEES, CONFIG, PAIR_INFO};
+use crate::state::{
+    AmpRamp, Observation, ALL_TIME_BURNED_FEES, AMP_RAMP, CONFIG, OBSERVATIONS, PAIR_INFO,
+};
 
 /// Migrate state of the factory from PascalCase to snake_case for the following items:
 /// [`PairInfoRaw`], [`PairInfo`]
@@ -158,3 +160,36 @@ pub fn migrate_to_v130(deps: DepsMut) -> Result<(), StdError> {
 
     Ok(())
 }
+
+/// Migrate to the deployment tracking the cumulative prices of the pair and ramping the amp of
+/// stableswap pairs.
+///
+/// The cumulative prices start being tracked at the current block, so the price given by the current
+/// reserves is accounted for from then on. Stableswap pairs keep their amp, stored as a ramp that
+/// has already ended.
+pub fn migrate_to_v140(deps: DepsMut, env: &Env) -> Result<(), StdError> {
+    let block_time = env.block.time.seconds();
+    OBSERVATIONS.save(
+        deps.storage,
+        block_time,
+        &Observation {
+            timestamp: block_time,
+            price0_cumulative: Uint256::zero(),
+            price1_cumulative: Uint256::zero(),
+        },
+    )?;
+
+    if let PairType::StableSwap { amp } = PAIR_INFO.load(deps.storage)?.pair_type {
+        AMP_RAMP.save(
+            deps.storage,
+            &AmpRamp {
+                initial_amp: amp,
+                future_amp: amp,
+                initial_amp_block: env.block.height,
+                future_amp_block: env.block.height,
+            },
+        )?;
+    }
+
+    Ok(())
+}
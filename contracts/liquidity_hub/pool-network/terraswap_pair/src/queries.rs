@@ -1,24 +1,25 @@
-use std::cmp::Ordering;
+use cosmwasm_std::{Decimal256, Deps, Env, Order, StdError, StdResult, Uint128, Uint256};
+use cw_storage_plus::{Bound, Item};
 
-use cosmwasm_std::{Decimal256, Deps, Fraction, StdResult, Uint128};
-use cw_storage_plus::Item;
-
-use white_whale::pool_network::asset::{Asset, AssetInfoRaw, PairInfo, PairInfoRaw, PairType};
+use white_whale::pool_network::asset::{
+    Asset, AssetInfo, AssetInfoRaw, PairInfo, PairInfoRaw, PairType,
+};
 use white_whale::pool_network::pair::{
-    ConfigResponse, PoolResponse, ProtocolFeesResponse, ReverseSimulationResponse,
-    SimulationResponse,
+    AmpResponse, ConfigResponse, PoolResponse, ProtocolFeesResponse,
+    ProvideLiquiditySimulationResponse, ReverseSimulationResponse, SimulationResponse,
+    SpotPriceResponse, TwapResponse, VirtualPriceResponse,
 };
 
 use crate::error::ContractError;
-use crate::helpers::{
-    self, calculate_stableswap_y, get_protocol_fee_for_asset, get_total_share, StableSwapDirection,
+use crate::helpers::{self, get_protocol_fee_for_asset, get_total_share};
+use crate::state::{
+    get_fees_for_asset, load_pair_info, Observation, AMP_RAMP, COLLECTED_PROTOCOL_FEES, CONFIG,
+    MAX_TWAP_AGE, OBSERVATIONS, PAIR_INFO,
 };
-use crate::math::Decimal256Helper;
-use crate::state::{get_fees_for_asset, COLLECTED_PROTOCOL_FEES, CONFIG, PAIR_INFO};
 
 /// Queries the [PairInfo] of the pool
-pub fn query_pair_info(deps: Deps) -> Result<PairInfo, ContractError> {
-    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+pub fn query_pair_info(deps: Deps, env: Env) -> Result<PairInfo, ContractError> {
+    let pair_info: PairInfoRaw = load_pair_info(deps.storage, env.block.height)?;
     let pair_info = pair_info.to_normal(deps.api)?;
 
     Ok(pair_info)
@@ -65,9 +66,10 @@ pub fn query_pool(deps: Deps) -> Result<PoolResponse, ContractError> {
 /// Queries a swap simulation. Used to know how much the target asset will be returned for the source token
 pub fn query_simulation(
     deps: Deps,
+    env: Env,
     offer_asset: Asset,
 ) -> Result<SimulationResponse, ContractError> {
-    let pair_info = PAIR_INFO.load(deps.storage)?;
+    let pair_info = load_pair_info(deps.storage, env.block.height)?;
 
     let contract_addr = deps.api.addr_humanize(&pair_info.contract_addr)?;
 
@@ -76,6 +78,7 @@ pub fn query_simulation(
 
     let ask_pool: Asset;
     let ask_decimal;
+    let swap_type: PairType;
 
     let collected_protocol_fees = COLLECTED_PROTOCOL_FEES.load(deps.storage)?;
 
@@ -99,12 +102,14 @@ pub fn query_simulation(
 
         ask_pool = pools[1].clone();
         ask_decimal = pair_info.asset_decimals[1];
+        swap_type = pair_info.pair_type.clone();
     } else if offer_asset.info.equal(&pools[1].info) {
         offer_pool = pools[1].clone();
         offer_decimal = pair_info.asset_decimals[1];
 
         ask_pool = pools[0].clone();
         ask_decimal = pair_info.asset_decimals[0];
+        swap_type = pair_info.pair_type.reversed();
     } else {
         return Err(ContractError::AssetMismatch {});
     }
@@ -116,7 +121,7 @@ pub fn query_simulation(
         ask_pool.amount,
         offer_asset.amount,
         pool_fees,
-        &pair_info.pair_type,
+        &swap_type,
         offer_decimal,
         ask_decimal,
     )?;
@@ -134,9 +139,10 @@ pub fn query_simulation(
 /// the number of target tokens.
 pub fn query_reverse_simulation(
     deps: Deps,
+    env: Env,
     ask_asset: Asset,
 ) -> Result<ReverseSimulationResponse, ContractError> {
-    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+    let pair_info: PairInfoRaw = load_pair_info(deps.storage, env.block.height)?;
 
     let contract_addr = deps.api.addr_humanize(&pair_info.contract_addr)?;
 
@@ -160,6 +166,7 @@ pub fn query_reverse_simulation(
     let ask_pool: Asset;
     let offer_decimal;
     let ask_decimal;
+    let swap_type: PairType;
 
     if ask_asset.info.equal(&pools[0].info) {
         ask_pool = pools[0].clone();
@@ -167,94 +174,93 @@ pub fn query_reverse_simulation(
 
         offer_pool = pools[1].clone();
         offer_decimal = pair_info.asset_decimals[1];
+        swap_type = pair_info.pair_type.reversed();
     } else if ask_asset.info.equal(&pools[1].info) {
         ask_pool = pools[1].clone();
         ask_decimal = pair_info.asset_decimals[1];
 
         offer_pool = pools[0].clone();
         offer_decimal = pair_info.asset_decimals[0];
+        swap_type = pair_info.pair_type.clone();
     } else {
         return Err(ContractError::AssetMismatch {});
     }
 
     let pool_fees = CONFIG.load(deps.storage)?.pool_fees;
 
-    match pair_info.pair_type {
-        PairType::ConstantProduct => {
-            let offer_amount_computation = helpers::compute_offer_amount(
-                offer_pool.amount,
-                ask_pool.amount,
-                ask_asset.amount,
-                pool_fees,
-            )?;
-
-            Ok(ReverseSimulationResponse {
-                offer_amount: offer_amount_computation.offer_amount,
-                spread_amount: offer_amount_computation.spread_amount,
-                swap_fee_amount: offer_amount_computation.swap_fee_amount,
-                protocol_fee_amount: offer_amount_computation.protocol_fee_amount,
-                burn_fee_amount: offer_amount_computation.burn_fee_amount,
-            })
-        }
-        PairType::StableSwap { amp } => {
-            let offer_pool = Decimal256::decimal_with_precision(offer_pool.amount, offer_decimal)?;
-            let ask_pool = Decimal256::decimal_with_precision(ask_pool.amount, ask_decimal)?;
-
-            let before_fees = (Decimal256::one()
-                .checked_sub(pool_fees.protocol_fee.to_decimal_256())?
-                .checked_sub(pool_fees.swap_fee.to_decimal_256())?
-                .checked_sub(pool_fees.burn_fee.to_decimal_256())?)
-            .inv()
-            .unwrap_or_else(Decimal256::one)
-            .checked_mul(Decimal256::decimal_with_precision(
-                ask_asset.amount,
-                ask_decimal,
-            )?)?;
-
-            let before_fees_offer = before_fees.to_uint256_with_precision(offer_decimal.into())?;
-            let before_fees_ask = before_fees.to_uint256_with_precision(ask_decimal.into())?;
-
-            let max_precision = offer_decimal.max(ask_decimal);
-
-            let new_offer_pool_amount = calculate_stableswap_y(
-                offer_pool,
-                ask_pool,
-                before_fees,
-                &amp,
-                max_precision,
-                StableSwapDirection::ReverseSimulate,
-            )?;
-
-            let offer_amount = new_offer_pool_amount.checked_sub(Uint128::try_from(
-                offer_pool.to_uint256_with_precision(u32::from(max_precision))?,
-            )?)?;
-
-            // convert into the original offer precision
-            let offer_amount = match max_precision.cmp(&offer_decimal) {
-                Ordering::Equal => offer_amount,
-                // note that Less should never happen (as max_precision = max(offer_decimal, ask_decimal))
-                Ordering::Less => offer_amount.checked_mul(Uint128::new(
-                    10u128.pow((offer_decimal - max_precision).into()),
-                ))?,
-                Ordering::Greater => offer_amount.checked_div(Uint128::new(
-                    10u128.pow((max_precision - offer_decimal).into()),
-                ))?,
-            };
-
-            let spread_amount = offer_amount.saturating_sub(Uint128::try_from(before_fees_offer)?);
-            let swap_fee_amount = pool_fees.swap_fee.compute(before_fees_ask);
-            let protocol_fee_amount = pool_fees.protocol_fee.compute(before_fees_ask);
-            let burn_fee_amount = pool_fees.burn_fee.compute(before_fees_ask);
-
-            Ok(ReverseSimulationResponse {
-                offer_amount,
-                spread_amount,
-                swap_fee_amount: swap_fee_amount.try_into()?,
-                protocol_fee_amount: protocol_fee_amount.try_into()?,
-                burn_fee_amount: burn_fee_amount.try_into()?,
-            })
-        }
+    let offer_amount_computation = helpers::compute_offer_amount(
+        offer_pool.amount,
+        ask_pool.amount,
+        ask_asset.amount,
+        pool_fees,
+        &swap_type,
+        offer_decimal,
+        ask_decimal,
+    )?;
+
+    Ok(ReverseSimulationResponse {
+        offer_amount: offer_amount_computation.offer_amount,
+        spread_amount: offer_amount_computation.spread_amount,
+        swap_fee_amount: offer_amount_computation.swap_fee_amount,
+        protocol_fee_amount: offer_amount_computation.protocol_fee_amount,
+        burn_fee_amount: offer_amount_computation.burn_fee_amount,
+    })
+}
+
+/// Queries a liquidity provision simulation. Used to know how many LP tokens would be minted for
+/// the given assets.
+pub fn query_provide_liquidity_simulation(
+    deps: Deps,
+    env: Env,
+    assets: [Asset; 2],
+) -> Result<ProvideLiquiditySimulationResponse, ContractError> {
+    let pair_info: PairInfoRaw = load_pair_info(deps.storage, env.block.height)?;
+    let contract_addr = deps.api.addr_humanize(&pair_info.contract_addr)?;
+    let collected_protocol_fees = COLLECTED_PROTOCOL_FEES.load(deps.storage)?;
+
+    // To calculate pool amounts properly we should subtract the protocol fees from the pool
+    let mut pools = pair_info.query_pools(&deps.querier, deps.api, contract_addr)?;
+    for pool in pools.iter_mut() {
+        let protocol_fee =
+            get_protocol_fee_for_asset(collected_protocol_fees.clone(), pool.clone().get_id());
+        pool.amount = pool.amount.checked_sub(protocol_fee)?;
+    }
+
+    let mut deposits = [Uint128::zero(); 2];
+    for (i, pool) in pools.iter().enumerate() {
+        deposits[i] = assets
+            .iter()
+            .find(|asset| asset.info.equal(&pool.info))
+            .map(|asset| asset.amount)
+            .ok_or(ContractError::AssetMismatch {})?;
     }
+
+    let liquidity_token = match &pair_info.liquidity_token {
+        AssetInfoRaw::Token { contract_addr } => deps.api.addr_humanize(contract_addr)?.to_string(),
+        AssetInfoRaw::NativeToken { denom } => denom.to_string(),
+    };
+    let total_share = get_total_share(&deps, liquidity_token)?;
+
+    let provision = helpers::compute_liquidity_provision(
+        deposits,
+        &pools,
+        total_share,
+        &pair_info,
+        CONFIG.load(deps.storage)?.pool_fees,
+        None,
+    )?;
+
+    Ok(ProvideLiquiditySimulationResponse {
+        share: provision.share,
+        deposits: pools
+            .into_iter()
+            .zip(provision.deposits)
+            .map(|(pool, amount)| Asset {
+                info: pool.info,
+                amount,
+            })
+            .collect(),
+    })
 }
 
 /// Queries the [Config], which contains the owner, pool_fees and feature_toggle
@@ -288,3 +294,197 @@ pub fn query_fees(
     let fees = fees_storage_item.load(deps.storage)?;
     Ok(ProtocolFeesResponse { fees })
 }
+
+/// Queries the time-weighted average prices of the pair between `start_age` and `end_age` seconds
+/// ago. The prices are expressed in the smallest units of the assets.
+pub fn query_twap(
+    deps: Deps,
+    env: Env,
+    start_age: u64,
+    end_age: Option<u64>,
+) -> Result<TwapResponse, ContractError> {
+    let end_age = end_age.unwrap_or_default();
+    if start_age <= end_age || start_age > MAX_TWAP_AGE {
+        return Err(ContractError::InvalidTwapWindow(MAX_TWAP_AGE));
+    }
+
+    let now = env.block.time.seconds();
+    let start_time = now
+        .checked_sub(start_age)
+        .ok_or(ContractError::TwapHistoryUnavailable {})?;
+    let end_time = now - end_age;
+
+    let pair_info: PairInfoRaw = load_pair_info(deps.storage, env.block.height)?;
+
+    // the price currently in effect is the one given by the pools net of protocol fees
+    let pools = helpers::get_pools_net_of_protocol_fees(&deps, &pair_info, env.contract.address)?;
+    let spot_prices =
+        helpers::compute_spot_prices(&pools, &pair_info.pair_type, pair_info.asset_decimals)?;
+
+    let start = cumulative_prices_at(deps, start_time, &spot_prices)?;
+    let end = cumulative_prices_at(deps, end_time, &spot_prices)?;
+    let elapsed = Uint256::from(end_time - start_time);
+
+    Ok(TwapResponse {
+        price0_average: Decimal256::new(
+            end.price0_cumulative
+                .wrapping_sub(start.price0_cumulative)
+                .checked_div(elapsed)?,
+        ),
+        price1_average: Decimal256::new(
+            end.price1_cumulative
+                .wrapping_sub(start.price1_cumulative)
+                .checked_div(elapsed)?,
+        ),
+        start_time,
+        end_time,
+    })
+}
+
+/// Computes the cumulative prices of the pair at the given timestamp. As prices only change between
+/// observations, timestamps falling in between two observations are linearly interpolated, while
+/// timestamps after the last observation are extrapolated with the current spot prices.
+fn cumulative_prices_at(
+    deps: Deps,
+    timestamp: u64,
+    spot_prices: &[Decimal256; 2],
+) -> Result<Observation, ContractError> {
+    let (_, before) = OBSERVATIONS
+        .range(
+            deps.storage,
+            None,
+            Some(Bound::inclusive(timestamp)),
+            Order::Descending,
+        )
+        .next()
+        .transpose()?
+        .ok_or(ContractError::TwapHistoryUnavailable {})?;
+
+    if before.timestamp == timestamp {
+        return Ok(before);
+    }
+
+    let after = OBSERVATIONS
+        .range(
+            deps.storage,
+            Some(Bound::exclusive(timestamp)),
+            None,
+            Order::Ascending,
+        )
+        .next()
+        .transpose()?;
+
+    let elapsed = Uint256::from(timestamp - before.timestamp);
+    let (price0_delta, price1_delta) = match after {
+        Some((_, after)) => {
+            let interval = Uint256::from(after.timestamp - before.timestamp);
+            (
+                after
+                    .price0_cumulative
+                    .wrapping_sub(before.price0_cumulative)
+                    .checked_multiply_ratio(elapsed, interval)?,
+                after
+                    .price1_cumulative
+                    .wrapping_sub(before.price1_cumulative)
+                    .checked_multiply_ratio(elapsed, interval)?,
+            )
+        }
+        None => (
+            spot_prices[0].atomics().wrapping_mul(elapsed),
+            spot_prices[1].atomics().wrapping_mul(elapsed),
+        ),
+    };
+
+    Ok(Observation {
+        timestamp,
+        price0_cumulative: before.price0_cumulative.wrapping_add(price0_delta),
+        price1_cumulative: before.price1_cumulative.wrapping_add(price1_delta),
+    })
+}
+
+/// Queries the amplification coefficient of a stableswap pair, along with its ramp if any
+pub fn query_amp(deps: Deps, env: Env) -> Result<AmpResponse, ContractError> {
+    let amp = match PAIR_INFO.load(deps.storage)?.pair_type {
+        PairType::StableSwap { amp } => amp,
+        _ => {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Amp can only be queried on stableswap pairs",
+            )))
+        }
+    };
+
+    let response = match AMP_RAMP.may_load(deps.storage)? {
+        Some(amp_ramp) => AmpResponse {
+            amp: amp_ramp.current_amp(env.block.height),
+            initial_amp: amp_ramp.initial_amp,
+            future_amp: amp_ramp.future_amp,
+            initial_amp_block: amp_ramp.initial_amp_block,
+            future_amp_block: amp_ramp.future_amp_block,
+        },
+        None => AmpResponse {
+            amp,
+            initial_amp: amp,
+            future_amp: amp,
+            initial_amp_block: 0,
+            future_amp_block: 0,
+        },
+    };
+
+    Ok(response)
+}
+
+/// Queries the virtual price of the LP token, i.e. the invariant of the pair per LP token. It is
+/// zero if there's no liquidity in the pair.
+pub fn query_virtual_price(deps: Deps, env: Env) -> Result<VirtualPriceResponse, ContractError> {
+    let pair_info: PairInfoRaw = load_pair_info(deps.storage, env.block.height)?;
+    let pools = helpers::get_pools_net_of_protocol_fees(&deps, &pair_info, env.contract.address)?;
+
+    let liquidity_token = match pair_info.liquidity_token {
+        AssetInfoRaw::Token { contract_addr } => {
+            deps.api.addr_humanize(&contract_addr)?.to_string()
+        }
+        AssetInfoRaw::NativeToken { denom } => denom,
+    };
+    let total_share = get_total_share(&deps, liquidity_token)?;
+
+    if total_share.is_zero() {
+        return Ok(VirtualPriceResponse {
+            virtual_price: Decimal256::zero(),
+        });
+    }
+
+    let invariant =
+        helpers::compute_invariant(&pools, &pair_info.pair_type, pair_info.asset_decimals)?;
+
+    Ok(VirtualPriceResponse {
+        virtual_price: invariant.checked_div(Decimal256::from_ratio(total_share, 1u8))?,
+    })
+}
+
+/// Queries the marginal price of the offer asset denominated in the ask asset, in the smallest units
+/// of the assets
+pub fn query_spot_price(
+    deps: Deps,
+    env: Env,
+    offer_asset_info: AssetInfo,
+    ask_asset_info: AssetInfo,
+) -> Result<SpotPriceResponse, ContractError> {
+    let pair_info: PairInfoRaw = load_pair_info(deps.storage, env.block.height)?;
+    let pools = helpers::get_pools_net_of_protocol_fees(&deps, &pair_info, env.contract.address)?;
+
+    let offer_index =
+        if offer_asset_info.equal(&pools[0].info) && ask_asset_info.equal(&pools[1].info) {
+            0
+        } else if offer_asset_info.equal(&pools[1].info) && ask_asset_info.equal(&pools[0].info) {
+            1
+        } else {
+            return Err(ContractError::AssetMismatch {});
+        };
+
+    let spot_prices =
+        helpers::compute_spot_prices(&pools, &pair_info.pair_type, pair_info.asset_decimals)?;
+
+    Ok(SpotPriceResponse {
+        spot_price: spot_prices[offer_index],
+    })
+}
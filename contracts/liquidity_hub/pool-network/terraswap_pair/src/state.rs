@@ -1,7 +1,8 @@
-use cosmwasm_std::{StdError, StdResult, Storage, Uint128};
-use cw_storage_plus::Item;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{StdError, StdResult, Storage, Uint128, Uint256};
+use cw_storage_plus::{Item, Map};
 
-use white_whale::pool_network::asset::{Asset, PairInfoRaw};
+use white_whale::pool_network::asset::{Asset, PairInfoRaw, PairType};
 use white_whale::pool_network::pair::Config;
 
 pub const LP_SYMBOL: &str = "uLP";
@@ -17,6 +18,76 @@ pub const ALL_TIME_COLLECTED_PROTOCOL_FEES: Item<Vec<Asset>> =
 // Fees that have been burned by the pool since the pool's inception
 pub const ALL_TIME_BURNED_FEES: Item<Vec<Asset>> = Item::new("all_time_burned_fees");
 
+/// Snapshot of the cumulative prices of the pair at a given point in time
+#[cw_serde]
+pub struct Observation {
+    pub timestamp: u64,
+    /// Sum of the price of the first asset (denominated in the second asset) times the seconds it
+    /// was in effect, stored as [cosmwasm_std::Decimal256] atomics. Overflows are expected and wrap.
+    pub price0_cumulative: Uint256,
+    /// Sum of the price of the second asset (denominated in the first asset) times the seconds it
+    /// was in effect, stored as [cosmwasm_std::Decimal256] atomics. Overflows are expected and wrap.
+    pub price1_cumulative: Uint256,
+}
+
+/// Price observations of the pair, keyed by timestamp in seconds
+pub const OBSERVATIONS: Map<u64, Observation> = Map::new("observations");
+/// Observations older than this (in seconds) are pruned, which bounds the TWAP window
+pub const MAX_TWAP_AGE: u64 = 86_400;
+
+/// Ramp of the amplification coefficient of a stableswap pair. The amplification coefficient goes
+/// linearly from the `initial_amp` at the `initial_amp_block` to the `future_amp` at the
+/// `future_amp_block`.
+#[cw_serde]
+pub struct AmpRamp {
+    pub initial_amp: u64,
+    pub future_amp: u64,
+    pub initial_amp_block: u64,
+    pub future_amp_block: u64,
+}
+
+impl AmpRamp {
+    /// Computes the amplification coefficient in effect at the given block height
+    pub fn current_amp(&self, block_height: u64) -> u64 {
+        if block_height >= self.future_amp_block {
+            return self.future_amp;
+        }
+
+        let block_range = u128::from(self.future_amp_block - self.initial_amp_block);
+        let block_delta = u128::from(block_height.saturating_sub(self.initial_amp_block));
+
+        if self.future_amp >= self.initial_amp {
+            // ramp up
+            let amp_delta =
+                u128::from(self.future_amp - self.initial_amp) * block_delta / block_range;
+            self.initial_amp + amp_delta as u64
+        } else {
+            // ramp down
+            let amp_delta =
+                u128::from(self.initial_amp - self.future_amp) * block_delta / block_range;
+            self.initial_amp - amp_delta as u64
+        }
+    }
+}
+
+/// The amplification coefficient ramp of a stableswap pair. If there's none, the amplification
+/// coefficient is the one the pair was created with.
+pub const AMP_RAMP: Item<AmpRamp> = Item::new("amp_ramp");
+
+/// Loads the [PairInfoRaw] of the pair, with the amplification coefficient of stableswap pairs
+/// set to the one in effect at the given block height.
+pub fn load_pair_info(storage: &dyn Storage, block_height: u64) -> StdResult<PairInfoRaw> {
+    let mut pair_info = PAIR_INFO.load(storage)?;
+
+    if let PairType::StableSwap { amp } = &mut pair_info.pair_type {
+        if let Some(amp_ramp) = AMP_RAMP.may_load(storage)? {
+            *amp = amp_ramp.current_amp(block_height);
+        }
+    }
+
+    Ok(pair_info)
+}
+
 /// Stores the fee for an asset in the given fees_storage_item
 pub fn store_fee(
     storage: &mut dyn Storage,
@@ -0,0 +1,268 @@
+use crate::contract::{execute, query};
+use crate::error::ContractError;
+use crate::tests::helpers::{instantiate_pair, no_fees};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{from_binary, Env, OwnedDeps, StdError, Uint128};
+use white_whale::pool_network::asset::{Asset, AssetInfo, PairInfo, PairType};
+use white_whale::pool_network::mock_querier::WasmMockQuerier;
+use white_whale::pool_network::pair::{AmpResponse, ExecuteMsg, QueryMsg, SimulationResponse};
+use white_whale::pool_network::trio::RampAmp;
+
+fn env_at_height(height: u64) -> Env {
+    let mut env = mock_env();
+    env.block.height = height;
+    env
+}
+
+fn ramp_amp_msg(future_a: u64, future_block: u64) -> ExecuteMsg {
+    ExecuteMsg::UpdateConfig {
+        owner: None,
+        fee_collector_addr: None,
+        pool_fees: None,
+        feature_toggle: None,
+        amp_factor: Some(RampAmp {
+            future_a,
+            future_block,
+        }),
+    }
+}
+
+fn query_amp(deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, height: u64) -> AmpResponse {
+    from_binary(&query(deps.as_ref(), env_at_height(height), QueryMsg::Amp {}).unwrap()).unwrap()
+}
+
+fn simulate_swap(deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, height: u64) -> Uint128 {
+    let simulation: SimulationResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env_at_height(height),
+            QueryMsg::Simulation {
+                offer_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    amount: Uint128::from(100_000u128),
+                },
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    simulation.return_amount
+}
+
+#[test]
+fn ramp_amp() {
+    let mut deps = instantiate_pair(
+        PairType::StableSwap { amp: 100 },
+        [1_000_000, 1_000_000],
+        0,
+        no_fees(),
+    );
+    let start = mock_env().block.height;
+
+    // there's no ramp yet
+    assert_eq!(
+        query_amp(&deps, start),
+        AmpResponse {
+            amp: 100,
+            initial_amp: 100,
+            future_amp: 100,
+            initial_amp_block: 0,
+            future_amp_block: 0,
+        }
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ramp_amp_msg(200, start + 10_000),
+    )
+    .unwrap();
+
+    assert_eq!(
+        query_amp(&deps, start),
+        AmpResponse {
+            amp: 100,
+            initial_amp: 100,
+            future_amp: 200,
+            initial_amp_block: start,
+            future_amp_block: start + 10_000,
+        }
+    );
+    assert_eq!(query_amp(&deps, start + 2_500).amp, 125);
+    assert_eq!(query_amp(&deps, start + 5_000).amp, 150);
+    assert_eq!(query_amp(&deps, start + 10_000).amp, 200);
+    assert_eq!(query_amp(&deps, start + 50_000).amp, 200);
+
+    // the pair info reflects the current amp
+    let pair_info: PairInfo = from_binary(
+        &query(
+            deps.as_ref(),
+            env_at_height(start + 5_000),
+            QueryMsg::Pair {},
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(pair_info.pair_type, PairType::StableSwap { amp: 150 });
+
+    // a higher amp flattens the curve around the peg, so the same swap returns more
+    assert!(simulate_swap(&deps, start + 10_000) > simulate_swap(&deps, start));
+
+    // ramping down from the current amp in the middle of a ramp
+    execute(
+        deps.as_mut(),
+        env_at_height(start + 5_000),
+        mock_info("addr0000", &[]),
+        ramp_amp_msg(50, start + 15_000),
+    )
+    .unwrap();
+
+    assert_eq!(query_amp(&deps, start + 5_000).amp, 150);
+    assert_eq!(query_amp(&deps, start + 10_000).amp, 100);
+    assert_eq!(query_amp(&deps, start + 15_000).amp, 50);
+}
+
+#[test]
+fn stop_amp_ramp() {
+    let mut deps = instantiate_pair(
+        PairType::StableSwap { amp: 100 },
+        [1_000_000, 1_000_000],
+        0,
+        no_fees(),
+    );
+    let start = mock_env().block.height;
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ramp_amp_msg(200, start + 10_000),
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        env_at_height(start + 5_000),
+        mock_info("not_owner", &[]),
+        ExecuteMsg::StopAmpRamp {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("unauthorized"))
+    );
+
+    execute(
+        deps.as_mut(),
+        env_at_height(start + 5_000),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::StopAmpRamp {},
+    )
+    .unwrap();
+
+    assert_eq!(
+        query_amp(&deps, start + 50_000),
+        AmpResponse {
+            amp: 150,
+            initial_amp: 150,
+            future_amp: 150,
+            initial_amp_block: start + 5_000,
+            future_amp_block: start + 5_000,
+        }
+    );
+}
+
+#[test]
+fn ramp_amp_validation() {
+    let mut deps = instantiate_pair(
+        PairType::StableSwap { amp: 100 },
+        [1_000_000, 1_000_000],
+        0,
+        no_fees(),
+    );
+    let start = mock_env().block.height;
+
+    for (msg, error) in [
+        (
+            ramp_amp_msg(0, start + 10_000),
+            "Future amp must be between 1 and 1000000",
+        ),
+        (
+            ramp_amp_msg(1_000_001, start + 10_000),
+            "Future amp must be between 1 and 1000000",
+        ),
+        (ramp_amp_msg(1_001, start + 10_000), "Amp change over max"),
+        (ramp_amp_msg(9, start + 10_000), "Amp change over max"),
+        (
+            ramp_amp_msg(200, start + 9_999),
+            "Amp change ramp time under minimum",
+        ),
+    ] {
+        let err = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::Std(StdError::generic_err(error)));
+    }
+
+    // only the owner can ramp the amp
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("not_owner", &[]),
+        ramp_amp_msg(200, start + 10_000),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("unauthorized"))
+    );
+}
+
+#[test]
+fn ramp_amp_on_constant_product_pair() {
+    let mut deps = instantiate_pair(
+        PairType::ConstantProduct,
+        [1_000_000, 1_000_000],
+        0,
+        no_fees(),
+    );
+    let start = mock_env().block.height;
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ramp_amp_msg(200, start + 10_000),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Amp can only be ramped on stableswap pairs"
+        ))
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::StopAmpRamp {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Amp can only be ramped on stableswap pairs"
+        ))
+    );
+
+    let err = query(deps.as_ref(), mock_env(), QueryMsg::Amp {}).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Amp can only be queried on stableswap pairs"
+        ))
+    );
+}
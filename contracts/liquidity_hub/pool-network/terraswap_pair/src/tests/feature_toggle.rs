@@ -68,6 +68,7 @@ fn test_feature_toggle_swap_disabled() {
             deposits_enabled: true,
             swaps_enabled: false,
         }),
+        amp_factor: None,
     };
     execute(deps.as_mut(), env.clone(), info, update_config_message).unwrap();
 
@@ -83,6 +84,7 @@ fn test_feature_toggle_swap_disabled() {
         belief_price: None,
         max_spread: None,
         to: None,
+        deadline: None,
     };
     let info = mock_info(
         "addr0000",
@@ -107,6 +109,7 @@ fn test_feature_toggle_swap_disabled() {
             belief_price: None,
             max_spread: None,
             to: None,
+            deadline: None,
         })
         .unwrap(),
     });
@@ -177,13 +180,19 @@ fn test_feature_toggle_withdrawals_disabled() {
             deposits_enabled: true,
             swaps_enabled: true,
         }),
+        amp_factor: None,
     };
     execute(deps.as_mut(), env, info, update_config_message).unwrap();
 
     // withdraw liquidity should fail
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
-        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+            target_asset: None,
+            min_receive: None,
+            deadline: None,
+        })
+        .unwrap(),
         amount: Uint128::from(100u128),
     });
 
@@ -253,6 +262,7 @@ fn test_feature_toggle_deposits_disabled() {
             deposits_enabled: false,
             swaps_enabled: true,
         }),
+        amp_factor: None,
     };
     execute(deps.as_mut(), env, info, update_config_message).unwrap();
 
@@ -274,6 +284,7 @@ fn test_feature_toggle_deposits_disabled() {
         ],
         slippage_tolerance: None,
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -0,0 +1,89 @@
+use crate::contract::{instantiate, reply};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{Coin, Decimal, OwnedDeps, Reply, SubMsgResponse, SubMsgResult, Uint128};
+use white_whale::fee::Fee;
+use white_whale::pool_network::asset::{AssetInfo, PairType};
+use white_whale::pool_network::mock_querier::{mock_dependencies, WasmMockQuerier};
+use white_whale::pool_network::pair::{InstantiateMsg, PoolFee};
+
+pub fn uusd() -> AssetInfo {
+    AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    }
+}
+
+pub fn asset0000() -> AssetInfo {
+    AssetInfo::Token {
+        contract_addr: "asset0000".to_string(),
+    }
+}
+
+/// Pool fees charging the given protocol and swap fee shares, without burn fee
+pub fn pool_fees(protocol_fee: Decimal, swap_fee: Decimal) -> PoolFee {
+    PoolFee {
+        protocol_fee: Fee {
+            share: protocol_fee,
+        },
+        swap_fee: Fee { share: swap_fee },
+        burn_fee: Fee {
+            share: Decimal::zero(),
+        },
+    }
+}
+
+pub fn no_fees() -> PoolFee {
+    pool_fees(Decimal::zero(), Decimal::zero())
+}
+
+/// Instantiates a uusd-asset0000 pair holding the given `[uusd, asset0000]` pools, with
+/// liquidity0000 as its LP token and `total_share` of it issued to addr0000
+pub fn instantiate_pair(
+    pair_type: PairType,
+    pools: [u128; 2],
+    total_share: u128,
+    pool_fees: PoolFee,
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(pools[0]),
+    }]);
+
+    deps.querier.with_token_balances(&[
+        (
+            &"asset0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(pools[1]))],
+        ),
+        (
+            &"liquidity0000".to_string(),
+            &[(&"addr0000".to_string(), &Uint128::from(total_share))],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: [uusd(), asset0000()],
+        token_code_id: 10u64,
+        asset_decimals: [6u8, 6u8],
+        pool_fees,
+        fee_collector_addr: "collector".to_string(),
+        pair_type,
+        token_factory_lp: false,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    // store liquidity token
+    let reply_msg = Reply {
+        id: 1,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: Some(
+                vec![
+                    10, 13, 108, 105, 113, 117, 105, 100, 105, 116, 121, 48, 48, 48, 48,
+                ]
+                .into(),
+            ),
+        }),
+    };
+    reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    deps
+}
@@ -1,8 +1,14 @@
+mod amp;
+mod deadline;
 mod feature_toggle;
+mod helpers;
 mod protocol_fees;
 mod provide_liquidity;
 mod queries;
 mod stableswap;
 mod swap;
+mod swap_exact_out;
 mod testing;
+mod twap;
+mod weighted;
 mod withdrawals;
@@ -95,6 +95,7 @@ fn test_protocol_fees() {
         belief_price: None,
         max_spread: None,
         to: None,
+        deadline: None,
     };
     let env = mock_env();
     let info = mock_info(
@@ -150,6 +151,7 @@ fn test_protocol_fees() {
         belief_price: None,
         max_spread: None,
         to: None,
+        deadline: None,
     };
     let env = mock_env();
     let info = mock_info(
@@ -279,6 +281,7 @@ fn test_collect_protocol_fees_successful() {
         belief_price: None,
         max_spread: None,
         to: None,
+        deadline: None,
     };
     let env = mock_env();
     let info = mock_info(
@@ -303,6 +306,7 @@ fn test_collect_protocol_fees_successful() {
             belief_price: None,
             max_spread: None,
             to: None,
+            deadline: None,
         })
         .unwrap(),
     });
@@ -507,6 +511,7 @@ fn test_collect_protocol_fees_successful_1_fee_only() {
         belief_price: None,
         max_spread: None,
         to: None,
+        deadline: None,
     };
     let env = mock_env();
     let info = mock_info(
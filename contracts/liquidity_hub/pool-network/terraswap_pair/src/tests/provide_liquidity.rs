@@ -1,7 +1,7 @@
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    coin, to_binary, BankMsg, Coin, CosmosMsg, Decimal, Reply, Response, StdError, SubMsg,
-    SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
+    coin, from_binary, to_binary, BankMsg, Coin, CosmosMsg, Decimal, Reply, Response, StdError,
+    SubMsg, SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
 
@@ -10,11 +10,15 @@ use white_whale::pool_network;
 use white_whale::pool_network::asset::{Asset, AssetInfo, PairType, MINIMUM_LIQUIDITY_AMOUNT};
 use white_whale::pool_network::denom::MsgMint;
 use white_whale::pool_network::mock_querier::mock_dependencies;
-use white_whale::pool_network::pair::{ExecuteMsg, InstantiateMsg, PoolFee};
+use white_whale::pool_network::pair::{
+    ExecuteMsg, InstantiateMsg, PoolFee, ProtocolFeesResponse, ProvideLiquiditySimulationResponse,
+    QueryMsg,
+};
 
-use crate::contract::{execute, instantiate, reply};
+use crate::contract::{execute, instantiate, query, reply};
 use crate::error::ContractError;
 use crate::state::LP_SYMBOL;
+use crate::tests::helpers::{instantiate_pair, pool_fees};
 
 #[test]
 fn provide_liquidity_cw20_lp() {
@@ -97,6 +101,7 @@ fn provide_liquidity_cw20_lp() {
         ],
         slippage_tolerance: None,
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -131,6 +136,7 @@ fn provide_liquidity_cw20_lp() {
         ],
         slippage_tolerance: None,
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -226,6 +232,7 @@ fn provide_liquidity_cw20_lp() {
         ],
         slippage_tolerance: None,
         receiver: Some("staking0000".to_string()), // try changing receiver
+        deadline: None,
     };
 
     let env = mock_env();
@@ -287,6 +294,7 @@ fn provide_liquidity_cw20_lp() {
         ],
         slippage_tolerance: None,
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -346,6 +354,7 @@ fn provide_liquidity_cw20_lp() {
         ],
         slippage_tolerance: Some(Decimal::percent(1)),
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -389,6 +398,7 @@ fn provide_liquidity_cw20_lp() {
         ],
         slippage_tolerance: Some(Decimal::percent(1)),
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -434,6 +444,7 @@ fn provide_liquidity_cw20_lp() {
         ],
         slippage_tolerance: Some(Decimal::percent(1)),
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -473,6 +484,7 @@ fn provide_liquidity_cw20_lp() {
         ],
         slippage_tolerance: Some(Decimal::percent(1)),
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -567,6 +579,7 @@ fn provide_liquidity_zero_amount() {
         ],
         slippage_tolerance: None,
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -667,6 +680,7 @@ fn provide_liquidity_invalid_minimum_lp_amount() {
         ],
         slippage_tolerance: None,
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -758,6 +772,7 @@ fn provide_liquidity_tokenfactory_lp() {
         ],
         slippage_tolerance: None,
         receiver: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -809,3 +824,287 @@ fn provide_liquidity_tokenfactory_lp() {
 
     assert_eq!(bank_send_msg, bank_send_msg_expected);
 }
+
+#[test]
+fn provide_liquidity_single_sided() {
+    // the pool has 1_000_000 of each asset, plus the 10_000 uusd provided
+    let mut deps = instantiate_pair(
+        PairType::ConstantProduct,
+        [1_010_000, 1_000_000],
+        1_000_000,
+        pool_fees(Decimal::percent(1u64), Decimal::percent(1u64)),
+    );
+
+    let assets = [
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::new(10_000u128),
+        },
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            amount: Uint128::zero(),
+        },
+    ];
+
+    // the simulation doesn't know about the funds sent, so query it with the pool before the deposit
+    let simulation_deps = instantiate_pair(
+        PairType::ConstantProduct,
+        [1_000_000, 1_000_000],
+        1_000_000,
+        pool_fees(Decimal::percent(1u64), Decimal::percent(1u64)),
+    );
+    let simulation: ProvideLiquiditySimulationResponse = from_binary(
+        &query(
+            simulation_deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProvideLiquiditySimulation {
+                assets: assets.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(10_000u128, "uusd")]),
+        ExecuteMsg::ProvideLiquidity {
+            assets,
+            slippage_tolerance: Some(Decimal::percent(5u64)),
+            receiver: None,
+            deadline: None,
+        },
+    )
+    .unwrap();
+
+    // part of the uusd is swapped, so the deposits are balanced with the pool after the swap
+    let swap_offer_amount: Uint128 = res
+        .attributes
+        .iter()
+        .find(|attr| attr.key == "swap_offer_amount")
+        .unwrap()
+        .value
+        .parse()
+        .unwrap();
+    let swap_return_amount: Uint128 = res
+        .attributes
+        .iter()
+        .find(|attr| attr.key == "swap_return_amount")
+        .unwrap()
+        .value
+        .parse()
+        .unwrap();
+    assert_eq!(swap_offer_amount, Uint128::new(5_037u128));
+    assert_eq!(swap_return_amount, Uint128::new(4_911u128));
+    assert_eq!(
+        simulation.deposits,
+        vec![
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::new(10_000u128) - swap_offer_amount,
+            },
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                amount: swap_return_amount,
+            },
+        ]
+    );
+
+    // no cw20 is transferred, only the LP is minted
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "liquidity0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Mint {
+                recipient: "addr0000".to_string(),
+                amount: simulation.share,
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+    assert_eq!(simulation.share, Uint128::new(4_935u128));
+
+    // the protocol fee of the swap is set aside
+    let protocol_fees: ProtocolFeesResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProtocolFees {
+                asset_id: Some("asset0000".to_string()),
+                all_time: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(protocol_fees.fees[0].amount, Uint128::new(50u128));
+}
+
+#[test]
+fn provide_liquidity_single_sided_slippage_tolerance() {
+    let mut deps = instantiate_pair(
+        PairType::ConstantProduct,
+        [1_000_000, 1_000_000],
+        1_000_000,
+        pool_fees(Decimal::percent(1u64), Decimal::percent(1u64)),
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::ProvideLiquidity {
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    amount: Uint128::zero(),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: "asset0000".to_string(),
+                    },
+                    amount: Uint128::new(100_000u128),
+                },
+            ],
+            slippage_tolerance: Some(Decimal::percent(1u64)),
+            receiver: None,
+            deadline: None,
+        },
+    );
+    assert_eq!(res.unwrap_err(), ContractError::MaxSpreadAssertion {});
+}
+
+#[test]
+fn provide_liquidity_single_sided_stableswap() {
+    let mut deps = instantiate_pair(
+        PairType::StableSwap { amp: 100 },
+        [1_000_000_000, 1_500_000_000],
+        2_500_000_000,
+        pool_fees(Decimal::percent(1u64), Decimal::percent(1u64)),
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::ProvideLiquidity {
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    amount: Uint128::zero(),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: "asset0000".to_string(),
+                    },
+                    amount: Uint128::new(10_000_000u128),
+                },
+            ],
+            slippage_tolerance: Some(Decimal::percent(1u64)),
+            receiver: None,
+            deadline: None,
+        },
+    )
+    .unwrap();
+
+    // the whole cw20 deposit is transferred
+    assert_eq!(
+        res.messages[0],
+        SubMsg::new(WasmMsg::Execute {
+            contract_addr: "asset0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: "addr0000".to_string(),
+                recipient: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Uint128::new(10_000_000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+
+    // the deposit is split in the same ratio as the pool after the swap, and minted accordingly
+    let share: Uint128 = res
+        .attributes
+        .iter()
+        .find(|attr| attr.key == "share")
+        .unwrap()
+        .value
+        .parse()
+        .unwrap();
+    assert!(share > Uint128::new(9_700_000u128) && share < Uint128::new(10_000_000u128));
+}
+
+#[test]
+fn provide_liquidity_weighted() {
+    let provide = |uusd_amount: u128, asset_amount: u128| -> Uint128 {
+        // an 80/20 pool where both assets are worth the same, plus the uusd provided
+        let mut deps = instantiate_pair(
+            PairType::Weighted { weights: [80, 20] },
+            [8_000_000_000u128 + uusd_amount, 2_000_000_000u128],
+            4_000_000_000u128,
+            pool_fees(Decimal::percent(1u64), Decimal::percent(1u64)),
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[coin(uusd_amount, "uusd")]),
+            ExecuteMsg::ProvideLiquidity {
+                assets: [
+                    Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "uusd".to_string(),
+                        },
+                        amount: Uint128::new(uusd_amount),
+                    },
+                    Asset {
+                        info: AssetInfo::Token {
+                            contract_addr: "asset0000".to_string(),
+                        },
+                        amount: Uint128::new(asset_amount),
+                    },
+                ],
+                slippage_tolerance: Some(Decimal::percent(5u64)),
+                receiver: None,
+                deadline: None,
+            },
+        )
+        .unwrap();
+
+        res.attributes
+            .iter()
+            .find(|attr| attr.key == "share")
+            .unwrap()
+            .value
+            .parse()
+            .unwrap()
+    };
+
+    // a proportional deposit mints the proportional share
+    assert_eq!(
+        provide(40_000_000u128, 10_000_000u128),
+        Uint128::new(20_000_000u128)
+    );
+
+    // a single-sided deposit worth 0.1% of the pool mints a bit less than 0.1% of the share, as
+    // part of the deposit is swapped paying the fees
+    let share = provide(0u128, 10_000_000u128);
+    assert!(share > Uint128::new(3_900_000u128) && share < Uint128::new(4_000_000u128));
+
+    let share = provide(10_000_000u128, 0u128);
+    assert!(share > Uint128::new(3_900_000u128) && share < Uint128::new(4_000_000u128));
+}
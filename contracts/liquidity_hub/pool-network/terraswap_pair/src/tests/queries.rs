@@ -1,12 +1,18 @@
 use crate::contract::{instantiate, query, reply};
 use crate::error::ContractError;
 use crate::queries::query_pool;
-use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{Coin, Decimal, Reply, SubMsgResponse, SubMsgResult, Uint128};
+use crate::state::{store_fee, COLLECTED_PROTOCOL_FEES};
+use crate::tests::helpers::{instantiate_pair, no_fees};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, Coin, Decimal, Decimal256, OwnedDeps, Reply, SubMsgResponse, SubMsgResult, Uint128,
+};
 use white_whale::fee::Fee;
 use white_whale::pool_network::asset::{Asset, AssetInfo, PairType};
-use white_whale::pool_network::mock_querier::mock_dependencies;
-use white_whale::pool_network::pair::{InstantiateMsg, PoolFee, PoolResponse, QueryMsg};
+use white_whale::pool_network::mock_querier::{mock_dependencies, WasmMockQuerier};
+use white_whale::pool_network::pair::{
+    InstantiateMsg, PoolFee, PoolResponse, QueryMsg, SpotPriceResponse, VirtualPriceResponse,
+};
 
 #[test]
 fn test_simulations_asset_missmatch() {
@@ -179,3 +185,129 @@ fn test_query_pool() {
     );
     assert_eq!(res.total_share, total_share_amount);
 }
+
+fn query_prices(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> (Decimal256, Decimal256, Decimal256) {
+    let uusd = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+    let asset = AssetInfo::Token {
+        contract_addr: "asset0000".to_string(),
+    };
+
+    let virtual_price: VirtualPriceResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::VirtualPrice {}).unwrap()).unwrap();
+    let spot_price = |offer_asset_info: &AssetInfo, ask_asset_info: &AssetInfo| {
+        from_binary::<SpotPriceResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::SpotPrice {
+                    offer_asset_info: offer_asset_info.clone(),
+                    ask_asset_info: ask_asset_info.clone(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+        .spot_price
+    };
+
+    (
+        virtual_price.virtual_price,
+        spot_price(&uusd, &asset),
+        spot_price(&asset, &uusd),
+    )
+}
+
+#[test]
+fn test_query_virtual_and_spot_prices() {
+    // 2 * sqrt(1_000_100 * 4_000_000) / 2_000_000, with 100uusd of protocol fees excluded
+    let mut deps = instantiate_pair(
+        PairType::ConstantProduct,
+        [1_000_100, 4_000_000],
+        2_000_000,
+        no_fees(),
+    );
+    store_fee(
+        deps.as_mut().storage,
+        Uint128::from(100u128),
+        "uusd".to_string(),
+        COLLECTED_PROTOCOL_FEES,
+    )
+    .unwrap();
+    assert_eq!(
+        query_prices(&deps),
+        (
+            Decimal256::from_ratio(2u128, 1u128),
+            Decimal256::from_ratio(4u128, 1u128),
+            Decimal256::from_ratio(1u128, 4u128)
+        )
+    );
+
+    // a balanced stableswap pair is worth the sum of its pools, at a 1:1 price
+    let deps = instantiate_pair(
+        PairType::StableSwap { amp: 100 },
+        [1_000_000, 1_000_000],
+        1_000_000,
+        no_fees(),
+    );
+    let (virtual_price, spot_price, reverse_spot_price) = query_prices(&deps);
+    assert_eq!(virtual_price, Decimal256::from_ratio(2u128, 1u128));
+    assert_eq!(spot_price, Decimal256::one());
+    assert_eq!(reverse_spot_price, Decimal256::one());
+
+    // a weighted pair holding its weights in value is worth the sum of its pools
+    let deps = instantiate_pair(
+        PairType::Weighted { weights: [80, 20] },
+        [800_000, 200_000],
+        1_000_000,
+        no_fees(),
+    );
+    let (virtual_price, spot_price, reverse_spot_price) = query_prices(&deps);
+    assert!(virtual_price > Decimal256::from_ratio(999_999u128, 1_000_000u128));
+    assert!(virtual_price < Decimal256::from_ratio(1_000_001u128, 1_000_000u128));
+    assert_eq!(spot_price, Decimal256::one());
+    assert_eq!(reverse_spot_price, Decimal256::one());
+}
+
+#[test]
+fn test_query_spot_price_asset_mismatch() {
+    let deps = instantiate_pair(
+        PairType::ConstantProduct,
+        [1_000_000, 1_000_000],
+        1_000_000,
+        no_fees(),
+    );
+
+    for (offer_asset_info, ask_asset_info) in [
+        (
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        ),
+        (
+            AssetInfo::NativeToken {
+                denom: "invalid".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ),
+    ] {
+        let err = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SpotPrice {
+                offer_asset_info,
+                ask_asset_info,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AssetMismatch {});
+    }
+}
@@ -0,0 +1,242 @@
+use crate::contract::{execute, query};
+use crate::error::ContractError;
+use crate::tests::helpers::{asset0000, instantiate_pair, pool_fees, uusd};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    coins, from_binary, to_binary, BankMsg, Coin, CosmosMsg, Decimal, OwnedDeps, SubMsg, Uint128,
+    WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use white_whale::pool_network::asset::{Asset, AssetInfo, PairType};
+use white_whale::pool_network::mock_querier::WasmMockQuerier;
+use white_whale::pool_network::pair::{ExecuteMsg, QueryMsg, ReverseSimulationResponse};
+
+/// Instantiates a pair holding 1_000_000_000 of each asset, on top of the uusd sent along with
+/// the swap
+fn instantiate_swap_pair(
+    pair_type: PairType,
+    uusd_sent: Uint128,
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    instantiate_pair(
+        pair_type,
+        [1_000_000_000u128 + uusd_sent.u128(), 1_000_000_000u128],
+        1_000_000_000u128,
+        pool_fees(Decimal::zero(), Decimal::permille(3u64)),
+    )
+}
+
+fn swap_exact_out_msg(
+    ask_asset_info: AssetInfo,
+    amount: u128,
+    max_offer_amount: u128,
+) -> ExecuteMsg {
+    ExecuteMsg::SwapExactOut {
+        ask_asset: Asset {
+            info: ask_asset_info,
+            amount: Uint128::new(amount),
+        },
+        max_offer_amount: Uint128::new(max_offer_amount),
+        to: None,
+        deadline: None,
+    }
+}
+
+#[test]
+fn swap_exact_out_native_offer() {
+    let mut deps = instantiate_swap_pair(PairType::ConstantProduct, Uint128::new(2_000u128));
+
+    // both divisions round up in favour of the pool
+    // ask' = 1_000 / (1 - 0.003) = 1_004
+    // offer = 1_000_000_000 * 1_000_000_000 / (1_000_000_000 - 1_004) - 1_000_000_000 = 1_005
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(2_000u128, "uusd")),
+        swap_exact_out_msg(asset0000(), 1_000, 1_500),
+    )
+    .unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: coins(995u128, "uusd"),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::new(1_000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+
+    // more than the maximum offer amount would be needed
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(2_000u128, "uusd")),
+        swap_exact_out_msg(asset0000(), 1_000, 1_000),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MaxOfferAssertion {
+            max_offer_amount: Uint128::new(1_000u128),
+            offer_amount: Uint128::new(1_005u128),
+        }
+    );
+}
+
+#[test]
+fn swap_exact_out_not_enough_funds() {
+    let mut deps = instantiate_swap_pair(PairType::ConstantProduct, Uint128::new(1_000u128));
+
+    // the funds sent cap the offer amount
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1_000u128, "uusd")),
+        swap_exact_out_msg(asset0000(), 1_000, 1_500),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MaxOfferAssertion {
+            max_offer_amount: Uint128::new(1_000u128),
+            offer_amount: Uint128::new(1_005u128),
+        }
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[Coin::new(1_000u128, "uluna")]),
+        swap_exact_out_msg(asset0000(), 1_000, 1_500),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MaxOfferAssertion {
+            max_offer_amount: Uint128::zero(),
+            offer_amount: Uint128::new(1_005u128),
+        }
+    );
+}
+
+#[test]
+fn swap_exact_out_token_offer() {
+    for pair_type in [PairType::ConstantProduct, PairType::StableSwap { amp: 100 }] {
+        let mut deps = instantiate_swap_pair(pair_type, Uint128::zero());
+
+        let ask_asset = Asset {
+            info: uusd(),
+            amount: Uint128::new(1_000_000u128),
+        };
+        let simulation: ReverseSimulationResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ReverseSimulation {
+                    ask_asset: ask_asset.clone(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        // the offer amount is pulled from the allowance given to the pair
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            ExecuteMsg::SwapExactOut {
+                ask_asset,
+                max_offer_amount: Uint128::new(1_100_000u128),
+                to: Some("addr0001".to_string()),
+                deadline: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "asset0000".to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                        owner: "addr0000".to_string(),
+                        recipient: MOCK_CONTRACT_ADDR.to_string(),
+                        amount: simulation.offer_amount,
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                })),
+                SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "addr0001".to_string(),
+                    amount: coins(1_000_000u128, "uusd"),
+                })),
+            ]
+        );
+    }
+}
+
+#[test]
+fn swap_exact_out_invalid_ask_asset() {
+    let mut deps = instantiate_swap_pair(PairType::ConstantProduct, Uint128::zero());
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        swap_exact_out_msg(
+            AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            1_000,
+            1_500,
+        ),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AssetMismatch {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        swap_exact_out_msg(uusd(), 0, 1_500),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidZeroAmount {});
+}
+
+#[test]
+fn swap_exact_out_beyond_pool_depth() {
+    let mut deps = instantiate_swap_pair(PairType::ConstantProduct, Uint128::new(2_000u128));
+
+    // the ask amount plus the fees can't be taken out of the pool
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(2_000u128, "uusd")),
+        swap_exact_out_msg(asset0000(), 999_000_000, 1_500),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::OverflowError(_)));
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::ReverseSimulation {
+            ask_asset: Asset {
+                info: asset0000(),
+                amount: Uint128::new(1_000_000_000u128),
+            },
+        },
+    );
+    assert!(res.is_err());
+}
@@ -1,8 +1,9 @@
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, CosmosMsg, Decimal, Reply, ReplyOn, StdError, SubMsg,
-    SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
+    from_binary, to_binary, Addr, CosmosMsg, Decimal, Order, Reply, ReplyOn, StdError, StdResult,
+    SubMsg, SubMsgResponse, SubMsgResult, Uint128, Uint256, WasmMsg,
 };
+use cw2::set_contract_version;
 use cw20::MinterResponse;
 
 use white_whale::fee::Fee;
@@ -17,7 +18,7 @@ use crate::contract::{execute, instantiate, migrate, query, reply};
 use crate::error::ContractError;
 use crate::helpers::{assert_max_spread, assert_slippage_tolerance};
 use crate::queries::query_pair_info;
-use crate::state::LP_SYMBOL;
+use crate::state::{AmpRamp, Observation, AMP_RAMP, LP_SYMBOL, OBSERVATIONS};
 
 #[test]
 fn proper_initialization_cw20_lp() {
@@ -103,7 +104,7 @@ fn proper_initialization_cw20_lp() {
     let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
 
     // it worked, let's query the state
-    let pair_info: PairInfo = query_pair_info(deps.as_ref()).unwrap();
+    let pair_info: PairInfo = query_pair_info(deps.as_ref(), mock_env()).unwrap();
     assert_eq!(
         "liquidity0000".to_string(),
         pair_info.liquidity_token.to_string()
@@ -170,7 +171,7 @@ fn proper_initialization_tokenfactory_lp() {
     assert_eq!(res.messages[0].msg, expected);
 
     // let's query the state
-    let pair_info: PairInfo = query_pair_info(deps.as_ref()).unwrap();
+    let pair_info: PairInfo = query_pair_info(deps.as_ref(), mock_env()).unwrap();
     assert_eq!(
         pair_info.liquidity_token,
         AssetInfo::NativeToken {
@@ -243,6 +244,7 @@ fn intialize_with_burnable_token_factory_asset() {
             },
         }),
         feature_toggle: None,
+        amp_factor: None,
     };
 
     let res = execute(
@@ -388,6 +390,72 @@ fn can_migrate_contract() {
     }
 }
 
+#[test]
+fn migrate_to_v140() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uusdc".to_string(),
+            },
+        ],
+        token_code_id: 10u64,
+        asset_decimals: [6u8, 6u8],
+        pool_fees: PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::zero(),
+            },
+            swap_fee: Fee {
+                share: Decimal::zero(),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+        },
+        fee_collector_addr: "collector".to_string(),
+        pair_type: PairType::StableSwap { amp: 100 },
+        token_factory_lp: false,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    // pairs deployed before 1.4.0 neither track their prices nor store an amp ramp
+    OBSERVATIONS.clear(&mut deps.storage);
+    set_contract_version(&mut deps.storage, "white_whale-pool", "1.3.0").unwrap();
+
+    let mut env = mock_env();
+    env.block.height += 100;
+    env.block.time = env.block.time.plus_seconds(600);
+    migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+
+    assert_eq!(
+        OBSERVATIONS
+            .range(&deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap(),
+        vec![(
+            env.block.time.seconds(),
+            Observation {
+                timestamp: env.block.time.seconds(),
+                price0_cumulative: Uint256::zero(),
+                price1_cumulative: Uint256::zero(),
+            }
+        )]
+    );
+    assert_eq!(
+        AMP_RAMP.load(&deps.storage).unwrap(),
+        AmpRamp {
+            initial_amp: 100,
+            future_amp: 100,
+            initial_amp_block: env.block.height,
+            future_amp_block: env.block.height,
+        }
+    );
+}
+
 #[test]
 fn test_max_spread() {
     let offer_asset_info = AssetInfo::NativeToken {
@@ -621,6 +689,7 @@ fn test_update_config_unsuccessful() {
             },
         }),
         feature_toggle: None,
+        amp_factor: None,
     };
 
     let res = execute(deps.as_mut(), env.clone(), info, update_config_message);
@@ -637,6 +706,7 @@ fn test_update_config_unsuccessful() {
         fee_collector_addr: None,
         pool_fees: None,
         feature_toggle: None,
+        amp_factor: None,
     };
 
     let res = execute(deps.as_mut(), env, info, update_config_message);
@@ -714,6 +784,7 @@ fn test_update_config_successful() {
             },
         }),
         feature_toggle: None,
+        amp_factor: None,
     };
 
     execute(deps.as_mut(), env, info, update_config_message).unwrap();
@@ -0,0 +1,266 @@
+use crate::contract::{execute, query};
+use crate::error::ContractError;
+use crate::helpers::{compute_spot_prices, update_price_accumulators};
+use crate::state::{MAX_TWAP_AGE, OBSERVATIONS, PAIR_INFO};
+use crate::tests::helpers::{instantiate_pair, no_fees};
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{coins, from_binary, Decimal256, Env, Order, StdResult, Uint128, Uint256};
+use white_whale::pool_network::asset::{Asset, AssetInfo, PairType};
+use white_whale::pool_network::pair::{ExecuteMsg, QueryMsg, TwapResponse};
+
+fn mock_env_at(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(seconds);
+    env
+}
+
+#[test]
+fn twap_accumulates_prices_on_swaps() {
+    // the pool holds 1_000_000 uusd and 2_000_000 asset0000 before the swap, plus the offer amount
+    let mut deps = instantiate_pair(
+        PairType::ConstantProduct,
+        [1_000_100, 2_000_000],
+        0,
+        no_fees(),
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env_at(100),
+        mock_info("addr0000", &coins(100u128, "uusd")),
+        ExecuteMsg::Swap {
+            offer_asset: Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::new(100u128),
+            },
+            belief_price: None,
+            max_spread: None,
+            to: None,
+            deadline: None,
+        },
+    )
+    .unwrap();
+
+    // the price before the swap was in effect during the first 100 seconds
+    let twap: TwapResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env_at(200),
+            QueryMsg::Twap {
+                start_age: 150,
+                end_age: Some(100),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    let start_time = mock_env_at(50).block.time.seconds();
+    assert_eq!(
+        twap,
+        TwapResponse {
+            price0_average: Decimal256::from_ratio(2u128, 1u128),
+            price1_average: Decimal256::from_ratio(1u128, 2u128),
+            start_time,
+            end_time: start_time + 50,
+        }
+    );
+
+    // the current pools are used after the last observation
+    let twap: TwapResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env_at(200),
+            QueryMsg::Twap {
+                start_age: 200,
+                end_age: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    let current_price0 = Decimal256::from_ratio(2_000_000u128, 1_000_100u128);
+    let current_price1 = Decimal256::from_ratio(1_000_100u128, 2_000_000u128);
+    assert_eq!(
+        twap.price0_average,
+        Decimal256::new(
+            (Decimal256::from_ratio(2u128, 1u128).atomics() + current_price0.atomics())
+                / Uint256::from(2u8)
+        )
+    );
+    assert_eq!(
+        twap.price1_average,
+        Decimal256::new(
+            (Decimal256::from_ratio(1u128, 2u128).atomics() + current_price1.atomics())
+                / Uint256::from(2u8)
+        )
+    );
+}
+
+#[test]
+fn twap_rejects_invalid_windows() {
+    let deps = instantiate_pair(
+        PairType::ConstantProduct,
+        [1_000_000, 1_000_000],
+        0,
+        no_fees(),
+    );
+
+    let err = query(
+        deps.as_ref(),
+        mock_env_at(100),
+        QueryMsg::Twap {
+            start_age: 50,
+            end_age: Some(50),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidTwapWindow(MAX_TWAP_AGE));
+
+    let err = query(
+        deps.as_ref(),
+        mock_env_at(100),
+        QueryMsg::Twap {
+            start_age: MAX_TWAP_AGE + 1,
+            end_age: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidTwapWindow(MAX_TWAP_AGE));
+
+    // the pair didn't exist 200 seconds ago
+    let err = query(
+        deps.as_ref(),
+        mock_env_at(100),
+        QueryMsg::Twap {
+            start_age: 200,
+            end_age: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::TwapHistoryUnavailable {});
+}
+
+#[test]
+fn stableswap_spot_prices() {
+    let pools = |amount_0: u128, amount_1: u128| {
+        vec![
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::new(amount_0),
+            },
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                amount: Uint128::new(amount_1),
+            },
+        ]
+    };
+
+    // balanced pools trade at par
+    let prices = compute_spot_prices(
+        &pools(1_000_000_000, 1_000_000_000),
+        &PairType::StableSwap { amp: 100 },
+        [6, 6],
+    )
+    .unwrap();
+    assert_eq!(prices, [Decimal256::one(), Decimal256::one()]);
+
+    // the scarce asset is more expensive, but less so than with a constant product curve
+    let prices = compute_spot_prices(
+        &pools(1_000_000_000, 3_000_000_000),
+        &PairType::StableSwap { amp: 100 },
+        [6, 6],
+    )
+    .unwrap();
+    assert!(prices[0] > Decimal256::one());
+    assert!(prices[0] < Decimal256::from_ratio(3u128, 1u128));
+    assert!(prices[1] < Decimal256::one());
+
+    // prices are expressed in the smallest units of the assets
+    let prices = compute_spot_prices(
+        &pools(1_000_000_000, 1_000_000_000_000_000_000_000),
+        &PairType::StableSwap { amp: 100 },
+        [6, 18],
+    )
+    .unwrap();
+    assert_eq!(
+        prices,
+        [
+            Decimal256::from_ratio(1_000_000_000_000u128, 1u128),
+            Decimal256::from_ratio(1u128, 1_000_000_000_000u128)
+        ]
+    );
+}
+
+#[test]
+fn stale_observations_are_pruned() {
+    let mut deps = instantiate_pair(
+        PairType::ConstantProduct,
+        [1_000_000, 1_000_000],
+        0,
+        no_fees(),
+    );
+    let pair_info = PAIR_INFO.load(deps.as_ref().storage).unwrap();
+    let pools = vec![
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::new(1_000_000u128),
+        },
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            amount: Uint128::new(1_000_000u128),
+        },
+    ];
+
+    for seconds in [10, 20, MAX_TWAP_AGE + 15, MAX_TWAP_AGE + 30] {
+        update_price_accumulators(
+            deps.as_mut().storage,
+            mock_env_at(seconds).block.time.seconds(),
+            &pools,
+            &pair_info,
+        )
+        .unwrap();
+    }
+
+    let timestamps = OBSERVATIONS
+        .keys(deps.as_ref().storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<u64>>>()
+        .unwrap();
+
+    // the observation at 20 seconds is the only one older than the window that is still needed
+    let start = mock_env().block.time.seconds();
+    assert_eq!(
+        timestamps,
+        vec![
+            start + 20,
+            start + MAX_TWAP_AGE + 15,
+            start + MAX_TWAP_AGE + 30
+        ]
+    );
+
+    // a whole window can be computed
+    let twap: TwapResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env_at(MAX_TWAP_AGE + 30),
+            QueryMsg::Twap {
+                start_age: MAX_TWAP_AGE,
+                end_age: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(twap.price0_average, Decimal256::one());
+}
@@ -0,0 +1,263 @@
+use crate::contract::{instantiate, query};
+use crate::error::ContractError;
+use crate::helpers::{compute_offer_amount, compute_spot_prices, compute_swap};
+use crate::math::Decimal256Helper;
+use crate::tests::helpers::no_fees;
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{from_binary, Coin, Decimal, Decimal256, StdError, Uint128};
+use white_whale::fee::Fee;
+use white_whale::pool_network::asset::{Asset, AssetInfo, PairType};
+use white_whale::pool_network::mock_querier::mock_dependencies;
+use white_whale::pool_network::pair::{
+    InstantiateMsg, PoolFee, QueryMsg, ReverseSimulationResponse, SimulationResponse,
+};
+
+#[test]
+fn decimal_pow() {
+    let decimal = |value: &str| value.parse::<Decimal256>().unwrap();
+    let pow = |base: &str, exponent: &str| {
+        decimal(base)
+            .checked_pow_decimal(decimal(exponent))
+            .unwrap()
+    };
+
+    assert_eq!(pow("0.25", "0.5"), decimal("0.5"));
+    assert_eq!(pow("0.64", "1.5"), decimal("0.512"));
+    assert_eq!(pow("2", "0"), Decimal256::one());
+    assert_eq!(pow("3", "2"), decimal("9"));
+
+    // 0.9 ^ 0.3 = 0.96888...
+    let result = pow("0.9", "0.3");
+    assert!(result > decimal("0.968886") && result < decimal("0.968887"));
+}
+
+#[test]
+fn weighted_swap_with_even_weights_matches_constant_product() {
+    let pool_fees = PoolFee {
+        protocol_fee: Fee {
+            share: Decimal::percent(1u64),
+        },
+        swap_fee: Fee {
+            share: Decimal::percent(1u64),
+        },
+        burn_fee: Fee {
+            share: Decimal::zero(),
+        },
+    };
+
+    let constant_product = compute_swap(
+        Uint128::new(1_000_000u128),
+        Uint128::new(3_000_000u128),
+        Uint128::new(10_000u128),
+        pool_fees.clone(),
+        &PairType::ConstantProduct,
+        6,
+        6,
+    )
+    .unwrap();
+    let weighted = compute_swap(
+        Uint128::new(1_000_000u128),
+        Uint128::new(3_000_000u128),
+        Uint128::new(10_000u128),
+        pool_fees,
+        &PairType::Weighted { weights: [50, 50] },
+        6,
+        6,
+    )
+    .unwrap();
+
+    assert_eq!(constant_product, weighted);
+}
+
+#[test]
+fn weighted_swap() {
+    // 800_000 of the 80% asset and 200_000 of the 20% asset, i.e. both assets are worth the same
+    let pools = [Uint128::new(800_000u128), Uint128::new(200_000u128)];
+    let pair_type = PairType::Weighted { weights: [80, 20] };
+
+    // 200_000 * (1 - (800_000 / 810_000) ^ 4) = 9_695.14
+    let computation = compute_swap(
+        pools[0],
+        pools[1],
+        Uint128::new(10_000u128),
+        no_fees(),
+        &pair_type,
+        6,
+        6,
+    )
+    .unwrap();
+    assert_eq!(computation.return_amount, Uint128::new(9_695u128));
+    assert_eq!(computation.spread_amount, Uint128::new(305u128));
+
+    // 800_000 * (1 - (200_000 / 210_000) ^ 0.25) = 9_698.76
+    let computation = compute_swap(
+        pools[1],
+        pools[0],
+        Uint128::new(10_000u128),
+        no_fees(),
+        &pair_type.reversed(),
+        6,
+        6,
+    )
+    .unwrap();
+    assert_eq!(computation.return_amount, Uint128::new(9_698u128));
+
+    // 800_000 * ((200_000 / 190_305) ^ 0.25 - 1) = 9_999.85, rounded up in favour of the pool
+    let computation = compute_offer_amount(
+        pools[0],
+        pools[1],
+        Uint128::new(9_695u128),
+        no_fees(),
+        &pair_type,
+        6,
+        6,
+    )
+    .unwrap();
+    assert_eq!(computation.offer_amount, Uint128::new(10_000u128));
+}
+
+#[test]
+fn weighted_spot_prices() {
+    let pools = vec![
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::new(600_000u128),
+        },
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            amount: Uint128::new(800_000u128),
+        },
+    ];
+
+    // each unit of weight of the first asset is worth 10_000, and 20_000 of the second one
+    let prices =
+        compute_spot_prices(&pools, &PairType::Weighted { weights: [60, 40] }, [6, 6]).unwrap();
+    assert_eq!(
+        prices,
+        [
+            Decimal256::from_ratio(2u128, 1u128),
+            Decimal256::from_ratio(1u128, 2u128)
+        ]
+    );
+}
+
+#[test]
+fn weighted_pair_simulations() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(800_000u128),
+    }]);
+
+    deps.querier.with_token_balances(&[(
+        &"asset0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::new(200_000u128))],
+    )]);
+
+    let msg = InstantiateMsg {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ],
+        token_code_id: 10u64,
+        asset_decimals: [6u8, 6u8],
+        pool_fees: no_fees(),
+        fee_collector_addr: "collector".to_string(),
+        pair_type: PairType::Weighted { weights: [80, 20] },
+        token_factory_lp: false,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let simulation: SimulationResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Simulation {
+                offer_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    amount: Uint128::new(10_000u128),
+                },
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(simulation.return_amount, Uint128::new(9_695u128));
+
+    // the weights are applied in the right order when offering the second asset
+    let simulation: SimulationResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Simulation {
+                offer_asset: Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: "asset0000".to_string(),
+                    },
+                    amount: Uint128::new(10_000u128),
+                },
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(simulation.return_amount, Uint128::new(9_698u128));
+
+    let reverse_simulation: ReverseSimulationResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ReverseSimulation {
+                ask_asset: Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: "asset0000".to_string(),
+                    },
+                    amount: Uint128::new(9_695u128),
+                },
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(reverse_simulation.offer_amount, Uint128::new(10_000u128));
+}
+
+#[test]
+fn weighted_pair_invalid_weights() {
+    let mut deps = mock_dependencies(&[]);
+
+    for weights in [[0, 100], [1, 100]] {
+        let msg = InstantiateMsg {
+            asset_infos: [
+                AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+            ],
+            token_code_id: 10u64,
+            asset_decimals: [6u8, 6u8],
+            pool_fees: no_fees(),
+            fee_collector_addr: "collector".to_string(),
+            pair_type: PairType::Weighted { weights },
+            token_factory_lp: false,
+        };
+
+        let err =
+            instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Std(StdError::generic_err("Invalid pair weights"))
+        );
+    }
+}
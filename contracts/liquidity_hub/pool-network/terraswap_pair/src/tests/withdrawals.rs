@@ -1,6 +1,6 @@
-use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    attr, coin, to_binary, BankMsg, Coin, CosmosMsg, Decimal, Reply, Response, SubMsg,
+    attr, coin, to_binary, BankMsg, Coin, CosmosMsg, Decimal, OwnedDeps, Reply, Response, SubMsg,
     SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
@@ -9,13 +9,14 @@ use white_whale::fee::Fee;
 use white_whale::pool_network;
 use white_whale::pool_network::asset::{AssetInfo, PairType};
 use white_whale::pool_network::denom::MsgBurn;
-use white_whale::pool_network::mock_querier::mock_dependencies;
+use white_whale::pool_network::mock_querier::{mock_dependencies, WasmMockQuerier};
 use white_whale::pool_network::pair::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolFee};
 
 use crate::contract::{execute, instantiate, reply};
 use crate::error::ContractError;
 use crate::state::LP_SYMBOL;
 use crate::state::{get_fees_for_asset, store_fee, COLLECTED_PROTOCOL_FEES};
+use crate::tests::helpers::{instantiate_pair, pool_fees};
 
 #[test]
 fn withdraw_xyk_liquidity_cw20_lp() {
@@ -102,7 +103,12 @@ fn withdraw_xyk_liquidity_cw20_lp() {
     // withdraw liquidity
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
-        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+            target_asset: None,
+            min_receive: None,
+            deadline: None,
+        })
+        .unwrap(),
         amount: Uint128::from(100u128),
     });
 
@@ -262,7 +268,12 @@ fn withdraw_stableswap_liquidity() {
     // withdraw liquidity
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
-        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+            target_asset: None,
+            min_receive: None,
+            deadline: None,
+        })
+        .unwrap(),
         amount: Uint128::from(100u128),
     });
 
@@ -380,7 +391,12 @@ fn test_withdrawal_unauthorized() {
     // withdraw liquidity should fail
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
-        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+            target_asset: None,
+            min_receive: None,
+            deadline: None,
+        })
+        .unwrap(),
         amount: Uint128::from(100u128),
     });
 
@@ -520,7 +536,11 @@ fn withdraw_xyk_liquidity_token_factory_lp() {
     let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
 
     // withdraw liquidity
-    let msg = ExecuteMsg::WithdrawLiquidity {};
+    let msg = ExecuteMsg::WithdrawLiquidity {
+        target_asset: None,
+        min_receive: None,
+        deadline: None,
+    };
 
     let env = mock_env();
     let info = mock_info(
@@ -630,7 +650,11 @@ fn withdraw_xyk_liquidity_token_factory_lp_wrong_asset() {
     let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
 
     // withdraw liquidity
-    let msg = ExecuteMsg::WithdrawLiquidity {};
+    let msg = ExecuteMsg::WithdrawLiquidity {
+        target_asset: None,
+        min_receive: None,
+        deadline: None,
+    };
 
     let env = mock_env();
     let info = mock_info(
@@ -644,3 +668,130 @@ fn withdraw_xyk_liquidity_token_factory_lp_wrong_asset() {
 
     assert_eq!(err, ContractError::AssetMismatch {});
 }
+
+fn instantiate_withdrawal_pair() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    instantiate_pair(
+        PairType::ConstantProduct,
+        [1_000_000, 1_000_000],
+        1_000_000,
+        pool_fees(Decimal::percent(1u64), Decimal::percent(1u64)),
+    )
+}
+
+#[test]
+fn withdraw_xyk_liquidity_to_single_asset() {
+    let mut deps = instantiate_withdrawal_pair();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+            target_asset: Some(AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            }),
+            min_receive: Some(Uint128::from(188_000u128)),
+            deadline: None,
+        })
+        .unwrap(),
+        amount: Uint128::from(100_000u128),
+    });
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("liquidity0000", &[]),
+        msg,
+    )
+    .unwrap();
+
+    // 100_000 uusd are refunded directly, and 100_000 asset0000 are swapped against the
+    // remaining 900_000 / 900_000 pools, returning 90_000 uusd minus the 1% swap and protocol fees
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: vec![coin(188_200u128, "uusd")],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "liquidity0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Burn {
+                    amount: Uint128::from(100_000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+    assert_eq!(
+        res.attributes.get(3).expect("no log"),
+        &attr("refund_assets", "188200uusd")
+    );
+
+    let protocol_fee = get_fees_for_asset(
+        deps.as_mut().storage,
+        "uusd".to_string(),
+        COLLECTED_PROTOCOL_FEES,
+    )
+    .unwrap();
+    assert_eq!(protocol_fee.amount, Uint128::from(900u128));
+}
+
+#[test]
+fn withdraw_liquidity_to_single_asset_minimum_receive() {
+    let mut deps = instantiate_withdrawal_pair();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+            target_asset: Some(AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            }),
+            min_receive: Some(Uint128::from(200_000u128)),
+            deadline: None,
+        })
+        .unwrap(),
+        amount: Uint128::from(100_000u128),
+    });
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("liquidity0000", &[]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MinimumReceiveAssertion {
+            minimum_receive: Uint128::from(200_000u128),
+            amount: Uint128::from(188_200u128),
+        }
+    );
+}
+
+#[test]
+fn withdraw_liquidity_to_unknown_asset() {
+    let mut deps = instantiate_withdrawal_pair();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+            target_asset: Some(AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            }),
+            min_receive: None,
+            deadline: None,
+        })
+        .unwrap(),
+        amount: Uint128::from(100_000u128),
+    });
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("liquidity0000", &[]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AssetMismatch {});
+}
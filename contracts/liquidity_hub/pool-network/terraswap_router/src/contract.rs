@@ -3,35 +3,46 @@ use std::collections::HashMap;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, from_binary, to_binary, Addr, Api, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
-    Response, StdError, StdResult, Uint128, WasmMsg,
+    attr, from_binary, to_binary, Addr, Api, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Response, StdError, StdResult, Timestamp, Uint128, WasmMsg,
 };
 use cw2::{get_contract_version, set_contract_version};
 use cw20::Cw20ReceiveMsg;
 use semver::Version;
 
-use white_whale::pool_network::asset::{Asset, AssetInfo, PairInfo};
-use white_whale::pool_network::pair::SimulationResponse;
-use white_whale::pool_network::querier::{query_pair_info, reverse_simulate, simulate};
+use white_whale::pool_network::asset::{Asset, AssetInfo};
+use white_whale::pool_network::pair::{ReverseSimulationResponse, SimulationResponse};
+use white_whale::pool_network::querier::{
+    query_pair_info, query_trio_info, reverse_simulate, reverse_simulate_trio, simulate,
+    simulate_trio,
+};
 use white_whale::pool_network::router::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
-    SimulateSwapOperationsResponse, SwapOperation, SwapRoute,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, Referral,
+    SimulateSplitSwapResponse, SimulateSwapOperationsDetailedResponse,
+    SimulateSwapOperationsResponse, SwapOperation, SwapOperationSimulation, SwapRoute,
+    SwapRouteValidity,
 };
 
 use crate::error::ContractError;
 use crate::error::ContractError::MigrateInvalidVersion;
+use crate::migrations;
 use crate::operations::execute_swap_operation;
-use crate::state::{Config, CONFIG, SWAP_ROUTES};
+use crate::route_finder::find_best_route;
+use crate::state::{read_swap_routes, Config, CONFIG, SWAP_ROUTES};
+use crate::zap::{execute_zap_into_pool, provide_zap_liquidity};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "white_whale-pool_router";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// The times the offer amount of an exact out swap can be topped up to meet the ask amount
+const MAX_EXACT_OUT_TOP_UPS: u8 = 3;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
@@ -39,7 +50,11 @@ pub fn instantiate(
     CONFIG.save(
         deps.storage,
         &Config {
+            owner: info.sender,
+            pending_owner: None,
             terraswap_factory: deps.api.addr_canonicalize(&msg.terraswap_factory)?,
+            default_max_spread: None,
+            max_referral_commission: Decimal::zero(),
         },
     )?;
 
@@ -57,26 +72,118 @@ pub fn execute(
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::ExecuteSwapOperations {
             operations,
+            ask_asset_info,
             minimum_receive,
             to,
+            deadline,
+            referral,
         } => {
             let api = deps.api;
             execute_swap_operations(
                 deps,
                 env,
                 info.sender,
+                native_offer_asset(&info.funds),
                 operations,
+                ask_asset_info,
                 minimum_receive,
                 optional_addr_validate(api, to)?,
+                deadline,
+                referral,
             )
         }
-        ExecuteMsg::ExecuteSwapOperation { operation, to } => {
+        ExecuteMsg::ExecuteSplitSwap {
+            routes,
+            minimum_receive,
+            to,
+            deadline,
+        } => {
+            let api = deps.api;
+            execute_split_swap(
+                deps,
+                env,
+                info.sender,
+                native_offer_asset(&info.funds),
+                routes,
+                minimum_receive,
+                optional_addr_validate(api, to)?,
+                deadline,
+            )
+        }
+        ExecuteMsg::ExecuteSwapOperationsExactOut {
+            operations,
+            ask_amount,
+            max_offer_amount,
+            to,
+            deadline,
+        } => {
+            let api = deps.api;
+            execute_swap_operations_exact_out(
+                deps,
+                env,
+                info.sender,
+                native_offer_asset(&info.funds),
+                operations,
+                ask_amount,
+                max_offer_amount,
+                optional_addr_validate(api, to)?,
+                deadline,
+            )
+        }
+        ExecuteMsg::ZapIntoPool {
+            offer_asset,
+            operations_per_side,
+            pair_address,
+            min_lp_out,
+            slippage_tolerance,
+            receiver,
+            deadline,
+        } => {
+            if native_offer_asset(&info.funds).as_ref() != Some(&offer_asset) {
+                return Err(ContractError::InvalidZapFunds {});
+            }
+
+            execute_zap_into_pool(
+                deps.as_ref(),
+                env,
+                info.sender,
+                offer_asset,
+                operations_per_side,
+                deps.api.addr_validate(&pair_address)?,
+                min_lp_out,
+                slippage_tolerance,
+                optional_addr_validate(deps.api, receiver)?,
+                deadline,
+            )
+        }
+        ExecuteMsg::ProvideZapLiquidity {
+            pair_address,
+            prev_balances,
+            slippage_tolerance,
+            receiver,
+            refund_to,
+        } => provide_zap_liquidity(
+            deps.as_ref(),
+            env,
+            info,
+            deps.api.addr_validate(&pair_address)?,
+            prev_balances,
+            slippage_tolerance,
+            deps.api.addr_validate(&receiver)?,
+            deps.api.addr_validate(&refund_to)?,
+        ),
+        ExecuteMsg::ExecuteSwapOperation {
+            operation,
+            offer_amount,
+            to,
+        } => {
             let api = deps.api;
             execute_swap_operation(
                 deps,
                 env,
                 info,
                 operation,
+                offer_amount,
                 optional_addr_validate(api, to)?.map(|v| v.to_string()),
             )
         }
@@ -92,9 +199,43 @@ pub fn execute(
             minimum_receive,
             deps.api.addr_validate(&receiver)?,
         ),
+        ExecuteMsg::TakeReferralCommission {
+            asset_info,
+            prev_balance,
+            referral,
+            receiver,
+        } => {
+            let receiver = deps.api.addr_validate(&receiver)?;
+            take_referral_commission(
+                deps.as_ref(),
+                env,
+                info,
+                asset_info,
+                prev_balance,
+                referral,
+                receiver,
+            )
+        }
         ExecuteMsg::AddSwapRoutes { swap_routes } => {
-            add_swap_routes(deps, env, info.sender, swap_routes)
+            add_swap_routes(deps, info.sender, swap_routes)
+        }
+        ExecuteMsg::RemoveSwapRoutes { swap_routes } => {
+            remove_swap_routes(deps, info.sender, swap_routes)
         }
+        ExecuteMsg::UpdateConfig {
+            default_max_spread,
+            remove_default_max_spread,
+            max_referral_commission,
+        } => update_config(
+            deps,
+            info.sender,
+            default_max_spread,
+            remove_default_max_spread.unwrap_or(false),
+            max_referral_commission,
+        ),
+        ExecuteMsg::ProposeNewOwner { owner } => propose_new_owner(deps, info.sender, owner),
+        ExecuteMsg::DropOwnershipProposal {} => drop_ownership_proposal(deps, info.sender),
+        ExecuteMsg::ClaimOwnership {} => claim_ownership(deps, info.sender),
     }
 }
 
@@ -111,40 +252,173 @@ fn optional_addr_validate(
     Ok(addr)
 }
 
+/// Gets the offer asset from the funds sent, which is only known if a single native coin is sent
+fn native_offer_asset(funds: &[Coin]) -> Option<Asset> {
+    match funds {
+        [coin] => Some(Asset {
+            info: AssetInfo::NativeToken {
+                denom: coin.denom.clone(),
+            },
+            amount: coin.amount,
+        }),
+        _ => None,
+    }
+}
+
+pub(crate) fn assert_deadline(env: &Env, deadline: Option<Timestamp>) -> Result<(), ContractError> {
+    if let Some(deadline) = deadline {
+        if env.block.time > deadline {
+            return Err(ContractError::DeadlineExceeded {
+                deadline,
+                current_time: env.block.time,
+            });
+        }
+    }
+
+    Ok(())
+}
+
 pub fn receive_cw20(
     deps: DepsMut,
     env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
     let sender = deps.api.addr_validate(&cw20_msg.sender)?;
     match from_binary(&cw20_msg.msg)? {
         Cw20HookMsg::ExecuteSwapOperations {
             operations,
+            ask_asset_info,
             minimum_receive,
             to,
+            deadline,
+            referral,
         } => {
             let api = deps.api;
+            let offer_asset = Asset {
+                info: AssetInfo::Token {
+                    contract_addr: info.sender.to_string(),
+                },
+                amount: cw20_msg.amount,
+            };
             execute_swap_operations(
                 deps,
                 env,
                 sender,
+                Some(offer_asset),
                 operations,
+                ask_asset_info,
                 minimum_receive,
                 optional_addr_validate(api, to)?,
+                deadline,
+                referral,
+            )
+        }
+        Cw20HookMsg::ExecuteSplitSwap {
+            routes,
+            minimum_receive,
+            to,
+            deadline,
+        } => {
+            let api = deps.api;
+            let offer_asset = Asset {
+                info: AssetInfo::Token {
+                    contract_addr: info.sender.to_string(),
+                },
+                amount: cw20_msg.amount,
+            };
+            execute_split_swap(
+                deps,
+                env,
+                sender,
+                Some(offer_asset),
+                routes,
+                minimum_receive,
+                optional_addr_validate(api, to)?,
+                deadline,
+            )
+        }
+        Cw20HookMsg::ExecuteSwapOperationsExactOut {
+            operations,
+            ask_amount,
+            max_offer_amount,
+            to,
+            deadline,
+        } => {
+            let api = deps.api;
+            let offer_asset = Asset {
+                info: AssetInfo::Token {
+                    contract_addr: info.sender.to_string(),
+                },
+                amount: cw20_msg.amount,
+            };
+            execute_swap_operations_exact_out(
+                deps,
+                env,
+                sender,
+                Some(offer_asset),
+                operations,
+                ask_amount,
+                max_offer_amount,
+                optional_addr_validate(api, to)?,
+                deadline,
+            )
+        }
+        Cw20HookMsg::ZapIntoPool {
+            operations_per_side,
+            pair_address,
+            min_lp_out,
+            slippage_tolerance,
+            receiver,
+            deadline,
+        } => {
+            let offer_asset = Asset {
+                info: AssetInfo::Token {
+                    contract_addr: info.sender.to_string(),
+                },
+                amount: cw20_msg.amount,
+            };
+            execute_zap_into_pool(
+                deps.as_ref(),
+                env,
+                sender,
+                offer_asset,
+                operations_per_side,
+                deps.api.addr_validate(&pair_address)?,
+                min_lp_out,
+                slippage_tolerance,
+                optional_addr_validate(deps.api, receiver)?,
+                deadline,
             )
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn execute_swap_operations(
     deps: DepsMut,
     env: Env,
     sender: Addr,
-    operations: Vec<SwapOperation>,
+    offer_asset: Option<Asset>,
+    operations: Option<Vec<SwapOperation>>,
+    ask_asset_info: Option<AssetInfo>,
     minimum_receive: Option<Uint128>,
     to: Option<Addr>,
+    deadline: Option<Timestamp>,
+    referral: Option<Referral>,
 ) -> Result<Response, ContractError> {
+    assert_deadline(&env, deadline)?;
+
+    // fall back to the best route found on-chain if no operations are given
+    let operations = match (operations, ask_asset_info) {
+        (Some(operations), _) => operations,
+        (None, Some(ask_asset_info)) => {
+            let offer_asset = offer_asset.ok_or(ContractError::InvalidRouteSearchFunds {})?;
+            find_best_route(deps.as_ref(), offer_asset, ask_asset_info, None)?.operations
+        }
+        (None, None) => return Err(ContractError::NoSwapOperationsProvided {}),
+    };
+
     let operations_len = operations.len();
     if operations_len == 0 {
         return Err(StdError::generic_err("Must provide swap operations to execute").into());
@@ -159,17 +433,235 @@ pub fn execute_swap_operations(
         .ok_or_else(|| ContractError::Std(StdError::generic_err("Couldn't get swap operation")))?
         .get_target_asset_info();
 
-    let mut operation_index = 0;
-    let mut messages: Vec<CosmosMsg> = operations
+    let mut messages = match referral {
+        // swap into the router first, so the commission can be taken from what it receives
+        Some(referral) => {
+            let referral = assert_referral(deps.as_ref(), referral)?;
+            let prev_balance = target_asset_info.query_pool(
+                &deps.querier,
+                deps.api,
+                env.contract.address.clone(),
+            )?;
+
+            let mut messages = swap_operations_msgs(&env, operations, None, &env.contract.address)?;
+            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::TakeReferralCommission {
+                    asset_info: target_asset_info.clone(),
+                    prev_balance,
+                    referral,
+                    receiver: to.to_string(),
+                })?,
+            }));
+            messages
+        }
+        None => swap_operations_msgs(&env, operations, None, &to)?,
+    };
+
+    // Execute minimum amount assertion
+    if let Some(minimum_receive) = minimum_receive {
+        messages.push(assert_minimum_receive_msg(
+            deps.as_ref(),
+            &env,
+            target_asset_info,
+            minimum_receive,
+            &to,
+        )?);
+    }
+
+    Ok(Response::new().add_messages(messages))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_split_swap(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    offer_asset: Option<Asset>,
+    routes: Vec<(Decimal, Vec<SwapOperation>)>,
+    minimum_receive: Option<Uint128>,
+    to: Option<Addr>,
+    deadline: Option<Timestamp>,
+) -> Result<Response, ContractError> {
+    assert_deadline(&env, deadline)?;
+
+    let (offer_asset_info, target_asset_info) = assert_split_routes(&routes)?;
+    let offer_asset = match offer_asset {
+        Some(offer_asset) if offer_asset.info == offer_asset_info => offer_asset,
+        _ => return Err(ContractError::InvalidSplitSwapFunds {}),
+    };
+
+    let to = if let Some(to) = to { to } else { sender };
+
+    let route_amounts = split_offer_amount(offer_asset.amount, &routes)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for ((_, operations), route_amount) in routes.into_iter().zip(route_amounts) {
+        if route_amount.is_zero() {
+            continue;
+        }
+
+        messages.extend(swap_operations_msgs(
+            &env,
+            operations,
+            Some(route_amount),
+            &to,
+        )?);
+    }
+
+    // Execute minimum amount assertion on what all routes return combined
+    if let Some(minimum_receive) = minimum_receive {
+        messages.push(assert_minimum_receive_msg(
+            deps.as_ref(),
+            &env,
+            target_asset_info,
+            minimum_receive,
+            &to,
+        )?);
+    }
+
+    Ok(Response::new().add_messages(messages))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_swap_operations_exact_out(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    offer_asset: Option<Asset>,
+    operations: Vec<SwapOperation>,
+    ask_amount: Uint128,
+    max_offer_amount: Option<Uint128>,
+    to: Option<Addr>,
+    deadline: Option<Timestamp>,
+) -> Result<Response, ContractError> {
+    assert_deadline(&env, deadline)?;
+
+    let (offer_asset_info, target_asset_info) = match (operations.first(), operations.last()) {
+        (Some(first), Some(last)) => (first.get_offer_asset_info(), last.get_target_asset_info()),
+        _ => return Err(ContractError::NoSwapOperationsProvided {}),
+    };
+
+    // Assert the operations are properly set
+    assert_operations(&operations)?;
+
+    let offer_asset = match offer_asset {
+        Some(offer_asset) if offer_asset.info == offer_asset_info => offer_asset,
+        _ => return Err(ContractError::InvalidExactOutSwapFunds {}),
+    };
+
+    let offer_amount = exact_out_offer_amount(deps.as_ref(), ask_amount, &operations)?;
+    let max_offer_amount = max_offer_amount
+        .unwrap_or(offer_asset.amount)
+        .min(offer_asset.amount);
+    if offer_amount > max_offer_amount {
+        return Err(ContractError::MaxOfferAmountAssertion {
+            max_offer_amount,
+            offer_amount,
+        });
+    }
+
+    let to = if let Some(to) = to {
+        to
+    } else {
+        sender.clone()
+    };
+
+    // refund the unused offer funds before swapping, so they can't be picked up by the hops
+    let refund_amount = offer_asset.amount.checked_sub(offer_amount)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !refund_amount.is_zero() {
+        messages.push(
+            Asset {
+                info: offer_asset_info,
+                amount: refund_amount,
+            }
+            .into_msg(sender)?,
+        );
+    }
+
+    messages.extend(swap_operations_msgs(
+        &env,
+        operations,
+        Some(offer_amount),
+        &to,
+    )?);
+
+    // Make sure at least the ask amount is received
+    messages.push(assert_minimum_receive_msg(
+        deps.as_ref(),
+        &env,
+        target_asset_info,
+        ask_amount,
+        &to,
+    )?);
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "execute_swap_operations_exact_out"),
+        attr("offer_amount", offer_amount),
+        attr("refund_amount", refund_amount),
+    ]))
+}
+
+/// Computes the offer amount for the swap operations to return at least `ask_amount`. The hops
+/// round their amounts, so the reverse simulation of the route is checked against a forward one,
+/// topping the offer amount up by the shortfall at the rate of the route until it's met.
+fn exact_out_offer_amount(
+    deps: Deps,
+    ask_amount: Uint128,
+    operations: &[SwapOperation],
+) -> Result<Uint128, ContractError> {
+    let mut offer_amount =
+        reverse_simulate_swap_operations(deps, ask_amount, operations.to_vec())?.amount;
+
+    let mut top_ups = 0u8;
+    loop {
+        let return_amount =
+            simulate_swap_operations(deps, offer_amount, operations.to_vec())?.amount;
+        if return_amount >= ask_amount {
+            return Ok(offer_amount);
+        }
+        if return_amount.is_zero() || top_ups == MAX_EXACT_OUT_TOP_UPS {
+            return Err(ContractError::ExactOutAskAmountUnreachable { ask_amount });
+        }
+
+        let top_up = ask_amount
+            .checked_sub(return_amount)?
+            .checked_multiply_ratio(offer_amount, return_amount)
+            .map_err(|_| ContractError::ExactOutAskAmountUnreachable { ask_amount })?;
+        offer_amount = offer_amount
+            .checked_add(top_up)?
+            .checked_add(Uint128::one())?;
+        top_ups += 1;
+    }
+}
+
+/// Creates the messages executing the given swap operations one after the other, sending the
+/// return of the last one to `to`. The first operation swaps `offer_amount` if given, or the
+/// router's whole balance of the offer asset otherwise.
+pub(crate) fn swap_operations_msgs(
+    env: &Env,
+    operations: Vec<SwapOperation>,
+    offer_amount: Option<Uint128>,
+    to: &Addr,
+) -> StdResult<Vec<CosmosMsg>> {
+    let operations_len = operations.len();
+
+    operations
         .into_iter()
-        .map(|op| {
-            operation_index += 1;
+        .enumerate()
+        .map(|(operation_index, op)| {
             Ok(CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: env.contract.address.to_string(),
                 funds: vec![],
                 msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
                     operation: op,
-                    to: if operation_index == operations_len {
+                    offer_amount: if operation_index == 0 {
+                        offer_amount
+                    } else {
+                        None
+                    },
+                    to: if operation_index + 1 == operations_len {
                         Some(to.to_string())
                     } else {
                         None
@@ -177,25 +669,154 @@ pub fn execute_swap_operations(
                 })?,
             }))
         })
-        .collect::<StdResult<Vec<CosmosMsg>>>()?;
+        .collect()
+}
 
-    // Execute minimum amount assertion
-    if let Some(minimum_receive) = minimum_receive {
-        let receiver_balance = target_asset_info.query_pool(&deps.querier, deps.api, to.clone())?;
-
-        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: env.contract.address.to_string(),
-            funds: vec![],
-            msg: to_binary(&ExecuteMsg::AssertMinimumReceive {
-                asset_info: target_asset_info,
-                prev_balance: receiver_balance,
-                minimum_receive,
-                receiver: to.to_string(),
-            })?,
-        }))
+/// Validates the referral address and asserts its commission doesn't exceed the configured max
+fn assert_referral(deps: Deps, referral: Referral) -> Result<Referral, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if referral.commission > config.max_referral_commission {
+        return Err(ContractError::ReferralCommissionTooHigh {
+            commission: referral.commission,
+            max_commission: config.max_referral_commission,
+        });
     }
 
-    Ok(Response::new().add_messages(messages))
+    Ok(Referral {
+        address: deps.api.addr_validate(&referral.address)?.to_string(),
+        commission: referral.commission,
+    })
+}
+
+fn take_referral_commission(
+    deps: Deps,
+    env: Env,
+    info: MessageInfo,
+    asset_info: AssetInfo,
+    prev_balance: Uint128,
+    referral: Referral,
+    receiver: Addr,
+) -> Result<Response, ContractError> {
+    if env.contract.address != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let balance = asset_info.query_pool(&deps.querier, deps.api, env.contract.address)?;
+    let return_amount = balance.checked_sub(prev_balance)?;
+    let commission_amount = return_amount * referral.commission;
+    let receive_amount = return_amount.checked_sub(commission_amount)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !commission_amount.is_zero() {
+        messages.push(
+            Asset {
+                info: asset_info.clone(),
+                amount: commission_amount,
+            }
+            .into_msg(Addr::unchecked(&referral.address))?,
+        );
+    }
+    if !receive_amount.is_zero() {
+        messages.push(
+            Asset {
+                info: asset_info,
+                amount: receive_amount,
+            }
+            .into_msg(receiver)?,
+        );
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "take_referral_commission"),
+        attr("referral", referral.address),
+        attr("commission", referral.commission.to_string()),
+        attr("commission_amount", commission_amount),
+    ]))
+}
+
+pub(crate) fn assert_minimum_receive_msg(
+    deps: Deps,
+    env: &Env,
+    target_asset_info: AssetInfo,
+    minimum_receive: Uint128,
+    to: &Addr,
+) -> StdResult<CosmosMsg> {
+    let receiver_balance = target_asset_info.query_pool(&deps.querier, deps.api, to.clone())?;
+
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: env.contract.address.to_string(),
+        funds: vec![],
+        msg: to_binary(&ExecuteMsg::AssertMinimumReceive {
+            asset_info: target_asset_info,
+            prev_balance: receiver_balance,
+            minimum_receive,
+            receiver: to.to_string(),
+        })?,
+    }))
+}
+
+/// Validates the routes of a split swap, returning the offer and ask assets they share
+fn assert_split_routes(
+    routes: &[(Decimal, Vec<SwapOperation>)],
+) -> Result<(AssetInfo, AssetInfo), ContractError> {
+    let (offer_asset_info, target_asset_info) = match routes.first() {
+        Some((_, operations)) => match (operations.first(), operations.last()) {
+            (Some(first), Some(last)) => {
+                (first.get_offer_asset_info(), last.get_target_asset_info())
+            }
+            _ => return Err(ContractError::NoSwapOperationsProvided {}),
+        },
+        None => return Err(ContractError::NoSwapOperationsProvided {}),
+    };
+
+    let mut total_weight = Decimal::zero();
+    for (weight, operations) in routes {
+        if weight.is_zero() {
+            return Err(ContractError::InvalidSplitWeights {});
+        }
+        total_weight = total_weight.checked_add(*weight)?;
+
+        match (operations.first(), operations.last()) {
+            (Some(first), Some(last)) => {
+                if first.get_offer_asset_info() != offer_asset_info
+                    || last.get_target_asset_info() != target_asset_info
+                {
+                    return Err(ContractError::InvalidSplitRoutes {});
+                }
+            }
+            _ => return Err(ContractError::NoSwapOperationsProvided {}),
+        }
+
+        assert_operations(operations)?;
+    }
+
+    if total_weight != Decimal::one() {
+        return Err(ContractError::InvalidSplitWeights {});
+    }
+
+    Ok((offer_asset_info, target_asset_info))
+}
+
+/// Divides the offer amount among the routes by their weights. The last route takes whatever is
+/// left so no dust stays in the router.
+fn split_offer_amount(
+    offer_amount: Uint128,
+    routes: &[(Decimal, Vec<SwapOperation>)],
+) -> Result<Vec<Uint128>, ContractError> {
+    let mut remaining_amount = offer_amount;
+    let mut route_amounts = vec![];
+
+    for (index, (weight, _)) in routes.iter().enumerate() {
+        let route_amount = if index + 1 == routes.len() {
+            remaining_amount
+        } else {
+            offer_amount * *weight
+        };
+        remaining_amount = remaining_amount.checked_sub(route_amount)?;
+        route_amounts.push(route_amount);
+    }
+
+    Ok(route_amounts)
 }
 
 fn assert_minimum_receive(
@@ -218,21 +839,56 @@ fn assert_minimum_receive(
     Ok(Response::default())
 }
 
-fn add_swap_routes(
+/// Asserts the sender is the owner of the router contract
+fn assert_owner(deps: Deps, sender: &Addr) -> Result<Config, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if *sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    Ok(config)
+}
+
+fn update_config(
     deps: DepsMut,
-    env: Env,
     sender: Addr,
-    swap_routes: Vec<SwapRoute>,
+    default_max_spread: Option<Decimal>,
+    remove_default_max_spread: bool,
+    max_referral_commission: Option<Decimal>,
 ) -> Result<Response, ContractError> {
-    let contract_info = deps
-        .querier
-        .query_wasm_contract_info(env.contract.address)?;
-    if let Some(admin) = contract_info.admin {
-        if sender != deps.api.addr_validate(admin.as_str())? {
-            return Err(ContractError::Unauthorized {});
+    let mut config = assert_owner(deps.as_ref(), &sender)?;
+
+    if let Some(max_spread) = default_max_spread {
+        if max_spread > Decimal::one() {
+            return Err(ContractError::InvalidMaxSpread { max_spread });
+        }
+        config.default_max_spread = Some(max_spread);
+    }
+    if remove_default_max_spread {
+        config.default_max_spread = None;
+    }
+
+    if let Some(max_referral_commission) = max_referral_commission {
+        if max_referral_commission > Decimal::one() {
+            return Err(ContractError::InvalidMaxReferralCommission {
+                max_referral_commission,
+            });
         }
+        config.max_referral_commission = max_referral_commission;
     }
 
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+fn add_swap_routes(
+    deps: DepsMut,
+    sender: Addr,
+    swap_routes: Vec<SwapRoute>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.as_ref(), &sender)?;
+
     let mut attributes = vec![];
 
     for swap_route in swap_routes {
@@ -265,6 +921,85 @@ fn add_swap_routes(
         .add_attributes(attributes))
 }
 
+fn remove_swap_routes(
+    deps: DepsMut,
+    sender: Addr,
+    swap_routes: Vec<SwapRoute>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.as_ref(), &sender)?;
+
+    let mut attributes = vec![];
+
+    for swap_route in swap_routes {
+        let offer_asset_label = swap_route
+            .offer_asset_info
+            .clone()
+            .get_label(&deps.as_ref())?;
+        let ask_asset_label = swap_route
+            .ask_asset_info
+            .clone()
+            .get_label(&deps.as_ref())?;
+        let swap_route_key =
+            SWAP_ROUTES.key((offer_asset_label.as_str(), ask_asset_label.as_str()));
+
+        if !swap_route_key.has(deps.storage) {
+            return Err(ContractError::NoSwapRouteForAssets {
+                offer_asset: swap_route.offer_asset_info.to_string(),
+                ask_asset: swap_route.ask_asset_info.to_string(),
+            });
+        }
+        swap_route_key.remove(deps.storage);
+
+        attributes.push(attr("swap_route", swap_route.to_string()));
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_swap_routes")
+        .add_attributes(attributes))
+}
+
+fn propose_new_owner(
+    deps: DepsMut,
+    sender: Addr,
+    owner: String,
+) -> Result<Response, ContractError> {
+    let mut config = assert_owner(deps.as_ref(), &sender)?;
+
+    let pending_owner = deps.api.addr_validate(&owner)?;
+    config.pending_owner = Some(pending_owner.clone());
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "propose_new_owner"),
+        attr("pending_owner", pending_owner),
+    ]))
+}
+
+fn drop_ownership_proposal(deps: DepsMut, sender: Addr) -> Result<Response, ContractError> {
+    let mut config = assert_owner(deps.as_ref(), &sender)?;
+
+    config.pending_owner = None;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "drop_ownership_proposal"))
+}
+
+fn claim_ownership(deps: DepsMut, sender: Addr) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if config.pending_owner.as_ref() != Some(&sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.owner = sender;
+    config.pending_owner = None;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "claim_ownership"),
+        attr("owner", config.owner),
+    ]))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
@@ -283,6 +1018,20 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
         } => Ok(to_binary(&reverse_simulate_swap_operations(
             deps, ask_amount, operations,
         )?)?),
+        QueryMsg::SimulateSwapOperationsDetailed {
+            offer_amount,
+            operations,
+        } => Ok(to_binary(&simulate_swap_operations_detailed(
+            deps,
+            offer_amount,
+            operations,
+        )?)?),
+        QueryMsg::ReverseSimulateSwapOperationsDetailed {
+            ask_amount,
+            operations,
+        } => Ok(to_binary(&reverse_simulate_swap_operations_detailed(
+            deps, ask_amount, operations,
+        )?)?),
         QueryMsg::SwapRoute {
             offer_asset_info,
             ask_asset_info,
@@ -291,63 +1040,147 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
             offer_asset_info,
             ask_asset_info,
         )?)?),
+        QueryMsg::SwapRoutes { start_after, limit } => {
+            Ok(to_binary(&get_swap_routes(deps, start_after, limit)?)?)
+        }
+        QueryMsg::SwapRoutesValidity { start_after, limit } => Ok(to_binary(
+            &get_swap_routes_validity(deps, start_after, limit)?,
+        )?),
+        QueryMsg::SimulateSplitSwap {
+            offer_amount,
+            routes,
+        } => Ok(to_binary(&simulate_split_swap(
+            deps,
+            offer_amount,
+            routes,
+        )?)?),
+        QueryMsg::FindBestRoute {
+            offer_asset,
+            ask_asset_info,
+            max_hops,
+        } => Ok(to_binary(&find_best_route(
+            deps,
+            offer_asset,
+            ask_asset_info,
+            max_hops,
+        )?)?),
     }
 }
 
 pub fn query_config(deps: Deps) -> Result<ConfigResponse, ContractError> {
     let state = CONFIG.load(deps.storage)?;
     let resp = ConfigResponse {
+        owner: state.owner.to_string(),
+        pending_owner: state
+            .pending_owner
+            .map(|pending_owner| pending_owner.to_string()),
         terraswap_factory: deps
             .api
             .addr_humanize(&state.terraswap_factory)?
             .to_string(),
+        default_max_spread: state.default_max_spread,
+        max_referral_commission: state.max_referral_commission,
     };
 
     Ok(resp)
 }
 
-fn simulate_swap_operations(
+pub(crate) fn simulate_swap_operations(
     deps: Deps,
     offer_amount: Uint128,
     operations: Vec<SwapOperation>,
 ) -> Result<SimulateSwapOperationsResponse, ContractError> {
+    Ok(SimulateSwapOperationsResponse {
+        amount: simulate_swap_operations_detailed(deps, offer_amount, operations)?.amount,
+    })
+}
+
+/// Simulates the swap operations hop by hop, offering the amount returned by each hop on the next
+fn simulate_swap_operations_detailed(
+    deps: Deps,
+    offer_amount: Uint128,
+    operations: Vec<SwapOperation>,
+) -> Result<SimulateSwapOperationsDetailedResponse, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
     let terraswap_factory = deps.api.addr_humanize(&config.terraswap_factory)?;
 
-    let operations_len = operations.len();
-    if operations_len == 0 {
+    if operations.is_empty() {
         return Err(ContractError::NoSwapOperationsProvided {});
     }
 
     let mut offer_amount = offer_amount;
+    let mut simulations = Vec::with_capacity(operations.len());
     for operation in operations.into_iter() {
-        match operation {
-            SwapOperation::TerraSwap {
-                offer_asset_info,
-                ask_asset_info,
-            } => {
-                let pair_info: PairInfo = query_pair_info(
-                    &deps.querier,
-                    terraswap_factory.clone(),
-                    &[offer_asset_info.clone(), ask_asset_info.clone()],
-                )?;
+        let pool = query_operation_pool(deps, terraswap_factory.clone(), &operation)?;
+        let offer_asset = Asset {
+            info: operation.get_offer_asset_info(),
+            amount: offer_amount,
+        };
 
-                let res: SimulationResponse = simulate(
-                    &deps.querier,
-                    Addr::unchecked(pair_info.contract_addr),
-                    &Asset {
-                        info: offer_asset_info,
-                        amount: offer_amount,
-                    },
-                )?;
+        let res: SimulationResponse = match &operation {
+            SwapOperation::TerraSwap { .. } => simulate(&deps.querier, pool.clone(), &offer_asset)?,
+            SwapOperation::StableSwap3Pool { ask_asset_info, .. } => {
+                let res = simulate_trio(&deps.querier, pool.clone(), &offer_asset, ask_asset_info)?;
 
-                offer_amount = res.return_amount;
+                SimulationResponse {
+                    return_amount: res.return_amount,
+                    spread_amount: res.spread_amount,
+                    swap_fee_amount: res.swap_fee_amount,
+                    protocol_fee_amount: res.protocol_fee_amount,
+                    burn_fee_amount: res.burn_fee_amount,
+                }
             }
-        }
+        };
+
+        simulations.push(SwapOperationSimulation {
+            operation,
+            pool: pool.to_string(),
+            offer_amount,
+            return_amount: res.return_amount,
+            spread_amount: res.spread_amount,
+            swap_fee_amount: res.swap_fee_amount,
+            protocol_fee_amount: res.protocol_fee_amount,
+            burn_fee_amount: res.burn_fee_amount,
+        });
+
+        offer_amount = res.return_amount;
     }
 
-    Ok(SimulateSwapOperationsResponse {
+    Ok(SimulateSwapOperationsDetailedResponse {
         amount: offer_amount,
+        price_impact: price_impact(&simulations)?,
+        simulations,
+    })
+}
+
+fn simulate_split_swap(
+    deps: Deps,
+    offer_amount: Uint128,
+    routes: Vec<(Decimal, Vec<SwapOperation>)>,
+) -> Result<SimulateSplitSwapResponse, ContractError> {
+    assert_split_routes(&routes)?;
+
+    let route_amounts = split_offer_amount(offer_amount, &routes)?
+        .into_iter()
+        .zip(routes)
+        .map(|(route_amount, (_, operations))| {
+            if route_amount.is_zero() {
+                return Ok(Uint128::zero());
+            }
+
+            Ok(simulate_swap_operations(deps, route_amount, operations)?.amount)
+        })
+        .collect::<Result<Vec<Uint128>, ContractError>>()?;
+
+    let amount = route_amounts
+        .iter()
+        .try_fold(Uint128::zero(), |acc, route_amount| {
+            acc.checked_add(*route_amount)
+        })?;
+
+    Ok(SimulateSplitSwapResponse {
+        route_amounts,
+        amount,
     })
 }
 
@@ -356,59 +1189,131 @@ fn reverse_simulate_swap_operations(
     ask_amount: Uint128,
     operations: Vec<SwapOperation>,
 ) -> Result<SimulateSwapOperationsResponse, ContractError> {
+    Ok(SimulateSwapOperationsResponse {
+        amount: reverse_simulate_swap_operations_detailed(deps, ask_amount, operations)?.amount,
+    })
+}
+
+/// Reverse simulates the swap operations hop by hop, starting from the last one and asking on each
+/// hop for the amount offered on the next
+fn reverse_simulate_swap_operations_detailed(
+    deps: Deps,
+    ask_amount: Uint128,
+    operations: Vec<SwapOperation>,
+) -> Result<SimulateSwapOperationsDetailedResponse, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
+    let terraswap_factory = deps.api.addr_humanize(&config.terraswap_factory)?;
 
-    let operations_len = operations.len();
-    if operations_len == 0 {
+    if operations.is_empty() {
         return Err(ContractError::NoSwapOperationsProvided {});
     }
 
     let mut ask_amount = ask_amount;
+    let mut simulations = Vec::with_capacity(operations.len());
     for operation in operations.into_iter().rev() {
-        ask_amount = match operation {
-            SwapOperation::TerraSwap {
-                offer_asset_info,
-                ask_asset_info,
-            } => {
-                let terraswap_factory = deps.api.addr_humanize(&config.terraswap_factory)?;
+        let pool = query_operation_pool(deps, terraswap_factory.clone(), &operation)?;
+        let ask_asset = Asset {
+            info: operation.get_target_asset_info(),
+            amount: ask_amount,
+        };
 
-                reverse_simulate_return_amount(
-                    deps,
-                    terraswap_factory,
-                    ask_amount,
+        let res: ReverseSimulationResponse = match &operation {
+            SwapOperation::TerraSwap { .. } => {
+                reverse_simulate(&deps.querier, pool.clone(), &ask_asset)?
+            }
+            SwapOperation::StableSwap3Pool {
+                offer_asset_info, ..
+            } => {
+                let res = reverse_simulate_trio(
+                    &deps.querier,
+                    pool.clone(),
                     offer_asset_info,
-                    ask_asset_info,
-                )?
+                    &ask_asset,
+                )?;
+
+                ReverseSimulationResponse {
+                    offer_amount: res.offer_amount,
+                    spread_amount: res.spread_amount,
+                    swap_fee_amount: res.swap_fee_amount,
+                    protocol_fee_amount: res.protocol_fee_amount,
+                    burn_fee_amount: res.burn_fee_amount,
+                }
             }
-        }
+        };
+
+        simulations.push(SwapOperationSimulation {
+            operation,
+            pool: pool.to_string(),
+            offer_amount: res.offer_amount,
+            return_amount: ask_amount,
+            spread_amount: res.spread_amount,
+            swap_fee_amount: res.swap_fee_amount,
+            protocol_fee_amount: res.protocol_fee_amount,
+            burn_fee_amount: res.burn_fee_amount,
+        });
+
+        ask_amount = res.offer_amount;
     }
+    simulations.reverse();
 
-    Ok(SimulateSwapOperationsResponse { amount: ask_amount })
+    Ok(SimulateSwapOperationsDetailedResponse {
+        amount: ask_amount,
+        price_impact: price_impact(&simulations)?,
+        simulations,
+    })
 }
 
-fn reverse_simulate_return_amount(
-    deps: Deps,
-    factory: Addr,
-    ask_amount: Uint128,
-    offer_asset_info: AssetInfo,
-    ask_asset_info: AssetInfo,
-) -> StdResult<Uint128> {
-    let pair_info: PairInfo = query_pair_info(
-        &deps.querier,
-        factory,
-        &[offer_asset_info, ask_asset_info.clone()],
-    )?;
+/// Queries the factory for the pair or trio the swap operation is executed on
+fn query_operation_pool(deps: Deps, factory: Addr, operation: &SwapOperation) -> StdResult<Addr> {
+    let contract_addr = match operation {
+        SwapOperation::TerraSwap {
+            offer_asset_info,
+            ask_asset_info,
+            pair_identifier,
+            ..
+        } => {
+            query_pair_info(
+                &deps.querier,
+                factory,
+                &[offer_asset_info.clone(), ask_asset_info.clone()],
+                pair_identifier.clone(),
+            )?
+            .contract_addr
+        }
+        SwapOperation::StableSwap3Pool {
+            pool_asset_infos, ..
+        } => query_trio_info(&deps.querier, factory, pool_asset_infos)?.contract_addr,
+    };
 
-    let res = reverse_simulate(
-        &deps.querier,
-        Addr::unchecked(pair_info.contract_addr),
-        &Asset {
-            amount: ask_amount,
-            info: ask_asset_info,
-        },
-    )?;
+    Ok(Addr::unchecked(contract_addr))
+}
+
+/// Aggregates the spread of the hops into the share of the spot price output lost to it, i.e.
+/// `1 - Π(1 - spread / (return + fees + spread))`
+fn price_impact(simulations: &[SwapOperationSimulation]) -> Result<Decimal, ContractError> {
+    let retained = simulations
+        .iter()
+        .try_fold(Decimal::one(), |retained, simulation| {
+            let spot_amount = simulation
+                .return_amount
+                .checked_add(simulation.spread_amount)?
+                .checked_add(simulation.swap_fee_amount)?
+                .checked_add(simulation.protocol_fee_amount)?
+                .checked_add(simulation.burn_fee_amount)?;
+
+            if spot_amount.is_zero() {
+                return Ok(retained);
+            }
 
-    Ok(res.offer_amount)
+            retained
+                .checked_mul(Decimal::from_ratio(
+                    spot_amount - simulation.spread_amount,
+                    spot_amount,
+                ))
+                .map_err(ContractError::from)
+        })?;
+
+    Ok(Decimal::one() - retained)
 }
 
 fn get_swap_route(
@@ -429,15 +1334,71 @@ fn get_swap_route(
         })
 }
 
-fn assert_operations(operations: &[SwapOperation]) -> Result<(), ContractError> {
-    let mut ask_asset_map: HashMap<String, bool> = HashMap::new();
-    for operation in operations.iter() {
-        let (offer_asset, ask_asset) = match operation {
-            SwapOperation::TerraSwap {
+fn get_swap_routes(
+    deps: Deps,
+    start_after: Option<[AssetInfo; 2]>,
+    limit: Option<u32>,
+) -> Result<Vec<SwapRoute>, ContractError> {
+    let start_after = start_after
+        .map(|[offer_asset_info, ask_asset_info]| {
+            Ok::<_, StdError>((
+                offer_asset_info.get_label(&deps)?,
+                ask_asset_info.get_label(&deps)?,
+            ))
+        })
+        .transpose()?;
+
+    read_swap_routes(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|swap_operations| {
+            let (offer_asset_info, ask_asset_info) =
+                match (swap_operations.first(), swap_operations.last()) {
+                    (Some(first), Some(last)) => {
+                        (first.get_offer_asset_info(), last.get_target_asset_info())
+                    }
+                    _ => return Err(ContractError::NoSwapOperationsProvided {}),
+                };
+
+            Ok(SwapRoute {
                 offer_asset_info,
                 ask_asset_info,
-            } => (offer_asset_info.clone(), ask_asset_info.clone()),
-        };
+                swap_operations,
+            })
+        })
+        .collect()
+}
+
+/// Flags the stored swap routes going through pools that can't be found in the factory anymore
+fn get_swap_routes_validity(
+    deps: Deps,
+    start_after: Option<[AssetInfo; 2]>,
+    limit: Option<u32>,
+) -> Result<Vec<SwapRouteValidity>, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let terraswap_factory = deps.api.addr_humanize(&config.terraswap_factory)?;
+
+    Ok(get_swap_routes(deps, start_after, limit)?
+        .into_iter()
+        .map(|swap_route| {
+            let is_valid = swap_route.swap_operations.iter().all(|operation| {
+                query_operation_pool(deps, terraswap_factory.clone(), operation).is_ok()
+            });
+
+            SwapRouteValidity {
+                swap_route,
+                is_valid,
+            }
+        })
+        .collect())
+}
+
+pub(crate) fn assert_operations(operations: &[SwapOperation]) -> Result<(), ContractError> {
+    let mut ask_asset_map: HashMap<String, bool> = HashMap::new();
+    for operation in operations.iter() {
+        let (offer_asset, ask_asset) = (
+            operation.get_offer_asset_info(),
+            operation.get_target_asset_info(),
+        );
 
         ask_asset_map.remove(&offer_asset.to_string());
         ask_asset_map.insert(ask_asset.to_string(), true);
@@ -464,6 +1425,9 @@ fn test_invalid_operations() {
             ask_asset_info: AssetInfo::Token {
                 contract_addr: "asset0001".to_string(),
             },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
         },
         SwapOperation::TerraSwap {
             offer_asset_info: AssetInfo::Token {
@@ -472,6 +1436,9 @@ fn test_invalid_operations() {
             ask_asset_info: AssetInfo::NativeToken {
                 denom: "uluna".to_string(),
             },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
         }
     ])
     .is_ok());
@@ -485,6 +1452,9 @@ fn test_invalid_operations() {
             ask_asset_info: AssetInfo::Token {
                 contract_addr: "asset0001".to_string(),
             },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
         },
         SwapOperation::TerraSwap {
             offer_asset_info: AssetInfo::Token {
@@ -493,6 +1463,9 @@ fn test_invalid_operations() {
             ask_asset_info: AssetInfo::NativeToken {
                 denom: "uluna".to_string(),
             },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
         },
         SwapOperation::TerraSwap {
             offer_asset_info: AssetInfo::NativeToken {
@@ -501,6 +1474,9 @@ fn test_invalid_operations() {
             ask_asset_info: AssetInfo::Token {
                 contract_addr: "asset0002".to_string(),
             },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
         },
     ])
     .is_ok());
@@ -508,7 +1484,7 @@ fn test_invalid_operations() {
 
 #[cfg(not(tarpaulin_include))]
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(mut deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
     let version: Version = CONTRACT_VERSION.parse()?;
     let storage_version: Version = get_contract_version(deps.storage)?.version.parse()?;
 
@@ -519,6 +1495,10 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
         });
     }
 
+    if storage_version < Version::parse("1.2.0")? {
+        migrations::migrate_to_v120(deps.branch(), env)?;
+    }
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     Ok(Response::default())
 }
@@ -1,4 +1,4 @@
-use cosmwasm_std::{OverflowError, StdError, Uint128};
+use cosmwasm_std::{Decimal, OverflowError, StdError, Timestamp, Uint128};
 use semver::Version;
 use thiserror::Error;
 
@@ -36,6 +36,40 @@ pub enum ContractError {
     #[error("Must provide swap operations to execute")]
     NoSwapOperationsProvided {},
 
+    #[error("A single native coin must be sent to find the swap route")]
+    InvalidRouteSearchFunds {},
+
+    #[error("Invalid split routes; the weights must be positive and add up to 1")]
+    InvalidSplitWeights {},
+
+    #[error(
+        "Invalid split routes; all routes must swap the same offer asset into the same ask asset"
+    )]
+    InvalidSplitRoutes {},
+
+    #[error("The offer asset of the split routes must be sent along with the split swap")]
+    InvalidSplitSwapFunds {},
+
+    #[error("The offer asset of the swap operations must be sent along with the exact out swap")]
+    InvalidExactOutSwapFunds {},
+
+    #[error("The swap operations can't return the ask amount {ask_amount}")]
+    ExactOutAskAmountUnreachable { ask_amount: Uint128 },
+
+    #[error("Invalid zap operations; each side must swap the offer asset into its pool asset")]
+    InvalidZapOperations {},
+
+    #[error("The offer asset of the zap must be sent along with it")]
+    InvalidZapFunds {},
+
+    #[error(
+        "Assertion failed; max offer amount: {max_offer_amount}, offer amount: {offer_amount}"
+    )]
+    MaxOfferAmountAssertion {
+        max_offer_amount: Uint128,
+        offer_amount: Uint128,
+    },
+
     #[error(
         "Assertion failed; minimum receive amount: {minimum_receive}, swap amount: {swap_amount}"
     )]
@@ -46,6 +80,28 @@ pub enum ContractError {
 
     #[error("Unauthorized")]
     Unauthorized {},
+
+    #[error("Invalid max spread {max_spread}; it can't be greater than 1")]
+    InvalidMaxSpread { max_spread: Decimal },
+
+    #[error(
+        "Invalid max referral commission {max_referral_commission}; it can't be greater than 1"
+    )]
+    InvalidMaxReferralCommission { max_referral_commission: Decimal },
+
+    #[error(
+        "Referral commission {commission} exceeds the max referral commission {max_commission}"
+    )]
+    ReferralCommissionTooHigh {
+        commission: Decimal,
+        max_commission: Decimal,
+    },
+
+    #[error("Deadline exceeded; deadline: {deadline}, current time: {current_time}")]
+    DeadlineExceeded {
+        deadline: Timestamp,
+        current_time: Timestamp,
+    },
 }
 
 impl From<semver::Error> for ContractError {
@@ -1,8 +1,11 @@
 pub mod contract;
 mod error;
+mod migrations;
 pub mod state;
 
 mod operations;
+mod route_finder;
+mod zap;
 
 #[cfg(test)]
 mod testing;
@@ -0,0 +1,37 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{CanonicalAddr, Decimal, DepsMut, Env, StdError};
+use cw_storage_plus::Item;
+
+use crate::state::{Config, CONFIG};
+
+/// Migrates state from v1.1.0 and lower to v1.2.0, which adds an owner, a default max spread and a
+/// max referral commission to the Config. The owner is set to the contract admin, who used to be
+/// the one managing the router, the swaps keep using the default max spread of the pools, and
+/// referrals are disabled until a max commission is set.
+pub fn migrate_to_v120(deps: DepsMut, env: Env) -> Result<(), StdError> {
+    #[cw_serde]
+    struct ConfigV110 {
+        pub terraswap_factory: CanonicalAddr,
+    }
+
+    const CONFIGV110: Item<ConfigV110> = Item::new("config");
+    let config_v110 = CONFIGV110.load(deps.storage)?;
+
+    let admin = deps
+        .querier
+        .query_wasm_contract_info(env.contract.address)?
+        .admin
+        .ok_or_else(|| StdError::generic_err("The router has no admin to set as owner"))?;
+
+    let config = Config {
+        owner: deps.api.addr_validate(&admin)?,
+        pending_owner: None,
+        terraswap_factory: config_v110.terraswap_factory,
+        default_max_spread: None,
+        max_referral_commission: Decimal::zero(),
+    };
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(())
+}
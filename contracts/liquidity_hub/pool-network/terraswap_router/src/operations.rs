@@ -1,5 +1,6 @@
 use cosmwasm_std::{
-    to_binary, Addr, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Response, WasmMsg,
+    to_binary, Addr, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Response, Uint128,
+    WasmMsg,
 };
 
 use crate::state::{Config, CONFIG};
@@ -7,10 +8,13 @@ use crate::state::{Config, CONFIG};
 use crate::error::ContractError;
 use cw20::Cw20ExecuteMsg;
 use white_whale::pool_network;
-use white_whale::pool_network::asset::{Asset, AssetInfo, PairInfo};
+use white_whale::pool_network::asset::{Asset, AssetInfo, PairInfo, TrioInfo};
 use white_whale::pool_network::pair::ExecuteMsg as PairExecuteMsg;
-use white_whale::pool_network::querier::{query_balance, query_pair_info, query_token_balance};
+use white_whale::pool_network::querier::{
+    query_balance, query_pair_info, query_token_balance, query_trio_info,
+};
 use white_whale::pool_network::router::SwapOperation;
+use white_whale::pool_network::trio::ExecuteMsg as TrioExecuteMsg;
 
 /// Execute swap operation
 /// swap all offer asset to ask asset
@@ -19,45 +23,62 @@ pub fn execute_swap_operation(
     env: Env,
     info: MessageInfo,
     operation: SwapOperation,
+    offer_amount: Option<Uint128>,
     to: Option<String>,
 ) -> Result<Response, ContractError> {
     if env.contract.address != info.sender {
         return Err(ContractError::Unauthorized {});
     }
 
+    let config: Config = CONFIG.load(deps.as_ref().storage)?;
+    let terraswap_factory = deps.api.addr_humanize(&config.terraswap_factory)?;
+
     let messages: Vec<CosmosMsg> = match operation {
         SwapOperation::TerraSwap {
             offer_asset_info,
             ask_asset_info,
+            belief_price,
+            max_spread,
+            pair_identifier,
         } => {
-            let config: Config = CONFIG.load(deps.as_ref().storage)?;
-            let terraswap_factory = deps.api.addr_humanize(&config.terraswap_factory)?;
             let pair_info: PairInfo = query_pair_info(
                 &deps.querier,
                 terraswap_factory,
                 &[offer_asset_info.clone(), ask_asset_info],
+                pair_identifier,
             )?;
 
-            let amount = match offer_asset_info.clone() {
-                AssetInfo::NativeToken { denom } => {
-                    query_balance(&deps.querier, env.contract.address, denom)?
-                }
-                AssetInfo::Token { contract_addr } => query_token_balance(
-                    &deps.querier,
-                    deps.api.addr_validate(contract_addr.as_str())?,
-                    env.contract.address,
-                )?,
-            };
-            let offer_asset: Asset = Asset {
-                info: offer_asset_info,
-                amount,
-            };
+            let offer_asset =
+                query_offer_asset(deps.as_ref(), &env, offer_asset_info, offer_amount)?;
 
             vec![asset_into_swap_msg(
                 deps.as_ref(),
                 Addr::unchecked(pair_info.contract_addr),
                 offer_asset,
-                None,
+                belief_price,
+                max_spread.or(config.default_max_spread),
+                to,
+            )?]
+        }
+        SwapOperation::StableSwap3Pool {
+            offer_asset_info,
+            ask_asset_info,
+            pool_asset_infos,
+            belief_price,
+            max_spread,
+        } => {
+            let trio_info: TrioInfo =
+                query_trio_info(&deps.querier, terraswap_factory, &pool_asset_infos)?;
+
+            let offer_asset =
+                query_offer_asset(deps.as_ref(), &env, offer_asset_info, offer_amount)?;
+
+            vec![asset_into_trio_swap_msg(
+                Addr::unchecked(trio_info.contract_addr),
+                offer_asset,
+                ask_asset_info,
+                belief_price,
+                max_spread.or(config.default_max_spread),
                 to,
             )?]
         }
@@ -66,10 +87,43 @@ pub fn execute_swap_operation(
     Ok(Response::new().add_messages(messages))
 }
 
+/// Gets the offer asset to swap, which is the router's whole balance of the `offer_asset_info` if no
+/// `offer_amount` is given
+fn query_offer_asset(
+    deps: Deps,
+    env: &Env,
+    offer_asset_info: AssetInfo,
+    offer_amount: Option<Uint128>,
+) -> Result<Asset, ContractError> {
+    if let Some(amount) = offer_amount {
+        return Ok(Asset {
+            info: offer_asset_info,
+            amount,
+        });
+    }
+
+    let amount = match offer_asset_info.clone() {
+        AssetInfo::NativeToken { denom } => {
+            query_balance(&deps.querier, env.contract.address.clone(), denom)?
+        }
+        AssetInfo::Token { contract_addr } => query_token_balance(
+            &deps.querier,
+            deps.api.addr_validate(contract_addr.as_str())?,
+            env.contract.address.clone(),
+        )?,
+    };
+
+    Ok(Asset {
+        info: offer_asset_info,
+        amount,
+    })
+}
+
 pub fn asset_into_swap_msg(
     _deps: Deps,
     pair_contract: Addr,
     offer_asset: Asset,
+    belief_price: Option<Decimal>,
     max_spread: Option<Decimal>,
     to: Option<String>,
 ) -> Result<CosmosMsg, ContractError> {
@@ -82,9 +136,10 @@ pub fn asset_into_swap_msg(
             }],
             msg: to_binary(&PairExecuteMsg::Swap {
                 offer_asset,
-                belief_price: None,
+                belief_price,
                 max_spread,
                 to,
+                deadline: None,
             })?,
         })),
         AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
@@ -94,9 +149,52 @@ pub fn asset_into_swap_msg(
                 contract: pair_contract.to_string(),
                 amount: offer_asset.amount,
                 msg: to_binary(&pool_network::pair::Cw20HookMsg::Swap {
-                    belief_price: None,
+                    belief_price,
+                    max_spread,
+                    to,
+                    deadline: None,
+                })?,
+            })?,
+        })),
+    }
+}
+
+pub fn asset_into_trio_swap_msg(
+    trio_contract: Addr,
+    offer_asset: Asset,
+    ask_asset: AssetInfo,
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+    to: Option<String>,
+) -> Result<CosmosMsg, ContractError> {
+    match offer_asset.info.clone() {
+        AssetInfo::NativeToken { denom } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: trio_contract.to_string(),
+            funds: vec![Coin {
+                denom,
+                amount: offer_asset.amount,
+            }],
+            msg: to_binary(&TrioExecuteMsg::Swap {
+                offer_asset,
+                ask_asset,
+                belief_price,
+                max_spread,
+                to,
+                deadline: None,
+            })?,
+        })),
+        AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: trio_contract.to_string(),
+                amount: offer_asset.amount,
+                msg: to_binary(&pool_network::trio::Cw20HookMsg::Swap {
+                    ask_asset,
+                    belief_price,
                     max_spread,
                     to,
+                    deadline: None,
                 })?,
             })?,
         })),
@@ -0,0 +1,273 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{Addr, Deps, Uint128};
+
+use white_whale::pool_network::asset::{Asset, AssetInfo};
+use white_whale::pool_network::factory::PoolInfo;
+use white_whale::pool_network::querier::{query_pools_by_asset, simulate, simulate_trio};
+use white_whale::pool_network::router::{BestRouteResponse, SwapOperation};
+
+use crate::error::ContractError;
+use crate::state::CONFIG;
+
+/// Amount of hops used when searching for a route if not specified otherwise
+const DEFAULT_MAX_HOPS: u32 = 3;
+/// Max amount of hops a route can have when searching for it
+const MAX_HOPS: u32 = 4;
+/// Max amount of pools holding an asset that are swapped through from it. As the search can run
+/// on-chain when executing swap operations, it's bounded by this and [MAX_SIMULATIONS] rather than
+/// by the amount of pools created by the factory.
+const MAX_POOLS_PER_ASSET: u32 = 30;
+/// Max amount of swaps simulated while searching for a route
+const MAX_SIMULATIONS: usize = 100;
+
+/// A pool created by the factory, i.e. an edge of the graph the routes are searched on
+enum Pool {
+    Pair {
+        contract_addr: Addr,
+        asset_infos: Vec<AssetInfo>,
+        pair_identifier: String,
+    },
+    Trio {
+        contract_addr: Addr,
+        asset_infos: Vec<AssetInfo>,
+    },
+}
+
+impl From<PoolInfo> for Pool {
+    fn from(pool_info: PoolInfo) -> Self {
+        match pool_info {
+            PoolInfo::Pair {
+                pair_identifier,
+                pair_info,
+            } => Pool::Pair {
+                contract_addr: Addr::unchecked(pair_info.contract_addr),
+                asset_infos: pair_info.asset_infos.to_vec(),
+                pair_identifier,
+            },
+            PoolInfo::Trio { trio_info } => Pool::Trio {
+                contract_addr: Addr::unchecked(trio_info.contract_addr),
+                asset_infos: trio_info.asset_infos,
+            },
+        }
+    }
+}
+
+impl Pool {
+    fn contract_addr(&self) -> &Addr {
+        match self {
+            Pool::Pair { contract_addr, .. } | Pool::Trio { contract_addr, .. } => contract_addr,
+        }
+    }
+
+    fn asset_infos(&self) -> &[AssetInfo] {
+        match self {
+            Pool::Pair { asset_infos, .. } | Pool::Trio { asset_infos, .. } => asset_infos,
+        }
+    }
+
+    fn swap_operation(
+        &self,
+        offer_asset_info: &AssetInfo,
+        ask_asset_info: &AssetInfo,
+    ) -> SwapOperation {
+        match self {
+            Pool::Pair {
+                pair_identifier, ..
+            } => SwapOperation::TerraSwap {
+                offer_asset_info: offer_asset_info.clone(),
+                ask_asset_info: ask_asset_info.clone(),
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: Some(pair_identifier.clone()),
+            },
+            Pool::Trio { asset_infos, .. } => SwapOperation::StableSwap3Pool {
+                offer_asset_info: offer_asset_info.clone(),
+                ask_asset_info: ask_asset_info.clone(),
+                pool_asset_infos: asset_infos.clone(),
+                belief_price: None,
+                max_spread: None,
+            },
+        }
+    }
+
+    /// Simulates swapping the `offer_asset` for the `ask_asset_info` in the pool. Returns [None] if
+    /// the swap can't be simulated or returns nothing, e.g. when the pool has no liquidity.
+    fn simulate(
+        &self,
+        deps: Deps,
+        offer_asset: &Asset,
+        ask_asset_info: &AssetInfo,
+    ) -> Option<Uint128> {
+        let return_amount = match self {
+            Pool::Pair { contract_addr, .. } => {
+                simulate(&deps.querier, contract_addr.clone(), offer_asset)
+                    .ok()?
+                    .return_amount
+            }
+            Pool::Trio { contract_addr, .. } => {
+                simulate_trio(
+                    &deps.querier,
+                    contract_addr.clone(),
+                    offer_asset,
+                    ask_asset_info,
+                )
+                .ok()?
+                .return_amount
+            }
+        };
+
+        (!return_amount.is_zero()).then_some(return_amount)
+    }
+}
+
+/// A route from the offer asset, with the amount it's simulated to return. Every pool is used at
+/// most once in a route, as the simulations don't account for earlier swaps in the same pool.
+struct Route {
+    operations: Vec<SwapOperation>,
+    pools: Vec<Addr>,
+    amount: Uint128,
+}
+
+impl Route {
+    /// Whether the route already goes through the given asset, either as offer or ask asset
+    fn visits(&self, asset_info: &AssetInfo) -> bool {
+        self.operations.iter().any(|operation| {
+            &operation.get_offer_asset_info() == asset_info
+                || &operation.get_target_asset_info() == asset_info
+        })
+    }
+}
+
+/// Finds the route with the best simulated return for swapping the `offer_asset` into the
+/// `ask_asset_info`, searching the pairs and trios created by the factory.
+///
+/// Since the return of a swap grows with its offer amount, only the best route into each asset is
+/// kept after every hop, which keeps the amount of simulations linear in the amount of hops. Only
+/// the first [MAX_POOLS_PER_ASSET] pools of each asset are searched, and the search stops after
+/// [MAX_SIMULATIONS] simulations, returning the best route found by then.
+pub fn find_best_route(
+    deps: Deps,
+    offer_asset: Asset,
+    ask_asset_info: AssetInfo,
+    max_hops: Option<u32>,
+) -> Result<BestRouteResponse, ContractError> {
+    let no_route_error = || ContractError::NoSwapRouteForAssets {
+        offer_asset: offer_asset.info.to_string(),
+        ask_asset: ask_asset_info.to_string(),
+    };
+
+    if offer_asset.info == ask_asset_info || offer_asset.amount.is_zero() {
+        return Err(no_route_error());
+    }
+
+    let max_hops = max_hops.unwrap_or(DEFAULT_MAX_HOPS).min(MAX_HOPS);
+    let config = CONFIG.load(deps.storage)?;
+    let terraswap_factory = deps.api.addr_humanize(&config.terraswap_factory)?;
+
+    // pools holding each of the assets searched from, keyed by asset
+    let mut asset_pools: BTreeMap<String, Vec<Pool>> = BTreeMap::new();
+    let mut simulations = 0usize;
+    let mut best_route: Option<Route> = None;
+    // best route found into each of the assets reached on the last hop, keyed by asset
+    let mut frontier: BTreeMap<String, (AssetInfo, Route)> = BTreeMap::from([(
+        offer_asset.info.to_string(),
+        (
+            offer_asset.info.clone(),
+            Route {
+                operations: vec![],
+                pools: vec![],
+                amount: offer_asset.amount,
+            },
+        ),
+    )]);
+
+    'search: for _ in 0..max_hops {
+        let mut next_frontier: BTreeMap<String, (AssetInfo, Route)> = BTreeMap::new();
+
+        for (asset_info, route) in frontier.values() {
+            let hop_offer_asset = Asset {
+                info: asset_info.clone(),
+                amount: route.amount,
+            };
+
+            let key = asset_info.to_string();
+            if !asset_pools.contains_key(&key) {
+                let pools = query_asset_pools(deps, &terraswap_factory, asset_info)?;
+                asset_pools.insert(key.clone(), pools);
+            }
+
+            for pool in asset_pools[&key]
+                .iter()
+                .filter(|pool| !route.pools.contains(pool.contract_addr()))
+            {
+                for hop_ask_asset_info in pool.asset_infos() {
+                    if hop_ask_asset_info == asset_info || route.visits(hop_ask_asset_info) {
+                        continue;
+                    }
+
+                    if simulations >= MAX_SIMULATIONS {
+                        break 'search;
+                    }
+                    simulations += 1;
+
+                    let Some(amount) = pool.simulate(deps, &hop_offer_asset, hop_ask_asset_info)
+                    else {
+                        continue;
+                    };
+
+                    let mut operations = route.operations.clone();
+                    operations.push(pool.swap_operation(asset_info, hop_ask_asset_info));
+                    let mut pools = route.pools.clone();
+                    pools.push(pool.contract_addr().clone());
+                    let hop_route = Route {
+                        operations,
+                        pools,
+                        amount,
+                    };
+
+                    if hop_ask_asset_info == &ask_asset_info {
+                        if !matches!(&best_route, Some(best_route) if amount <= best_route.amount) {
+                            best_route = Some(hop_route);
+                        }
+                        continue;
+                    }
+
+                    let key = hop_ask_asset_info.to_string();
+                    if !matches!(next_frontier.get(&key), Some((_, next_route)) if amount <= next_route.amount)
+                    {
+                        next_frontier.insert(key, (hop_ask_asset_info.clone(), hop_route));
+                    }
+                }
+            }
+        }
+
+        frontier = next_frontier;
+    }
+
+    let best_route = best_route.ok_or_else(no_route_error)?;
+
+    Ok(BestRouteResponse {
+        operations: best_route.operations,
+        amount: best_route.amount,
+    })
+}
+
+/// Queries the first [MAX_POOLS_PER_ASSET] pairs and trios holding the given asset
+fn query_asset_pools(
+    deps: Deps,
+    terraswap_factory: &Addr,
+    asset_info: &AssetInfo,
+) -> Result<Vec<Pool>, ContractError> {
+    Ok(query_pools_by_asset(
+        &deps.querier,
+        terraswap_factory.clone(),
+        asset_info.clone(),
+        None,
+        Some(MAX_POOLS_PER_ASSET),
+    )?
+    .pools
+    .into_iter()
+    .map(Pool::from)
+    .collect())
+}
@@ -1,12 +1,44 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::CanonicalAddr;
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, CanonicalAddr, Decimal, Order, StdResult, Storage};
+use cw_storage_plus::{Bound, Item, Map};
 use white_whale::pool_network::router::SwapOperation;
 
 #[cw_serde]
 pub struct Config {
+    pub owner: Addr,
+    /// Owner proposed with [white_whale::pool_network::router::ExecuteMsg::ProposeNewOwner], who
+    /// becomes the owner once the ownership is claimed
+    pub pending_owner: Option<Addr>,
     pub terraswap_factory: CanonicalAddr,
+    /// Max spread used on the swap operations that don't set their own
+    pub default_max_spread: Option<Decimal>,
+    /// Max commission a referral can take from the swaps it refers
+    pub max_referral_commission: Decimal,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const SWAP_ROUTES: Map<(&str, &str), Vec<SwapOperation>> = Map::new("swap_routes");
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+/// Reads the swap routes stored after the given (offer asset label, ask asset label) key
+pub fn read_swap_routes(
+    storage: &dyn Storage,
+    start_after: Option<(String, String)>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Vec<SwapOperation>>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_ref().map(|(offer_label, ask_label)| {
+        Bound::exclusive((offer_label.as_str(), ask_label.as_str()))
+    });
+
+    SWAP_ROUTES
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, swap_operations) = item?;
+            Ok(swap_operations)
+        })
+        .collect()
+}
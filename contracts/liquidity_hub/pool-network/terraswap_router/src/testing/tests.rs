@@ -1,17 +1,22 @@
-use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    attr, coin, from_binary, to_binary, Addr, Coin, CosmosMsg, StdError, SubMsg, Uint128, WasmMsg,
+    attr, coin, from_binary, to_binary, Addr, Api, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps,
+    OwnedDeps, StdError, Storage, SubMsg, Uint128, WasmMsg,
 };
+use cw2::{get_contract_version, set_contract_version};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use white_whale::pool_network;
 
-use white_whale::pool_network::asset::{Asset, AssetInfo, PairInfo, PairType};
-use white_whale::pool_network::mock_querier::mock_dependencies;
+use white_whale::pool_network::asset::{Asset, AssetInfo, PairInfo, PairType, TrioInfo};
+use white_whale::pool_network::mock_querier::{mock_dependencies, WasmMockQuerier};
 use white_whale::pool_network::pair::ExecuteMsg as PairExecuteMsg;
 use white_whale::pool_network::router::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
-    SimulateSwapOperationsResponse, SwapOperation, SwapRoute,
+    BestRouteResponse, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg,
+    QueryMsg, Referral, SimulateSplitSwapResponse, SimulateSwapOperationsDetailedResponse,
+    SimulateSwapOperationsResponse, SwapOperation, SwapOperationSimulation, SwapRoute,
+    SwapRouteValidity,
 };
+use white_whale::pool_network::trio::ExecuteMsg as TrioExecuteMsg;
 
 use crate::contract::{execute, instantiate, migrate, query};
 use crate::error::ContractError;
@@ -55,9 +60,12 @@ fn execute_swap_operations() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::ExecuteSwapOperations {
-        operations: vec![],
+        operations: Some(vec![]),
+        ask_asset_info: None,
         minimum_receive: None,
         to: None,
+        deadline: None,
+        referral: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -71,7 +79,7 @@ fn execute_swap_operations() {
     }
 
     let msg = ExecuteMsg::ExecuteSwapOperations {
-        operations: vec![
+        operations: Some(vec![
             SwapOperation::TerraSwap {
                 offer_asset_info: AssetInfo::NativeToken {
                     denom: "ukrw".to_string(),
@@ -79,6 +87,9 @@ fn execute_swap_operations() {
                 ask_asset_info: AssetInfo::Token {
                     contract_addr: "asset0001".to_string(),
                 },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
             },
             SwapOperation::TerraSwap {
                 offer_asset_info: AssetInfo::Token {
@@ -87,6 +98,9 @@ fn execute_swap_operations() {
                 ask_asset_info: AssetInfo::NativeToken {
                     denom: "uluna".to_string(),
                 },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
             },
             SwapOperation::TerraSwap {
                 offer_asset_info: AssetInfo::NativeToken {
@@ -95,10 +109,16 @@ fn execute_swap_operations() {
                 ask_asset_info: AssetInfo::Token {
                     contract_addr: "asset0002".to_string(),
                 },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
             },
-        ],
+        ]),
+        ask_asset_info: None,
         minimum_receive: Some(Uint128::from(1000000u128)),
         to: None,
+        deadline: None,
+        referral: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -117,8 +137,12 @@ fn execute_swap_operations() {
                         ask_asset_info: AssetInfo::Token {
                             contract_addr: "asset0001".to_string(),
                         },
+                        belief_price: None,
+                        max_spread: None,
+                        pair_identifier: None,
                     },
                     to: None,
+                    offer_amount: None,
                 })
                 .unwrap(),
             })),
@@ -133,8 +157,12 @@ fn execute_swap_operations() {
                         ask_asset_info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
                         },
+                        belief_price: None,
+                        max_spread: None,
+                        pair_identifier: None,
                     },
                     to: None,
+                    offer_amount: None,
                 })
                 .unwrap(),
             })),
@@ -149,8 +177,12 @@ fn execute_swap_operations() {
                         ask_asset_info: AssetInfo::Token {
                             contract_addr: "asset0002".to_string(),
                         },
+                        belief_price: None,
+                        max_spread: None,
+                        pair_identifier: None,
                     },
                     to: Some("addr0000".to_string()),
+                    offer_amount: None,
                 })
                 .unwrap(),
             })),
@@ -174,7 +206,7 @@ fn execute_swap_operations() {
         sender: "addr0000".to_string(),
         amount: Uint128::from(1000000u128),
         msg: to_binary(&Cw20HookMsg::ExecuteSwapOperations {
-            operations: vec![
+            operations: Some(vec![
                 SwapOperation::TerraSwap {
                     offer_asset_info: AssetInfo::NativeToken {
                         denom: "ukrw".to_string(),
@@ -182,6 +214,9 @@ fn execute_swap_operations() {
                     ask_asset_info: AssetInfo::Token {
                         contract_addr: "asset0001".to_string(),
                     },
+                    belief_price: None,
+                    max_spread: None,
+                    pair_identifier: None,
                 },
                 SwapOperation::TerraSwap {
                     offer_asset_info: AssetInfo::Token {
@@ -190,6 +225,9 @@ fn execute_swap_operations() {
                     ask_asset_info: AssetInfo::NativeToken {
                         denom: "uluna".to_string(),
                     },
+                    belief_price: None,
+                    max_spread: None,
+                    pair_identifier: None,
                 },
                 SwapOperation::TerraSwap {
                     offer_asset_info: AssetInfo::NativeToken {
@@ -198,10 +236,16 @@ fn execute_swap_operations() {
                     ask_asset_info: AssetInfo::Token {
                         contract_addr: "asset0002".to_string(),
                     },
+                    belief_price: None,
+                    max_spread: None,
+                    pair_identifier: None,
                 },
-            ],
+            ]),
+            ask_asset_info: None,
             minimum_receive: None,
             to: Some("addr0002".to_string()),
+            deadline: None,
+            referral: None,
         })
         .unwrap(),
     });
@@ -222,8 +266,12 @@ fn execute_swap_operations() {
                         ask_asset_info: AssetInfo::Token {
                             contract_addr: "asset0001".to_string(),
                         },
+                        belief_price: None,
+                        max_spread: None,
+                        pair_identifier: None,
                     },
                     to: None,
+                    offer_amount: None,
                 })
                 .unwrap(),
             })),
@@ -238,8 +286,12 @@ fn execute_swap_operations() {
                         ask_asset_info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
                         },
+                        belief_price: None,
+                        max_spread: None,
+                        pair_identifier: None,
                     },
                     to: None,
+                    offer_amount: None,
                 })
                 .unwrap(),
             })),
@@ -254,8 +306,12 @@ fn execute_swap_operations() {
                         ask_asset_info: AssetInfo::Token {
                             contract_addr: "asset0002".to_string(),
                         },
+                        belief_price: None,
+                        max_spread: None,
+                        pair_identifier: None,
                     },
                     to: Some("addr0002".to_string()),
+                    offer_amount: None,
                 })
                 .unwrap(),
             })),
@@ -263,6 +319,93 @@ fn execute_swap_operations() {
     );
 }
 
+#[test]
+fn execute_swap_operations_past_deadline() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let env = mock_env();
+    let deadline = env.block.time.minus_seconds(1);
+    let operations = vec![SwapOperation::TerraSwap {
+        offer_asset_info: AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        },
+        ask_asset_info: AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+        belief_price: None,
+        max_spread: None,
+        pair_identifier: None,
+    }];
+
+    // the swap operations can be executed up until the deadline
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::ExecuteSwapOperations {
+            operations: Some(operations.clone()),
+            ask_asset_info: None,
+            minimum_receive: None,
+            to: None,
+            deadline: Some(env.block.time),
+            referral: None,
+        },
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::ExecuteSwapOperations {
+            operations: Some(operations.clone()),
+            ask_asset_info: None,
+            minimum_receive: None,
+            to: None,
+            deadline: Some(deadline),
+            referral: None,
+        },
+    );
+    match res {
+        Err(ContractError::DeadlineExceeded {
+            deadline: err_deadline,
+            current_time,
+        }) => {
+            assert_eq!(err_deadline, deadline);
+            assert_eq!(current_time, env.block.time);
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info("asset0000", &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr0000".to_string(),
+            amount: Uint128::from(1_000_000u128),
+            msg: to_binary(&Cw20HookMsg::ExecuteSwapOperations {
+                operations: Some(operations),
+                ask_asset_info: None,
+                minimum_receive: None,
+                to: None,
+                deadline: Some(deadline),
+                referral: None,
+            })
+            .unwrap(),
+        }),
+    );
+    match res {
+        Err(ContractError::DeadlineExceeded { .. }) => {}
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+}
+
 #[test]
 fn execute_swap_operation() {
     let mut deps = mock_dependencies(&[]);
@@ -314,8 +457,12 @@ fn execute_swap_operation() {
             ask_asset_info: AssetInfo::Token {
                 contract_addr: "asset0000".to_string(),
             },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
         },
         to: None,
+        offer_amount: None,
     };
     let info = mock_info("addr0000", &[]);
     let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
@@ -340,6 +487,7 @@ fn execute_swap_operation() {
                 },
                 None,
                 None,
+                None,
             )
             .unwrap()
         )],
@@ -355,8 +503,12 @@ fn execute_swap_operation() {
             ask_asset_info: AssetInfo::Token {
                 contract_addr: "asset0000".to_string(),
             },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
         },
         to: Some("addr0000".to_string()),
+        offer_amount: None,
     };
     let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -373,6 +525,7 @@ fn execute_swap_operation() {
                     amount: Uint128::from(1000000u128),
                 },
                 None,
+                None,
                 Some("addr0000".to_string()),
             )
             .unwrap()
@@ -413,8 +566,12 @@ fn execute_swap_operation() {
             ask_asset_info: AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
         },
         to: Some("addr0000".to_string()),
+        offer_amount: None,
     };
 
     let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
@@ -431,6 +588,7 @@ fn execute_swap_operation() {
                     belief_price: None,
                     max_spread: None,
                     to: Some("addr0000".to_string()),
+                    deadline: None,
                 })
                 .unwrap(),
             })
@@ -440,549 +598,2729 @@ fn execute_swap_operation() {
 }
 
 #[test]
-fn query_buy_with_routes() {
+fn execute_swap_operation_with_pair_identifier() {
     let mut deps = mock_dependencies(&[]);
-
-    let msg = InstantiateMsg {
-        terraswap_factory: "terraswapfactory".to_string(),
+    let uusd = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
     };
-
-    let info = mock_info("addr0000", &[]);
-
-    // we can just call .unwrap() to assert this was a success
-    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
-
-    // try simulating with empty operations
-    let empty_operations_msg = QueryMsg::SimulateSwapOperations {
-        offer_amount: Uint128::from(1000000u128),
-        operations: vec![],
+    let asset0000 = AssetInfo::Token {
+        contract_addr: "asset0000".to_string(),
+    };
+    let pair = |contract_addr: &str| PairInfo {
+        asset_infos: [uusd.clone(), asset0000.clone()],
+        contract_addr: contract_addr.to_string(),
+        liquidity_token: AssetInfo::Token {
+            contract_addr: format!("liquidity{contract_addr}"),
+        },
+        asset_decimals: [6u8, 6u8],
+        pair_type: PairType::ConstantProduct,
     };
 
-    let error = query(deps.as_ref(), mock_env(), empty_operations_msg).unwrap_err();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            terraswap_factory: "terraswapfactory".to_string(),
+        },
+    )
+    .unwrap();
 
-    match error {
-        ContractError::NoSwapOperationsProvided {} => (),
-        _ => panic!("should return ContractError::NoSwapOperationsProvided"),
-    }
+    // pair0000 is the default pair of the assets, pair0001 a pair of the same assets created later
+    // with an identifier sorting before the default one
+    deps.querier.with_pool_factory(
+        &[
+            (&"uusdasset0000".to_string(), &pair("pair0000")),
+            (&"uusdasset0000other".to_string(), &pair("pair0001")),
+        ],
+        &[("uusd".to_string(), 6u8)],
+    );
+    deps.querier.with_pair_identifiers(&[(
+        &"pair0001".to_string(),
+        &"0ConstantProduct-0.001".to_string(),
+    )]);
+    deps.querier
+        .with_simulation_rates(&[(&"pair0001".to_string(), Decimal::percent(110))]);
+    deps.querier.with_balance(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![coin(1000000u128, "uusd")],
+    )]);
 
-    let msg = QueryMsg::SimulateSwapOperations {
-        offer_amount: Uint128::from(1000000u128),
-        operations: vec![
-            SwapOperation::TerraSwap {
-                offer_asset_info: AssetInfo::NativeToken {
-                    denom: "ukrw".to_string(),
-                },
-                ask_asset_info: AssetInfo::Token {
-                    contract_addr: "asset0000".to_string(),
+    let swap_msg = |deps: Deps, contract_addr: &str| {
+        SubMsg::new(
+            asset_into_swap_msg(
+                deps,
+                Addr::unchecked(contract_addr),
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    amount: Uint128::from(1000000u128),
                 },
+                None,
+                None,
+                None,
+            )
+            .unwrap(),
+        )
+    };
+
+    // swaps through the default pair if no identifier is given
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::ExecuteSwapOperation {
+            operation: SwapOperation::TerraSwap {
+                offer_asset_info: uusd.clone(),
+                ask_asset_info: asset0000.clone(),
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
             },
-            SwapOperation::TerraSwap {
-                offer_asset_info: AssetInfo::Token {
-                    contract_addr: "asset0000".to_string(),
-                },
-                ask_asset_info: AssetInfo::NativeToken {
-                    denom: "uluna".to_string(),
+            to: None,
+            offer_amount: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![swap_msg(deps.as_ref(), "pair0000")]);
+
+    // the best route is pinned to the pair it was simulated on
+    let res: BestRouteResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::FindBestRoute {
+                offer_asset: Asset {
+                    info: uusd.clone(),
+                    amount: Uint128::from(1000000u128),
                 },
+                ask_asset_info: asset0000.clone(),
+                max_hops: Some(1),
             },
-        ],
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let operation = SwapOperation::TerraSwap {
+        offer_asset_info: uusd,
+        ask_asset_info: asset0000,
+        belief_price: None,
+        max_spread: None,
+        pair_identifier: Some("0ConstantProduct-0.001".to_string()),
     };
-
-    deps.querier.with_pool_factory(
-        &[
-            (
-                &"ukrwasset0000".to_string(),
-                &PairInfo {
-                    asset_infos: [
-                        AssetInfo::NativeToken {
-                            denom: "ukrw".to_string(),
-                        },
-                        AssetInfo::Token {
-                            contract_addr: "asset0000".to_string(),
-                        },
-                    ],
-                    contract_addr: "pair0000".to_string(),
-                    liquidity_token: AssetInfo::Token {
-                        contract_addr: "liquidity0000".to_string(),
-                    },
-                    asset_decimals: [6u8, 6u8],
-                    pair_type: PairType::ConstantProduct,
-                },
-            ),
-            (
-                &"asset0000uluna".to_string(),
-                &PairInfo {
-                    asset_infos: [
-                        AssetInfo::Token {
-                            contract_addr: "asset0000".to_string(),
-                        },
-                        AssetInfo::NativeToken {
-                            denom: "uluna".to_string(),
-                        },
-                    ],
-                    contract_addr: "pair0001".to_string(),
-                    liquidity_token: AssetInfo::Token {
-                        contract_addr: "liquidity0001".to_string(),
-                    },
-                    asset_decimals: [6u8, 6u8],
-                    pair_type: PairType::ConstantProduct,
-                },
-            ),
-        ],
-        &[("ukrw".to_string(), 6u8), ("uluna".to_string(), 6u8)],
-    );
-
-    let res: SimulateSwapOperationsResponse =
-        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
     assert_eq!(
         res,
-        SimulateSwapOperationsResponse {
-            amount: Uint128::from(1000000u128)
+        BestRouteResponse {
+            operations: vec![operation.clone()],
+            amount: Uint128::from(1100000u128),
         }
     );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::ExecuteSwapOperation {
+            operation,
+            to: None,
+            offer_amount: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![swap_msg(deps.as_ref(), "pair0001")]);
 }
 
 #[test]
-fn query_reverse_routes_with_from_native() {
+fn execute_swap_operation_stableswap_3pool() {
     let mut deps = mock_dependencies(&[]);
-
     let msg = InstantiateMsg {
         terraswap_factory: "terraswapfactory".to_string(),
     };
 
-    let target_amount = 1000000u128;
-
-    let info = mock_info("addr0000", &[coin(10000000, "ukrw")]);
-
-    // we can just call .unwrap() to assert this was a success
+    let info = mock_info("addr0000", &[]);
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
+    let pool_asset_infos = vec![
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: "uusdc".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+    ];
+    deps.querier.with_trios(&[(
+        &"uusduusdcasset0000".to_string(),
+        &TrioInfo {
+            asset_infos: pool_asset_infos.clone(),
+            contract_addr: "trio0000".to_string(),
+            liquidity_token: AssetInfo::Token {
+                contract_addr: "liquidity0000".to_string(),
+            },
+            asset_decimals: vec![6u8, 6u8, 6u8],
+        },
+    )]);
     deps.querier.with_balance(&[(
         &MOCK_CONTRACT_ADDR.to_string(),
         [Coin {
             amount: Uint128::from(1000000u128),
-            denom: "ukrw".to_string(),
+            denom: "uusd".to_string(),
         }]
         .to_vec(),
     )]);
-
     deps.querier.with_token_balances(&[(
-        &"asset0001".to_string(),
+        &"asset0000".to_string(),
         &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000000u128))],
     )]);
 
-    // try simulating with empty operations
-    let empty_operations_msg = QueryMsg::ReverseSimulateSwapOperations {
-        ask_amount: Uint128::from(target_amount),
-        operations: vec![],
-    };
-
-    let error = query(deps.as_ref(), mock_env(), empty_operations_msg).unwrap_err();
-
-    match error {
-        ContractError::NoSwapOperationsProvided {} => (),
-        _ => panic!("should return ContractError::NoSwapOperationsProvided"),
-    }
-
-    let msg = QueryMsg::ReverseSimulateSwapOperations {
-        ask_amount: Uint128::from(target_amount),
-        operations: vec![SwapOperation::TerraSwap {
+    let msg = ExecuteMsg::ExecuteSwapOperation {
+        operation: SwapOperation::StableSwap3Pool {
             offer_asset_info: AssetInfo::NativeToken {
-                denom: "ukrw".to_string(),
+                denom: "uusd".to_string(),
             },
-            ask_asset_info: AssetInfo::Token {
-                contract_addr: "asset0000".to_string(),
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uusdc".to_string(),
             },
-        }],
+            pool_asset_infos: pool_asset_infos.clone(),
+            belief_price: None,
+            max_spread: None,
+        },
+        to: None,
+        offer_amount: None,
     };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
 
-    deps.querier.with_pool_factory(
-        &[
-            (
-                &"ukrwasset0000".to_string(),
-                &PairInfo {
-                    contract_addr: "pair0000".to_string(),
-                    liquidity_token: AssetInfo::Token {
-                        contract_addr: "liquidity0000".to_string(),
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "trio0000".to_string(),
+            funds: vec![coin(1000000u128, "uusd")],
+            msg: to_binary(&TrioExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
                     },
-                    asset_infos: [
-                        AssetInfo::Token {
-                            contract_addr: "asset0000".to_string(),
-                        },
-                        AssetInfo::NativeToken {
-                            denom: "ukrw".to_string(),
-                        },
-                    ],
-                    asset_decimals: [8u8, 6u8],
-                    pair_type: PairType::ConstantProduct,
+                    amount: Uint128::from(1000000u128),
                 },
-            ),
-            (
-                &"asset0000uluna".to_string(),
-                &PairInfo {
-                    contract_addr: "pair0001".to_string(),
-                    liquidity_token: AssetInfo::Token {
-                        contract_addr: "liquidity0001".to_string(),
-                    },
-                    asset_infos: [
-                        AssetInfo::Token {
-                            contract_addr: "asset0000".to_string(),
-                        },
-                        AssetInfo::NativeToken {
-                            denom: "uluna".to_string(),
-                        },
-                    ],
-                    asset_decimals: [8u8, 6u8],
-                    pair_type: PairType::ConstantProduct,
+                ask_asset: AssetInfo::NativeToken {
+                    denom: "uusdc".to_string(),
                 },
-            ),
-        ],
-        &[("ukrw".to_string(), 6u8), ("uluna".to_string(), 6u8)],
-    );
-
-    let res: SimulateSwapOperationsResponse =
-        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
-
-    assert_eq!(
-        res,
-        SimulateSwapOperationsResponse {
-            amount: Uint128::from(1000000u128),
-        }
+                belief_price: None,
+                max_spread: None,
+                to: None,
+                deadline: None,
+            })
+            .unwrap(),
+        }))],
     );
 
-    let offer_amount = res.amount;
-
     let msg = ExecuteMsg::ExecuteSwapOperation {
-        operation: SwapOperation::TerraSwap {
-            offer_asset_info: AssetInfo::NativeToken {
-                denom: "ukrw".to_string(),
-            },
-            ask_asset_info: AssetInfo::Token {
+        operation: SwapOperation::StableSwap3Pool {
+            offer_asset_info: AssetInfo::Token {
                 contract_addr: "asset0000".to_string(),
             },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            pool_asset_infos: pool_asset_infos.clone(),
+            belief_price: None,
+            max_spread: None,
         },
-        to: None,
+        to: Some("addr0000".to_string()),
+        offer_amount: None,
     };
-    let info = mock_info("addr0", &[coin(offer_amount.u128(), "ukrw")]);
-    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
-    match res {
-        Err(ContractError::Unauthorized {}) => (),
-        _ => panic!("DO NOT ENTER HERE"),
-    }
-
     let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-
     assert_eq!(
         res.messages,
         vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: "pair0000".to_string(),
-            funds: vec![coin(target_amount, "ukrw")],
-            msg: to_binary(&PairExecuteMsg::Swap {
-                offer_asset: Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: "ukrw".to_string(),
+            contract_addr: "asset0000".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "trio0000".to_string(),
+                amount: Uint128::from(1000000u128),
+                msg: to_binary(&pool_network::trio::Cw20HookMsg::Swap {
+                    ask_asset: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
                     },
-                    amount: Uint128::from(target_amount),
-                },
-                belief_price: None,
-                max_spread: None,
-                to: None,
+                    belief_price: None,
+                    max_spread: None,
+                    to: Some("addr0000".to_string()),
+                    deadline: None,
+                })
+                .unwrap(),
             })
             .unwrap(),
-        })),],
+        }))]
     );
+
+    // the trio must exist in the factory
+    let msg = ExecuteMsg::ExecuteSwapOperation {
+        operation: SwapOperation::StableSwap3Pool {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uusdc".to_string(),
+            },
+            pool_asset_infos: pool_asset_infos[..2].to_vec(),
+            belief_price: None,
+            max_spread: None,
+        },
+        to: None,
+        offer_amount: None,
+    };
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
 }
 
 #[test]
-fn query_reverse_routes_with_to_native() {
+fn query_routes_with_stableswap_3pool() {
     let mut deps = mock_dependencies(&[]);
 
     let msg = InstantiateMsg {
         terraswap_factory: "terraswapfactory".to_string(),
     };
 
-    let target_amount = 1000000u128;
-
     let info = mock_info("addr0000", &[]);
-
-    // we can just call .unwrap() to assert this was a success
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-    deps.querier.with_token_balances(&[
-        (
-            &"asset0000".to_string(),
-            &[(&"pair0000".to_string(), &Uint128::from(1000000u128))],
-        ),
-        (
-            &"asset0000".to_string(),
-            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000000u128))],
-        ),
-    ]);
-
-    let msg = QueryMsg::ReverseSimulateSwapOperations {
-        ask_amount: Uint128::from(target_amount),
-        operations: vec![SwapOperation::TerraSwap {
-            offer_asset_info: AssetInfo::Token {
-                contract_addr: "asset0000".to_string(),
-            },
-            ask_asset_info: AssetInfo::NativeToken {
-                denom: "ukrw".to_string(),
-            },
-        }],
-    };
-
+    let pool_asset_infos = vec![
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: "uusdc".to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: "uusdt".to_string(),
+        },
+    ];
     deps.querier.with_pool_factory(
-        &[
-            (
-                &"ukrwasset0000".to_string(),
-                &PairInfo {
-                    contract_addr: "pair0000".to_string(),
-                    liquidity_token: AssetInfo::Token {
-                        contract_addr: "liquidity0000".to_string(),
+        &[(
+            &"ukrwuusd".to_string(),
+            &PairInfo {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: "ukrw".to_string(),
                     },
-                    asset_infos: [
-                        AssetInfo::Token {
-                            contract_addr: "asset0000".to_string(),
-                        },
-                        AssetInfo::NativeToken {
-                            denom: "ukrw".to_string(),
-                        },
-                    ],
-                    asset_decimals: [8u8, 6u8],
-                    pair_type: PairType::ConstantProduct,
-                },
-            ),
-            (
-                &"asset0000uluna".to_string(),
-                &PairInfo {
-                    contract_addr: "pair0001".to_string(),
-                    liquidity_token: AssetInfo::Token {
-                        contract_addr: "liquidity0001".to_string(),
+                    AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
                     },
-                    asset_infos: [
-                        AssetInfo::Token {
-                            contract_addr: "asset0000".to_string(),
-                        },
-                        AssetInfo::NativeToken {
-                            denom: "uluna".to_string(),
-                        },
-                    ],
-                    asset_decimals: [8u8, 6u8],
-                    pair_type: PairType::ConstantProduct,
+                ],
+                contract_addr: "pair0000".to_string(),
+                liquidity_token: AssetInfo::Token {
+                    contract_addr: "liquidity0000".to_string(),
                 },
-            ),
-        ],
-        &[("ukrw".to_string(), 6u8), ("uluna".to_string(), 6u8)],
+                asset_decimals: [6u8, 6u8],
+                pair_type: PairType::ConstantProduct,
+            },
+        )],
+        &[("ukrw".to_string(), 6u8), ("uusd".to_string(), 6u8)],
     );
+    deps.querier.with_trios(&[(
+        &"uusduusdcuusdt".to_string(),
+        &TrioInfo {
+            asset_infos: pool_asset_infos.clone(),
+            contract_addr: "trio0000".to_string(),
+            liquidity_token: AssetInfo::Token {
+                contract_addr: "liquidity0001".to_string(),
+            },
+            asset_decimals: vec![6u8, 6u8, 6u8],
+        },
+    )]);
+    // the trio returns 99% of the offer amount
+    deps.querier
+        .with_simulation_rates(&[(&"trio0000".to_string(), Decimal::percent(99))]);
 
-    let res: SimulateSwapOperationsResponse =
-        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    let operations = vec![
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
+        },
+        SwapOperation::StableSwap3Pool {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uusdt".to_string(),
+            },
+            pool_asset_infos,
+            belief_price: None,
+            max_spread: None,
+        },
+    ];
 
+    let res: SimulateSwapOperationsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SimulateSwapOperations {
+                offer_amount: Uint128::from(1000000u128),
+                operations: operations.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
     assert_eq!(
         res,
         SimulateSwapOperationsResponse {
-            amount: Uint128::from(target_amount),
+            amount: Uint128::from(990000u128)
         }
     );
 
-    let offer_amount = res.amount;
+    // 1_000_000 / 99% = 1_010_101.01 uusd, rounded up, are needed from the pair
+    let res: SimulateSwapOperationsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ReverseSimulateSwapOperations {
+                ask_amount: Uint128::from(1000000u128),
+                operations: operations.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        SimulateSwapOperationsResponse {
+            amount: Uint128::from(1010102u128)
+        }
+    );
 
-    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-        sender: "addr0".to_string(),
-        amount: offer_amount,
-        msg: to_binary(&Cw20HookMsg::ExecuteSwapOperations {
-            operations: vec![SwapOperation::TerraSwap {
-                offer_asset_info: AssetInfo::Token {
-                    contract_addr: "asset0000".to_string(),
+    // the route can be stored
+    let swap_route = SwapRoute {
+        offer_asset_info: AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        },
+        ask_asset_info: AssetInfo::NativeToken {
+            denom: "uusdt".to_string(),
+        },
+        swap_operations: operations.clone(),
+    };
+    let info = mock_info("addr0000", &[]);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::AddSwapRoutes {
+            swap_routes: vec![swap_route],
+        },
+    )
+    .unwrap();
+
+    let route: Vec<SwapOperation> = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SwapRoute {
+                offer_asset_info: AssetInfo::NativeToken {
+                    denom: "ukrw".to_string(),
                 },
                 ask_asset_info: AssetInfo::NativeToken {
-                    denom: "ukrw".to_string(),
+                    denom: "uusdt".to_string(),
                 },
-            }],
-            minimum_receive: None,
-            to: None,
-        })
+            },
+        )
         .unwrap(),
-    });
-    let info = mock_info("addr0", &[]);
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    )
+    .unwrap();
+    assert_eq!(route, operations);
+}
 
-    assert_eq!(
-        res.messages,
-        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: MOCK_CONTRACT_ADDR.to_string(),
-            funds: vec![],
-            msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
-                operation: SwapOperation::TerraSwap {
-                    offer_asset_info: AssetInfo::Token {
-                        contract_addr: "asset0000".to_string(),
-                    },
-                    ask_asset_info: AssetInfo::NativeToken {
-                        denom: "ukrw".to_string(),
-                    },
-                },
-                to: Some("addr0".to_string()),
-            })
-            .unwrap(),
-        })),],
-    );
-
-    let msg = ExecuteMsg::ExecuteSwapOperation {
-        operation: SwapOperation::TerraSwap {
-            offer_asset_info: AssetInfo::Token {
-                contract_addr: "asset0000".to_string(),
-            },
-            ask_asset_info: AssetInfo::NativeToken {
-                denom: "ukrw".to_string(),
-            },
+/// Sets up ukrw-uusd, uusd-uluna and ukrw-uluna pairs, plus a uusd-uusdc-uluna trio. The direct
+/// ukrw-uluna pair returns less than the others.
+fn setup_route_search(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let native = |denom: &str| AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    };
+    let pair = |contract_addr: &str, asset_infos: [AssetInfo; 2]| PairInfo {
+        asset_infos,
+        contract_addr: contract_addr.to_string(),
+        liquidity_token: AssetInfo::Token {
+            contract_addr: format!("liquidity{contract_addr}"),
         },
-        to: None,
+        asset_decimals: [6u8, 6u8],
+        pair_type: PairType::ConstantProduct,
     };
 
-    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            terraswap_factory: "terraswapfactory".to_string(),
+        },
+    )
+    .unwrap();
 
-    assert_eq!(
-        res.messages,
-        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: "asset0000".to_string(),
-            funds: vec![],
-            msg: to_binary(&Cw20ExecuteMsg::Send {
-                contract: "pair0000".to_string(),
-                amount: Uint128::from(target_amount),
-                msg: to_binary(&pool_network::pair::Cw20HookMsg::Swap {
-                    belief_price: None,
-                    max_spread: None,
-                    to: None,
-                })
-                .unwrap(),
-            })
-            .unwrap(),
-        }))],
+    deps.querier.with_pool_factory(
+        &[
+            (
+                &"ukrwuusd".to_string(),
+                &pair("pair0000", [native("ukrw"), native("uusd")]),
+            ),
+            (
+                &"uusduluna".to_string(),
+                &pair("pair0001", [native("uusd"), native("uluna")]),
+            ),
+            (
+                &"ukrwuluna".to_string(),
+                &pair("pair0002", [native("ukrw"), native("uluna")]),
+            ),
+        ],
+        &[],
     );
+    deps.querier.with_trios(&[(
+        &"uusduusdculuna".to_string(),
+        &TrioInfo {
+            asset_infos: vec![native("uusd"), native("uusdc"), native("uluna")],
+            contract_addr: "trio0000".to_string(),
+            liquidity_token: AssetInfo::Token {
+                contract_addr: "liquiditytrio0000".to_string(),
+            },
+            asset_decimals: vec![6u8, 6u8, 6u8],
+        },
+    )]);
+    deps.querier.with_simulation_rates(&[
+        (&"pair0002".to_string(), Decimal::percent(90)),
+        (&"trio0000".to_string(), Decimal::percent(105)),
+    ]);
 }
 
 #[test]
-fn assert_minimum_receive_native_token() {
+fn find_best_route() {
     let mut deps = mock_dependencies(&[]);
-    deps.querier.with_balance(&[(
-        &"addr0000".to_string(),
-        [Coin {
-            denom: "uusd".to_string(),
-            amount: Uint128::from(1000000u128),
-        }]
-        .to_vec(),
-    )]);
+    setup_route_search(&mut deps);
 
-    let info = mock_info("addr0000", &[]);
-    // success
-    let msg = ExecuteMsg::AssertMinimumReceive {
-        asset_info: AssetInfo::NativeToken {
-            denom: "uusd".to_string(),
+    let offer_asset = Asset {
+        info: AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
         },
-        prev_balance: Uint128::zero(),
-        minimum_receive: Uint128::from(1000000u128),
-        receiver: "addr0000".to_string(),
+        amount: Uint128::from(1000000u128),
     };
-    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
-
-    // assertion failed; native token
-    let msg = ExecuteMsg::AssertMinimumReceive {
-        asset_info: AssetInfo::NativeToken {
-            denom: "uusd".to_string(),
-        },
-        prev_balance: Uint128::zero(),
-        minimum_receive: Uint128::from(1000001u128),
-        receiver: "addr0000".to_string(),
+    let ask_asset_info = AssetInfo::NativeToken {
+        denom: "uluna".to_string(),
     };
-    let res = execute(deps.as_mut(), mock_env(), info, msg);
-    match res {
-        Err(ContractError::MinimumReceiveAssertion {
-            minimum_receive,
-            swap_amount,
-        }) => {
-            assert_eq!(minimum_receive, Uint128::new(1000001));
-            assert_eq!(swap_amount, Uint128::new(1000000));
-        }
-        _ => panic!("DO NOT ENTER HERE"),
-    }
-}
 
-#[test]
-fn assert_minimum_receive_token() {
-    let mut deps = mock_dependencies(&[]);
-    deps.querier.with_token_balances(&[(
-        &"token0000".to_string(),
-        &[(&"addr0000".to_string(), &Uint128::from(1000000u128))],
-    )]);
+    // the trio gives the best return
+    let res: BestRouteResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::FindBestRoute {
+                offer_asset: offer_asset.clone(),
+                ask_asset_info: ask_asset_info.clone(),
+                max_hops: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        BestRouteResponse {
+            operations: vec![
+                SwapOperation::TerraSwap {
+                    offer_asset_info: AssetInfo::NativeToken {
+                        denom: "ukrw".to_string(),
+                    },
+                    ask_asset_info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    belief_price: None,
+                    max_spread: None,
+                    pair_identifier: Some("ConstantProduct-0.003".to_string()),
+                },
+                SwapOperation::StableSwap3Pool {
+                    offer_asset_info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    ask_asset_info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                    pool_asset_infos: vec![
+                        AssetInfo::NativeToken {
+                            denom: "uusd".to_string(),
+                        },
+                        AssetInfo::NativeToken {
+                            denom: "uusdc".to_string(),
+                        },
+                        AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                    ],
+                    belief_price: None,
+                    max_spread: None,
+                },
+            ],
+            amount: Uint128::from(1050000u128),
+        }
+    );
 
-    let info = mock_info("addr0000", &[]);
-    // success
-    let msg = ExecuteMsg::AssertMinimumReceive {
-        asset_info: AssetInfo::Token {
-            contract_addr: "token0000".to_string(),
-        },
-        prev_balance: Uint128::zero(),
-        minimum_receive: Uint128::from(1000000u128),
-        receiver: "addr0000".to_string(),
-    };
-    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    // only the direct pair can be used with a single hop
+    let res: BestRouteResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::FindBestRoute {
+                offer_asset: offer_asset.clone(),
+                ask_asset_info,
+                max_hops: Some(1),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        BestRouteResponse {
+            operations: vec![SwapOperation::TerraSwap {
+                offer_asset_info: AssetInfo::NativeToken {
+                    denom: "ukrw".to_string(),
+                },
+                ask_asset_info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: Some("ConstantProduct-0.003".to_string()),
+            }],
+            amount: Uint128::from(900000u128),
+        }
+    );
 
-    // assertion failed; native token
-    let msg = ExecuteMsg::AssertMinimumReceive {
-        asset_info: AssetInfo::Token {
-            contract_addr: "token0000".to_string(),
+    // no pool holds the ask asset
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::FindBestRoute {
+            offer_asset,
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uatom".to_string(),
+            },
+            max_hops: None,
         },
-        prev_balance: Uint128::zero(),
-        minimum_receive: Uint128::from(1000001u128),
-        receiver: "addr0000".to_string(),
-    };
-    let res = execute(deps.as_mut(), mock_env(), info, msg);
-    match res {
-        Err(ContractError::MinimumReceiveAssertion {
-            minimum_receive,
-            swap_amount,
-        }) => {
-            assert_eq!(minimum_receive, Uint128::new(1000001));
-            assert_eq!(swap_amount, Uint128::new(1000000));
-        }
-        _ => panic!("DO NOT ENTER HERE"),
+    )
+    .unwrap_err();
+    match err {
+        ContractError::NoSwapRouteForAssets { .. } => (),
+        _ => panic!("should return ContractError::NoSwapRouteForAssets"),
     }
 }
 
 #[test]
-fn can_migrate_contract() {
+fn find_best_route_searches_a_bounded_amount_of_pools() {
     let mut deps = mock_dependencies(&[]);
-    let msg = InstantiateMsg {
-        terraswap_factory: "terraswap_factory".to_string(),
+    let native = |denom: &str| AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    };
+    let pair = |contract_addr: String, asset_infos: [AssetInfo; 2]| PairInfo {
+        asset_infos,
+        liquidity_token: AssetInfo::Token {
+            contract_addr: format!("liquidity{contract_addr}"),
+        },
+        contract_addr,
+        asset_decimals: [6u8, 6u8],
+        pair_type: PairType::ConstantProduct,
     };
 
-    let info = mock_info("addr0000", &[]);
-    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            terraswap_factory: "terraswapfactory".to_string(),
+        },
+    )
+    .unwrap();
 
-    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+    // a direct ukrw-uluna pair, 30 ukrw pairs leading nowhere, and a better route through uusd
+    // whose ukrw pool comes after all of them
+    let mut pairs = vec![(
+        "ukrwuluna".to_string(),
+        pair("pair0000".to_string(), [native("ukrw"), native("uluna")]),
+    )];
+    // the mock querier keys the pairs by their sorted characters, so the denoms differ in length
+    pairs.extend((1..=30).map(|i| {
+        let denom = format!("asset{}", "x".repeat(i));
+        (
+            format!("ukrw{denom}"),
+            pair(format!("pair{i:04}"), [native("ukrw"), native(&denom)]),
+        )
+    }));
+    pairs.push((
+        "ukrwuusd".to_string(),
+        pair("pair0031".to_string(), [native("ukrw"), native("uusd")]),
+    ));
+    pairs.push((
+        "uusduluna".to_string(),
+        pair("pair0032".to_string(), [native("uusd"), native("uluna")]),
+    ));
+    deps.querier.with_pool_factory(
+        &pairs
+            .iter()
+            .map(|(key, pair)| (key, pair))
+            .collect::<Vec<(&String, &PairInfo)>>(),
+        &[],
+    );
+    deps.querier
+        .with_simulation_rates(&[(&"pair0000".to_string(), Decimal::percent(90))]);
 
-    // should not be able to migrate as the version is lower
-    match res {
-        Err(ContractError::MigrateInvalidVersion { .. }) => (),
-        _ => panic!("should return ContractError::MigrateInvalidVersion"),
-    }
+    let res: BestRouteResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::FindBestRoute {
+                offer_asset: Asset {
+                    info: native("ukrw"),
+                    amount: Uint128::from(1000000u128),
+                },
+                ask_asset_info: native("uluna"),
+                max_hops: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        BestRouteResponse {
+            operations: vec![SwapOperation::TerraSwap {
+                offer_asset_info: native("ukrw"),
+                ask_asset_info: native("uluna"),
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: Some("ConstantProduct-0.003".to_string()),
+            }],
+            amount: Uint128::from(900000u128),
+        }
+    );
 }
 
 #[test]
-fn add_swap_routes() {
+fn execute_swap_operations_with_best_route() {
     let mut deps = mock_dependencies(&[]);
+    setup_route_search(&mut deps);
 
-    let msg = InstantiateMsg {
-        terraswap_factory: "terraswapfactory".to_string(),
-    };
-
-    let info = mock_info("creator", &[]);
-
-    // we can just call .unwrap() to assert this was a success
-    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    // a single native coin must be sent to know the offer asset
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::ExecuteSwapOperations {
+            operations: None,
+            ask_asset_info: Some(AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            }),
+            minimum_receive: None,
+            to: None,
+            deadline: None,
+            referral: None,
+        },
+    )
+    .unwrap_err();
+    match err {
+        ContractError::InvalidRouteSearchFunds {} => (),
+        _ => panic!("should return ContractError::InvalidRouteSearchFunds"),
+    }
 
-    deps.querier.with_pool_factory(
+    // either the operations or the ask asset must be given
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1000000u128, "ukrw")]),
+        ExecuteMsg::ExecuteSwapOperations {
+            operations: None,
+            ask_asset_info: None,
+            minimum_receive: None,
+            to: None,
+            deadline: None,
+            referral: None,
+        },
+    )
+    .unwrap_err();
+    match err {
+        ContractError::NoSwapOperationsProvided {} => (),
+        _ => panic!("should return ContractError::NoSwapOperationsProvided"),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1000000u128, "ukrw")]),
+        ExecuteMsg::ExecuteSwapOperations {
+            operations: None,
+            ask_asset_info: Some(AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            }),
+            minimum_receive: None,
+            to: None,
+            deadline: None,
+            referral: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+            funds: vec![],
+            msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                operation: SwapOperation::TerraSwap {
+                    offer_asset_info: AssetInfo::NativeToken {
+                        denom: "ukrw".to_string(),
+                    },
+                    ask_asset_info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    belief_price: None,
+                    max_spread: None,
+                    pair_identifier: Some("ConstantProduct-0.003".to_string()),
+                },
+                to: Some("addr0000".to_string()),
+                offer_amount: None,
+            })
+            .unwrap(),
+        }))]
+    );
+}
+
+fn split_routes() -> Vec<(Decimal, Vec<SwapOperation>)> {
+    vec![
+        (
+            Decimal::percent(70),
+            vec![SwapOperation::TerraSwap {
+                offer_asset_info: AssetInfo::NativeToken {
+                    denom: "ukrw".to_string(),
+                },
+                ask_asset_info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
+            }],
+        ),
+        (
+            Decimal::percent(30),
+            vec![
+                SwapOperation::TerraSwap {
+                    offer_asset_info: AssetInfo::NativeToken {
+                        denom: "ukrw".to_string(),
+                    },
+                    ask_asset_info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    belief_price: None,
+                    max_spread: None,
+                    pair_identifier: None,
+                },
+                SwapOperation::TerraSwap {
+                    offer_asset_info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    ask_asset_info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                    belief_price: None,
+                    max_spread: None,
+                    pair_identifier: None,
+                },
+            ],
+        ),
+    ]
+}
+
+#[test]
+fn execute_split_swap() {
+    let mut deps = mock_dependencies(&[]);
+    setup_route_search(&mut deps);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1000001u128, "ukrw")]),
+        ExecuteMsg::ExecuteSplitSwap {
+            routes: split_routes(),
+            minimum_receive: Some(Uint128::from(900000u128)),
+            to: Some("addr0001".to_string()),
+            deadline: None,
+        },
+    )
+    .unwrap();
+
+    // the last route takes the remainder of the offer amount
+    let routes = split_routes();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: routes[0].1[0].clone(),
+                    offer_amount: Some(Uint128::from(700000u128)),
+                    to: Some("addr0001".to_string()),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: routes[1].1[0].clone(),
+                    offer_amount: Some(Uint128::from(300001u128)),
+                    to: None,
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: routes[1].1[1].clone(),
+                    offer_amount: None,
+                    to: Some("addr0001".to_string()),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::AssertMinimumReceive {
+                    asset_info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                    prev_balance: Uint128::zero(),
+                    minimum_receive: Uint128::from(900000u128),
+                    receiver: "addr0001".to_string(),
+                })
+                .unwrap(),
+            })),
+        ]
+    );
+
+    // the offer asset must be sent
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1000000u128, "uusd")]),
+        ExecuteMsg::ExecuteSplitSwap {
+            routes: split_routes(),
+            minimum_receive: None,
+            to: None,
+            deadline: None,
+        },
+    )
+    .unwrap_err();
+    match err {
+        ContractError::InvalidSplitSwapFunds {} => (),
+        _ => panic!("should return ContractError::InvalidSplitSwapFunds"),
+    }
+
+    // the weights must add up to 1
+    let mut routes = split_routes();
+    routes[1].0 = Decimal::percent(20);
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1000000u128, "ukrw")]),
+        ExecuteMsg::ExecuteSplitSwap {
+            routes,
+            minimum_receive: None,
+            to: None,
+            deadline: None,
+        },
+    )
+    .unwrap_err();
+    match err {
+        ContractError::InvalidSplitWeights {} => (),
+        _ => panic!("should return ContractError::InvalidSplitWeights"),
+    }
+
+    // all routes must end in the same asset
+    let mut routes = split_routes();
+    routes[1].1.pop();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1000000u128, "ukrw")]),
+        ExecuteMsg::ExecuteSplitSwap {
+            routes,
+            minimum_receive: None,
+            to: None,
+            deadline: None,
+        },
+    )
+    .unwrap_err();
+    match err {
+        ContractError::InvalidSplitRoutes {} => (),
+        _ => panic!("should return ContractError::InvalidSplitRoutes"),
+    }
+}
+
+#[test]
+fn execute_split_swap_token_offer() {
+    let mut deps = mock_dependencies(&[]);
+    setup_route_search(&mut deps);
+
+    let routes = vec![
+        (
+            Decimal::percent(50),
+            vec![SwapOperation::TerraSwap {
+                offer_asset_info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                ask_asset_info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
+            }],
+        ),
+        (
+            Decimal::percent(50),
+            vec![SwapOperation::TerraSwap {
+                offer_asset_info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                ask_asset_info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
+            }],
+        ),
+    ];
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("asset0000", &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr0000".to_string(),
+            amount: Uint128::from(1000000u128),
+            msg: to_binary(&Cw20HookMsg::ExecuteSplitSwap {
+                routes: routes.clone(),
+                minimum_receive: None,
+                to: None,
+                deadline: None,
+            })
+            .unwrap(),
+        }),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        routes
+            .into_iter()
+            .map(
+                |(_, operations)| SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                    funds: vec![],
+                    msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                        operation: operations[0].clone(),
+                        offer_amount: Some(Uint128::from(500000u128)),
+                        to: Some("addr0000".to_string()),
+                    })
+                    .unwrap(),
+                }))
+            )
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn execute_swap_operations_with_referral() {
+    let mut deps = mock_dependencies(&[]);
+    setup_route_search(&mut deps);
+
+    let operations = vec![
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
+        },
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
+        },
+    ];
+    let swap_msg = |referral: Referral| ExecuteMsg::ExecuteSwapOperations {
+        operations: Some(operations.clone()),
+        ask_asset_info: None,
+        minimum_receive: Some(Uint128::from(900000u128)),
+        to: Some("addr0001".to_string()),
+        deadline: None,
+        referral: Some(referral),
+    };
+
+    // referrals are disabled until a max commission is set
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1000000u128, "ukrw")]),
+        swap_msg(Referral {
+            address: "referral0000".to_string(),
+            commission: Decimal::permille(5),
+        }),
+    )
+    .unwrap_err();
+    match err {
+        ContractError::ReferralCommissionTooHigh {
+            commission,
+            max_commission,
+        } => {
+            assert_eq!(commission, Decimal::permille(5));
+            assert_eq!(max_commission, Decimal::zero());
+        }
+        _ => panic!("should return ContractError::ReferralCommissionTooHigh"),
+    }
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateConfig {
+            default_max_spread: None,
+            max_referral_commission: Some(Decimal::percent(101)),
+            remove_default_max_spread: None,
+        },
+    )
+    .unwrap_err();
+    match err {
+        ContractError::InvalidMaxReferralCommission { .. } => (),
+        _ => panic!("should return ContractError::InvalidMaxReferralCommission"),
+    }
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateConfig {
+            default_max_spread: None,
+            max_referral_commission: Some(Decimal::percent(1)),
+            remove_default_max_spread: None,
+        },
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1000000u128, "ukrw")]),
+        swap_msg(Referral {
+            address: "referral0000".to_string(),
+            commission: Decimal::percent(2),
+        }),
+    )
+    .unwrap_err();
+    match err {
+        ContractError::ReferralCommissionTooHigh { .. } => (),
+        _ => panic!("should return ContractError::ReferralCommissionTooHigh"),
+    }
+
+    // the swap returns to the router, which takes the commission before the minimum receive
+    // assertion
+    let referral = Referral {
+        address: "referral0000".to_string(),
+        commission: Decimal::percent(1),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1000000u128, "ukrw")]),
+        swap_msg(referral.clone()),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: operations[0].clone(),
+                    offer_amount: None,
+                    to: None,
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: operations[1].clone(),
+                    offer_amount: None,
+                    to: Some(MOCK_CONTRACT_ADDR.to_string()),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::TakeReferralCommission {
+                    asset_info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                    prev_balance: Uint128::zero(),
+                    referral: referral.clone(),
+                    receiver: "addr0001".to_string(),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::AssertMinimumReceive {
+                    asset_info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                    prev_balance: Uint128::zero(),
+                    minimum_receive: Uint128::from(900000u128),
+                    receiver: "addr0001".to_string(),
+                })
+                .unwrap(),
+            })),
+        ]
+    );
+
+    let take_referral_commission_msg = ExecuteMsg::TakeReferralCommission {
+        asset_info: AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        },
+        prev_balance: Uint128::from(100u128),
+        referral,
+        receiver: "addr0001".to_string(),
+    };
+
+    // only the router can take the commission
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        take_referral_commission_msg.clone(),
+    )
+    .unwrap_err();
+    match err {
+        ContractError::Unauthorized {} => (),
+        _ => panic!("should return ContractError::Unauthorized"),
+    }
+
+    deps.querier.with_balance(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![coin(1000100u128, "uluna")],
+    )]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        take_referral_commission_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "referral0000".to_string(),
+                amount: vec![coin(10000u128, "uluna")],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0001".to_string(),
+                amount: vec![coin(990000u128, "uluna")],
+            })),
+        ]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "take_referral_commission"),
+            attr("referral", "referral0000"),
+            attr("commission", "0.01"),
+            attr("commission_amount", "10000"),
+        ]
+    );
+}
+
+#[test]
+fn execute_swap_operations_exact_out() {
+    let mut deps = mock_dependencies(&[]);
+    setup_route_search(&mut deps);
+
+    let operations = vec![
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
+        },
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
+        },
+    ];
+
+    // pair0001 returns 80% of the offer amount, so 600000 / 80% = 750000ukrw are needed and the
+    // rest is refunded
+    deps.querier
+        .with_simulation_rates(&[(&"pair0001".to_string(), Decimal::percent(80))]);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1000000u128, "ukrw")]),
+        ExecuteMsg::ExecuteSwapOperationsExactOut {
+            operations: operations.clone(),
+            ask_amount: Uint128::from(600000u128),
+            max_offer_amount: None,
+            to: Some("addr0001".to_string()),
+            deadline: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: vec![coin(250000u128, "ukrw")],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: operations[0].clone(),
+                    offer_amount: Some(Uint128::from(750000u128)),
+                    to: None,
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: operations[1].clone(),
+                    offer_amount: None,
+                    to: Some("addr0001".to_string()),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::AssertMinimumReceive {
+                    asset_info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                    prev_balance: Uint128::zero(),
+                    minimum_receive: Uint128::from(600000u128),
+                    receiver: "addr0001".to_string(),
+                })
+                .unwrap(),
+            })),
+        ]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "execute_swap_operations_exact_out"),
+            attr("offer_amount", "750000"),
+            attr("refund_amount", "250000"),
+        ]
+    );
+
+    // the swap fails if it needs more than the max offer amount
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1000000u128, "ukrw")]),
+        ExecuteMsg::ExecuteSwapOperationsExactOut {
+            operations: operations.clone(),
+            ask_amount: Uint128::from(600000u128),
+            max_offer_amount: Some(Uint128::from(500000u128)),
+            to: None,
+            deadline: None,
+        },
+    )
+    .unwrap_err();
+    match err {
+        ContractError::MaxOfferAmountAssertion {
+            max_offer_amount,
+            offer_amount,
+        } => {
+            assert_eq!(max_offer_amount, Uint128::from(500000u128));
+            assert_eq!(offer_amount, Uint128::from(750000u128));
+        }
+        _ => panic!("should return ContractError::MaxOfferAmountAssertion"),
+    }
+
+    // or more than what was sent
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(500000u128, "ukrw")]),
+        ExecuteMsg::ExecuteSwapOperationsExactOut {
+            operations: operations.clone(),
+            ask_amount: Uint128::from(600000u128),
+            max_offer_amount: Some(Uint128::from(1000000u128)),
+            to: None,
+            deadline: None,
+        },
+    )
+    .unwrap_err();
+    match err {
+        ContractError::MaxOfferAmountAssertion { .. } => (),
+        _ => panic!("should return ContractError::MaxOfferAmountAssertion"),
+    }
+
+    // the offer asset must be sent
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1000000u128, "uusd")]),
+        ExecuteMsg::ExecuteSwapOperationsExactOut {
+            operations,
+            ask_amount: Uint128::from(600000u128),
+            max_offer_amount: None,
+            to: None,
+            deadline: None,
+        },
+    )
+    .unwrap_err();
+    match err {
+        ContractError::InvalidExactOutSwapFunds {} => (),
+        _ => panic!("should return ContractError::InvalidExactOutSwapFunds"),
+    }
+}
+
+#[test]
+fn execute_swap_operations_exact_out_tops_up_the_offer() {
+    let mut deps = mock_dependencies(&[]);
+    setup_route_search(&mut deps);
+
+    let operations = vec![
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
+        },
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
+        },
+    ];
+
+    // pair0001 returns 80% of the offer amount but reverse simulates at 81%, so the 740741ukrw it
+    // asks for only return 592592uluna. The offer is topped up by the 7408uluna shortfall at the
+    // rate of the route, 7408 * 740741 / 592592 = 9260.1, plus a unit
+    deps.querier
+        .with_simulation_rates(&[(&"pair0001".to_string(), Decimal::percent(80))]);
+    deps.querier
+        .with_reverse_simulation_rates(&[(&"pair0001".to_string(), Decimal::percent(81))]);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1000000u128, "ukrw")]),
+        ExecuteMsg::ExecuteSwapOperationsExactOut {
+            operations: operations.clone(),
+            ask_amount: Uint128::from(600000u128),
+            max_offer_amount: None,
+            to: None,
+            deadline: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "execute_swap_operations_exact_out"),
+            attr("offer_amount", "750002"),
+            attr("refund_amount", "249998"),
+        ]
+    );
+
+    // the route can't return anything
+    deps.querier
+        .with_simulation_rates(&[(&"pair0001".to_string(), Decimal::zero())]);
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1000000u128, "ukrw")]),
+        ExecuteMsg::ExecuteSwapOperationsExactOut {
+            operations,
+            ask_amount: Uint128::from(600000u128),
+            max_offer_amount: None,
+            to: None,
+            deadline: None,
+        },
+    )
+    .unwrap_err();
+    match err {
+        ContractError::ExactOutAskAmountUnreachable { ask_amount } => {
+            assert_eq!(ask_amount, Uint128::from(600000u128))
+        }
+        _ => panic!("should return ContractError::ExactOutAskAmountUnreachable"),
+    }
+}
+#[test]
+fn simulate_split_swap() {
+    let mut deps = mock_dependencies(&[]);
+    setup_route_search(&mut deps);
+
+    // the direct ukrw-uluna pair returns 90%
+    let res: SimulateSplitSwapResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SimulateSplitSwap {
+                offer_amount: Uint128::from(1000000u128),
+                routes: split_routes(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        SimulateSplitSwapResponse {
+            route_amounts: vec![Uint128::from(630000u128), Uint128::from(300000u128)],
+            amount: Uint128::from(930000u128),
+        }
+    );
+}
+
+#[test]
+fn update_config() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+    let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        default_max_spread: Some(Decimal::percent(2)),
+        max_referral_commission: None,
+        remove_default_max_spread: None,
+    };
+
+    // only the contract admin can update the config
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("unauthorized", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    match err {
+        ContractError::Unauthorized {} => (),
+        _ => panic!("should return ContractError::Unauthorized"),
+    }
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator", &[]),
+        ExecuteMsg::UpdateConfig {
+            default_max_spread: Some(Decimal::percent(101)),
+            max_referral_commission: None,
+            remove_default_max_spread: None,
+        },
+    )
+    .unwrap_err();
+    match err {
+        ContractError::InvalidMaxSpread { max_spread } => {
+            assert_eq!(max_spread, Decimal::percent(101))
+        }
+        _ => panic!("should return ContractError::InvalidMaxSpread"),
+    }
+
+    execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+    let config: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(
+        config,
+        ConfigResponse {
+            owner: "creator".to_string(),
+            pending_owner: None,
+            terraswap_factory: "terraswapfactory".to_string(),
+            default_max_spread: Some(Decimal::percent(2)),
+            max_referral_commission: Decimal::zero(),
+        }
+    );
+
+    // updating the max referral commission only leaves the default max spread unchanged
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator", &[]),
+        ExecuteMsg::UpdateConfig {
+            default_max_spread: None,
+            remove_default_max_spread: None,
+            max_referral_commission: Some(Decimal::percent(1)),
+        },
+    )
+    .unwrap();
+    let config: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config.default_max_spread, Some(Decimal::percent(2)));
+    assert_eq!(config.max_referral_commission, Decimal::percent(1));
+
+    // the default max spread is only removed explicitly
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator", &[]),
+        ExecuteMsg::UpdateConfig {
+            default_max_spread: None,
+            remove_default_max_spread: Some(true),
+            max_referral_commission: None,
+        },
+    )
+    .unwrap();
+    let config: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config.default_max_spread, None);
+    assert_eq!(config.max_referral_commission, Decimal::percent(1));
+}
+
+#[test]
+fn execute_swap_operation_with_slippage_protection() {
+    let mut deps = mock_dependencies(&[]);
+    setup_route_search(&mut deps);
+
+    let operation = |max_spread: Option<Decimal>| SwapOperation::TerraSwap {
+        offer_asset_info: AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        },
+        ask_asset_info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        belief_price: Some(Decimal::percent(99)),
+        max_spread,
+        pair_identifier: None,
+    };
+    let swap_msg = |deps: Deps, max_spread: Option<Decimal>| {
+        SubMsg::new(
+            asset_into_swap_msg(
+                deps,
+                Addr::unchecked("pair0000"),
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "ukrw".to_string(),
+                    },
+                    amount: Uint128::from(1000000u128),
+                },
+                Some(Decimal::percent(99)),
+                max_spread,
+                None,
+            )
+            .unwrap(),
+        )
+    };
+
+    // the belief price and max spread are forwarded to the pair
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::ExecuteSwapOperation {
+            operation: operation(Some(Decimal::percent(1))),
+            offer_amount: Some(Uint128::from(1000000u128)),
+            to: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![swap_msg(deps.as_ref(), Some(Decimal::percent(1)))]
+    );
+
+    // the default max spread is used if the operation doesn't set one
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateConfig {
+            default_max_spread: Some(Decimal::percent(2)),
+            max_referral_commission: None,
+            remove_default_max_spread: None,
+        },
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::ExecuteSwapOperation {
+            operation: operation(None),
+            offer_amount: Some(Uint128::from(1000000u128)),
+            to: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![swap_msg(deps.as_ref(), Some(Decimal::percent(2)))]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::ExecuteSwapOperation {
+            operation: operation(Some(Decimal::percent(1))),
+            offer_amount: Some(Uint128::from(1000000u128)),
+            to: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![swap_msg(deps.as_ref(), Some(Decimal::percent(1)))]
+    );
+}
+
+#[test]
+fn query_buy_with_routes() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // try simulating with empty operations
+    let empty_operations_msg = QueryMsg::SimulateSwapOperations {
+        offer_amount: Uint128::from(1000000u128),
+        operations: vec![],
+    };
+
+    let error = query(deps.as_ref(), mock_env(), empty_operations_msg).unwrap_err();
+
+    match error {
+        ContractError::NoSwapOperationsProvided {} => (),
+        _ => panic!("should return ContractError::NoSwapOperationsProvided"),
+    }
+
+    let msg = QueryMsg::SimulateSwapOperations {
+        offer_amount: Uint128::from(1000000u128),
+        operations: vec![
+            SwapOperation::TerraSwap {
+                offer_asset_info: AssetInfo::NativeToken {
+                    denom: "ukrw".to_string(),
+                },
+                ask_asset_info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
+            },
+            SwapOperation::TerraSwap {
+                offer_asset_info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                ask_asset_info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
+            },
+        ],
+    };
+
+    deps.querier.with_pool_factory(
+        &[
+            (
+                &"ukrwasset0000".to_string(),
+                &PairInfo {
+                    asset_infos: [
+                        AssetInfo::NativeToken {
+                            denom: "ukrw".to_string(),
+                        },
+                        AssetInfo::Token {
+                            contract_addr: "asset0000".to_string(),
+                        },
+                    ],
+                    contract_addr: "pair0000".to_string(),
+                    liquidity_token: AssetInfo::Token {
+                        contract_addr: "liquidity0000".to_string(),
+                    },
+                    asset_decimals: [6u8, 6u8],
+                    pair_type: PairType::ConstantProduct,
+                },
+            ),
+            (
+                &"asset0000uluna".to_string(),
+                &PairInfo {
+                    asset_infos: [
+                        AssetInfo::Token {
+                            contract_addr: "asset0000".to_string(),
+                        },
+                        AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                    ],
+                    contract_addr: "pair0001".to_string(),
+                    liquidity_token: AssetInfo::Token {
+                        contract_addr: "liquidity0001".to_string(),
+                    },
+                    asset_decimals: [6u8, 6u8],
+                    pair_type: PairType::ConstantProduct,
+                },
+            ),
+        ],
+        &[("ukrw".to_string(), 6u8), ("uluna".to_string(), 6u8)],
+    );
+
+    let res: SimulateSwapOperationsResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        SimulateSwapOperationsResponse {
+            amount: Uint128::from(1000000u128)
+        }
+    );
+}
+
+#[test]
+fn simulate_swap_operations_detailed() {
+    let mut deps = mock_dependencies(&[]);
+    setup_route_search(&mut deps);
+
+    let operations = vec![
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
+        },
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
+        },
+    ];
+
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::SimulateSwapOperationsDetailed {
+            offer_amount: Uint128::from(1000000u128),
+            operations: vec![],
+        },
+    )
+    .unwrap_err();
+    match err {
+        ContractError::NoSwapOperationsProvided {} => (),
+        _ => panic!("should return ContractError::NoSwapOperationsProvided"),
+    }
+
+    // pair0002 returns 90% of the offer amount, the rest being spread
+    let res: SimulateSwapOperationsDetailedResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SimulateSwapOperationsDetailed {
+                offer_amount: Uint128::from(1000000u128),
+                operations: operations.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        SimulateSwapOperationsDetailedResponse {
+            amount: Uint128::from(900000u128),
+            simulations: vec![
+                SwapOperationSimulation {
+                    operation: operations[0].clone(),
+                    pool: "pair0002".to_string(),
+                    offer_amount: Uint128::from(1000000u128),
+                    return_amount: Uint128::from(900000u128),
+                    spread_amount: Uint128::from(100000u128),
+                    swap_fee_amount: Uint128::zero(),
+                    protocol_fee_amount: Uint128::zero(),
+                    burn_fee_amount: Uint128::zero(),
+                },
+                SwapOperationSimulation {
+                    operation: operations[1].clone(),
+                    pool: "pair0001".to_string(),
+                    offer_amount: Uint128::from(900000u128),
+                    return_amount: Uint128::from(900000u128),
+                    spread_amount: Uint128::zero(),
+                    swap_fee_amount: Uint128::zero(),
+                    protocol_fee_amount: Uint128::zero(),
+                    burn_fee_amount: Uint128::zero(),
+                },
+            ],
+            price_impact: Decimal::percent(10),
+        }
+    );
+
+    // the amount matches the one of the regular simulation
+    let res: SimulateSwapOperationsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SimulateSwapOperations {
+                offer_amount: Uint128::from(1000000u128),
+                operations: operations.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.amount, Uint128::from(900000u128));
+
+    // the hops are reverse simulated from the last one, but returned in the order of the operations.
+    // pair0002 needs 1000 / 90% = 1111.1 ukrw, rounded up, to return the uluna of the second hop
+    let res: SimulateSwapOperationsDetailedResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ReverseSimulateSwapOperationsDetailed {
+                ask_amount: Uint128::from(1000u128),
+                operations: operations.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        SimulateSwapOperationsDetailedResponse {
+            amount: Uint128::from(1112u128),
+            simulations: vec![
+                SwapOperationSimulation {
+                    operation: operations[0].clone(),
+                    pool: "pair0002".to_string(),
+                    offer_amount: Uint128::from(1112u128),
+                    return_amount: Uint128::from(1000u128),
+                    spread_amount: Uint128::from(112u128),
+                    swap_fee_amount: Uint128::zero(),
+                    protocol_fee_amount: Uint128::zero(),
+                    burn_fee_amount: Uint128::zero(),
+                },
+                SwapOperationSimulation {
+                    operation: operations[1].clone(),
+                    pool: "pair0001".to_string(),
+                    offer_amount: Uint128::from(1000u128),
+                    return_amount: Uint128::from(1000u128),
+                    spread_amount: Uint128::zero(),
+                    swap_fee_amount: Uint128::zero(),
+                    protocol_fee_amount: Uint128::zero(),
+                    burn_fee_amount: Uint128::zero(),
+                },
+            ],
+            price_impact: Decimal::one() - Decimal::from_ratio(1000u128, 1112u128),
+        }
+    );
+}
+
+#[test]
+fn query_reverse_routes_with_from_native() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+
+    let target_amount = 1000000u128;
+
+    let info = mock_info("addr0000", &[coin(10000000, "ukrw")]);
+
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps.querier.with_balance(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        [Coin {
+            amount: Uint128::from(1000000u128),
+            denom: "ukrw".to_string(),
+        }]
+        .to_vec(),
+    )]);
+
+    deps.querier.with_token_balances(&[(
+        &"asset0001".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000000u128))],
+    )]);
+
+    // try simulating with empty operations
+    let empty_operations_msg = QueryMsg::ReverseSimulateSwapOperations {
+        ask_amount: Uint128::from(target_amount),
+        operations: vec![],
+    };
+
+    let error = query(deps.as_ref(), mock_env(), empty_operations_msg).unwrap_err();
+
+    match error {
+        ContractError::NoSwapOperationsProvided {} => (),
+        _ => panic!("should return ContractError::NoSwapOperationsProvided"),
+    }
+
+    let msg = QueryMsg::ReverseSimulateSwapOperations {
+        ask_amount: Uint128::from(target_amount),
+        operations: vec![SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
+        }],
+    };
+
+    deps.querier.with_pool_factory(
+        &[
+            (
+                &"ukrwasset0000".to_string(),
+                &PairInfo {
+                    contract_addr: "pair0000".to_string(),
+                    liquidity_token: AssetInfo::Token {
+                        contract_addr: "liquidity0000".to_string(),
+                    },
+                    asset_infos: [
+                        AssetInfo::Token {
+                            contract_addr: "asset0000".to_string(),
+                        },
+                        AssetInfo::NativeToken {
+                            denom: "ukrw".to_string(),
+                        },
+                    ],
+                    asset_decimals: [8u8, 6u8],
+                    pair_type: PairType::ConstantProduct,
+                },
+            ),
+            (
+                &"asset0000uluna".to_string(),
+                &PairInfo {
+                    contract_addr: "pair0001".to_string(),
+                    liquidity_token: AssetInfo::Token {
+                        contract_addr: "liquidity0001".to_string(),
+                    },
+                    asset_infos: [
+                        AssetInfo::Token {
+                            contract_addr: "asset0000".to_string(),
+                        },
+                        AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                    ],
+                    asset_decimals: [8u8, 6u8],
+                    pair_type: PairType::ConstantProduct,
+                },
+            ),
+        ],
+        &[("ukrw".to_string(), 6u8), ("uluna".to_string(), 6u8)],
+    );
+
+    let res: SimulateSwapOperationsResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+
+    assert_eq!(
+        res,
+        SimulateSwapOperationsResponse {
+            amount: Uint128::from(1000000u128),
+        }
+    );
+
+    let offer_amount = res.amount;
+
+    let msg = ExecuteMsg::ExecuteSwapOperation {
+        operation: SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
+        },
+        to: None,
+        offer_amount: None,
+    };
+    let info = mock_info("addr0", &[coin(offer_amount.u128(), "ukrw")]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "pair0000".to_string(),
+            funds: vec![coin(target_amount, "ukrw")],
+            msg: to_binary(&PairExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "ukrw".to_string(),
+                    },
+                    amount: Uint128::from(target_amount),
+                },
+                belief_price: None,
+                max_spread: None,
+                to: None,
+                deadline: None,
+            })
+            .unwrap(),
+        })),],
+    );
+}
+
+#[test]
+fn query_reverse_routes_with_to_native() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+
+    let target_amount = 1000000u128;
+
+    let info = mock_info("addr0000", &[]);
+
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps.querier.with_token_balances(&[
+        (
+            &"asset0000".to_string(),
+            &[(&"pair0000".to_string(), &Uint128::from(1000000u128))],
+        ),
+        (
+            &"asset0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000000u128))],
+        ),
+    ]);
+
+    let msg = QueryMsg::ReverseSimulateSwapOperations {
+        ask_amount: Uint128::from(target_amount),
+        operations: vec![SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
+        }],
+    };
+
+    deps.querier.with_pool_factory(
+        &[
+            (
+                &"ukrwasset0000".to_string(),
+                &PairInfo {
+                    contract_addr: "pair0000".to_string(),
+                    liquidity_token: AssetInfo::Token {
+                        contract_addr: "liquidity0000".to_string(),
+                    },
+                    asset_infos: [
+                        AssetInfo::Token {
+                            contract_addr: "asset0000".to_string(),
+                        },
+                        AssetInfo::NativeToken {
+                            denom: "ukrw".to_string(),
+                        },
+                    ],
+                    asset_decimals: [8u8, 6u8],
+                    pair_type: PairType::ConstantProduct,
+                },
+            ),
+            (
+                &"asset0000uluna".to_string(),
+                &PairInfo {
+                    contract_addr: "pair0001".to_string(),
+                    liquidity_token: AssetInfo::Token {
+                        contract_addr: "liquidity0001".to_string(),
+                    },
+                    asset_infos: [
+                        AssetInfo::Token {
+                            contract_addr: "asset0000".to_string(),
+                        },
+                        AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                    ],
+                    asset_decimals: [8u8, 6u8],
+                    pair_type: PairType::ConstantProduct,
+                },
+            ),
+        ],
+        &[("ukrw".to_string(), 6u8), ("uluna".to_string(), 6u8)],
+    );
+
+    let res: SimulateSwapOperationsResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+
+    assert_eq!(
+        res,
+        SimulateSwapOperationsResponse {
+            amount: Uint128::from(target_amount),
+        }
+    );
+
+    let offer_amount = res.amount;
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0".to_string(),
+        amount: offer_amount,
+        msg: to_binary(&Cw20HookMsg::ExecuteSwapOperations {
+            operations: Some(vec![SwapOperation::TerraSwap {
+                offer_asset_info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                ask_asset_info: AssetInfo::NativeToken {
+                    denom: "ukrw".to_string(),
+                },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
+            }]),
+            ask_asset_info: None,
+            minimum_receive: None,
+            to: None,
+            deadline: None,
+            referral: None,
+        })
+        .unwrap(),
+    });
+    let info = mock_info("addr0", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+            funds: vec![],
+            msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                operation: SwapOperation::TerraSwap {
+                    offer_asset_info: AssetInfo::Token {
+                        contract_addr: "asset0000".to_string(),
+                    },
+                    ask_asset_info: AssetInfo::NativeToken {
+                        denom: "ukrw".to_string(),
+                    },
+                    belief_price: None,
+                    max_spread: None,
+                    pair_identifier: None,
+                },
+                to: Some("addr0".to_string()),
+                offer_amount: None,
+            })
+            .unwrap(),
+        })),],
+    );
+
+    let msg = ExecuteMsg::ExecuteSwapOperation {
+        operation: SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+            belief_price: None,
+            max_spread: None,
+            pair_identifier: None,
+        },
+        to: None,
+        offer_amount: None,
+    };
+
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "asset0000".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "pair0000".to_string(),
+                amount: Uint128::from(target_amount),
+                msg: to_binary(&pool_network::pair::Cw20HookMsg::Swap {
+                    belief_price: None,
+                    max_spread: None,
+                    to: None,
+                    deadline: None,
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+        }))],
+    );
+}
+
+#[test]
+fn assert_minimum_receive_native_token() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_balance(&[(
+        &"addr0000".to_string(),
+        [Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }]
+        .to_vec(),
+    )]);
+
+    let info = mock_info("addr0000", &[]);
+    // success
+    let msg = ExecuteMsg::AssertMinimumReceive {
+        asset_info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        prev_balance: Uint128::zero(),
+        minimum_receive: Uint128::from(1000000u128),
+        receiver: "addr0000".to_string(),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    // assertion failed; native token
+    let msg = ExecuteMsg::AssertMinimumReceive {
+        asset_info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        prev_balance: Uint128::zero(),
+        minimum_receive: Uint128::from(1000001u128),
+        receiver: "addr0000".to_string(),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    match res {
+        Err(ContractError::MinimumReceiveAssertion {
+            minimum_receive,
+            swap_amount,
+        }) => {
+            assert_eq!(minimum_receive, Uint128::new(1000001));
+            assert_eq!(swap_amount, Uint128::new(1000000));
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+}
+
+#[test]
+fn assert_minimum_receive_token() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_balances(&[(
+        &"token0000".to_string(),
+        &[(&"addr0000".to_string(), &Uint128::from(1000000u128))],
+    )]);
+
+    let info = mock_info("addr0000", &[]);
+    // success
+    let msg = ExecuteMsg::AssertMinimumReceive {
+        asset_info: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
+        prev_balance: Uint128::zero(),
+        minimum_receive: Uint128::from(1000000u128),
+        receiver: "addr0000".to_string(),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    // assertion failed; native token
+    let msg = ExecuteMsg::AssertMinimumReceive {
+        asset_info: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
+        prev_balance: Uint128::zero(),
+        minimum_receive: Uint128::from(1000001u128),
+        receiver: "addr0000".to_string(),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    match res {
+        Err(ContractError::MinimumReceiveAssertion {
+            minimum_receive,
+            swap_amount,
+        }) => {
+            assert_eq!(minimum_receive, Uint128::new(1000001));
+            assert_eq!(swap_amount, Uint128::new(1000000));
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+}
+
+#[test]
+fn can_migrate_contract() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswap_factory".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+
+    // should not be able to migrate as the version is lower
+    match res {
+        Err(ContractError::MigrateInvalidVersion { .. }) => (),
+        _ => panic!("should return ContractError::MigrateInvalidVersion"),
+    }
+}
+
+#[test]
+fn migrate_from_v110() {
+    let mut deps = mock_dependencies(&[]);
+
+    // the v1.1.0 config only held the factory
+    let terraswap_factory = deps.api.addr_canonicalize("terraswapfactory").unwrap();
+    deps.storage.set(
+        b"config",
+        format!(
+            r#"{{"terraswap_factory":"{}"}}"#,
+            Binary::from(terraswap_factory.as_slice()).to_base64()
+        )
+        .as_bytes(),
+    );
+    set_contract_version(&mut deps.storage, "white_whale-pool_router", "1.1.0").unwrap();
+
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    // the contract admin becomes the owner, and the new settings are left unset
+    let config: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(
+        config,
+        ConfigResponse {
+            owner: "creator".to_string(),
+            pending_owner: None,
+            terraswap_factory: "terraswapfactory".to_string(),
+            default_max_spread: None,
+            max_referral_commission: Decimal::zero(),
+        }
+    );
+    assert_eq!(
+        get_contract_version(&deps.storage).unwrap().version,
+        env!("CARGO_PKG_VERSION")
+    );
+}
+
+#[test]
+fn add_swap_routes() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+
+    let info = mock_info("creator", &[]);
+
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps.querier.with_pool_factory(
+        &[
+            (
+                &"ukrwasset0000".to_string(),
+                &PairInfo {
+                    asset_infos: [
+                        AssetInfo::NativeToken {
+                            denom: "ukrw".to_string(),
+                        },
+                        AssetInfo::Token {
+                            contract_addr: "asset0000".to_string(),
+                        },
+                    ],
+                    contract_addr: "pair0000".to_string(),
+                    liquidity_token: AssetInfo::Token {
+                        contract_addr: "liquidity0000".to_string(),
+                    },
+                    asset_decimals: [6u8, 6u8],
+                    pair_type: PairType::ConstantProduct,
+                },
+            ),
+            (
+                &"asset0000uluna".to_string(),
+                &PairInfo {
+                    asset_infos: [
+                        AssetInfo::Token {
+                            contract_addr: "asset0000".to_string(),
+                        },
+                        AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                    ],
+                    contract_addr: "pair0001".to_string(),
+                    liquidity_token: AssetInfo::Token {
+                        contract_addr: "liquidity0001".to_string(),
+                    },
+                    asset_decimals: [6u8, 6u8],
+                    pair_type: PairType::ConstantProduct,
+                },
+            ),
+            (
+                &"ulunauwhale".to_string(),
+                &PairInfo {
+                    asset_infos: [
+                        AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                        AssetInfo::NativeToken {
+                            denom: "uwhale".to_string(),
+                        },
+                    ],
+                    contract_addr: "pair0002".to_string(),
+                    liquidity_token: AssetInfo::Token {
+                        contract_addr: "liquidity0002".to_string(),
+                    },
+                    asset_decimals: [6u8, 6u8],
+                    pair_type: PairType::ConstantProduct,
+                },
+            ),
+        ],
+        &[
+            ("ukrw".to_string(), 6u8),
+            ("uluna".to_string(), 6u8),
+            ("uwhale".to_string(), 6u8),
+        ],
+    );
+
+    let swap_route_1 = SwapRoute {
+        offer_asset_info: AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        },
+        ask_asset_info: AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        },
+        swap_operations: vec![
+            SwapOperation::TerraSwap {
+                offer_asset_info: AssetInfo::NativeToken {
+                    denom: "ukrw".to_string(),
+                },
+                ask_asset_info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
+            },
+            SwapOperation::TerraSwap {
+                offer_asset_info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                ask_asset_info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
+            },
+        ],
+    };
+
+    let swap_route_2 = SwapRoute {
+        offer_asset_info: AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        },
+        ask_asset_info: AssetInfo::NativeToken {
+            denom: "uwhale".to_string(),
+        },
+        swap_operations: vec![
+            SwapOperation::TerraSwap {
+                offer_asset_info: AssetInfo::NativeToken {
+                    denom: "ukrw".to_string(),
+                },
+                ask_asset_info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
+            },
+            SwapOperation::TerraSwap {
+                offer_asset_info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                ask_asset_info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
+            },
+            SwapOperation::TerraSwap {
+                offer_asset_info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                ask_asset_info: AssetInfo::NativeToken {
+                    denom: "uwhale".to_string(),
+                },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
+            },
+        ],
+    };
+
+    let swap_route_1_key = SWAP_ROUTES.key((
+        swap_route_1
+            .clone()
+            .offer_asset_info
+            .get_label(&deps.as_ref())
+            .unwrap()
+            .as_str(),
+        swap_route_1
+            .clone()
+            .ask_asset_info
+            .get_label(&deps.as_ref())
+            .unwrap()
+            .as_str(),
+    ));
+
+    let swap_route_2_key = SWAP_ROUTES.key((
+        swap_route_2
+            .clone()
+            .offer_asset_info
+            .get_label(&deps.as_ref())
+            .unwrap()
+            .as_str(),
+        swap_route_2
+            .clone()
+            .ask_asset_info
+            .get_label(&deps.as_ref())
+            .unwrap()
+            .as_str(),
+    ));
+
+    // verify the keys are not there
+    assert_eq!(None, swap_route_1_key.may_load(&deps.storage).unwrap());
+    assert_eq!(None, swap_route_2_key.may_load(&deps.storage).unwrap());
+
+    // add swap route
+    let msg = ExecuteMsg::AddSwapRoutes {
+        swap_routes: vec![swap_route_1.clone(), swap_route_2.clone()],
+    };
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+    let expected_attributes = vec![
+        attr("action", "add_swap_routes"),
+        attr("swap_route", swap_route_1.to_string()),
+        attr("swap_route", swap_route_2.to_string()),
+    ];
+
+    assert_eq!(res.messages.len(), 0usize);
+    assert_eq!(res.attributes, expected_attributes);
+
+    // query swap route
+    let msg = QueryMsg::SwapRoute {
+        offer_asset_info: swap_route_1.offer_asset_info.clone(),
+        ask_asset_info: swap_route_1.ask_asset_info.clone(),
+    };
+
+    let res: Vec<SwapOperation> =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res, swap_route_1.swap_operations);
+}
+
+#[test]
+fn add_swap_routes_invalid_route() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+
+    let info = mock_info("creator", &[]);
+
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps.querier.with_pool_factory(
         &[
             (
                 &"ukrwasset0000".to_string(),
@@ -1003,25 +3341,6 @@ fn add_swap_routes() {
                     pair_type: PairType::ConstantProduct,
                 },
             ),
-            (
-                &"asset0000uluna".to_string(),
-                &PairInfo {
-                    asset_infos: [
-                        AssetInfo::Token {
-                            contract_addr: "asset0000".to_string(),
-                        },
-                        AssetInfo::NativeToken {
-                            denom: "uluna".to_string(),
-                        },
-                    ],
-                    contract_addr: "pair0001".to_string(),
-                    liquidity_token: AssetInfo::Token {
-                        contract_addr: "liquidity0001".to_string(),
-                    },
-                    asset_decimals: [6u8, 6u8],
-                    pair_type: PairType::ConstantProduct,
-                },
-            ),
             (
                 &"ulunauwhale".to_string(),
                 &PairInfo {
@@ -1049,311 +3368,981 @@ fn add_swap_routes() {
         ],
     );
 
-    let swap_route_1 = SwapRoute {
-        offer_asset_info: AssetInfo::NativeToken {
-            denom: "ukrw".to_string(),
-        },
-        ask_asset_info: AssetInfo::NativeToken {
-            denom: "uluna".to_string(),
-        },
-        swap_operations: vec![
-            SwapOperation::TerraSwap {
-                offer_asset_info: AssetInfo::NativeToken {
-                    denom: "ukrw".to_string(),
-                },
-                ask_asset_info: AssetInfo::Token {
-                    contract_addr: "asset0000".to_string(),
-                },
+    let offer_asset_info = AssetInfo::NativeToken {
+        denom: "ukrw".to_string(),
+    };
+
+    let ask_asset_info = AssetInfo::NativeToken {
+        denom: "uluna".to_string(),
+    };
+
+    let swap_route_1 = SwapRoute {
+        offer_asset_info: offer_asset_info.clone(),
+        ask_asset_info: ask_asset_info.clone(),
+        swap_operations: vec![
+            SwapOperation::TerraSwap {
+                offer_asset_info: AssetInfo::NativeToken {
+                    denom: "ukrw".to_string(),
+                },
+                ask_asset_info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
+            },
+            SwapOperation::TerraSwap {
+                offer_asset_info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                ask_asset_info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
+            },
+        ],
+    };
+
+    // add swap route
+    let msg = ExecuteMsg::AddSwapRoutes {
+        swap_routes: vec![swap_route_1.clone()],
+    };
+
+    let error = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+
+    match error {
+        ContractError::InvalidSwapRoute(swap_route) => assert_eq!(swap_route, swap_route_1),
+        _ => panic!("should return ContractError::InvalidSwapRoute"),
+    }
+
+    // query swap route
+    let msg = QueryMsg::SwapRoute {
+        offer_asset_info: offer_asset_info.clone(),
+        ask_asset_info: ask_asset_info.clone(),
+    };
+
+    let error = query(deps.as_ref(), mock_env(), msg).unwrap_err();
+
+    match error {
+        ContractError::NoSwapRouteForAssets {
+            offer_asset,
+            ask_asset,
+        } => {
+            assert_eq!(offer_asset_info.to_string(), offer_asset);
+            assert_eq!(ask_asset_info.to_string(), ask_asset);
+        }
+        _ => panic!("should return ContractError::NoSwapRouteForAssets"),
+    }
+}
+
+#[test]
+fn add_swap_routes_unauthorized() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+
+    let info = mock_info("creator", &[]);
+
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let offer_asset_info = AssetInfo::NativeToken {
+        denom: "ukrw".to_string(),
+    };
+
+    let ask_asset_info = AssetInfo::NativeToken {
+        denom: "uluna".to_string(),
+    };
+
+    let swap_route_1 = SwapRoute {
+        offer_asset_info,
+        ask_asset_info,
+        swap_operations: vec![
+            SwapOperation::TerraSwap {
+                offer_asset_info: AssetInfo::NativeToken {
+                    denom: "ukrw".to_string(),
+                },
+                ask_asset_info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
+            },
+            SwapOperation::TerraSwap {
+                offer_asset_info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                ask_asset_info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                belief_price: None,
+                max_spread: None,
+                pair_identifier: None,
+            },
+        ],
+    };
+
+    // add swap route
+    let msg = ExecuteMsg::AddSwapRoutes {
+        swap_routes: vec![swap_route_1],
+    };
+
+    let error = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("unauthorized", &[]),
+        msg,
+    )
+    .unwrap_err();
+
+    match error {
+        ContractError::Unauthorized {} => (),
+        _ => panic!("should return ContractError::Unauthorized"),
+    }
+}
+
+/// Swap routes over the pools of [setup_route_search], in the order they're stored in
+fn stored_swap_routes() -> Vec<SwapRoute> {
+    let native = |denom: &str| AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    };
+    let operation = |offer: &str, ask: &str| SwapOperation::TerraSwap {
+        offer_asset_info: native(offer),
+        ask_asset_info: native(ask),
+        belief_price: None,
+        max_spread: None,
+        pair_identifier: None,
+    };
+
+    vec![
+        SwapRoute {
+            offer_asset_info: native("ukrw"),
+            ask_asset_info: native("uluna"),
+            swap_operations: vec![operation("ukrw", "uusd"), operation("uusd", "uluna")],
+        },
+        SwapRoute {
+            offer_asset_info: native("ukrw"),
+            ask_asset_info: native("uusd"),
+            swap_operations: vec![operation("ukrw", "uusd")],
+        },
+        SwapRoute {
+            offer_asset_info: native("uusd"),
+            ask_asset_info: native("ukrw"),
+            swap_operations: vec![operation("uusd", "ukrw")],
+        },
+    ]
+}
+
+#[test]
+fn remove_swap_routes() {
+    let mut deps = mock_dependencies(&[]);
+    setup_route_search(&mut deps);
+
+    let swap_routes = stored_swap_routes();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::AddSwapRoutes {
+            swap_routes: swap_routes.clone(),
+        },
+    )
+    .unwrap();
+
+    let res: Vec<SwapRoute> = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SwapRoutes {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, swap_routes);
+
+    // paginate the routes
+    let res: Vec<SwapRoute> = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SwapRoutes {
+                start_after: None,
+                limit: Some(2),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, swap_routes[..2]);
+
+    let res: Vec<SwapRoute> = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SwapRoutes {
+                start_after: Some([
+                    swap_routes[1].offer_asset_info.clone(),
+                    swap_routes[1].ask_asset_info.clone(),
+                ]),
+                limit: Some(2),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, swap_routes[2..]);
+
+    // only the owner can remove routes
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator", &[]),
+        ExecuteMsg::RemoveSwapRoutes {
+            swap_routes: vec![swap_routes[1].clone()],
+        },
+    )
+    .unwrap_err();
+    match err {
+        ContractError::Unauthorized {} => (),
+        _ => panic!("should return ContractError::Unauthorized"),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::RemoveSwapRoutes {
+            swap_routes: vec![swap_routes[1].clone()],
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "remove_swap_routes"),
+            attr("swap_route", swap_routes[1].to_string()),
+        ]
+    );
+
+    let res: Vec<SwapRoute> = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SwapRoutes {
+                start_after: None,
+                limit: None,
             },
-            SwapOperation::TerraSwap {
-                offer_asset_info: AssetInfo::Token {
-                    contract_addr: "asset0000".to_string(),
-                },
-                ask_asset_info: AssetInfo::NativeToken {
-                    denom: "uluna".to_string(),
-                },
-            },
-        ],
-    };
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, vec![swap_routes[0].clone(), swap_routes[2].clone()]);
 
-    let swap_route_2 = SwapRoute {
-        offer_asset_info: AssetInfo::NativeToken {
-            denom: "ukrw".to_string(),
+    // the route can't be removed twice
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::RemoveSwapRoutes {
+            swap_routes: vec![swap_routes[1].clone()],
         },
-        ask_asset_info: AssetInfo::NativeToken {
-            denom: "uwhale".to_string(),
+    )
+    .unwrap_err();
+    match err {
+        ContractError::NoSwapRouteForAssets { .. } => (),
+        _ => panic!("should return ContractError::NoSwapRouteForAssets"),
+    }
+}
+
+#[test]
+fn swap_routes_validity() {
+    let mut deps = mock_dependencies(&[]);
+    setup_route_search(&mut deps);
+
+    let swap_routes = stored_swap_routes();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::AddSwapRoutes {
+            swap_routes: swap_routes.clone(),
         },
-        swap_operations: vec![
-            SwapOperation::TerraSwap {
-                offer_asset_info: AssetInfo::NativeToken {
-                    denom: "ukrw".to_string(),
-                },
-                ask_asset_info: AssetInfo::Token {
-                    contract_addr: "asset0000".to_string(),
-                },
-            },
-            SwapOperation::TerraSwap {
-                offer_asset_info: AssetInfo::Token {
-                    contract_addr: "asset0000".to_string(),
-                },
-                ask_asset_info: AssetInfo::NativeToken {
-                    denom: "uluna".to_string(),
+    )
+    .unwrap();
+
+    // the uusd-uluna pair is removed from the factory
+    let native = |denom: &str| AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    };
+    deps.querier.with_pool_factory(
+        &[(
+            &"ukrwuusd".to_string(),
+            &PairInfo {
+                asset_infos: [native("ukrw"), native("uusd")],
+                contract_addr: "pair0000".to_string(),
+                liquidity_token: AssetInfo::Token {
+                    contract_addr: "liquiditypair0000".to_string(),
                 },
+                asset_decimals: [6u8, 6u8],
+                pair_type: PairType::ConstantProduct,
             },
-            SwapOperation::TerraSwap {
-                offer_asset_info: AssetInfo::NativeToken {
-                    denom: "uluna".to_string(),
-                },
-                ask_asset_info: AssetInfo::NativeToken {
-                    denom: "uwhale".to_string(),
-                },
+        )],
+        &[],
+    );
+
+    let res: Vec<SwapRouteValidity> = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SwapRoutesValidity {
+                start_after: None,
+                limit: None,
             },
-        ],
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        swap_routes
+            .into_iter()
+            .zip([false, true, true])
+            .map(|(swap_route, is_valid)| SwapRouteValidity {
+                swap_route,
+                is_valid,
+            })
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn transfer_ownership() {
+    let mut deps = mock_dependencies(&[]);
+    setup_route_search(&mut deps);
+
+    let query_config = |deps: Deps| -> ConfigResponse {
+        from_binary(&query(deps, mock_env(), QueryMsg::Config {}).unwrap()).unwrap()
     };
+    assert_eq!(query_config(deps.as_ref()).owner, "addr0000");
 
-    let swap_route_1_key = SWAP_ROUTES.key((
-        swap_route_1
-            .clone()
-            .offer_asset_info
-            .get_label(&deps.as_ref())
-            .unwrap()
-            .as_str(),
-        swap_route_1
-            .clone()
-            .ask_asset_info
-            .get_label(&deps.as_ref())
-            .unwrap()
-            .as_str(),
-    ));
+    // only the owner can propose a new owner
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::ProposeNewOwner {
+            owner: "addr0001".to_string(),
+        },
+    )
+    .unwrap_err();
+    match err {
+        ContractError::Unauthorized {} => (),
+        _ => panic!("should return ContractError::Unauthorized"),
+    }
 
-    let swap_route_2_key = SWAP_ROUTES.key((
-        swap_route_2
-            .clone()
-            .offer_asset_info
-            .get_label(&deps.as_ref())
-            .unwrap()
-            .as_str(),
-        swap_route_2
-            .clone()
-            .ask_asset_info
-            .get_label(&deps.as_ref())
-            .unwrap()
-            .as_str(),
-    ));
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::ProposeNewOwner {
+            owner: "addr0001".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        query_config(deps.as_ref()).pending_owner,
+        Some("addr0001".to_string())
+    );
 
-    // verify the keys are not there
-    assert_eq!(None, swap_route_1_key.may_load(&deps.storage).unwrap());
-    assert_eq!(None, swap_route_2_key.may_load(&deps.storage).unwrap());
+    // only the proposed owner can claim the ownership
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0002", &[]),
+        ExecuteMsg::ClaimOwnership {},
+    )
+    .unwrap_err();
+    match err {
+        ContractError::Unauthorized {} => (),
+        _ => panic!("should return ContractError::Unauthorized"),
+    }
 
-    // add swap route
-    let msg = ExecuteMsg::AddSwapRoutes {
-        swap_routes: vec![swap_route_1.clone(), swap_route_2.clone()],
-    };
+    // the proposal can't be claimed once dropped
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::DropOwnershipProposal {},
+    )
+    .unwrap();
+    assert_eq!(query_config(deps.as_ref()).pending_owner, None);
 
-    let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
-    let expected_attributes = vec![
-        attr("action", "add_swap_routes"),
-        attr("swap_route", swap_route_1.to_string()),
-        attr("swap_route", swap_route_2.to_string()),
-    ];
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::ClaimOwnership {},
+    )
+    .unwrap_err();
+    match err {
+        ContractError::Unauthorized {} => (),
+        _ => panic!("should return ContractError::Unauthorized"),
+    }
 
-    assert_eq!(res.messages.len(), 0usize);
-    assert_eq!(res.attributes, expected_attributes);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::ProposeNewOwner {
+            owner: "addr0001".to_string(),
+        },
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::ClaimOwnership {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "claim_ownership"), attr("owner", "addr0001")]
+    );
 
-    // query swap route
-    let msg = QueryMsg::SwapRoute {
-        offer_asset_info: swap_route_1.offer_asset_info.clone(),
-        ask_asset_info: swap_route_1.ask_asset_info.clone(),
-    };
+    let config = query_config(deps.as_ref());
+    assert_eq!(config.owner, "addr0001");
+    assert_eq!(config.pending_owner, None);
 
-    let res: Vec<SwapOperation> =
-        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
-    assert_eq!(res, swap_route_1.swap_operations);
+    // the previous owner can't manage the router anymore
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::AddSwapRoutes {
+            swap_routes: stored_swap_routes(),
+        },
+    )
+    .unwrap_err();
+    match err {
+        ContractError::Unauthorized {} => (),
+        _ => panic!("should return ContractError::Unauthorized"),
+    }
 }
 
 #[test]
-fn add_swap_routes_invalid_route() {
+fn zap_into_pool() {
     let mut deps = mock_dependencies(&[]);
+    setup_route_search(&mut deps);
+    deps.querier.with_token_balances(&[(
+        &"liquiditypair0000".to_string(),
+        &[(&"addr0001".to_string(), &Uint128::zero())],
+    )]);
+    deps.querier.with_balance(&[(
+        &"pair0000".to_string(),
+        vec![coin(2000000u128, "ukrw"), coin(1000000u128, "uusd")],
+    )]);
 
-    let msg = InstantiateMsg {
-        terraswap_factory: "terraswapfactory".to_string(),
+    let native = |denom: &str| AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    };
+    let operation = |offer: &str, ask: &str| SwapOperation::TerraSwap {
+        offer_asset_info: native(offer),
+        ask_asset_info: native(ask),
+        belief_price: None,
+        max_spread: None,
+        pair_identifier: None,
+    };
+    let offer_asset = Asset {
+        info: native("uluna"),
+        amount: Uint128::from(1000001u128),
+    };
+    let zap_msg = |operations_per_side: Vec<Vec<SwapOperation>>| ExecuteMsg::ZapIntoPool {
+        offer_asset: offer_asset.clone(),
+        operations_per_side,
+        pair_address: "pair0000".to_string(),
+        min_lp_out: Some(Uint128::from(1000u128)),
+        slippage_tolerance: Some(Decimal::percent(1)),
+        receiver: Some("addr0001".to_string()),
+        deadline: None,
     };
 
-    let info = mock_info("creator", &[]);
-
-    // we can just call .unwrap() to assert this was a success
-    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
-
-    deps.querier.with_pool_factory(
-        &[
-            (
-                &"ukrwasset0000".to_string(),
-                &PairInfo {
-                    asset_infos: [
-                        AssetInfo::NativeToken {
-                            denom: "ukrw".to_string(),
-                        },
-                        AssetInfo::Token {
-                            contract_addr: "asset0000".to_string(),
-                        },
-                    ],
-                    contract_addr: "pair0000".to_string(),
-                    liquidity_token: AssetInfo::Token {
-                        contract_addr: "liquidity0000".to_string(),
-                    },
-                    asset_decimals: [6u8, 6u8],
-                    pair_type: PairType::ConstantProduct,
-                },
-            ),
-            (
-                &"ulunauwhale".to_string(),
-                &PairInfo {
-                    asset_infos: [
-                        AssetInfo::NativeToken {
-                            denom: "uluna".to_string(),
+    // the offer amount is split so the sides return the 2:1 ratio of the ukrw-uusd pair reserves,
+    // the uluna-ukrw hop returning 90% of its offer and the last side taking the remainder
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1000001u128, "uluna")]),
+        zap_msg(vec![
+            vec![operation("uluna", "ukrw")],
+            vec![operation("uluna", "uusd")],
+        ]),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: operation("uluna", "ukrw"),
+                    offer_amount: Some(Uint128::from(689655u128)),
+                    to: Some(MOCK_CONTRACT_ADDR.to_string()),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: operation("uluna", "uusd"),
+                    offer_amount: Some(Uint128::from(310346u128)),
+                    to: Some(MOCK_CONTRACT_ADDR.to_string()),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ProvideZapLiquidity {
+                    pair_address: "pair0000".to_string(),
+                    prev_balances: vec![
+                        Asset {
+                            info: native("ukrw"),
+                            amount: Uint128::zero(),
                         },
-                        AssetInfo::NativeToken {
-                            denom: "uwhale".to_string(),
+                        Asset {
+                            info: native("uusd"),
+                            amount: Uint128::zero(),
                         },
                     ],
-                    contract_addr: "pair0002".to_string(),
-                    liquidity_token: AssetInfo::Token {
-                        contract_addr: "liquidity0002".to_string(),
+                    slippage_tolerance: Some(Decimal::percent(1)),
+                    receiver: "addr0001".to_string(),
+                    refund_to: "addr0000".to_string(),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::AssertMinimumReceive {
+                    asset_info: AssetInfo::Token {
+                        contract_addr: "liquiditypair0000".to_string(),
                     },
-                    asset_decimals: [6u8, 6u8],
-                    pair_type: PairType::ConstantProduct,
-                },
-            ),
-        ],
-        &[
-            ("ukrw".to_string(), 6u8),
-            ("uluna".to_string(), 6u8),
-            ("uwhale".to_string(), 6u8),
-        ],
+                    prev_balance: Uint128::zero(),
+                    minimum_receive: Uint128::from(1000u128),
+                    receiver: "addr0001".to_string(),
+                })
+                .unwrap(),
+            })),
+        ]
     );
 
-    let offer_asset_info = AssetInfo::NativeToken {
-        denom: "ukrw".to_string(),
-    };
-
-    let ask_asset_info = AssetInfo::NativeToken {
-        denom: "uluna".to_string(),
-    };
-
-    let swap_route_1 = SwapRoute {
-        offer_asset_info: offer_asset_info.clone(),
-        ask_asset_info: ask_asset_info.clone(),
-        swap_operations: vec![
-            SwapOperation::TerraSwap {
-                offer_asset_info: AssetInfo::NativeToken {
-                    denom: "ukrw".to_string(),
-                },
-                ask_asset_info: AssetInfo::Token {
-                    contract_addr: "asset0000".to_string(),
-                },
-            },
-            SwapOperation::TerraSwap {
-                offer_asset_info: AssetInfo::Token {
-                    contract_addr: "asset0000".to_string(),
-                },
-                ask_asset_info: AssetInfo::NativeToken {
-                    denom: "uluna".to_string(),
-                },
-            },
-        ],
-    };
+    // the offer asset must be sent
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1000u128, "uluna")]),
+        zap_msg(vec![
+            vec![operation("uluna", "ukrw")],
+            vec![operation("uluna", "uusd")],
+        ]),
+    )
+    .unwrap_err();
+    match err {
+        ContractError::InvalidZapFunds {} => (),
+        _ => panic!("should return ContractError::InvalidZapFunds"),
+    }
 
-    // add swap route
-    let msg = ExecuteMsg::AddSwapRoutes {
-        swap_routes: vec![swap_route_1.clone()],
-    };
+    // there must be a side per pool asset
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1000001u128, "uluna")]),
+        zap_msg(vec![vec![operation("uluna", "ukrw")]]),
+    )
+    .unwrap_err();
+    match err {
+        ContractError::InvalidZapOperations {} => (),
+        _ => panic!("should return ContractError::InvalidZapOperations"),
+    }
 
-    let error = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+    // each side must end in its pool asset
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1000001u128, "uluna")]),
+        zap_msg(vec![
+            vec![operation("uluna", "uusd")],
+            vec![operation("uluna", "ukrw")],
+        ]),
+    )
+    .unwrap_err();
+    match err {
+        ContractError::InvalidZapOperations {} => (),
+        _ => panic!("should return ContractError::InvalidZapOperations"),
+    }
 
-    match error {
-        ContractError::InvalidSwapRoute(swap_route) => assert_eq!(swap_route, swap_route_1),
-        _ => panic!("should return ContractError::InvalidSwapRoute"),
+    // a side can't go through the assets of another side, as the whole balance would be swapped
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1000001u128, "uluna")]),
+        zap_msg(vec![
+            vec![operation("uluna", "ukrw")],
+            vec![operation("uluna", "ukrw"), operation("ukrw", "uusd")],
+        ]),
+    )
+    .unwrap_err();
+    match err {
+        ContractError::InvalidZapOperations {} => (),
+        _ => panic!("should return ContractError::InvalidZapOperations"),
     }
+}
 
-    // query swap route
-    let msg = QueryMsg::SwapRoute {
-        offer_asset_info: offer_asset_info.clone(),
-        ask_asset_info: ask_asset_info.clone(),
+#[test]
+fn zap_into_trio() {
+    let mut deps = mock_dependencies(&[]);
+    setup_route_search(&mut deps);
+    // the sent funds are already part of the router's balance
+    deps.querier.with_balance(&[
+        (&MOCK_CONTRACT_ADDR.to_string(), vec![coin(900u128, "uusd")]),
+        (
+            &"trio0000".to_string(),
+            vec![
+                coin(3000u128, "uusd"),
+                coin(1000u128, "uusdc"),
+                coin(2000u128, "uluna"),
+            ],
+        ),
+    ]);
+
+    let native = |denom: &str| AssetInfo::NativeToken {
+        denom: denom.to_string(),
     };
 
-    let error = query(deps.as_ref(), mock_env(), msg).unwrap_err();
+    let stableswap_operation = SwapOperation::StableSwap3Pool {
+        offer_asset_info: native("uusd"),
+        ask_asset_info: native("uusdc"),
+        pool_asset_infos: vec![native("uusd"), native("uusdc"), native("uluna")],
+        belief_price: None,
+        max_spread: None,
+    };
+    let terraswap_operation = SwapOperation::TerraSwap {
+        offer_asset_info: native("uusd"),
+        ask_asset_info: native("uluna"),
+        belief_price: None,
+        max_spread: None,
+        pair_identifier: None,
+    };
 
-    match error {
-        ContractError::NoSwapRouteForAssets {
-            offer_asset,
-            ask_asset,
-        } => {
-            assert_eq!(offer_asset_info.to_string(), offer_asset);
-            assert_eq!(ask_asset_info.to_string(), ask_asset);
-        }
-        _ => panic!("should return ContractError::NoSwapRouteForAssets"),
-    }
+    // the offer asset is one of the trio assets, so it's provided as is on its side. The offer
+    // amount is split in the 3:1:2 ratio of the trio reserves, the uusd-uusdc hop returning 105%
+    // of its offer
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(900u128, "uusd")]),
+        ExecuteMsg::ZapIntoPool {
+            offer_asset: Asset {
+                info: native("uusd"),
+                amount: Uint128::from(900u128),
+            },
+            operations_per_side: vec![
+                vec![],
+                vec![stableswap_operation.clone()],
+                vec![terraswap_operation.clone()],
+            ],
+            pair_address: "trio0000".to_string(),
+            min_lp_out: None,
+            slippage_tolerance: None,
+            receiver: None,
+            deadline: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: stableswap_operation,
+                    offer_amount: Some(Uint128::from(143u128)),
+                    to: Some(MOCK_CONTRACT_ADDR.to_string()),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: terraswap_operation,
+                    offer_amount: Some(Uint128::from(304u128)),
+                    to: Some(MOCK_CONTRACT_ADDR.to_string()),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ProvideZapLiquidity {
+                    pair_address: "trio0000".to_string(),
+                    prev_balances: ["uusd", "uusdc", "uluna"]
+                        .into_iter()
+                        .map(|denom| Asset {
+                            info: native(denom),
+                            amount: Uint128::zero(),
+                        })
+                        .collect(),
+                    slippage_tolerance: None,
+                    receiver: "addr0000".to_string(),
+                    refund_to: "addr0000".to_string(),
+                })
+                .unwrap(),
+            })),
+        ]
+    );
 }
 
 #[test]
-fn add_swap_routes_unauthorized() {
+fn zap_into_four_asset_pool() {
     let mut deps = mock_dependencies(&[]);
+    setup_route_search(&mut deps);
 
-    let msg = InstantiateMsg {
-        terraswap_factory: "terraswapfactory".to_string(),
+    let native = |denom: &str| AssetInfo::NativeToken {
+        denom: denom.to_string(),
     };
+    let pool_asset_infos = vec![
+        native("uusd"),
+        native("uusdc"),
+        native("ukrw"),
+        native("uluna"),
+    ];
+    deps.querier.with_trios(&[(
+        &"uusduusdcukrwuluna".to_string(),
+        &TrioInfo {
+            asset_infos: pool_asset_infos.clone(),
+            contract_addr: "pool0000".to_string(),
+            liquidity_token: AssetInfo::Token {
+                contract_addr: "liquiditypool0000".to_string(),
+            },
+            asset_decimals: vec![6u8; 4],
+        },
+    )]);
+    deps.querier.with_balance(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![coin(1000u128, "uusd")],
+    )]);
 
-    let info = mock_info("creator", &[]);
+    let terraswap_operation = |ask: &str| SwapOperation::TerraSwap {
+        offer_asset_info: native("uusd"),
+        ask_asset_info: native(ask),
+        belief_price: None,
+        max_spread: None,
+        pair_identifier: None,
+    };
 
-    // we can just call .unwrap() to assert this was a success
-    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    // the pool is told apart from a pair by its own info rather than by the number of its assets,
+    // and being empty it takes an even split of the offer amount
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(1000u128, "uusd")]),
+        ExecuteMsg::ZapIntoPool {
+            offer_asset: Asset {
+                info: native("uusd"),
+                amount: Uint128::from(1000u128),
+            },
+            operations_per_side: vec![
+                vec![],
+                vec![SwapOperation::StableSwap3Pool {
+                    offer_asset_info: native("uusd"),
+                    ask_asset_info: native("uusdc"),
+                    pool_asset_infos: pool_asset_infos.clone(),
+                    belief_price: None,
+                    max_spread: None,
+                }],
+                vec![terraswap_operation("ukrw")],
+                vec![terraswap_operation("uluna")],
+            ],
+            pair_address: "pool0000".to_string(),
+            min_lp_out: None,
+            slippage_tolerance: None,
+            receiver: None,
+            deadline: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 4);
+    assert_eq!(
+        res.messages[2],
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+            funds: vec![],
+            msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                operation: terraswap_operation("uluna"),
+                offer_amount: Some(Uint128::from(250u128)),
+                to: Some(MOCK_CONTRACT_ADDR.to_string()),
+            })
+            .unwrap(),
+        }))
+    );
+    assert_eq!(
+        res.messages[3],
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+            funds: vec![],
+            msg: to_binary(&ExecuteMsg::ProvideZapLiquidity {
+                pair_address: "pool0000".to_string(),
+                prev_balances: pool_asset_infos
+                    .into_iter()
+                    .map(|info| Asset {
+                        info,
+                        amount: Uint128::zero(),
+                    })
+                    .collect(),
+                slippage_tolerance: None,
+                receiver: "addr0000".to_string(),
+                refund_to: "addr0000".to_string(),
+            })
+            .unwrap(),
+        }))
+    );
+}
 
-    let offer_asset_info = AssetInfo::NativeToken {
-        denom: "ukrw".to_string(),
-    };
+#[test]
+fn provide_zap_liquidity() {
+    let mut deps = mock_dependencies(&[]);
+    setup_route_search(&mut deps);
+    deps.querier.with_token_balances(&[(
+        &"liquiditypair0000".to_string(),
+        &[(&"addr0001".to_string(), &Uint128::from(1000u128))],
+    )]);
 
-    let ask_asset_info = AssetInfo::NativeToken {
-        denom: "uluna".to_string(),
+    let native = |denom: &str| AssetInfo::NativeToken {
+        denom: denom.to_string(),
     };
-
-    let swap_route_1 = SwapRoute {
-        offer_asset_info,
-        ask_asset_info,
-        swap_operations: vec![
-            SwapOperation::TerraSwap {
-                offer_asset_info: AssetInfo::NativeToken {
-                    denom: "ukrw".to_string(),
-                },
-                ask_asset_info: AssetInfo::Token {
-                    contract_addr: "asset0000".to_string(),
-                },
+    let msg = ExecuteMsg::ProvideZapLiquidity {
+        pair_address: "pair0000".to_string(),
+        prev_balances: vec![
+            Asset {
+                info: native("uusd"),
+                amount: Uint128::from(100u128),
             },
-            SwapOperation::TerraSwap {
-                offer_asset_info: AssetInfo::Token {
-                    contract_addr: "asset0000".to_string(),
-                },
-                ask_asset_info: AssetInfo::NativeToken {
-                    denom: "uluna".to_string(),
-                },
+            Asset {
+                info: native("ukrw"),
+                amount: Uint128::zero(),
             },
         ],
+        slippage_tolerance: Some(Decimal::percent(1)),
+        receiver: "addr0001".to_string(),
+        refund_to: "addr0002".to_string(),
     };
 
-    // add swap route
-    let msg = ExecuteMsg::AddSwapRoutes {
-        swap_routes: vec![swap_route_1],
-    };
-
-    let error = execute(
+    // only the router can provide the zapped liquidity
+    let err = execute(
         deps.as_mut(),
         mock_env(),
-        mock_info("unauthorized", &[]),
-        msg,
+        mock_info("addr0000", &[]),
+        msg.clone(),
     )
     .unwrap_err();
-
-    match error {
+    match err {
         ContractError::Unauthorized {} => (),
         _ => panic!("should return ContractError::Unauthorized"),
     }
+
+    // the deposits are trimmed to the 1:2 uusd-ukrw ratio of the pair reserves, and what's trimmed
+    // off is refunded
+    deps.querier.with_balance(&[
+        (
+            &MOCK_CONTRACT_ADDR.to_string(),
+            vec![coin(600u128, "uusd"), coin(500u128, "ukrw")],
+        ),
+        (
+            &"pair0000".to_string(),
+            vec![coin(2000u128, "ukrw"), coin(1000u128, "uusd")],
+        ),
+    ]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "pair0000".to_string(),
+                funds: vec![coin(500u128, "ukrw"), coin(250u128, "uusd")],
+                msg: to_binary(&PairExecuteMsg::ProvideLiquidity {
+                    assets: [
+                        Asset {
+                            info: native("uusd"),
+                            amount: Uint128::from(250u128),
+                        },
+                        Asset {
+                            info: native("ukrw"),
+                            amount: Uint128::from(500u128),
+                        },
+                    ],
+                    slippage_tolerance: Some(Decimal::percent(1)),
+                    receiver: Some("addr0001".to_string()),
+                    deadline: None,
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0002".to_string(),
+                amount: vec![coin(250u128, "uusd")],
+            })),
+        ]
+    );
+
+    // a missing side is left to the single-sided provision of the pair, with nothing to refund
+    deps.querier
+        .with_balance(&[(&MOCK_CONTRACT_ADDR.to_string(), vec![coin(600u128, "uusd")])]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "pair0000".to_string(),
+            funds: vec![coin(500u128, "uusd")],
+            msg: to_binary(&PairExecuteMsg::ProvideLiquidity {
+                assets: [
+                    Asset {
+                        info: native("uusd"),
+                        amount: Uint128::from(500u128),
+                    },
+                    Asset {
+                        info: native("ukrw"),
+                        amount: Uint128::zero(),
+                    },
+                ],
+                slippage_tolerance: Some(Decimal::percent(1)),
+                receiver: Some("addr0001".to_string()),
+                deadline: None,
+            })
+            .unwrap(),
+        }))]
+    );
 }
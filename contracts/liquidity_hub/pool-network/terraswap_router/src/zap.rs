@@ -0,0 +1,412 @@
+use cosmwasm_std::{
+    attr, to_binary, Addr, Coin, CosmosMsg, Decimal, Deps, Env, MessageInfo, Response, StdError,
+    Timestamp, Uint128, Uint256, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use white_whale::pool_network::asset::{Asset, AssetInfo};
+use white_whale::pool_network::pair::{
+    ExecuteMsg as PairExecuteMsg, PoolResponse as PairPoolResponse, QueryMsg as PairQueryMsg,
+};
+use white_whale::pool_network::querier::{query_pair_info_from_pair, query_trio_info_from_trio};
+use white_whale::pool_network::router::{ExecuteMsg, SwapOperation};
+use white_whale::pool_network::trio::{
+    ExecuteMsg as TrioExecuteMsg, PoolResponse as TrioPoolResponse, QueryMsg as TrioQueryMsg,
+};
+
+use crate::contract::{
+    assert_deadline, assert_minimum_receive_msg, assert_operations, simulate_swap_operations,
+    swap_operations_msgs,
+};
+use crate::error::ContractError;
+
+/// Swaps the offer asset into each of the assets of the pool and provides them as liquidity
+#[allow(clippy::too_many_arguments)]
+pub fn execute_zap_into_pool(
+    deps: Deps,
+    env: Env,
+    sender: Addr,
+    offer_asset: Asset,
+    operations_per_side: Vec<Vec<SwapOperation>>,
+    pair_address: Addr,
+    min_lp_out: Option<Uint128>,
+    slippage_tolerance: Option<Decimal>,
+    receiver: Option<Addr>,
+    deadline: Option<Timestamp>,
+) -> Result<Response, ContractError> {
+    assert_deadline(&env, deadline)?;
+
+    if offer_asset.amount.is_zero() {
+        return Err(ContractError::InvalidZapFunds {});
+    }
+
+    let pool = query_zap_pool(deps, &pair_address)?;
+    assert_zap_operations(&offer_asset.info, &pool.asset_infos, &operations_per_side)?;
+
+    let receiver = receiver.unwrap_or_else(|| sender.clone());
+
+    // the offer asset was already received, so it's not part of the balance the amounts to
+    // provide are computed from
+    let prev_balances = pool
+        .asset_infos
+        .iter()
+        .map(|asset_info| {
+            let mut amount =
+                asset_info.query_pool(&deps.querier, deps.api, env.contract.address.clone())?;
+            if asset_info == &offer_asset.info {
+                amount = amount.checked_sub(offer_asset.amount)?;
+            }
+
+            Ok(Asset {
+                info: asset_info.clone(),
+                amount,
+            })
+        })
+        .collect::<Result<Vec<Asset>, ContractError>>()?;
+
+    let (reserves, _) = pool.query_reserves(deps, &pair_address)?;
+    let side_amounts = split_zap_offer(deps, offer_asset.amount, &operations_per_side, &reserves)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for (operations, amount) in operations_per_side.into_iter().zip(side_amounts) {
+        if operations.is_empty() || amount.is_zero() {
+            continue;
+        }
+
+        messages.extend(swap_operations_msgs(
+            &env,
+            operations,
+            Some(amount),
+            &env.contract.address,
+        )?);
+    }
+
+    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: env.contract.address.to_string(),
+        funds: vec![],
+        msg: to_binary(&ExecuteMsg::ProvideZapLiquidity {
+            pair_address: pair_address.to_string(),
+            prev_balances,
+            slippage_tolerance,
+            receiver: receiver.to_string(),
+            refund_to: sender.to_string(),
+        })?,
+    }));
+
+    if let Some(min_lp_out) = min_lp_out {
+        messages.push(assert_minimum_receive_msg(
+            deps,
+            &env,
+            pool.liquidity_token,
+            min_lp_out,
+            &receiver,
+        )?);
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "zap_into_pool"),
+        attr("offer_asset", offer_asset.to_string()),
+        attr("pair_address", pair_address),
+        attr("receiver", receiver),
+    ]))
+}
+
+/// Provides what the router received of each pool asset since the `prev_balances` as liquidity,
+/// minting the LP tokens to the `receiver` and refunding what the pool doesn't take to `refund_to`
+#[allow(clippy::too_many_arguments)]
+pub fn provide_zap_liquidity(
+    deps: Deps,
+    env: Env,
+    info: MessageInfo,
+    pair_address: Addr,
+    prev_balances: Vec<Asset>,
+    slippage_tolerance: Option<Decimal>,
+    receiver: Addr,
+    refund_to: Addr,
+) -> Result<Response, ContractError> {
+    if env.contract.address != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let received_assets = prev_balances
+        .into_iter()
+        .map(|prev_balance| {
+            let balance = prev_balance.info.query_pool(
+                &deps.querier,
+                deps.api,
+                env.contract.address.clone(),
+            )?;
+
+            Ok(Asset {
+                info: prev_balance.info,
+                amount: balance.checked_sub(prev_balance.amount)?,
+            })
+        })
+        .collect::<Result<Vec<Asset>, ContractError>>()?;
+
+    let pool = query_zap_pool(deps, &pair_address)?;
+    let assets = match pool.kind {
+        PoolKind::Pair => {
+            let (reserves, total_share) = pool.query_reserves(deps, &pair_address)?;
+            balance_pair_deposits(&received_assets, &reserves, total_share)
+        }
+        PoolKind::Trio => received_assets.clone(),
+    };
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut funds: Vec<Coin> = vec![];
+    for asset in assets.iter().filter(|asset| !asset.amount.is_zero()) {
+        match &asset.info {
+            AssetInfo::NativeToken { denom } => funds.push(Coin {
+                denom: denom.clone(),
+                amount: asset.amount,
+            }),
+            AssetInfo::Token { contract_addr } => {
+                messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: contract_addr.clone(),
+                    funds: vec![],
+                    msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                        spender: pair_address.to_string(),
+                        amount: asset.amount,
+                        expires: None,
+                    })?,
+                }))
+            }
+        }
+    }
+    funds.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+    let msg = match (pool.kind, assets.as_slice()) {
+        (PoolKind::Pair, [asset_0, asset_1]) => to_binary(&PairExecuteMsg::ProvideLiquidity {
+            assets: [asset_0.clone(), asset_1.clone()],
+            slippage_tolerance,
+            receiver: Some(receiver.to_string()),
+            deadline: None,
+        })?,
+        (PoolKind::Trio, _) => to_binary(&TrioExecuteMsg::ProvideLiquidity {
+            assets: assets.clone(),
+            slippage_tolerance,
+            receiver: Some(receiver.to_string()),
+            deadline: None,
+        })?,
+        _ => return Err(ContractError::InvalidZapOperations {}),
+    };
+    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: pair_address.to_string(),
+        funds,
+        msg,
+    }));
+
+    // refund what was trimmed off the deposits
+    for (received_asset, asset) in received_assets.into_iter().zip(assets.iter()) {
+        let refund_amount = received_asset.amount.checked_sub(asset.amount)?;
+        if !refund_amount.is_zero() {
+            messages.push(
+                Asset {
+                    info: received_asset.info,
+                    amount: refund_amount,
+                }
+                .into_msg(refund_to.clone())?,
+            );
+        }
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "provide_zap_liquidity"),
+        attr(
+            "assets",
+            assets
+                .iter()
+                .map(|asset| asset.to_string())
+                .collect::<Vec<String>>()
+                .join(", "),
+        ),
+    ]))
+}
+
+/// The kinds of pools liquidity can be zapped into
+#[derive(Clone, Copy)]
+enum PoolKind {
+    Pair,
+    Trio,
+}
+
+/// The pool liquidity is zapped into
+struct ZapPool {
+    kind: PoolKind,
+    asset_infos: Vec<AssetInfo>,
+    liquidity_token: AssetInfo,
+}
+
+impl ZapPool {
+    /// Queries the pools of the pool at `pair_address` and its total share
+    fn query_reserves(
+        &self,
+        deps: Deps,
+        pair_address: &Addr,
+    ) -> Result<(Vec<Asset>, Uint128), ContractError> {
+        Ok(match self.kind {
+            PoolKind::Pair => {
+                let pool: PairPoolResponse = deps
+                    .querier
+                    .query_wasm_smart(pair_address, &PairQueryMsg::Pool {})?;
+                (pool.assets, pool.total_share)
+            }
+            PoolKind::Trio => {
+                let pool: TrioPoolResponse = deps
+                    .querier
+                    .query_wasm_smart(pair_address, &TrioQueryMsg::Pool {})?;
+                (pool.assets, pool.total_share)
+            }
+        })
+    }
+}
+
+/// Gets the kind, the assets and the LP token of the pool at `pair_address`, which is a trio if it
+/// answers the trio info query and a pair otherwise
+fn query_zap_pool(deps: Deps, pair_address: &Addr) -> Result<ZapPool, ContractError> {
+    if let Ok(trio_info) = query_trio_info_from_trio(&deps.querier, pair_address.clone()) {
+        return Ok(ZapPool {
+            kind: PoolKind::Trio,
+            asset_infos: trio_info.asset_infos,
+            liquidity_token: trio_info.liquidity_token,
+        });
+    }
+
+    let pair_info = query_pair_info_from_pair(&deps.querier, pair_address.clone())?;
+    Ok(ZapPool {
+        kind: PoolKind::Pair,
+        asset_infos: pair_info.asset_infos.to_vec(),
+        liquidity_token: pair_info.liquidity_token,
+    })
+}
+
+/// Splits the offer amount among the sides so they return the pool assets in the proportion of
+/// the `reserves`, the last side taking the remainder. The rate of each side is estimated by
+/// simulating its operations with an even split of the offer amount, and an empty pool takes the
+/// assets in any proportion, so it gets an even split.
+fn split_zap_offer(
+    deps: Deps,
+    offer_amount: Uint128,
+    operations_per_side: &[Vec<SwapOperation>],
+    reserves: &[Asset],
+) -> Result<Vec<Uint128>, ContractError> {
+    let sides = operations_per_side.len();
+    let even_amount = offer_amount.multiply_ratio(1u128, sides as u128);
+
+    // the offer amount each side needs to return its reserve
+    let weights = if even_amount.is_zero() || reserves.iter().any(|r| r.amount.is_zero()) {
+        vec![Uint256::one(); sides]
+    } else {
+        operations_per_side
+            .iter()
+            .zip(reserves)
+            .map(|(operations, reserve)| {
+                let return_amount = if operations.is_empty() {
+                    even_amount
+                } else {
+                    simulate_swap_operations(deps, even_amount, operations.clone())?.amount
+                };
+                if return_amount.is_zero() {
+                    return Err(ContractError::InvalidZapOperations {});
+                }
+
+                Ok(Uint256::from(reserve.amount).multiply_ratio(even_amount, return_amount))
+            })
+            .collect::<Result<Vec<Uint256>, ContractError>>()?
+    };
+    let total_weight = weights
+        .iter()
+        .try_fold(Uint256::zero(), |total, weight| total.checked_add(*weight))?;
+    if total_weight.is_zero() {
+        return Err(ContractError::InvalidZapOperations {});
+    }
+
+    let mut remaining_amount = offer_amount;
+    let mut side_amounts = vec![];
+    for (index, weight) in weights.into_iter().enumerate() {
+        let amount = if index + 1 == sides {
+            remaining_amount
+        } else {
+            Uint128::try_from(Uint256::from(offer_amount).multiply_ratio(weight, total_weight))
+                .map_err(StdError::from)?
+        };
+        remaining_amount = remaining_amount.checked_sub(amount)?;
+        side_amounts.push(amount);
+    }
+
+    Ok(side_amounts)
+}
+
+/// Trims the deposits into a pair to the proportion of its `reserves`, as the pair only mints LP
+/// tokens for the smaller share of the deposits and keeps the rest. Deposits into an empty pair,
+/// or with a side missing, which the pair provides single-sided, are left as they are.
+fn balance_pair_deposits(
+    deposits: &[Asset],
+    reserves: &[Asset],
+    total_share: Uint128,
+) -> Vec<Asset> {
+    let mut deposits = deposits.to_vec();
+    let reserve_of = |info: &AssetInfo| {
+        reserves
+            .iter()
+            .find(|reserve| &reserve.info == info)
+            .map(|reserve| reserve.amount)
+            .unwrap_or_default()
+    };
+
+    if let [deposit_0, deposit_1] = deposits.as_mut_slice() {
+        let reserve_0 = reserve_of(&deposit_0.info);
+        let reserve_1 = reserve_of(&deposit_1.info);
+        if total_share.is_zero()
+            || [deposit_0.amount, deposit_1.amount, reserve_0, reserve_1]
+                .iter()
+                .any(|amount| amount.is_zero())
+        {
+            return deposits;
+        }
+
+        if deposit_0.amount.full_mul(reserve_1) > deposit_1.amount.full_mul(reserve_0) {
+            deposit_0.amount = deposit_1.amount.multiply_ratio(reserve_0, reserve_1);
+        } else {
+            deposit_1.amount = deposit_0.amount.multiply_ratio(reserve_1, reserve_0);
+        }
+    }
+
+    deposits
+}
+
+/// Asserts each side swaps the offer asset into its pool asset, or is the offer asset itself.
+/// As the hops after the first one swap the router's whole balance, a side can't go through the
+/// offer asset or any of the pool assets along the way.
+fn assert_zap_operations(
+    offer_asset_info: &AssetInfo,
+    pool_asset_infos: &[AssetInfo],
+    operations_per_side: &[Vec<SwapOperation>],
+) -> Result<(), ContractError> {
+    if operations_per_side.len() != pool_asset_infos.len() {
+        return Err(ContractError::InvalidZapOperations {});
+    }
+
+    for (operations, pool_asset_info) in operations_per_side.iter().zip(pool_asset_infos) {
+        let Some((last, hops)) = operations.split_last() else {
+            if offer_asset_info != pool_asset_info {
+                return Err(ContractError::InvalidZapOperations {});
+            }
+            continue;
+        };
+
+        if &operations[0].get_offer_asset_info() != offer_asset_info
+            || &last.get_target_asset_info() != pool_asset_info
+            || hops.iter().any(|operation| {
+                let asset_info = operation.get_target_asset_info();
+                &asset_info == offer_asset_info || pool_asset_infos.contains(&asset_info)
+            })
+        {
+            return Err(ContractError::InvalidZapOperations {});
+        }
+
+        assert_operations(operations)?;
+    }
+
+    Ok(())
+}
@@ -69,6 +69,7 @@ pub enum FactoryType {
     /// Pool Factory
     Pool {
         start_after: Option<[AssetInfo; 2]>,
+        start_after_identifier: Option<String>,
         limit: Option<u32>,
     },
 }
@@ -9,7 +9,8 @@ use cosmwasm_std::{
 use cw20::Cw20ExecuteMsg;
 
 use crate::pool_network::querier::{
-    query_balance, query_native_decimals, query_token_balance, query_token_info,
+    query_balance, query_denom_metadata_decimals, query_registered_native_decimals,
+    query_token_balance, query_token_info,
 };
 
 pub const MINIMUM_LIQUIDITY_AMOUNT: Uint128 = Uint128::new(1_000u128);
@@ -207,10 +208,16 @@ impl AssetInfo {
         }
     }
 
+    /// Queries the decimals of the asset. The decimals of native tokens are the ones registered in
+    /// the factory at `account_addr`, or the ones in the bank metadata of the denom when the factory
+    /// has none registered.
     pub fn query_decimals(&self, account_addr: Addr, querier: &QuerierWrapper) -> StdResult<u8> {
         match self {
             AssetInfo::NativeToken { denom } => {
-                query_native_decimals(querier, account_addr, denom.to_string())
+                match query_registered_native_decimals(querier, account_addr, denom)? {
+                    Some(decimals) => Ok(decimals),
+                    None => query_denom_metadata_decimals(querier, denom.to_string()),
+                }
             }
             AssetInfo::Token { contract_addr } => {
                 let token_info = query_token_info(querier, Addr::unchecked(contract_addr))?;
@@ -481,6 +488,12 @@ pub enum PairType {
         amp: u64,
     },
     ConstantProduct,
+    /// A constant product pair where the value of the pools is split according to the given weights
+    /// instead of 50/50, i.e. the invariant is `pool_0^weight_0 * pool_1^weight_1`.
+    Weighted {
+        /// The relative weights of the assets, in the same order as the assets of the pair.
+        weights: [u64; 2],
+    },
 }
 
 impl PairType {
@@ -489,6 +502,34 @@ impl PairType {
         match self {
             PairType::ConstantProduct => "ConstantProduct",
             PairType::StableSwap { .. } => "StableSwap",
+            PairType::Weighted { .. } => "Weighted",
+        }
+    }
+
+    /// Checks that the given [PairType] is valid, i.e. each of the weights of a weighted pair is
+    /// non-zero and at least 1% of the total weight
+    pub fn is_valid(&self) -> StdResult<()> {
+        if let PairType::Weighted { weights } = self {
+            let total_weight = u128::from(weights[0]) + u128::from(weights[1]);
+
+            if weights
+                .iter()
+                .any(|weight| *weight == 0 || u128::from(*weight) * 100 < total_weight)
+            {
+                return Err(StdError::generic_err("Invalid pair weights"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Gets the pair type with the assets in reverse order. Only the weights of a weighted pair
+    /// depend on the order of the assets.
+    pub fn reversed(&self) -> PairType {
+        match self {
+            PairType::Weighted { weights } => PairType::Weighted {
+                weights: [weights[1], weights[0]],
+            },
+            pair_type => pair_type.clone(),
         }
     }
 }
@@ -556,18 +597,18 @@ pub fn deduct_assets(assets: Vec<Asset>, to_deduct: Vec<Asset>) -> StdResult<Vec
 
 #[cw_serde]
 pub struct TrioInfo {
-    pub asset_infos: [AssetInfo; 3],
+    pub asset_infos: Vec<AssetInfo>,
     pub contract_addr: String,
     pub liquidity_token: AssetInfo,
-    pub asset_decimals: [u8; 3],
+    pub asset_decimals: Vec<u8>,
 }
 
 #[cw_serde]
 pub struct TrioInfoRaw {
-    pub asset_infos: [AssetInfoRaw; 3],
+    pub asset_infos: Vec<AssetInfoRaw>,
     pub contract_addr: CanonicalAddr,
     pub liquidity_token: AssetInfoRaw,
-    pub asset_decimals: [u8; 3],
+    pub asset_decimals: Vec<u8>,
 }
 
 impl TrioInfoRaw {
@@ -575,12 +616,12 @@ impl TrioInfoRaw {
         Ok(TrioInfo {
             liquidity_token: self.liquidity_token.to_normal(api)?,
             contract_addr: api.addr_humanize(&self.contract_addr)?.to_string(),
-            asset_infos: [
-                self.asset_infos[0].to_normal(api)?,
-                self.asset_infos[1].to_normal(api)?,
-                self.asset_infos[2].to_normal(api)?,
-            ],
-            asset_decimals: self.asset_decimals,
+            asset_infos: self
+                .asset_infos
+                .iter()
+                .map(|asset_info| asset_info.to_normal(api))
+                .collect::<StdResult<Vec<AssetInfo>>>()?,
+            asset_decimals: self.asset_decimals.clone(),
         })
     }
 
@@ -589,23 +630,16 @@ impl TrioInfoRaw {
         querier: &QuerierWrapper,
         api: &dyn Api,
         contract_addr: Addr,
-    ) -> StdResult<[Asset; 3]> {
-        let info_0: AssetInfo = self.asset_infos[0].to_normal(api)?;
-        let info_1: AssetInfo = self.asset_infos[1].to_normal(api)?;
-        let info_2: AssetInfo = self.asset_infos[2].to_normal(api)?;
-        Ok([
-            Asset {
-                amount: info_0.query_pool(querier, api, contract_addr.clone())?,
-                info: info_0,
-            },
-            Asset {
-                amount: info_1.query_pool(querier, api, contract_addr.clone())?,
-                info: info_1,
-            },
-            Asset {
-                amount: info_2.query_pool(querier, api, contract_addr)?,
-                info: info_2,
-            },
-        ])
+    ) -> StdResult<Vec<Asset>> {
+        self.asset_infos
+            .iter()
+            .map(|asset_info| {
+                let info: AssetInfo = asset_info.to_normal(api)?;
+                Ok(Asset {
+                    amount: info.query_pool(querier, api, contract_addr.clone())?,
+                    info,
+                })
+            })
+            .collect()
     }
 }
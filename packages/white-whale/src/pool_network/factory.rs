@@ -1,6 +1,6 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 
-use crate::pool_network::asset::{AssetInfo, PairInfo, PairType, TrioInfo};
+use crate::pool_network::asset::{Asset, AssetInfo, PairInfo, PairType, TrioInfo};
 use crate::pool_network::pair::{FeatureToggle, PoolFee};
 use crate::pool_network::trio::{
     FeatureToggle as TrioFeatureToggle, PoolFee as TrioPoolFee, RampAmp,
@@ -10,7 +10,7 @@ use crate::pool_network::trio::{
 pub struct InstantiateMsg {
     /// Pair contract code ID, which is used to
     pub pair_code_id: u64,
-    /// trio code id used for 3 pool stable swap
+    /// trio code id used for the multi-asset stable swap pools
     pub trio_code_id: u64,
     pub token_code_id: u64,
     pub fee_collector_addr: String,
@@ -25,6 +25,19 @@ pub enum ExecuteMsg {
         token_code_id: Option<u64>,
         pair_code_id: Option<u64>,
         trio_code_id: Option<u64>,
+        /// Native fee charged for creating a pool, which is sent to the fee collector. A fee with a
+        /// zero amount removes it.
+        pool_creation_fee: Option<Asset>,
+        /// If true, only the owner and the allowed creators of an asset can create pools with it.
+        /// Creators must be allowed for every asset of the pool that has allowed creators.
+        allowlist_enabled: Option<bool>,
+    },
+    /// Adds and removes the creators allowed to create pools with the given asset when the
+    /// allowlist is enabled
+    UpdateAllowedCreators {
+        asset_info: AssetInfo,
+        add: Vec<String>,
+        remove: Vec<String>,
     },
     /// Updates a pair config
     UpdatePairConfig {
@@ -33,7 +46,10 @@ pub enum ExecuteMsg {
         fee_collector_addr: Option<String>,
         pool_fees: Option<PoolFee>,
         feature_toggle: Option<FeatureToggle>,
+        amp_factor: Option<RampAmp>,
     },
+    /// Stops the amplification coefficient ramp of a stableswap pair
+    StopPairAmpRamp { pair_addr: String },
     /// Updates a trio config
     UpdateTrioConfig {
         trio_addr: String,
@@ -43,7 +59,8 @@ pub enum ExecuteMsg {
         feature_toggle: Option<TrioFeatureToggle>,
         amp_factor: Option<RampAmp>,
     },
-    /// Instantiates pair contract
+    /// Instantiates pair contract. The pool creation fee, if any, must be sent along with the
+    /// message.
     CreatePair {
         /// Asset infos
         asset_infos: [AssetInfo; 2],
@@ -53,18 +70,33 @@ pub enum ExecuteMsg {
         /// If true, the pair will use the token factory to create the LP token. If false, it will
         /// use a cw20 token instead.
         token_factory_lp: bool,
+        /// Identifier telling the pair apart from other pairs of the same assets. Defaults to the
+        /// pair type and the total fee of the pair, e.g. `ConstantProduct-0.003`. It's made of up
+        /// to 64 ASCII letters, digits, `.`, `-` or `_`.
+        pair_identifier: Option<String>,
+        /// Liquidity provided to the pair right after its creation, so its initial price can't be
+        /// front-run. Native assets must be sent along with the message, on top of the pool
+        /// creation fee, and cw20 assets must be approved to the factory beforehand.
+        initial_liquidity: Option<[Asset; 2]>,
+        /// Receiver of the LP tokens minted for the initial liquidity. Defaults to the sender.
+        receiver: Option<String>,
     },
-    /// Instantiates pair contract
+    /// Instantiates trio contract. The pool creation fee, if any, must be sent along with the
+    /// message.
     CreateTrio {
-        /// Asset infos
-        asset_infos: [AssetInfo; 3],
+        /// Asset infos, between 2 and 8 of them
+        asset_infos: Vec<AssetInfo>,
         pool_fees: TrioPoolFee,
         amp_factor: u64,
         /// If true, the pair will use the token factory to create the LP token. If false, it will
         /// use a cw20 token instead.
         token_factory_lp: bool,
+        /// Liquidity provided to the trio right after its creation, as with the pairs
+        initial_liquidity: Option<Vec<Asset>>,
+        /// Receiver of the LP tokens minted for the initial liquidity. Defaults to the sender.
+        receiver: Option<String>,
     },
-    /// Adds native token info to the contract so it can instantiate pair contracts that include it
+    /// Registers the decimals of a native token, overriding the ones derived from its bank metadata
     AddNativeTokenDecimals { denom: String, decimals: u8 },
     /// Migrates a pair contract to a given code_id
     MigratePair {
@@ -76,10 +108,34 @@ pub enum ExecuteMsg {
         contract: String,
         code_id: Option<u64>,
     },
-    /// Removes pair contract given asset infos
-    RemovePair { asset_infos: [AssetInfo; 2] },
+    /// Migrates a page of the pairs created by the factory to a given code_id, defaulting to the
+    /// pair code id in the config. Only the pairs of the variant of `pair_type` are migrated if
+    /// given, whatever their amp or weights. Pages work as in the Pairs query, and the
+    /// `start_after` and `start_after_identifier` of the next page are reported in the
+    /// `next_start_after` and `next_start_after_identifier` attributes while there are pairs left.
+    MigratePairs {
+        code_id: Option<u64>,
+        start_after: Option<[AssetInfo; 2]>,
+        start_after_identifier: Option<String>,
+        limit: Option<u32>,
+        pair_type: Option<PairType>,
+    },
+    /// Migrates a page of the trios created by the factory to a given code_id, defaulting to the
+    /// trio code id in the config. Pages work as in the Trios query, and the `start_after` of the
+    /// next page is reported in the `next_start_after` attribute while there are trios left.
+    MigrateTrios {
+        code_id: Option<u64>,
+        start_after: Option<Vec<AssetInfo>>,
+        limit: Option<u32>,
+    },
+    /// Removes pair contract given asset infos and pair identifier. If no identifier is given, the
+    /// default pair of the assets, i.e. the first one created for them, is removed.
+    RemovePair {
+        asset_infos: [AssetInfo; 2],
+        pair_identifier: Option<String>,
+    },
     /// Removes trio contract given asset infos
-    RemoveTrio { asset_infos: [AssetInfo; 3] },
+    RemoveTrio { asset_infos: Vec<AssetInfo> },
 }
 
 #[cw_serde]
@@ -88,31 +144,69 @@ pub enum QueryMsg {
     /// Retrieves the configuration of the factory.
     #[returns(ConfigResponse)]
     Config {},
-    /// Retrieves the info for the pair with the given asset_infos.
+    /// Retrieves the info for the pair with the given asset_infos and pair identifier. If no
+    /// identifier is given, the default pair of the assets is returned, i.e. the first one created
+    /// for them. Once the default pair is removed, the identifier must be given.
     #[returns(PairInfo)]
-    Pair { asset_infos: [AssetInfo; 2] },
+    Pair {
+        asset_infos: [AssetInfo; 2],
+        pair_identifier: Option<String>,
+    },
     /// Retrieves the pairs created by the factory. This query has pagination enabled, querying ten
     /// items by default if not specified otherwise. The max amount of items that can be queried at
-    /// once is 30. `start_after` is the last asset_info of a page.
+    /// once is 30. `start_after` and `start_after_identifier` are the asset_infos and the pair
+    /// identifier of the last pair of a page. Without `start_after_identifier`, the page starts
+    /// after all the pairs of the `start_after` assets. If `asset_infos` is given, all the pairs of
+    /// those assets are returned instead.
     #[returns(PairsResponse)]
     Pairs {
         start_after: Option<[AssetInfo; 2]>,
+        start_after_identifier: Option<String>,
         limit: Option<u32>,
+        asset_infos: Option<[AssetInfo; 2]>,
     },
     /// Retrieves the info for the trio with the given asset_infos.
     #[returns(TrioInfo)]
-    Trio { asset_infos: [AssetInfo; 3] },
+    Trio { asset_infos: Vec<AssetInfo> },
     /// Retrieves the trios created by the factory. This query has pagination enabled, querying ten
     /// items by default if not specified otherwise. The max amount of items that can be queried at
     /// once is 30. `start_after` is the last asset_info of a page.
     #[returns(TriosResponse)]
     Trios {
-        start_after: Option<[AssetInfo; 3]>,
+        start_after: Option<Vec<AssetInfo>>,
         limit: Option<u32>,
     },
-    /// Retrieves the decimals for the given native or ibc denom.
+    /// Retrieves the decimals for the given native or ibc denom. Registered decimals take
+    /// precedence, otherwise they are derived from the display unit of the denom's bank metadata.
     #[returns(NativeTokenDecimalsResponse)]
     NativeTokenDecimals { denom: String },
+    /// Retrieves the decimals registered for native or ibc denoms, ordered by denom. This query has
+    /// pagination enabled, querying ten items by default if not specified otherwise. The max amount
+    /// of items that can be queried at once is 30.
+    #[returns(AllNativeTokenDecimalsResponse)]
+    AllNativeTokenDecimals {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Retrieves the creators allowed to create pools with the given asset when the allowlist is
+    /// enabled. This query has pagination enabled, querying ten items by default if not specified
+    /// otherwise. The max amount of items that can be queried at once is 30.
+    #[returns(AllowedCreatorsResponse)]
+    AllowedCreators {
+        asset_info: AssetInfo,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Retrieves the pairs and trios holding the given asset, ordered by contract address. This
+    /// query has pagination enabled, querying ten items by default if not specified otherwise. The
+    /// max amount of items that can be queried at once is 30. `start_after` is the contract address
+    /// of the last pool of a page.
+    #[returns(PoolsByAssetResponse)]
+    PoolsByAsset {
+        asset_info: AssetInfo,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 // We define a custom struct for each query response
@@ -123,6 +217,8 @@ pub struct ConfigResponse {
     pub pair_code_id: u64,
     pub trio_code_id: u64,
     pub token_code_id: u64,
+    pub pool_creation_fee: Option<Asset>,
+    pub allowlist_enabled: bool,
 }
 
 /// We currently take no arguments for migrations
@@ -133,6 +229,9 @@ pub struct MigrateMsg {}
 #[cw_serde]
 pub struct PairsResponse {
     pub pairs: Vec<PairInfo>,
+    /// The identifiers of the `pairs`, in the same order
+    #[serde(default)]
+    pub pair_identifiers: Vec<String>,
 }
 
 #[cw_serde]
@@ -144,3 +243,37 @@ pub struct TriosResponse {
 pub struct NativeTokenDecimalsResponse {
     pub decimals: u8,
 }
+
+#[cw_serde]
+pub struct NativeTokenDecimalsInfo {
+    pub denom: String,
+    pub decimals: u8,
+}
+
+#[cw_serde]
+pub struct AllNativeTokenDecimalsResponse {
+    pub native_token_decimals: Vec<NativeTokenDecimalsInfo>,
+}
+
+#[cw_serde]
+pub struct AllowedCreatorsResponse {
+    pub creators: Vec<String>,
+}
+
+/// A pool created by the factory
+#[cw_serde]
+pub enum PoolInfo {
+    /// A pair, along with the identifier telling it apart from other pairs of the same assets
+    Pair {
+        pair_identifier: String,
+        pair_info: PairInfo,
+    },
+    Trio {
+        trio_info: TrioInfo,
+    },
+}
+
+#[cw_serde]
+pub struct PoolsByAssetResponse {
+    pub pools: Vec<PoolInfo>,
+}
@@ -5,15 +5,26 @@ use std::panic;
 
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_binary, from_slice, to_binary, Coin, ContractInfoResponse, ContractResult, Empty,
-    OwnedDeps, Querier, QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+    from_binary, from_slice, to_binary, Addr, BalanceResponse, BankQuery, Coin,
+    ContractInfoResponse, ContractResult, Decimal, Empty, OwnedDeps, Querier, QuerierResult,
+    QueryRequest, SystemError, SystemResult, Uint128, Uint256, WasmQuery,
 };
 use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
+use prost::Message;
 
+use crate::fee::Fee;
 use crate::pool_network::asset::{Asset, AssetInfo, PairInfo, PairType, TrioInfo};
-use crate::pool_network::factory::{NativeTokenDecimalsResponse, QueryMsg as FactoryQueryMsg};
-use crate::pool_network::pair::{PoolResponse as PairPoolResponse, QueryMsg as PairQueryMsg};
+use crate::pool_network::denom::{Metadata, QueryDenomMetadataRequest, QueryDenomMetadataResponse};
+use crate::pool_network::factory::{
+    NativeTokenDecimalsResponse, PairsResponse, PoolInfo, PoolsByAssetResponse,
+    QueryMsg as FactoryQueryMsg, TriosResponse,
+};
+use crate::pool_network::pair::{
+    ConfigResponse as PairConfigResponse, FeatureToggle, PoolFee, PoolResponse as PairPoolResponse,
+    QueryMsg as PairQueryMsg,
+};
 use crate::pool_network::pair::{ReverseSimulationResponse, SimulationResponse};
+use crate::pool_network::querier::native_decimals_key;
 use crate::pool_network::trio;
 use crate::pool_network::trio::{PoolResponse as TrioPoolResponse, QueryMsg as TrioQueryMsg};
 
@@ -51,6 +62,12 @@ pub struct WasmMockQuerier {
     base: MockQuerier,
     token_querier: TokenQuerier,
     pool_factory_querier: PoolFactoryQuerier,
+    // rate at which the pools return the offer asset on simulations, by pool address
+    simulation_rates: HashMap<String, Decimal>,
+    // rate assumed by the pools on reverse simulations when it differs from the simulation one
+    reverse_simulation_rates: HashMap<String, Decimal>,
+    // bank metadata of native denoms, by denom
+    denom_metadata: HashMap<String, Metadata>,
 }
 
 pub struct WasmMockTrioQuerier {
@@ -91,14 +108,18 @@ pub fn balances_to_map(
 #[derive(Clone, Default)]
 pub struct PoolFactoryQuerier {
     pairs: HashMap<String, PairInfo>,
+    trios: HashMap<String, TrioInfo>,
     native_token_decimals: HashMap<String, u8>,
+    pair_identifiers: HashMap<String, String>,
 }
 
 impl PoolFactoryQuerier {
     pub fn new(pairs: &[(&String, &PairInfo)], native_token_decimals: &[(String, u8)]) -> Self {
         PoolFactoryQuerier {
             pairs: pairs_to_map(pairs),
+            trios: HashMap::new(),
             native_token_decimals: native_token_decimals_to_map(native_token_decimals),
+            pair_identifiers: HashMap::new(),
         }
     }
 }
@@ -113,6 +134,16 @@ pub fn pairs_to_map(pairs: &[(&String, &PairInfo)]) -> HashMap<String, PairInfo>
     pairs_map
 }
 
+pub fn trios_to_map(trios: &[(&String, &TrioInfo)]) -> HashMap<String, TrioInfo> {
+    let mut trios_map: HashMap<String, TrioInfo> = HashMap::new();
+    for (key, trio) in trios.iter() {
+        let mut sort_key: Vec<char> = key.chars().collect();
+        sort_key.sort_by(|a, b| b.cmp(a));
+        trios_map.insert(String::from_iter(sort_key.iter()), (**trio).clone());
+    }
+    trios_map
+}
+
 pub fn native_token_decimals_to_map(native_token_decimals: &[(String, u8)]) -> HashMap<String, u8> {
     let mut native_token_decimals_map: HashMap<String, u8> = HashMap::new();
 
@@ -157,95 +188,173 @@ impl Querier for WasmMockTrioQuerier {
 impl WasmMockQuerier {
     pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
         match &request {
-            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
-                match from_binary(msg) {
-                    Ok(FactoryQueryMsg::Pair { asset_infos }) => {
-                        let key = [asset_infos[0].to_string(), asset_infos[1].to_string()].join("");
-                        let mut sort_key: Vec<char> = key.chars().collect();
-                        sort_key.sort_by(|a, b| b.cmp(a));
-                        match self
-                            .pool_factory_querier
-                            .pairs
-                            .get(&String::from_iter(sort_key.iter()))
-                        {
-                            Some(v) => SystemResult::Ok(ContractResult::Ok(to_binary(v).unwrap())),
-                            None => SystemResult::Err(SystemError::InvalidRequest {
-                                error: "No pair info exists".to_string(),
-                                request: msg.as_slice().into(),
-                            }),
-                        }
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })
+                if matches!(
+                    from_binary(msg),
+                    Ok(TrioQueryMsg::Simulation { .. }
+                        | TrioQueryMsg::ReverseSimulation { .. }
+                        | TrioQueryMsg::Trio {})
+                ) =>
+            {
+                match from_binary(msg).unwrap() {
+                    TrioQueryMsg::Trio {} => self.query_trio_info(contract_addr),
+                    TrioQueryMsg::Simulation { offer_asset, .. } => SystemResult::Ok(
+                        ContractResult::from(to_binary(&trio::SimulationResponse {
+                            return_amount: self.simulate(contract_addr, offer_asset.amount),
+                            swap_fee_amount: Uint128::zero(),
+                            spread_amount: self.spread(contract_addr, offer_asset.amount),
+                            protocol_fee_amount: Uint128::zero(),
+                            burn_fee_amount: Uint128::zero(),
+                        })),
+                    ),
+                    TrioQueryMsg::ReverseSimulation { ask_asset, .. } => SystemResult::Ok(
+                        ContractResult::from(to_binary(&trio::ReverseSimulationResponse {
+                            offer_amount: self.reverse_simulate(contract_addr, ask_asset.amount),
+                            swap_fee_amount: Uint128::zero(),
+                            spread_amount: self.reverse_spread(contract_addr, ask_asset.amount),
+                            protocol_fee_amount: Uint128::zero(),
+                            burn_fee_amount: Uint128::zero(),
+                        })),
+                    ),
+                    _ => panic!("DO NOT ENTER HERE"),
+                }
+            }
+            QueryRequest::Wasm(WasmQuery::Raw { key, .. }) => {
+                // only the native token decimals of the factory are read raw
+                let decimals = self
+                    .pool_factory_querier
+                    .native_token_decimals
+                    .iter()
+                    .find(|(denom, _)| native_decimals_key(denom).as_slice() == key.as_slice())
+                    .map(|(_, decimals)| to_binary(decimals).unwrap())
+                    .unwrap_or_default();
+                SystemResult::Ok(ContractResult::Ok(decimals))
+            }
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => match from_binary(msg) {
+                Ok(FactoryQueryMsg::Pair {
+                    asset_infos,
+                    pair_identifier: Some(pair_identifier),
+                }) => match self.pool_factory_querier.pairs.values().find(|pair| {
+                    asset_infos
+                        .iter()
+                        .all(|asset_info| pair.asset_infos.contains(asset_info))
+                        && self.pair_identifier(pair) == pair_identifier
+                }) {
+                    Some(v) => SystemResult::Ok(ContractResult::Ok(to_binary(v).unwrap())),
+                    None => SystemResult::Err(SystemError::InvalidRequest {
+                        error: "No pair info exists".to_string(),
+                        request: msg.as_slice().into(),
+                    }),
+                },
+                Ok(FactoryQueryMsg::Pair { asset_infos, .. }) => {
+                    let key = [asset_infos[0].to_string(), asset_infos[1].to_string()].join("");
+                    let mut sort_key: Vec<char> = key.chars().collect();
+                    sort_key.sort_by(|a, b| b.cmp(a));
+                    match self
+                        .pool_factory_querier
+                        .pairs
+                        .get(&String::from_iter(sort_key.iter()))
+                    {
+                        Some(v) => SystemResult::Ok(ContractResult::Ok(to_binary(v).unwrap())),
+                        None => SystemResult::Err(SystemError::InvalidRequest {
+                            error: "No pair info exists".to_string(),
+                            request: msg.as_slice().into(),
+                        }),
                     }
-                    Ok(FactoryQueryMsg::NativeTokenDecimals { denom }) => {
-                        match self.pool_factory_querier.native_token_decimals.get(&denom) {
-                            Some(decimals) => SystemResult::Ok(ContractResult::Ok(
-                                to_binary(&NativeTokenDecimalsResponse {
-                                    decimals: *decimals,
-                                })
-                                .unwrap(),
-                            )),
-                            None => SystemResult::Err(SystemError::InvalidRequest {
-                                error: "No decimal info exist".to_string(),
-                                request: msg.as_slice().into(),
-                            }),
-                        }
+                }
+                Ok(FactoryQueryMsg::Trio { asset_infos }) => {
+                    let key = asset_infos
+                        .iter()
+                        .map(|asset_info| asset_info.to_string())
+                        .collect::<Vec<String>>()
+                        .join("");
+                    let mut sort_key: Vec<char> = key.chars().collect();
+                    sort_key.sort_by(|a, b| b.cmp(a));
+                    match self
+                        .pool_factory_querier
+                        .trios
+                        .get(&String::from_iter(sort_key.iter()))
+                    {
+                        Some(v) => SystemResult::Ok(ContractResult::Ok(to_binary(v).unwrap())),
+                        None => SystemResult::Err(SystemError::InvalidRequest {
+                            error: "No trio info exists".to_string(),
+                            request: msg.as_slice().into(),
+                        }),
                     }
-                    _ => match from_binary(msg) {
-                        Ok(PairQueryMsg::Pool {}) => {
-                            SystemResult::Ok(ContractResult::from(to_binary(&PairPoolResponse {
-                                assets: vec![
-                                    Asset {
-                                        info: AssetInfo::NativeToken {
-                                            denom: "uluna".to_string(),
-                                        },
-                                        amount: Uint128::new(1_000_000_000u128),
-                                    },
-                                    Asset {
-                                        info: AssetInfo::NativeToken {
-                                            denom: "ujuno".to_string(),
-                                        },
-                                        amount: Uint128::new(1_000_000_000u128),
-                                    },
-                                ],
-                                total_share: Uint128::new(2_000_000_000u128),
-                            })))
-                        }
-                        Ok(PairQueryMsg::Pair {}) => {
-                            SystemResult::Ok(ContractResult::from(to_binary(&PairInfo {
-                                asset_infos: [
-                                    AssetInfo::NativeToken {
-                                        denom: "uluna".to_string(),
-                                    },
-                                    AssetInfo::NativeToken {
-                                        denom: "uluna".to_string(),
-                                    },
-                                ],
-                                asset_decimals: [6u8, 6u8],
-                                contract_addr: "pair0000".to_string(),
-                                liquidity_token: AssetInfo::Token {
-                                    contract_addr: "liquidity0000".to_string(),
+                }
+                Ok(FactoryQueryMsg::Pairs {
+                    start_after,
+                    start_after_identifier,
+                    limit,
+                    ..
+                }) => self.query_pairs(start_after, start_after_identifier, limit),
+                Ok(FactoryQueryMsg::Trios { start_after, limit }) => {
+                    self.query_trios(start_after, limit)
+                }
+                Ok(FactoryQueryMsg::PoolsByAsset {
+                    asset_info,
+                    start_after,
+                    limit,
+                }) => self.query_pools_by_asset(asset_info, start_after, limit),
+                Ok(FactoryQueryMsg::NativeTokenDecimals { denom }) => {
+                    match self.pool_factory_querier.native_token_decimals.get(&denom) {
+                        Some(decimals) => SystemResult::Ok(ContractResult::Ok(
+                            to_binary(&NativeTokenDecimalsResponse {
+                                decimals: *decimals,
+                            })
+                            .unwrap(),
+                        )),
+                        None => SystemResult::Err(SystemError::InvalidRequest {
+                            error: "No decimal info exist".to_string(),
+                            request: msg.as_slice().into(),
+                        }),
+                    }
+                }
+                _ => match from_binary(msg) {
+                    Ok(PairQueryMsg::Pool {}) => self.query_pool(contract_addr),
+                    Ok(PairQueryMsg::Pair {}) => self.query_pair_info(contract_addr),
+                    Ok(PairQueryMsg::Config {}) => {
+                        SystemResult::Ok(ContractResult::from(to_binary(&PairConfigResponse {
+                            owner: Addr::unchecked("owner"),
+                            fee_collector_addr: Addr::unchecked("collector"),
+                            pool_fees: PoolFee {
+                                protocol_fee: Fee {
+                                    share: Decimal::permille(1),
                                 },
-                                pair_type: PairType::ConstantProduct,
-                            })))
-                        }
-                        Ok(PairQueryMsg::Simulation { offer_asset }) => {
-                            SystemResult::Ok(ContractResult::from(to_binary(&SimulationResponse {
-                                return_amount: offer_asset.amount,
-                                swap_fee_amount: Uint128::zero(),
-                                spread_amount: Uint128::zero(),
-                                protocol_fee_amount: Uint128::zero(),
-                                burn_fee_amount: Uint128::zero(),
-                            })))
-                        }
-                        Ok(PairQueryMsg::ReverseSimulation { ask_asset }) => SystemResult::Ok(
-                            ContractResult::from(to_binary(&ReverseSimulationResponse {
-                                offer_amount: ask_asset.amount,
-                                swap_fee_amount: Uint128::zero(),
-                                spread_amount: Uint128::zero(),
-                                protocol_fee_amount: Uint128::zero(),
-                                burn_fee_amount: Uint128::zero(),
-                            })),
-                        ),
-                        _ => match from_binary(msg).unwrap() {
+                                swap_fee: Fee {
+                                    share: Decimal::permille(2),
+                                },
+                                burn_fee: Fee {
+                                    share: Decimal::zero(),
+                                },
+                            },
+                            feature_toggle: FeatureToggle {
+                                withdrawals_enabled: true,
+                                deposits_enabled: true,
+                                swaps_enabled: true,
+                            },
+                        })))
+                    }
+                    Ok(PairQueryMsg::Simulation { offer_asset }) => {
+                        SystemResult::Ok(ContractResult::from(to_binary(&SimulationResponse {
+                            return_amount: self.simulate(contract_addr, offer_asset.amount),
+                            swap_fee_amount: Uint128::zero(),
+                            spread_amount: self.spread(contract_addr, offer_asset.amount),
+                            protocol_fee_amount: Uint128::zero(),
+                            burn_fee_amount: Uint128::zero(),
+                        })))
+                    }
+                    Ok(PairQueryMsg::ReverseSimulation { ask_asset }) => SystemResult::Ok(
+                        ContractResult::from(to_binary(&ReverseSimulationResponse {
+                            offer_amount: self.reverse_simulate(contract_addr, ask_asset.amount),
+                            swap_fee_amount: Uint128::zero(),
+                            spread_amount: self.reverse_spread(contract_addr, ask_asset.amount),
+                            protocol_fee_amount: Uint128::zero(),
+                            burn_fee_amount: Uint128::zero(),
+                        })),
+                    ),
+                    _ => {
+                        match from_binary(msg).unwrap() {
                             Cw20QueryMsg::TokenInfo {} => {
                                 let balances: &HashMap<String, Uint128> =
                                 match self.token_querier.balances.get(contract_addr) {
@@ -308,10 +417,10 @@ impl WasmMockQuerier {
                             }
 
                             _ => panic!("DO NOT ENTER HERE"),
-                        },
-                    },
-                }
-            }
+                        }
+                    }
+                },
+            },
             QueryRequest::Wasm(WasmQuery::ContractInfo { .. }) => {
                 let mut contract_info_response = ContractInfoResponse::default();
                 contract_info_response.code_id = 0u64;
@@ -322,6 +431,24 @@ impl WasmMockQuerier {
                     to_binary(&contract_info_response).unwrap(),
                 ))
             }
+            QueryRequest::Stargate { path, data }
+                if path == "/cosmos.bank.v1beta1.Query/DenomMetadata" =>
+            {
+                let denom = QueryDenomMetadataRequest::decode(data.as_slice())
+                    .unwrap()
+                    .denom;
+                match self.denom_metadata.get(&denom) {
+                    Some(metadata) => SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&QueryDenomMetadataResponse {
+                            metadata: Some(metadata.clone()),
+                        })
+                        .unwrap(),
+                    )),
+                    None => SystemResult::Ok(ContractResult::Err(format!(
+                        "client metadata for denom {denom}: key not found"
+                    ))),
+                }
+            }
             _ => self.base.handle_query(request),
         }
     }
@@ -333,12 +460,11 @@ impl WasmMockTrioQuerier {
             QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
                 match from_binary(msg) {
                     Ok(FactoryQueryMsg::Trio { asset_infos }) => {
-                        let key = [
-                            asset_infos[0].to_string(),
-                            asset_infos[1].to_string(),
-                            asset_infos[2].to_string(),
-                        ]
-                        .join("");
+                        let key = asset_infos
+                            .iter()
+                            .map(|asset_info| asset_info.to_string())
+                            .collect::<Vec<String>>()
+                            .join("");
                         let mut sort_key: Vec<char> = key.chars().collect();
                         sort_key.sort_by(|a, b| b.cmp(a));
                         match self
@@ -395,7 +521,7 @@ impl WasmMockTrioQuerier {
                         }
                         Ok(TrioQueryMsg::Trio {}) => {
                             SystemResult::Ok(ContractResult::from(to_binary(&TrioInfo {
-                                asset_infos: [
+                                asset_infos: vec![
                                     AssetInfo::NativeToken {
                                         denom: "uluna".to_string(),
                                     },
@@ -406,7 +532,7 @@ impl WasmMockTrioQuerier {
                                         denom: "uatom".to_string(),
                                     },
                                 ],
-                                asset_decimals: [6u8, 6u8, 10u8],
+                                asset_decimals: vec![6u8, 6u8, 10u8],
                                 contract_addr: "trio0000".to_string(),
                                 liquidity_token: AssetInfo::Token {
                                     contract_addr: "liquidity0000".to_string(),
@@ -519,6 +645,9 @@ impl WasmMockQuerier {
             base,
             token_querier: TokenQuerier::default(),
             pool_factory_querier: PoolFactoryQuerier::default(),
+            simulation_rates: HashMap::new(),
+            reverse_simulation_rates: HashMap::new(),
+            denom_metadata: HashMap::new(),
         }
     }
 
@@ -536,6 +665,329 @@ impl WasmMockQuerier {
         self.pool_factory_querier = PoolFactoryQuerier::new(pairs, native_token_decimals);
     }
 
+    // configure the identifiers of the given pairs, keyed by contract address. The identifier of a
+    // pair defaults to its type and the total fee of the mocked pair config, e.g. ConstantProduct-0.003
+    pub fn with_pair_identifiers(&mut self, pair_identifiers: &[(&String, &String)]) {
+        for (contract_addr, pair_identifier) in pair_identifiers {
+            self.pool_factory_querier
+                .pair_identifiers
+                .insert(contract_addr.to_string(), pair_identifier.to_string());
+        }
+    }
+
+    // configure the trios
+    pub fn with_trios(&mut self, trios: &[(&String, &TrioInfo)]) {
+        self.pool_factory_querier.trios = trios_to_map(trios);
+    }
+
+    // configure the rate at which the given pools return the offer asset on simulations, 1 if not set.
+    // The amount not returned is reported as spread. Reverse simulations ask for the offer amount
+    // returning at least the ask amount at that rate.
+    pub fn with_simulation_rates(&mut self, rates: &[(&String, Decimal)]) {
+        for (pool, rate) in rates {
+            self.simulation_rates.insert(pool.to_string(), *rate);
+        }
+    }
+
+    // configure the rate assumed by the given pools on reverse simulations, making them inconsistent
+    // with their simulations
+    pub fn with_reverse_simulation_rates(&mut self, rates: &[(&String, Decimal)]) {
+        for (pool, rate) in rates {
+            self.reverse_simulation_rates
+                .insert(pool.to_string(), *rate);
+        }
+    }
+
+    // configure the bank metadata of the given denoms
+    pub fn with_denom_metadata(&mut self, denom_metadata: &[(&String, &Metadata)]) {
+        for (denom, metadata) in denom_metadata {
+            self.denom_metadata
+                .insert(denom.to_string(), (*metadata).clone());
+        }
+    }
+
+    fn pair_identifier(&self, pair: &PairInfo) -> String {
+        self.pool_factory_querier
+            .pair_identifiers
+            .get(&pair.contract_addr)
+            .cloned()
+            .unwrap_or_else(|| format!("{}-0.003", pair.pair_type.get_label()))
+    }
+
+    fn simulation_rate(&self, pool: &str) -> Decimal {
+        self.simulation_rates
+            .get(pool)
+            .copied()
+            .unwrap_or_else(Decimal::one)
+    }
+
+    fn simulate(&self, pool: &str, offer_amount: Uint128) -> Uint128 {
+        offer_amount * self.simulation_rate(pool)
+    }
+
+    fn spread(&self, pool: &str, offer_amount: Uint128) -> Uint128 {
+        offer_amount.saturating_sub(self.simulate(pool, offer_amount))
+    }
+
+    // the offer amount needed to get the ask amount back on simulations, rounded up
+    fn reverse_simulate(&self, pool: &str, ask_amount: Uint128) -> Uint128 {
+        let rate = self
+            .reverse_simulation_rates
+            .get(pool)
+            .copied()
+            .unwrap_or_else(|| self.simulation_rate(pool));
+        let rate = Uint256::from(rate.atomics());
+        let numerator = ask_amount.full_mul(Decimal::one().atomics());
+        let offer_amount = (numerator + rate - Uint256::one()) / rate;
+        Uint128::try_from(offer_amount).unwrap()
+    }
+
+    fn reverse_spread(&self, pool: &str, ask_amount: Uint128) -> Uint128 {
+        self.reverse_simulate(pool, ask_amount)
+            .saturating_sub(ask_amount)
+    }
+
+    // returns the pair configured with the given contract address, or a default one
+    fn query_pair_info(&self, contract_addr: &str) -> QuerierResult {
+        let pair_info = self
+            .pool_factory_querier
+            .pairs
+            .values()
+            .find(|pair| pair.contract_addr == contract_addr)
+            .cloned()
+            .unwrap_or_else(|| PairInfo {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                ],
+                asset_decimals: [6u8, 6u8],
+                contract_addr: "pair0000".to_string(),
+                liquidity_token: AssetInfo::Token {
+                    contract_addr: "liquidity0000".to_string(),
+                },
+                pair_type: PairType::ConstantProduct,
+            });
+
+        SystemResult::Ok(ContractResult::from(to_binary(&pair_info)))
+    }
+
+    /// Returns the balances the pair or trio at `contract_addr` holds of its assets as its pools,
+    /// or default pools if the pool is unknown
+    fn query_pool(&self, contract_addr: &str) -> QuerierResult {
+        let factory = &self.pool_factory_querier;
+        let pool_info = factory
+            .pairs
+            .values()
+            .find(|pair| pair.contract_addr == contract_addr)
+            .map(|pair| (pair.asset_infos.to_vec(), pair.liquidity_token.clone()))
+            .or_else(|| {
+                factory
+                    .trios
+                    .values()
+                    .find(|trio| trio.contract_addr == contract_addr)
+                    .map(|trio| (trio.asset_infos.clone(), trio.liquidity_token.clone()))
+            });
+
+        let Some((asset_infos, liquidity_token)) = pool_info else {
+            return SystemResult::Ok(ContractResult::from(to_binary(&PairPoolResponse {
+                assets: vec![
+                    Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                        amount: Uint128::new(1_000_000_000u128),
+                    },
+                    Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "ujuno".to_string(),
+                        },
+                        amount: Uint128::new(1_000_000_000u128),
+                    },
+                ],
+                total_share: Uint128::new(2_000_000_000u128),
+            })));
+        };
+
+        let assets = asset_infos
+            .into_iter()
+            .map(|info| {
+                let amount = match &info {
+                    AssetInfo::NativeToken { denom } => {
+                        let request = QueryRequest::Bank(BankQuery::Balance {
+                            address: contract_addr.to_string(),
+                            denom: denom.to_string(),
+                        });
+                        match self.base.handle_query(&request) {
+                            SystemResult::Ok(ContractResult::Ok(res)) => {
+                                from_binary::<BalanceResponse>(&res).unwrap().amount.amount
+                            }
+                            _ => Uint128::zero(),
+                        }
+                    }
+                    AssetInfo::Token {
+                        contract_addr: token,
+                    } => self
+                        .token_querier
+                        .balances
+                        .get(token)
+                        .and_then(|balances| balances.get(contract_addr))
+                        .copied()
+                        .unwrap_or_default(),
+                };
+                Asset { info, amount }
+            })
+            .collect();
+        let total_share = match &liquidity_token {
+            AssetInfo::Token { contract_addr } => self
+                .token_querier
+                .balances
+                .get(contract_addr)
+                .map(|balances| balances.values().copied().sum())
+                .unwrap_or_default(),
+            AssetInfo::NativeToken { .. } => Uint128::zero(),
+        };
+
+        SystemResult::Ok(ContractResult::from(to_binary(&PairPoolResponse {
+            assets,
+            total_share,
+        })))
+    }
+
+    fn query_trio_info(&self, contract_addr: &str) -> QuerierResult {
+        match self
+            .pool_factory_querier
+            .trios
+            .values()
+            .find(|trio| trio.contract_addr == contract_addr)
+        {
+            Some(trio_info) => SystemResult::Ok(ContractResult::from(to_binary(trio_info))),
+            None => SystemResult::Ok(ContractResult::Err(format!(
+                "Trio {contract_addr} not found"
+            ))),
+        }
+    }
+
+    fn query_pairs(
+        &self,
+        start_after: Option<[AssetInfo; 2]>,
+        start_after_identifier: Option<String>,
+        limit: Option<u32>,
+    ) -> QuerierResult {
+        let mut pairs: Vec<&PairInfo> = self.pool_factory_querier.pairs.values().collect();
+        pairs.sort_by(|a, b| a.contract_addr.cmp(&b.contract_addr));
+        let start = start_after
+            .and_then(|start_after| {
+                pairs.iter().rposition(|pair| {
+                    pair.asset_infos == start_after
+                        && match &start_after_identifier {
+                            Some(identifier) => &self.pair_identifier(pair) == identifier,
+                            None => true,
+                        }
+                })
+            })
+            .map_or(0, |position| position + 1);
+        let pairs: Vec<PairInfo> = pairs
+            .into_iter()
+            .skip(start)
+            .take(limit.unwrap_or(10) as usize)
+            .cloned()
+            .collect();
+
+        SystemResult::Ok(ContractResult::Ok(
+            to_binary(&PairsResponse {
+                pair_identifiers: pairs
+                    .iter()
+                    .map(|pair| self.pair_identifier(pair))
+                    .collect(),
+                pairs,
+            })
+            .unwrap(),
+        ))
+    }
+
+    fn query_trios(
+        &self,
+        start_after: Option<Vec<AssetInfo>>,
+        limit: Option<u32>,
+    ) -> QuerierResult {
+        let mut trios: Vec<&TrioInfo> = self.pool_factory_querier.trios.values().collect();
+        trios.sort_by(|a, b| a.contract_addr.cmp(&b.contract_addr));
+        let start = start_after
+            .and_then(|start_after| {
+                trios
+                    .iter()
+                    .position(|trio| trio.asset_infos == start_after)
+            })
+            .map_or(0, |position| position + 1);
+
+        SystemResult::Ok(ContractResult::Ok(
+            to_binary(&TriosResponse {
+                trios: trios
+                    .into_iter()
+                    .skip(start)
+                    .take(limit.unwrap_or(10) as usize)
+                    .cloned()
+                    .collect(),
+            })
+            .unwrap(),
+        ))
+    }
+
+    fn query_pools_by_asset(
+        &self,
+        asset_info: AssetInfo,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> QuerierResult {
+        let pairs = self
+            .pool_factory_querier
+            .pairs
+            .values()
+            .filter(|pair| pair.asset_infos.contains(&asset_info))
+            .map(|pair| {
+                (
+                    pair.contract_addr.clone(),
+                    PoolInfo::Pair {
+                        pair_identifier: self.pair_identifier(pair),
+                        pair_info: pair.clone(),
+                    },
+                )
+            });
+        let trios = self
+            .pool_factory_querier
+            .trios
+            .values()
+            .filter(|trio| trio.asset_infos.contains(&asset_info))
+            .map(|trio| {
+                (
+                    trio.contract_addr.clone(),
+                    PoolInfo::Trio {
+                        trio_info: trio.clone(),
+                    },
+                )
+            });
+        let mut pools: Vec<(String, PoolInfo)> = pairs.chain(trios).collect();
+        pools.sort_by(|a, b| a.0.cmp(&b.0));
+
+        SystemResult::Ok(ContractResult::Ok(
+            to_binary(&PoolsByAssetResponse {
+                pools: pools
+                    .into_iter()
+                    .filter(|(contract_addr, _)| {
+                        !matches!(&start_after, Some(start_after) if contract_addr <= start_after)
+                    })
+                    .take(limit.unwrap_or(10) as usize)
+                    .map(|(_, pool)| pool)
+                    .collect(),
+            })
+            .unwrap(),
+        ))
+    }
+
     pub fn with_balance(&mut self, balances: &[(&String, Vec<Coin>)]) {
         for (addr, balance) in balances {
             self.base.update_balance(addr.to_string(), balance.clone());
@@ -604,6 +1056,7 @@ mod mock_exception {
                     denom: "ulunc".to_string(),
                 },
             ],
+            pair_identifier: None,
         })
         .unwrap();
         assert_eq!(
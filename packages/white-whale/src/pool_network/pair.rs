@@ -1,9 +1,10 @@
 use crate::fee::Fee;
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Decimal, StdError, StdResult, Uint128};
+use cosmwasm_std::{Addr, Decimal, Decimal256, StdError, StdResult, Timestamp, Uint128};
 use cw20::Cw20ReceiveMsg;
 
 use crate::pool_network::asset::{Asset, AssetInfo, PairInfo, PairType};
+use crate::pool_network::trio::RampAmp;
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -25,28 +26,51 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     /// Used to trigger the [Cw20HookMsg] messages
     Receive(Cw20ReceiveMsg),
-    /// Provides liquidity to the pool
+    /// Provides liquidity to the pool. If only one of the assets has a non-zero amount, part of it
+    /// is swapped for the other asset before providing liquidity.
     ProvideLiquidity {
         assets: [Asset; 2],
         slippage_tolerance: Option<Decimal>,
         receiver: Option<String>,
+        deadline: Option<Timestamp>,
     },
     /// Withdraws liquidity from the pool. Used only when the LP is a token factory token.
-    WithdrawLiquidity {},
+    /// If `target_asset` is set, the whole withdrawal is paid out in that asset and `min_receive`
+    /// is the minimum amount of it to be received.
+    WithdrawLiquidity {
+        target_asset: Option<AssetInfo>,
+        min_receive: Option<Uint128>,
+        deadline: Option<Timestamp>,
+    },
     /// Swap an offer asset to the other
     Swap {
         offer_asset: Asset,
         belief_price: Option<Decimal>,
         max_spread: Option<Decimal>,
         to: Option<String>,
+        deadline: Option<Timestamp>,
+    },
+    /// Swaps the other asset of the pair for the exact amount of `ask_asset`. The offer amount
+    /// needed is taken out of the native funds sent, refunding the surplus, or pulled from the cw20
+    /// allowance given to the pool. Fails if more than `max_offer_amount` would be needed.
+    SwapExactOut {
+        ask_asset: Asset,
+        max_offer_amount: Uint128,
+        to: Option<String>,
+        deadline: Option<Timestamp>,
     },
-    /// Updates the pair pool config
+    /// Updates the pair pool config. The `amp_factor` ramps the amplification coefficient of a
+    /// stableswap pair linearly from its current value to `future_a` by the `future_block`.
     UpdateConfig {
         owner: Option<String>,
         fee_collector_addr: Option<String>,
         pool_fees: Option<PoolFee>,
         feature_toggle: Option<FeatureToggle>,
+        amp_factor: Option<RampAmp>,
     },
+    /// Stops the ramping of the amplification coefficient of a stableswap pair, keeping its current
+    /// value.
+    StopAmpRamp {},
     /// Collects the Protocol fees accrued by the pool
     CollectProtocolFees {},
 }
@@ -58,9 +82,15 @@ pub enum Cw20HookMsg {
         belief_price: Option<Decimal>,
         max_spread: Option<Decimal>,
         to: Option<String>,
+        deadline: Option<Timestamp>,
+    },
+    /// Withdraws liquidity. If `target_asset` is set, the whole withdrawal is paid out in that asset
+    /// and `min_receive` is the minimum amount of it to be received.
+    WithdrawLiquidity {
+        target_asset: Option<AssetInfo>,
+        min_receive: Option<Uint128>,
+        deadline: Option<Timestamp>,
     },
-    /// Withdraws liquidity
-    WithdrawLiquidity {},
 }
 
 #[cw_serde]
@@ -94,6 +124,32 @@ pub enum QueryMsg {
     /// perform the swap.
     #[returns(ReverseSimulationResponse)]
     ReverseSimulation { ask_asset: Asset },
+    /// Simulates a liquidity provision, i.e. how many LP tokens would be minted for the given
+    /// assets. If only one of the assets has a non-zero amount, part of it is swapped for the other
+    /// asset first.
+    #[returns(ProvideLiquiditySimulationResponse)]
+    ProvideLiquiditySimulation { assets: [Asset; 2] },
+    /// Retrieves the time-weighted average price of the pair between `start_age` and `end_age`
+    /// seconds ago, in both directions. `end_age` defaults to `0`, i.e. the current block.
+    #[returns(TwapResponse)]
+    Twap {
+        start_age: u64,
+        end_age: Option<u64>,
+    },
+    /// Retrieves the current amplification coefficient of a stableswap pair, along with its ramp.
+    #[returns(AmpResponse)]
+    Amp {},
+    /// Retrieves the virtual price of the LP token, i.e. the invariant of the pair per LP token. The
+    /// protocol fees are excluded from the reserves.
+    #[returns(VirtualPriceResponse)]
+    VirtualPrice {},
+    /// Retrieves the marginal price of the offer asset denominated in the ask asset, expressed in
+    /// the smallest units of the assets and excluding fees.
+    #[returns(SpotPriceResponse)]
+    SpotPrice {
+        offer_asset_info: AssetInfo,
+        ask_asset_info: AssetInfo,
+    },
 }
 
 /// Pool feature toggle
@@ -176,6 +232,52 @@ pub struct ReverseSimulationResponse {
     pub burn_fee_amount: Uint128,
 }
 
+/// ProvideLiquiditySimulationResponse returns liquidity provision simulation response
+#[cw_serde]
+pub struct ProvideLiquiditySimulationResponse {
+    /// The amount of LP tokens that would be minted
+    pub share: Uint128,
+    /// The assets that would be deposited into the pool, after the swap of single-sided provisions
+    pub deposits: Vec<Asset>,
+}
+
+/// TwapResponse returns the time-weighted average prices of the pair
+#[cw_serde]
+pub struct TwapResponse {
+    /// Average price of the first asset of the pair, denominated in the second asset
+    pub price0_average: Decimal256,
+    /// Average price of the second asset of the pair, denominated in the first asset
+    pub price1_average: Decimal256,
+    /// Start of the window the average was taken over, in seconds
+    pub start_time: u64,
+    /// End of the window the average was taken over, in seconds
+    pub end_time: u64,
+}
+
+/// The amplification coefficient of a stableswap pair. It ramps linearly from the `initial_amp` at
+/// the `initial_amp_block` to the `future_amp` at the `future_amp_block`.
+#[cw_serde]
+pub struct AmpResponse {
+    /// The amplification coefficient in effect at the current block
+    pub amp: u64,
+    pub initial_amp: u64,
+    pub future_amp: u64,
+    pub initial_amp_block: u64,
+    pub future_amp_block: u64,
+}
+
+/// VirtualPriceResponse returns the virtual price of the LP token
+#[cw_serde]
+pub struct VirtualPriceResponse {
+    pub virtual_price: Decimal256,
+}
+
+/// SpotPriceResponse returns the marginal price of the offer asset in the ask asset
+#[cw_serde]
+pub struct SpotPriceResponse {
+    pub spot_price: Decimal256,
+}
+
 /// We currently take no arguments for migrations
 #[cw_serde]
 pub struct MigrateMsg {}
@@ -1,13 +1,20 @@
 use crate::pool_network::asset::{Asset, AssetInfo, PairInfo, TrioInfo};
-use crate::pool_network::factory::{NativeTokenDecimalsResponse, QueryMsg as FactoryQueryMsg};
+use crate::pool_network::denom::QueryDenomMetadataRequest;
+use crate::pool_network::factory::{
+    NativeTokenDecimalsResponse, PairsResponse, PoolsByAssetResponse, QueryMsg as FactoryQueryMsg,
+    TriosResponse,
+};
 use crate::pool_network::pair::{
     QueryMsg as PairQueryMsg, ReverseSimulationResponse, SimulationResponse,
 };
-use crate::pool_network::trio::QueryMsg as TrioQueryMsg;
+use crate::pool_network::trio::{
+    QueryMsg as TrioQueryMsg, ReverseSimulationResponse as TrioReverseSimulationResponse,
+    SimulationResponse as TrioSimulationResponse,
+};
 
 use cosmwasm_std::{
-    to_binary, Addr, AllBalanceResponse, BalanceResponse, BankQuery, Coin, QuerierWrapper,
-    QueryRequest, StdResult, Uint128, WasmQuery,
+    from_slice, to_binary, Addr, AllBalanceResponse, BalanceResponse, BankQuery, Coin,
+    QuerierWrapper, QueryRequest, StdError, StdResult, Uint128, WasmQuery,
 };
 
 use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
@@ -75,15 +82,114 @@ pub fn query_native_decimals(
     Ok(res.decimals)
 }
 
+/// Queries the decimals registered for a native denom in the factory, if any. They're read straight
+/// from the factory's storage, so a denom without registered decimals can be told apart from a
+/// failing query.
+pub fn query_registered_native_decimals(
+    querier: &QuerierWrapper,
+    factory_contract: Addr,
+    denom: &str,
+) -> StdResult<Option<u8>> {
+    querier
+        .query_wasm_raw(factory_contract, native_decimals_key(denom))?
+        .map(|decimals| from_slice(&decimals))
+        .transpose()
+}
+
+/// The key of the decimals registered for a native denom in the `allow_native_token` map of the
+/// factory
+pub fn native_decimals_key(denom: &str) -> Vec<u8> {
+    const NAMESPACE: &[u8] = b"allow_native_token";
+    [
+        (NAMESPACE.len() as u16).to_be_bytes().as_slice(),
+        NAMESPACE,
+        denom.as_bytes(),
+    ]
+    .concat()
+}
+
+/// Queries the decimals of a native denom from its bank metadata, i.e. the exponent of its display
+/// unit.
+pub fn query_denom_metadata_decimals(querier: &QuerierWrapper, denom: String) -> StdResult<u8> {
+    let metadata = QueryDenomMetadataRequest {
+        denom: denom.clone(),
+    }
+    .query(querier)?
+    .metadata
+    .ok_or_else(|| StdError::not_found(format!("Metadata of {denom}")))?;
+
+    let display_unit = metadata
+        .denom_units
+        .iter()
+        .find(|unit| unit.denom == metadata.display || unit.aliases.contains(&metadata.display))
+        .ok_or_else(|| StdError::not_found(format!("Display unit of {denom}")))?;
+
+    u8::try_from(display_unit.exponent).map_err(|_| {
+        StdError::generic_err(format!(
+            "Invalid display exponent {} for {denom}",
+            display_unit.exponent
+        ))
+    })
+}
+
 pub fn query_pair_info(
     querier: &QuerierWrapper,
     factory_contract: Addr,
     asset_infos: &[AssetInfo; 2],
+    pair_identifier: Option<String>,
 ) -> StdResult<PairInfo> {
     querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: factory_contract.to_string(),
         msg: to_binary(&FactoryQueryMsg::Pair {
             asset_infos: asset_infos.clone(),
+            pair_identifier,
+        })?,
+    }))
+}
+
+pub fn query_pairs(
+    querier: &QuerierWrapper,
+    factory_contract: Addr,
+    start_after: Option<[AssetInfo; 2]>,
+    start_after_identifier: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<PairsResponse> {
+    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: factory_contract.to_string(),
+        msg: to_binary(&FactoryQueryMsg::Pairs {
+            start_after,
+            start_after_identifier,
+            limit,
+            asset_infos: None,
+        })?,
+    }))
+}
+
+pub fn query_trios(
+    querier: &QuerierWrapper,
+    factory_contract: Addr,
+    start_after: Option<Vec<AssetInfo>>,
+    limit: Option<u32>,
+) -> StdResult<TriosResponse> {
+    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: factory_contract.to_string(),
+        msg: to_binary(&FactoryQueryMsg::Trios { start_after, limit })?,
+    }))
+}
+
+pub fn query_pools_by_asset(
+    querier: &QuerierWrapper,
+    factory_contract: Addr,
+    asset_info: AssetInfo,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<PoolsByAssetResponse> {
+    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: factory_contract.to_string(),
+        msg: to_binary(&FactoryQueryMsg::PoolsByAsset {
+            asset_info,
+            start_after,
+            limit,
         })?,
     }))
 }
@@ -114,6 +220,55 @@ pub fn reverse_simulate(
     }))
 }
 
+pub fn query_trio_info(
+    querier: &QuerierWrapper,
+    factory_contract: Addr,
+    asset_infos: &[AssetInfo],
+) -> StdResult<TrioInfo> {
+    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: factory_contract.to_string(),
+        msg: to_binary(&FactoryQueryMsg::Trio {
+            asset_infos: asset_infos.to_vec(),
+        })?,
+    }))
+}
+
+pub fn simulate_trio(
+    querier: &QuerierWrapper,
+    trio_contract: Addr,
+    offer_asset: &Asset,
+    ask_asset_info: &AssetInfo,
+) -> StdResult<TrioSimulationResponse> {
+    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: trio_contract.to_string(),
+        msg: to_binary(&TrioQueryMsg::Simulation {
+            offer_asset: offer_asset.clone(),
+            ask_asset: Asset {
+                info: ask_asset_info.clone(),
+                amount: Uint128::zero(),
+            },
+        })?,
+    }))
+}
+
+pub fn reverse_simulate_trio(
+    querier: &QuerierWrapper,
+    trio_contract: Addr,
+    offer_asset_info: &AssetInfo,
+    ask_asset: &Asset,
+) -> StdResult<TrioReverseSimulationResponse> {
+    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: trio_contract.to_string(),
+        msg: to_binary(&TrioQueryMsg::ReverseSimulation {
+            ask_asset: ask_asset.clone(),
+            offer_asset: Asset {
+                info: offer_asset_info.clone(),
+                amount: Uint128::zero(),
+            },
+        })?,
+    }))
+}
+
 pub fn query_pair_info_from_pair(
     querier: &QuerierWrapper,
     pair_contract: Addr,
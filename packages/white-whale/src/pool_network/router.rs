@@ -1,28 +1,56 @@
 use std::fmt;
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Decimal, Timestamp, Uint128};
 use cw20::Cw20ReceiveMsg;
 
-use crate::pool_network::asset::AssetInfo;
+use crate::pool_network::asset::{Asset, AssetInfo};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub terraswap_factory: String,
 }
 
+/// The `belief_price` and `max_spread` of an operation are forwarded to the pool it swaps through.
+/// If no `max_spread` is given, the router's default one is used.
 #[cw_serde]
 pub enum SwapOperation {
+    /// Swaps through the pair of the offer and ask assets with the given `pair_identifier`, or
+    /// through the default pair of the assets in the factory if none is given.
     TerraSwap {
         offer_asset_info: AssetInfo,
         ask_asset_info: AssetInfo,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        pair_identifier: Option<String>,
+    },
+    /// Swaps through the stableswap pool holding the `pool_asset_infos`, which are used to look
+    /// the pool up in the factory.
+    StableSwap3Pool {
+        offer_asset_info: AssetInfo,
+        ask_asset_info: AssetInfo,
+        pool_asset_infos: Vec<AssetInfo>,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
     },
 }
 
 impl SwapOperation {
+    pub fn get_offer_asset_info(&self) -> AssetInfo {
+        match self {
+            SwapOperation::TerraSwap {
+                offer_asset_info, ..
+            }
+            | SwapOperation::StableSwap3Pool {
+                offer_asset_info, ..
+            } => offer_asset_info.clone(),
+        }
+    }
+
     pub fn get_target_asset_info(&self) -> AssetInfo {
         match self {
-            SwapOperation::TerraSwap { ask_asset_info, .. } => ask_asset_info.clone(),
+            SwapOperation::TerraSwap { ask_asset_info, .. }
+            | SwapOperation::StableSwap3Pool { ask_asset_info, .. } => ask_asset_info.clone(),
         }
     }
 }
@@ -33,10 +61,25 @@ impl fmt::Display for SwapOperation {
             SwapOperation::TerraSwap {
                 offer_asset_info,
                 ask_asset_info,
+                ..
             } => write!(
                 f,
                 "TerraSwap {{ offer_asset_info: {offer_asset_info}, ask_asset_info: {ask_asset_info} }}"
             ),
+            SwapOperation::StableSwap3Pool {
+                offer_asset_info,
+                ask_asset_info,
+                pool_asset_infos,
+                ..
+            } => write!(
+                f,
+                "StableSwap3Pool {{ offer_asset_info: {offer_asset_info}, ask_asset_info: {ask_asset_info}, pool_asset_infos: {} }}",
+                pool_asset_infos
+                    .iter()
+                    .map(|asset_info| asset_info.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
         }
     }
 }
@@ -58,18 +101,81 @@ impl fmt::Display for SwapRoute {
     }
 }
 
+/// An integrator taking a `commission` of the amount returned by a swap, sent to the `address`
+#[cw_serde]
+pub struct Referral {
+    pub address: String,
+    pub commission: Decimal,
+}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     Receive(Cw20ReceiveMsg),
-    /// Execute multiple [SwapOperation]s, i.e. multi-hop swaps.
+    /// Execute multiple [SwapOperation]s, i.e. multi-hop swaps. If no `operations` are given, the
+    /// best route to `ask_asset_info` is searched on-chain, as in [QueryMsg::FindBestRoute]. The
+    /// offer asset is then the single native coin sent along with the message.
+    ///
+    /// If a `referral` is given, its commission is taken from the amount returned by the swap and
+    /// sent to the referral address before asserting the `minimum_receive`.
     ExecuteSwapOperations {
-        operations: Vec<SwapOperation>,
+        operations: Option<Vec<SwapOperation>>,
+        ask_asset_info: Option<AssetInfo>,
         minimum_receive: Option<Uint128>,
         to: Option<String>,
+        deadline: Option<Timestamp>,
+        referral: Option<Referral>,
+    },
+    /// Divides the offer amount among the `routes` by their weights, which must add up to 1, and
+    /// executes each of them. All routes must swap the same offer asset into the same ask asset, and
+    /// `minimum_receive` is asserted on what they return combined.
+    ExecuteSplitSwap {
+        routes: Vec<(Decimal, Vec<SwapOperation>)>,
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+        deadline: Option<Timestamp>,
+    },
+    /// Executes the swap operations so that exactly `ask_amount` of the ask asset is received. The
+    /// offer amount needed is reverse simulated, and whatever is sent in excess is refunded to the
+    /// sender. The swap fails if it needs more than `max_offer_amount`, which defaults to the amount
+    /// sent.
+    ExecuteSwapOperationsExactOut {
+        operations: Vec<SwapOperation>,
+        ask_amount: Uint128,
+        max_offer_amount: Option<Uint128>,
+        to: Option<String>,
+        deadline: Option<Timestamp>,
+    },
+    /// Swaps the native `offer_asset` into the assets of the pair or trio at `pair_address`, and
+    /// provides them as liquidity, sending the LP tokens to the `receiver`. The offer amount is
+    /// split among the pool assets in the proportion of the pool reserves, with
+    /// `operations_per_side` holding the operations that swap into each pool asset, in the order
+    /// of the pool assets. An empty list of operations provides the offer asset itself. What the
+    /// pool doesn't take is refunded to the sender.
+    ZapIntoPool {
+        offer_asset: Asset,
+        operations_per_side: Vec<Vec<SwapOperation>>,
+        pair_address: String,
+        min_lp_out: Option<Uint128>,
+        slippage_tolerance: Option<Decimal>,
+        receiver: Option<String>,
+        deadline: Option<Timestamp>,
+    },
+    /// Provides what the router received of each of the pool assets since `prev_balances` as
+    /// liquidity to the pair or trio at `pair_address`, minting the LP tokens to the `receiver`
+    /// and refunding what the pool doesn't take to `refund_to`.
+    /// This message can only be called internally by the router contract.
+    ProvideZapLiquidity {
+        pair_address: String,
+        prev_balances: Vec<Asset>,
+        slippage_tolerance: Option<Decimal>,
+        receiver: String,
+        refund_to: String,
     },
     /// Swap the offer to ask token. This message can only be called internally by the router contract.
+    /// The router's whole balance of the offer token is swapped unless `offer_amount` is given.
     ExecuteSwapOperation {
         operation: SwapOperation,
+        offer_amount: Option<Uint128>,
         to: Option<String>,
     },
     /// Checks if the swap amount exceeds the minimum_receive. This message can only be called
@@ -80,18 +186,71 @@ pub enum ExecuteMsg {
         minimum_receive: Uint128,
         receiver: String,
     },
+    /// Takes the referral commission from what the router received since `prev_balance`, sending
+    /// the rest to the receiver. This message can only be called internally by the router contract.
+    TakeReferralCommission {
+        asset_info: AssetInfo,
+        prev_balance: Uint128,
+        referral: Referral,
+        receiver: String,
+    },
     /// Adds swap routes to the router.
     AddSwapRoutes {
         swap_routes: Vec<SwapRoute>,
     },
+    /// Removes swap routes from the router.
+    RemoveSwapRoutes {
+        swap_routes: Vec<SwapRoute>,
+    },
+    /// Updates the router config. Fields left as [None] are unchanged. The `default_max_spread` is
+    /// used on the swap operations that don't set their own `max_spread`, and is removed if
+    /// `remove_default_max_spread` is true. The `max_referral_commission` caps the commission
+    /// referrals can take.
+    UpdateConfig {
+        default_max_spread: Option<Decimal>,
+        remove_default_max_spread: Option<bool>,
+        max_referral_commission: Option<Decimal>,
+    },
+    /// Proposes a new owner for the router, who has to claim the ownership to become the owner.
+    ProposeNewOwner {
+        owner: String,
+    },
+    /// Drops the pending ownership proposal.
+    DropOwnershipProposal {},
+    /// Claims the ownership of the router. Can only be called by the proposed owner.
+    ClaimOwnership {},
 }
 
 #[cw_serde]
 pub enum Cw20HookMsg {
     ExecuteSwapOperations {
-        operations: Vec<SwapOperation>,
+        operations: Option<Vec<SwapOperation>>,
+        ask_asset_info: Option<AssetInfo>,
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+        deadline: Option<Timestamp>,
+        referral: Option<Referral>,
+    },
+    ExecuteSplitSwap {
+        routes: Vec<(Decimal, Vec<SwapOperation>)>,
         minimum_receive: Option<Uint128>,
         to: Option<String>,
+        deadline: Option<Timestamp>,
+    },
+    ExecuteSwapOperationsExactOut {
+        operations: Vec<SwapOperation>,
+        ask_amount: Uint128,
+        max_offer_amount: Option<Uint128>,
+        to: Option<String>,
+        deadline: Option<Timestamp>,
+    },
+    ZapIntoPool {
+        operations_per_side: Vec<Vec<SwapOperation>>,
+        pair_address: String,
+        min_lp_out: Option<Uint128>,
+        slippage_tolerance: Option<Decimal>,
+        receiver: Option<String>,
+        deadline: Option<Timestamp>,
     },
 }
 
@@ -114,18 +273,72 @@ pub enum QueryMsg {
         ask_amount: Uint128,
         operations: Vec<SwapOperation>,
     },
+    /// Simulates swap operations, returning the simulation of each hop along with the final amount.
+    #[returns(SimulateSwapOperationsDetailedResponse)]
+    SimulateSwapOperationsDetailed {
+        offer_amount: Uint128,
+        operations: Vec<SwapOperation>,
+    },
+    /// Reverse simulates swap operations, returning the simulation of each hop along with the
+    /// offer amount needed to get the `ask_amount`.
+    #[returns(SimulateSwapOperationsDetailedResponse)]
+    ReverseSimulateSwapOperationsDetailed {
+        ask_amount: Uint128,
+        operations: Vec<SwapOperation>,
+    },
+    /// Simulates a split swap, i.e. the offer amount divided among the `routes` by their weights.
+    /// Each route is simulated on its own, so pools shared by several routes aren't accounted for.
+    #[returns(SimulateSplitSwapResponse)]
+    SimulateSplitSwap {
+        offer_amount: Uint128,
+        routes: Vec<(Decimal, Vec<SwapOperation>)>,
+    },
     /// Gets the swap route for the given offer and ask assets.
     #[returns(Vec<SwapOperation>)]
     SwapRoute {
         offer_asset_info: AssetInfo,
         ask_asset_info: AssetInfo,
     },
+    /// Gets the swap routes stored in the router, paginated by their offer and ask assets.
+    #[returns(Vec<SwapRoute>)]
+    SwapRoutes {
+        start_after: Option<[AssetInfo; 2]>,
+        limit: Option<u32>,
+    },
+    /// Checks whether the pools the stored swap routes go through can still be found in the
+    /// factory, paginated as [QueryMsg::SwapRoutes].
+    #[returns(Vec<SwapRouteValidity>)]
+    SwapRoutesValidity {
+        start_after: Option<[AssetInfo; 2]>,
+        limit: Option<u32>,
+    },
+    /// Searches the pairs and trios created by the factory for the route with the best simulated
+    /// return, using at most `max_hops` swap operations. Defaults to 3 hops, capped at 4. The search
+    /// is bounded: only the first 30 pools holding each asset are used, and it stops after 100
+    /// simulated swaps, returning the best route found by then.
+    #[returns(BestRouteResponse)]
+    FindBestRoute {
+        offer_asset: Asset,
+        ask_asset_info: AssetInfo,
+        max_hops: Option<u32>,
+    },
 }
 
 // We define a custom struct for each query response
 #[cw_serde]
 pub struct ConfigResponse {
+    pub owner: String,
+    pub pending_owner: Option<String>,
     pub terraswap_factory: String,
+    pub default_max_spread: Option<Decimal>,
+    pub max_referral_commission: Decimal,
+}
+
+#[cw_serde]
+pub struct SwapRouteValidity {
+    pub swap_route: SwapRoute,
+    /// Whether all the pools the route goes through are found in the factory
+    pub is_valid: bool,
 }
 
 // We define a custom struct for each query response
@@ -134,6 +347,46 @@ pub struct SimulateSwapOperationsResponse {
     pub amount: Uint128,
 }
 
+/// Simulation of a single swap operation, i.e. a hop of the route
+#[cw_serde]
+pub struct SwapOperationSimulation {
+    pub operation: SwapOperation,
+    /// The pair or trio the swap is simulated on
+    pub pool: String,
+    pub offer_amount: Uint128,
+    pub return_amount: Uint128,
+    pub spread_amount: Uint128,
+    pub swap_fee_amount: Uint128,
+    pub protocol_fee_amount: Uint128,
+    pub burn_fee_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct SimulateSwapOperationsDetailedResponse {
+    /// The amount returned by the last hop, or the amount offered on the first hop when reverse
+    /// simulating
+    pub amount: Uint128,
+    /// The simulation of each hop, in the order of the swap operations
+    pub simulations: Vec<SwapOperationSimulation>,
+    /// The share of the spot price output lost to the spread across all hops
+    pub price_impact: Decimal,
+}
+
+#[cw_serde]
+pub struct SimulateSplitSwapResponse {
+    /// The amount returned by each of the routes
+    pub route_amounts: Vec<Uint128>,
+    /// The amount returned by all routes combined
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct BestRouteResponse {
+    pub operations: Vec<SwapOperation>,
+    /// The simulated amount of the ask asset returned by the route
+    pub amount: Uint128,
+}
+
 /// We currently take no arguments for migrations
 #[cw_serde]
 pub struct MigrateMsg {}
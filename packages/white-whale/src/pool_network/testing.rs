@@ -1,4 +1,5 @@
 use crate::pool_network::asset::{Asset, AssetInfo, AssetInfoRaw, AssetRaw, PairInfo, PairType};
+use crate::pool_network::denom::{DenomUnit, Metadata};
 use crate::pool_network::mock_querier::mock_dependencies;
 use crate::pool_network::querier::{
     query_all_balances, query_balance, query_pair_info, query_token_balance, query_token_info,
@@ -413,6 +414,7 @@ fn query_terraswap_pair_contract() {
                 denom: "uusd".to_string(),
             },
         ],
+        None,
     )
     .unwrap();
 
@@ -423,6 +425,46 @@ fn query_terraswap_pair_contract() {
     );
 }
 
+#[test]
+fn query_native_asset_decimals() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier
+        .with_pool_factory(&[], &[("uusd".to_string(), 6u8)]);
+    let metadata = |denom: &str, exponent: u32| Metadata {
+        denom_units: vec![
+            DenomUnit {
+                denom: denom.to_string(),
+                exponent: 0,
+                aliases: vec![],
+            },
+            DenomUnit {
+                denom: "display".to_string(),
+                exponent,
+                aliases: vec![],
+            },
+        ],
+        base: denom.to_string(),
+        display: "display".to_string(),
+        ..Metadata::default()
+    };
+    deps.querier.with_denom_metadata(&[
+        (&"uusd".to_string(), &metadata("uusd", 8)),
+        (&"uatom".to_string(), &metadata("uatom", 6)),
+    ]);
+    let query_decimals = |denom: &str| {
+        AssetInfo::NativeToken {
+            denom: denom.to_string(),
+        }
+        .query_decimals(Addr::unchecked("factory"), &deps.as_ref().querier)
+    };
+
+    // the registered decimals take precedence over the metadata
+    assert_eq!(query_decimals("uusd").unwrap(), 6u8);
+    // denoms without registered decimals fall back to their metadata
+    assert_eq!(query_decimals("uatom").unwrap(), 6u8);
+    query_decimals("uxxx").unwrap_err();
+}
+
 #[test]
 fn get_native_asset_label() {
     let deps = mock_dependencies(&[]);
@@ -1,18 +1,23 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Decimal, StdError, StdResult, Uint128};
+use cosmwasm_std::{Addr, Decimal, Decimal256, StdError, StdResult, Timestamp, Uint128};
 use cw20::Cw20ReceiveMsg;
 
 use crate::fee::Fee;
 
 use crate::pool_network::asset::{Asset, AssetInfo, TrioInfo};
 
+/// Minimum number of assets a stableswap pool can hold
+pub const MIN_TRIO_ASSETS: usize = 2;
+/// Maximum number of assets a stableswap pool can hold
+pub const MAX_TRIO_ASSETS: usize = 8;
+
 #[cw_serde]
 pub struct InstantiateMsg {
-    /// Asset infos
-    pub asset_infos: [AssetInfo; 3],
+    /// Asset infos, between [MIN_TRIO_ASSETS] and [MAX_TRIO_ASSETS] of them
+    pub asset_infos: Vec<AssetInfo>,
     /// Token contract code id for initialization
     pub token_code_id: u64,
-    pub asset_decimals: [u8; 3],
+    pub asset_decimals: Vec<u8>,
     pub pool_fees: PoolFee,
     pub fee_collector_addr: String,
     pub amp_factor: u64,
@@ -27,12 +32,29 @@ pub enum ExecuteMsg {
     Receive(Cw20ReceiveMsg),
     /// Provides liquidity to the pool
     ProvideLiquidity {
-        assets: [Asset; 3],
+        assets: Vec<Asset>,
         slippage_tolerance: Option<Decimal>,
         receiver: Option<String>,
+        deadline: Option<Timestamp>,
     },
     /// Withdraws liquidity from the pool. Used only when the LP is a token factory token.
-    WithdrawLiquidity {},
+    /// If `target_asset` is set, the whole withdrawal is paid out in that asset and `min_receive`
+    /// is the minimum amount of it to be received.
+    WithdrawLiquidity {
+        target_asset: Option<AssetInfo>,
+        min_receive: Option<Uint128>,
+        deadline: Option<Timestamp>,
+    },
+    /// Withdraws exactly the given amounts of the pool assets, burning just enough LP tokens to
+    /// cover them plus the fee charged for imbalancing the pool. Fails if more than
+    /// `max_burn_amount` LP tokens would be burned. If the LP is a cw20 token, the pool must have
+    /// an allowance of at least `max_burn_amount` on it. If it's a token factory token, the LP
+    /// tokens are sent along with the message and the ones not burned are refunded.
+    WithdrawImbalance {
+        assets: Vec<Asset>,
+        max_burn_amount: Uint128,
+        deadline: Option<Timestamp>,
+    },
     /// Swap an offer asset to the other
     Swap {
         offer_asset: Asset,
@@ -40,6 +62,17 @@ pub enum ExecuteMsg {
         belief_price: Option<Decimal>,
         max_spread: Option<Decimal>,
         to: Option<String>,
+        deadline: Option<Timestamp>,
+    },
+    /// Swaps the `offer_asset` for the exact amount of `ask_asset`. The offer amount needed is taken
+    /// out of the native funds sent, refunding the surplus, or pulled from the cw20 allowance given
+    /// to the pool. Fails if more than `max_offer_amount` would be needed.
+    SwapExactOut {
+        offer_asset: AssetInfo,
+        ask_asset: Asset,
+        max_offer_amount: Uint128,
+        to: Option<String>,
+        deadline: Option<Timestamp>,
     },
     /// Updates the trio pool config
     UpdateConfig {
@@ -67,9 +100,15 @@ pub enum Cw20HookMsg {
         belief_price: Option<Decimal>,
         max_spread: Option<Decimal>,
         to: Option<String>,
+        deadline: Option<Timestamp>,
+    },
+    /// Withdraws liquidity. If `target_asset` is set, the whole withdrawal is paid out in that asset
+    /// and `min_receive` is the minimum amount of it to be received.
+    WithdrawLiquidity {
+        target_asset: Option<AssetInfo>,
+        min_receive: Option<Uint128>,
+        deadline: Option<Timestamp>,
     },
-    /// Withdraws liquidity
-    WithdrawLiquidity {},
 }
 
 #[cw_serde]
@@ -109,6 +148,21 @@ pub enum QueryMsg {
         ask_asset: Asset,
         offer_asset: Asset,
     },
+    /// Simulates an imbalanced withdrawal, i.e. given the amounts of the pool assets to withdraw,
+    /// how many LP tokens would be burned.
+    #[returns(WithdrawImbalanceSimulationResponse)]
+    SimulateWithdrawImbalance { assets: Vec<Asset> },
+    /// Retrieves the virtual price of the LP token, i.e. the invariant of the pool per LP token. The
+    /// protocol fees are excluded from the reserves.
+    #[returns(VirtualPriceResponse)]
+    VirtualPrice {},
+    /// Retrieves the marginal price of the offer asset denominated in the ask asset, expressed in
+    /// the smallest units of the assets and excluding fees.
+    #[returns(SpotPriceResponse)]
+    SpotPrice {
+        offer_asset_info: AssetInfo,
+        ask_asset_info: AssetInfo,
+    },
 }
 
 /// Pool feature toggle
@@ -195,6 +249,28 @@ pub struct ReverseSimulationResponse {
     pub burn_fee_amount: Uint128,
 }
 
+/// WithdrawImbalanceSimulationResponse returns imbalanced withdrawal simulation response
+#[cw_serde]
+pub struct WithdrawImbalanceSimulationResponse {
+    /// Amount of LP tokens burned by the withdrawal
+    pub burn_amount: Uint128,
+    pub swap_fee_amounts: Vec<Asset>,
+    pub protocol_fee_amounts: Vec<Asset>,
+    pub burn_fee_amounts: Vec<Asset>,
+}
+
+/// VirtualPriceResponse returns the virtual price of the LP token
+#[cw_serde]
+pub struct VirtualPriceResponse {
+    pub virtual_price: Decimal256,
+}
+
+/// SpotPriceResponse returns the marginal price of the offer asset in the ask asset
+#[cw_serde]
+pub struct SpotPriceResponse {
+    pub spot_price: Decimal256,
+}
+
 /// We currently take no arguments for migrations
 #[cw_serde]
 pub struct MigrateMsg {}